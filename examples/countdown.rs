@@ -1,5 +1,4 @@
-// rsevents_extra re-exports rsevents::Awaitable
-use rsevents_extra::{Awaitable, CountdownEvent};
+use rsevents_extra::CountdownEvent;
 use std::{thread, time::Duration};
 
 fn main() {
@@ -9,6 +8,7 @@ fn main() {
         // Start two worker threads to each do some of the work
         for i in 0..2 {
             // Shadow some variables to allow us to `move` into the closure
+            #[allow(clippy::redundant_locals)]
             let i = i;
             let countdown = &countdown;
 
@@ -27,11 +27,11 @@ fn main() {
         }
 
         // The main thread will wait for 42 tasks to be completed before it does
-        // its thing... whatever that is.
-        while !countdown.wait_for(Duration::from_secs(1)) {
-            // Report progress every 1 second until we've finished
-            eprintln!("Work in progress. {} items remaining.", countdown.count());
-        }
+        // its thing... whatever that is, reporting progress every 1 second until
+        // we've finished.
+        countdown.wait_with_progress(Duration::from_secs(1), |remaining| {
+            eprintln!("Work in progress. {} items remaining.", remaining);
+        });
 
         eprintln!("Work completed!");
     });
@@ -0,0 +1,543 @@
+#[cfg(feature = "async")]
+use crate::backoff::Backoff;
+use crate::multi_wait::wait_first;
+use crate::subscribe::{polled, subscribed, Subscribe, SubscribeFn, Subscription};
+#[cfg(feature = "async")]
+use crate::wake_timer::wake_after;
+use rsevents::{Awaitable, TimeoutError};
+use std::convert::Infallible;
+use std::fmt::Debug;
+#[cfg(feature = "async")]
+use std::future::Future;
+#[cfg(feature = "async")]
+use std::pin::Pin;
+use std::task::Waker;
+#[cfg(feature = "async")]
+use std::task::{Context, Poll};
+use std::time::{Duration, Instant};
+
+/// An extension trait for [`Awaitable`] types, composing them into a single awaitable object with
+/// [`or()`](Self::or) and [`and()`](Self::and), which can be stored in a struct and waited on like
+/// any other awaitable. Implemented for every `Awaitable` whose timed waits fail with a
+/// [`TimeoutError`], including all of the crate's primitives and the events of `rsevents`.
+///
+/// ## Example:
+///
+/// ```rust
+/// use rsevents_extra::{
+///     AnyOf, Awaitable, AwaitableExt, CancellationToken, CancellationTokenSource, CountdownEvent,
+///     OneOf,
+/// };
+///
+/// struct Job {
+///     done: AnyOf<CountdownEvent, CancellationToken>,
+/// }
+///
+/// let cancel = CancellationTokenSource::new();
+/// let job = Job {
+///     done: CountdownEvent::new(2).or_subscribed(cancel.token()),
+/// };
+///
+/// std::thread::scope(|scope| {
+///     scope.spawn(|| {
+///         job.done.first().tick();
+///         cancel.cancel();
+///     });
+///
+///     match job.done.wait() {
+///         OneOf::First(()) => println!("the job finished"),
+///         OneOf::Second(()) => println!("the job was cancelled"),
+///     }
+/// });
+/// ```
+pub trait AwaitableExt<'a>: Awaitable<'a, Error = TimeoutError> + Sized {
+    /// Combines this awaitable with `other` into one that becomes ready as soon as either of the
+    /// two does, waiting on only that one (see [`AnyOf`]). The two are polled while waiting.
+    fn or<B>(self, other: B) -> AnyOf<Self, B>
+    where
+        B: Awaitable<'a, Error = TimeoutError>,
+    {
+        AnyOf {
+            first: self,
+            second: other,
+            subscribe: (polled, polled),
+        }
+    }
+
+    /// Combines this awaitable with `other` like [`or()`](Self::or), but subscribes to the two
+    /// while waiting, sleeping until woken by either of them instead of polling them.
+    fn or_subscribed<B>(self, other: B) -> AnyOf<Self, B>
+    where
+        Self: Subscribe,
+        B: Awaitable<'a, Error = TimeoutError> + Subscribe,
+    {
+        AnyOf {
+            first: self,
+            second: other,
+            subscribe: (subscribed, subscribed),
+        }
+    }
+
+    /// Combines this awaitable with `other` into one that becomes ready once both of the two are
+    /// (see [`AllOf`]).
+    fn and<B>(self, other: B) -> AllOf<Self, B>
+    where
+        B: Awaitable<'a, Error = TimeoutError>,
+    {
+        AllOf {
+            first: self,
+            second: other,
+        }
+    }
+
+    /// Returns a [`Future`] that completes with the result of waiting on this awaitable, letting
+    /// it be `.await`ed from async code on any executor. Only available with the `async` feature
+    /// enabled.
+    ///
+    /// The future doesn't block the executor or occupy a thread of its own: it attempts the wait
+    /// without blocking each time it is polled, and is otherwise retried by a timer thread shared
+    /// by all such futures, with an exponential backoff (capped at a few milliseconds) between
+    /// attempts, so it may complete up to that much later than the awaitable became ready. Use
+    /// [`into_subscribed_future()`](Self::into_subscribed_future) to have the awaitable wake it
+    /// directly instead.
+    ///
+    /// ## Example:
+    ///
+    /// ```rust
+    /// use rsevents_extra::{AwaitableExt, CountdownEvent};
+    /// # fn block_on<F: std::future::Future>(future: F) -> F::Output {
+    /// #     struct Unparker(std::thread::Thread);
+    /// #     impl std::task::Wake for Unparker {
+    /// #         fn wake(self: std::sync::Arc<Self>) {
+    /// #             self.0.unpark();
+    /// #         }
+    /// #     }
+    /// #     let waker = std::sync::Arc::new(Unparker(std::thread::current())).into();
+    /// #     let mut context = std::task::Context::from_waker(&waker);
+    /// #     let mut future = std::pin::pin!(future);
+    /// #     loop {
+    /// #         match future.as_mut().poll(&mut context) {
+    /// #             std::task::Poll::Ready(result) => return result,
+    /// #             std::task::Poll::Pending => std::thread::park(),
+    /// #         }
+    /// #     }
+    /// # }
+    ///
+    /// let countdown = CountdownEvent::new(1);
+    ///
+    /// std::thread::scope(|scope| {
+    ///     scope.spawn(|| countdown.tick());
+    ///
+    ///     // e.g. from within an async fn
+    ///     block_on(async {
+    ///         countdown.into_future().await;
+    ///     });
+    /// });
+    /// ```
+    #[cfg(feature = "async")]
+    #[allow(clippy::wrong_self_convention)]
+    fn into_future(&'a self) -> AwaitableFuture<'a, Self> {
+        AwaitableFuture {
+            awaitable: self,
+            subscribe: polled,
+            subscription: None,
+            backoff: Backoff::new(),
+        }
+    }
+
+    /// Returns a [`Future`] like [`into_future()`](Self::into_future), which [subscribes](Subscribe)
+    /// its task's waker to the awaitable before each attempt so that the awaitable wakes it
+    /// directly once it is worth trying again, instead of it being retried on a timer. Only
+    /// available with the `async` feature enabled.
+    #[cfg(feature = "async")]
+    #[allow(clippy::wrong_self_convention)]
+    fn into_subscribed_future(&'a self) -> AwaitableFuture<'a, Self>
+    where
+        Self: Subscribe,
+    {
+        AwaitableFuture {
+            awaitable: self,
+            subscribe: subscribed,
+            subscription: None,
+            backoff: Backoff::new(),
+        }
+    }
+}
+
+impl<'a, A: Awaitable<'a, Error = TimeoutError>> AwaitableExt<'a> for A {}
+
+/// The result of waiting on an [`AnyOf`], telling which of its two awaitables was waited on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OneOf<A, B> {
+    /// The first awaitable became ready, with the result of waiting on it.
+    First(A),
+    /// The second awaitable became ready, with the result of waiting on it.
+    Second(B),
+}
+
+/// An awaitable that becomes ready as soon as either of two awaitables does, created by
+/// [`AwaitableExt::or()`].
+///
+/// Exactly one of the two is waited on successfully, with the first winning if both are ready at
+/// once, as with [`wait_any()`](crate::wait_any). Waiting returns a [`OneOf`] telling which one it
+/// was; use [`Awaitable::try_wait_for()`] for timed waits.
+///
+/// While waiting, the two are polled if combined with [`or()`](AwaitableExt::or), or subscribed to
+/// if combined with [`or_subscribed()`](AwaitableExt::or_subscribed).
+pub struct AnyOf<A, B> {
+    first: A,
+    second: B,
+    subscribe: (SubscribeFn<A>, SubscribeFn<B>),
+}
+
+impl<A, B> AnyOf<A, B> {
+    /// Returns the first of the two awaitables, e.g. to signal it.
+    pub fn first(&self) -> &A {
+        &self.first
+    }
+
+    /// Returns the second of the two awaitables, e.g. to signal it.
+    pub fn second(&self) -> &B {
+        &self.second
+    }
+
+    /// Splits the combination back into its two awaitables.
+    pub fn into_inner(self) -> (A, B) {
+        (self.first, self.second)
+    }
+}
+
+impl<A: Debug, B: Debug> Debug for AnyOf<A, B> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AnyOf")
+            .field("first", &self.first)
+            .field("second", &self.second)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<'a, A, B> AnyOf<A, B>
+where
+    A: Awaitable<'a, Error = TimeoutError>,
+    B: Awaitable<'a, Error = TimeoutError>,
+{
+    fn wait_until(&'a self, deadline: Option<Instant>) -> Result<OneOf<A::T, B::T>, TimeoutError> {
+        let (_, result) = wait_first(
+            2,
+            deadline,
+            |index, waker| match index {
+                0 => (self.subscribe.0)(&self.first, waker),
+                _ => (self.subscribe.1)(&self.second, waker),
+            },
+            |index| match index {
+                0 => self.first.try_wait0().ok().map(OneOf::First),
+                _ => self.second.try_wait0().ok().map(OneOf::Second),
+            },
+        )?;
+        Ok(result)
+    }
+}
+
+impl<'a, A, B> Awaitable<'a> for AnyOf<A, B>
+where
+    A: Awaitable<'a, Error = TimeoutError>,
+    B: Awaitable<'a, Error = TimeoutError>,
+{
+    type T = OneOf<A::T, B::T>;
+    type Error = TimeoutError;
+
+    /// Waits until either of the two awaitables becomes ready.
+    fn try_wait(&'a self) -> Result<Self::T, Infallible> {
+        match self.wait_until(None) {
+            Ok(result) => Ok(result),
+            Err(_) => unreachable!(),
+        }
+    }
+
+    /// Waits for up to `limit` until either of the two awaitables becomes ready.
+    fn try_wait_for(&'a self, limit: Duration) -> Result<Self::T, TimeoutError> {
+        self.wait_until(Some(Instant::now() + limit))
+    }
+
+    /// Waits on either of the two awaitables if one of them is ready right away.
+    fn try_wait0(&'a self) -> Result<Self::T, TimeoutError> {
+        match self.first.try_wait0() {
+            Ok(result) => Ok(OneOf::First(result)),
+            Err(_) => self.second.try_wait0().map(OneOf::Second),
+        }
+    }
+}
+
+impl<A: Subscribe, B: Subscribe> Subscribe for AnyOf<A, B> {
+    fn subscribe(&self, waker: &Waker) -> Option<Subscription<'_>> {
+        let first = self.first.subscribe(waker)?;
+        let second = self.second.subscribe(waker)?;
+        Some(Subscription::both(first, second))
+    }
+}
+
+/// An awaitable that becomes ready once both of two awaitables are, created by
+/// [`AwaitableExt::and()`].
+///
+/// As with [`wait_all()`](crate::wait_all), nothing is held while blocking: when one of the two
+/// isn't ready, the result of waiting on the other is dropped (releasing e.g. a semaphore slot)
+/// before blocking on it. Waiting returns the results of waiting on both; use
+/// [`Awaitable::try_wait_for()`] for timed waits.
+#[derive(Debug)]
+pub struct AllOf<A, B> {
+    first: A,
+    second: B,
+}
+
+impl<A, B> AllOf<A, B> {
+    /// Returns the first of the two awaitables, e.g. to signal it.
+    pub fn first(&self) -> &A {
+        &self.first
+    }
+
+    /// Returns the second of the two awaitables, e.g. to signal it.
+    pub fn second(&self) -> &B {
+        &self.second
+    }
+
+    /// Splits the combination back into its two awaitables.
+    pub fn into_inner(self) -> (A, B) {
+        (self.first, self.second)
+    }
+}
+
+impl<'a, A, B> AllOf<A, B>
+where
+    A: Awaitable<'a, Error = TimeoutError>,
+    B: Awaitable<'a, Error = TimeoutError>,
+{
+    fn wait_until(&'a self, deadline: Option<Instant>) -> Result<(A::T, B::T), TimeoutError> {
+        let remaining = || match deadline {
+            Some(deadline) => deadline.saturating_duration_since(Instant::now()),
+            None => Duration::MAX,
+        };
+
+        loop {
+            // Block on one of the two with nothing else held, then take the other without waiting.
+            let first = match deadline {
+                None => self.first.wait(),
+                Some(_) => self.first.try_wait_for(remaining())?,
+            };
+            if let Ok(second) = self.second.try_wait0() {
+                return Ok((first, second));
+            }
+            drop(first);
+
+            let second = match deadline {
+                None => self.second.wait(),
+                Some(_) => self.second.try_wait_for(remaining())?,
+            };
+            if let Ok(first) = self.first.try_wait0() {
+                return Ok((first, second));
+            }
+        }
+    }
+}
+
+impl<'a, A, B> Awaitable<'a> for AllOf<A, B>
+where
+    A: Awaitable<'a, Error = TimeoutError>,
+    B: Awaitable<'a, Error = TimeoutError>,
+{
+    type T = (A::T, B::T);
+    type Error = TimeoutError;
+
+    /// Waits until both awaitables are ready.
+    fn try_wait(&'a self) -> Result<Self::T, Infallible> {
+        match self.wait_until(None) {
+            Ok(result) => Ok(result),
+            Err(_) => unreachable!(),
+        }
+    }
+
+    /// Waits for up to `limit` until both awaitables are ready.
+    fn try_wait_for(&'a self, limit: Duration) -> Result<Self::T, TimeoutError> {
+        self.wait_until(Some(Instant::now() + limit))
+    }
+
+    /// Waits on both awaitables if both are ready right away.
+    fn try_wait0(&'a self) -> Result<Self::T, TimeoutError> {
+        let first = self.first.try_wait0()?;
+        let second = self.second.try_wait0()?;
+        Ok((first, second))
+    }
+}
+
+impl<A: Subscribe, B: Subscribe> Subscribe for AllOf<A, B> {
+    fn subscribe(&self, waker: &Waker) -> Option<Subscription<'_>> {
+        let first = self.first.subscribe(waker)?;
+        let second = self.second.subscribe(waker)?;
+        Some(Subscription::both(first, second))
+    }
+}
+
+/// The [`Future`] returned by [`AwaitableExt::into_future()`], completing with the result of
+/// waiting on an awaitable. Only available with the `async` feature enabled.
+#[cfg(feature = "async")]
+#[must_use = "futures do nothing unless polled"]
+pub struct AwaitableFuture<'a, A> {
+    awaitable: &'a A,
+    /// How we subscribe to the awaitable, depending on which method created us.
+    subscribe: SubscribeFn<A>,
+    /// Our waker's subscription to the awaitable, renewed each time we are polled.
+    subscription: Option<Subscription<'a>>,
+    /// The retry interval for awaitables that aren't subscribed to or decline the subscription.
+    backoff: Backoff,
+}
+
+#[cfg(feature = "async")]
+impl<'a, A> Future for AwaitableFuture<'a, A>
+where
+    A: Awaitable<'a, Error = TimeoutError>,
+{
+    type Output = A::T;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<A::T> {
+        // Subscribe before checking, so that the awaitable becoming ready in between still wakes us.
+        let awaitable = self.awaitable;
+        self.subscription = (self.subscribe)(awaitable, cx.waker());
+        match awaitable.try_wait0() {
+            Ok(result) => {
+                self.subscription = None;
+                Poll::Ready(result)
+            }
+            Err(_) => {
+                if self.subscription.is_none() {
+                    let delay = self.backoff.next();
+                    wake_after(cx.waker().clone(), delay);
+                }
+                Poll::Pending
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{Awaitable, AwaitableExt, OneOf, Semaphore};
+    use rsevents::{EventState, ManualResetEvent};
+    #[cfg(feature = "async")]
+    use std::future::Future;
+    use std::thread;
+    use std::time::Duration;
+
+    #[test]
+    fn any_of_reports_which() {
+        let either = ManualResetEvent::new(EventState::Unset).or(Semaphore::new(0, 1));
+        assert!(either.try_wait_for(Duration::from_millis(10)).is_err());
+
+        thread::scope(|scope| {
+            let waiter = scope.spawn(|| matches!(either.wait(), OneOf::Second(_)));
+            thread::sleep(Duration::from_millis(10));
+            either.second().release(1);
+            assert!(waiter.join().unwrap());
+        });
+
+        either.first().set();
+        assert!(matches!(either.wait(), OneOf::First(())));
+    }
+
+    #[test]
+    fn any_of_woken_by_subscriptions() {
+        use crate::CountdownEvent;
+
+        let either = Semaphore::new(0, 1).or_subscribed(CountdownEvent::new(1));
+        assert!(either.try_wait_for(Duration::from_millis(10)).is_err());
+
+        thread::scope(|scope| {
+            let waiter = scope.spawn(|| matches!(either.wait(), OneOf::Second(())));
+            thread::sleep(Duration::from_millis(10));
+            either.second().tick();
+            assert!(waiter.join().unwrap());
+        });
+    }
+
+    #[test]
+    fn all_of_holds_nothing_while_blocked() {
+        let both = Semaphore::new(1, 1).and(Semaphore::new(0, 1));
+        assert!(both.try_wait_for(Duration::from_millis(10)).is_err());
+        assert_eq!(both.first().count(), 1);
+
+        thread::scope(|scope| {
+            let waiter = scope.spawn(|| drop(both.wait()));
+            thread::sleep(Duration::from_millis(10));
+            drop(both.first().wait_for(Duration::from_secs(1)).unwrap());
+            both.second().release(1);
+            waiter.join().unwrap();
+        });
+        assert_eq!((both.first().count(), both.second().count()), (1, 1));
+    }
+
+    #[cfg(feature = "async")]
+    #[test]
+    fn future_wakes_when_ready() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        use std::sync::Arc;
+        use std::task::{Context, Poll, Wake};
+
+        struct Unparker(thread::Thread, AtomicUsize);
+        impl Wake for Unparker {
+            fn wake(self: Arc<Self>) {
+                self.1.fetch_add(1, Ordering::SeqCst);
+                self.0.unpark();
+            }
+        }
+
+        let sem = Semaphore::new(0, 1);
+        let unparker = Arc::new(Unparker(thread::current(), AtomicUsize::new(0)));
+        let waker = unparker.clone().into();
+        let mut context = Context::from_waker(&waker);
+        let mut future = std::pin::pin!(sem.into_future());
+        assert!(future.as_mut().poll(&mut context).is_pending());
+
+        thread::scope(|scope| {
+            scope.spawn(|| sem.release(1));
+            let guard = loop {
+                match future.as_mut().poll(&mut context) {
+                    Poll::Ready(guard) => break guard,
+                    Poll::Pending => thread::park(),
+                }
+            };
+            assert_eq!(sem.count(), 0);
+            drop(guard);
+        });
+        assert!(unparker.1.load(Ordering::SeqCst) > 0);
+    }
+
+    #[cfg(feature = "async")]
+    #[test]
+    fn future_is_woken_by_subscription() {
+        use crate::CountdownEvent;
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        use std::sync::Arc;
+        use std::task::{Context, Wake};
+
+        struct CountingWaker(AtomicUsize);
+        impl Wake for CountingWaker {
+            fn wake(self: Arc<Self>) {
+                self.0.fetch_add(1, Ordering::SeqCst);
+            }
+        }
+
+        let countdown = CountdownEvent::new(1);
+        let wakes = Arc::new(CountingWaker(AtomicUsize::new(0)));
+        let waker = wakes.clone().into();
+        let mut context = Context::from_waker(&waker);
+        let mut future = std::pin::pin!(countdown.into_subscribed_future());
+        assert!(future.as_mut().poll(&mut context).is_pending());
+
+        // No timer retries the future while it is subscribed...
+        thread::sleep(Duration::from_millis(20));
+        assert_eq!(wakes.0.load(Ordering::SeqCst), 0);
+
+        // ...as the countdown wakes it itself.
+        countdown.tick();
+        assert_eq!(wakes.0.load(Ordering::SeqCst), 1);
+        assert!(future.as_mut().poll(&mut context).is_ready());
+    }
+}
@@ -0,0 +1,29 @@
+use std::time::Duration;
+
+/// An exponential backoff for polling [`Awaitable`](rsevents::Awaitable) types that can't wake us
+/// directly (e.g. to observe a cancellation token while blocked on something else).
+///
+/// The first few intervals are short enough to react promptly to an event that fires soon after
+/// the wait begins, after which the interval is capped so that a long wait doesn't degrade into
+/// polling with a large latency.
+pub(crate) struct Backoff {
+    next: Duration,
+}
+
+impl Backoff {
+    const INITIAL: Duration = Duration::from_micros(50);
+    const MAX: Duration = Duration::from_millis(5);
+
+    pub(crate) const fn new() -> Self {
+        Backoff {
+            next: Self::INITIAL,
+        }
+    }
+
+    /// Returns the next interval to wait for, doubling the interval after that up to the cap.
+    pub(crate) fn next(&mut self) -> Duration {
+        let next = self.next;
+        self.next = (next * 2).min(Self::MAX);
+        next
+    }
+}
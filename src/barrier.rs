@@ -0,0 +1,170 @@
+use crate::waiter_queue::{Waiter, WaiterQueue};
+use rsevents::TimeoutError;
+use std::sync::Mutex;
+use std::time::Duration;
+
+/// A reusable barrier, releasing threads in groups of a fixed size.
+///
+/// Each call to [`Barrier::wait()`] blocks until the number of threads passed to
+/// [`Barrier::new()`] have called it, at which point they are all released together and the
+/// barrier re-arms itself for the next round. As with [`std::sync::Barrier`], a single thread of
+/// each round is told it is the round's leader, e.g. to carry out some work between rounds.
+///
+/// Unlike `std::sync::Barrier`, a `Barrier` can be created in a `const` context and declared as a
+/// `static`, and supports time-bounded waits via [`Barrier::wait_for()`].
+///
+/// ## Example:
+///
+/// ```rust
+/// use rsevents_extra::Barrier;
+/// use std::sync::atomic::{AtomicUsize, Ordering};
+///
+/// static STEP: Barrier = Barrier::new(4);
+/// static LEADERS: AtomicUsize = AtomicUsize::new(0);
+///
+/// std::thread::scope(|scope| {
+///     for _ in 0..4 {
+///         scope.spawn(|| {
+///             for _ in 0..3 {
+///                 // <compute this step of the simulation here>
+///                 if STEP.wait() {
+///                     LEADERS.fetch_add(1, Ordering::Relaxed);
+///                 }
+///             }
+///         });
+///     }
+/// });
+///
+/// // Exactly one thread led each of the three rounds.
+/// assert_eq!(LEADERS.load(Ordering::Relaxed), 3);
+/// ```
+pub struct Barrier {
+    parties: usize,
+    state: Mutex<BarrierState>,
+}
+
+struct BarrierState {
+    /// The number of threads that have arrived in the current round.
+    arrived: usize,
+    /// The threads of the current round waiting for the rest to arrive.
+    waiters: WaiterQueue,
+}
+
+impl Barrier {
+    /// Creates a new barrier that releases waiting threads in groups of `parties`. A barrier of
+    /// zero parties behaves like a barrier of one, never blocking.
+    ///
+    /// This is a `const` function and can be used in a `static` context.
+    pub const fn new(parties: usize) -> Self {
+        Barrier {
+            parties,
+            state: Mutex::new(BarrierState {
+                arrived: 0,
+                waiters: WaiterQueue::new(),
+            }),
+        }
+    }
+
+    /// Returns the number of threads released together by the barrier.
+    pub fn parties(&self) -> usize {
+        self.parties
+    }
+
+    /// Blocks until all parties have arrived at the barrier, returning `true` for exactly one
+    /// thread (the leader) of each round and `false` for all others.
+    pub fn wait(&self) -> bool {
+        match self.arrive() {
+            Some(waiter) => {
+                waiter.wait();
+                false
+            }
+            None => true,
+        }
+    }
+
+    /// Blocks for up to `limit` for all parties to arrive at the barrier, returning whether this
+    /// thread is the leader of its round (see [`wait()`](Self::wait)) or an error in case of a
+    /// timeout. A thread that times out is withdrawn from the round, which still needs the full
+    /// number of parties to arrive.
+    pub fn wait_for(&self, limit: Duration) -> Result<bool, TimeoutError> {
+        let waiter = match self.arrive() {
+            Some(waiter) => waiter,
+            None => return Ok(true),
+        };
+        let released = waiter.wait_for(limit, |waiter| {
+            let mut state = self.state.lock().unwrap();
+            let withdrawn = state.waiters.withdraw(waiter);
+            if withdrawn {
+                state.arrived -= 1;
+            }
+            withdrawn
+        });
+        match released {
+            true => Ok(false),
+            false => Err(TimeoutError),
+        }
+    }
+
+    /// Registers the calling thread's arrival, returning its place among the round's waiters or
+    /// `None` if this thread completed the round (and so is its leader).
+    fn arrive(&self) -> Option<Waiter> {
+        let mut state = self.state.lock().unwrap();
+        state.arrived += 1;
+        if state.arrived < self.parties {
+            return Some(state.waiters.push());
+        }
+
+        state.arrived = 0;
+        state.waiters.release_all();
+        None
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::Barrier;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::thread;
+    use std::time::Duration;
+
+    #[test]
+    fn rounds_and_leaders() {
+        let barrier = Barrier::new(3);
+        let leaders = AtomicUsize::new(0);
+        let arrived = AtomicUsize::new(0);
+
+        thread::scope(|scope| {
+            for _ in 0..3 {
+                scope.spawn(|| {
+                    for round in 0..50 {
+                        arrived.fetch_add(1, Ordering::SeqCst);
+                        if barrier.wait() {
+                            leaders.fetch_add(1, Ordering::SeqCst);
+                        }
+                        // Nobody can pass the barrier before everyone has arrived.
+                        assert!(arrived.load(Ordering::SeqCst) >= (round + 1) * 3);
+                    }
+                });
+            }
+        });
+
+        assert_eq!(leaders.load(Ordering::SeqCst), 50);
+    }
+
+    #[test]
+    fn timed_out_waiter_withdraws() {
+        let barrier = Barrier::new(2);
+        assert!(barrier.wait_for(Duration::from_millis(10)).is_err());
+
+        thread::scope(|scope| {
+            let other = scope.spawn(|| barrier.wait());
+            // Had the timed out waiter still been counted, this would release the round early.
+            thread::sleep(Duration::from_millis(10));
+            assert!(!other.is_finished());
+            let leader = barrier.wait();
+            assert!(leader != other.join().unwrap());
+        });
+
+        assert!(Barrier::new(0).wait());
+    }
+}
@@ -0,0 +1,168 @@
+use crate::sync::loom_const_fn;
+use crate::Semaphore;
+use rsevents::TimeoutError;
+use std::collections::VecDeque;
+use std::sync::Mutex;
+use std::time::Duration;
+
+/// A fixed-capacity, blocking, multi-producer/multi-consumer FIFO queue.
+///
+/// `BoundedQueue` is the textbook bounded buffer built from a pair of counting semaphores: one
+/// tracking the free slots in the queue (which producers wait on) and one tracking the filled
+/// slots (which consumers wait on). Pushing to a full queue blocks until a value has been popped,
+/// and popping from an empty queue blocks until a value has been pushed.
+///
+/// ## Example:
+///
+/// ```rust
+/// use rsevents_extra::BoundedQueue;
+///
+/// // Never buffer more than 8 pending jobs.
+/// static JOBS: BoundedQueue<u32> = BoundedQueue::new(8);
+///
+/// std::thread::scope(|scope| {
+///     scope.spawn(|| {
+///         for job in 0..100 {
+///             JOBS.push(job);
+///         }
+///     });
+///
+///     let sum: u32 = (0..100).map(|_| JOBS.pop()).sum();
+///     assert_eq!(sum, 4950);
+/// });
+/// ```
+pub struct BoundedQueue<T> {
+    /// The number of free slots, taken by producers and returned by consumers.
+    empty: Semaphore,
+    /// The number of filled slots, taken by consumers and returned by producers.
+    filled: Semaphore,
+    values: Mutex<VecDeque<T>>,
+}
+
+impl<T> BoundedQueue<T> {
+    loom_const_fn! {
+        /// Creates a new, empty queue that can hold up to `capacity` values.
+        pub fn new(capacity: u16) -> Self {
+            BoundedQueue {
+                empty: Semaphore::new(capacity, capacity),
+                filled: Semaphore::new(0, capacity),
+                values: Mutex::new(VecDeque::new()),
+            }
+        }
+    }
+
+    /// Adds `value` to the back of the queue, blocking until there is room for it.
+    pub fn push(&self, value: T) {
+        self.empty.wait().forget();
+        self.push_internal(value);
+    }
+
+    /// Attempts to add `value` to the back of the queue, handing it back as `Err(value)` if there
+    /// is no room for it within the time limit.
+    pub fn push_for(&self, value: T, limit: Duration) -> Result<(), T> {
+        match self.empty.wait_for(limit) {
+            Ok(slot) => slot.forget(),
+            Err(TimeoutError) => return Err(value),
+        }
+        self.push_internal(value);
+        Ok(())
+    }
+
+    /// Attempts to add `value` to the back of the queue without blocking, handing it back as
+    /// `Err(value)` if the queue is full.
+    pub fn try_push(&self, value: T) -> Result<(), T> {
+        self.push_for(value, Duration::ZERO)
+    }
+
+    fn push_internal(&self, value: T) {
+        self.values.lock().unwrap().push_back(value);
+        self.filled.release(1);
+    }
+
+    /// Removes the value at the front of the queue, blocking until there is one.
+    pub fn pop(&self) -> T {
+        self.filled.wait().forget();
+        self.pop_internal()
+    }
+
+    /// Attempts to remove the value at the front of the queue, returning a [`TimeoutError`] if the
+    /// queue remains empty for the duration of the time limit.
+    pub fn pop_for(&self, limit: Duration) -> Result<T, TimeoutError> {
+        self.filled.wait_for(limit)?.forget();
+        Ok(self.pop_internal())
+    }
+
+    /// Attempts to remove the value at the front of the queue without blocking, returning a
+    /// [`TimeoutError`] if the queue is empty.
+    pub fn try_pop(&self) -> Result<T, TimeoutError> {
+        self.pop_for(Duration::ZERO)
+    }
+
+    fn pop_internal(&self) -> T {
+        // Holding a filled slot guarantees there's a value for us.
+        let value = self.values.lock().unwrap().pop_front().unwrap();
+        self.empty.release(1);
+        value
+    }
+
+    /// Returns the number of values currently in the queue.
+    ///
+    /// Note that this may race with other calls such as `push()` or `pop()`.
+    pub fn len(&self) -> usize {
+        self.values.lock().unwrap().len()
+    }
+
+    /// Returns whether the queue is currently empty.
+    ///
+    /// Note that this may race with other calls such as `push()` or `pop()`.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::BoundedQueue;
+    use std::thread;
+    use std::time::Duration;
+
+    #[test]
+    fn full_and_empty() {
+        let queue = BoundedQueue::new(2);
+        queue.try_pop().unwrap_err();
+        queue.push(1);
+        queue.try_push(2).unwrap();
+        assert_eq!(queue.push_for(3, Duration::from_millis(10)), Err(3));
+        assert_eq!(queue.len(), 2);
+
+        assert_eq!(queue.pop(), 1);
+        queue.try_push(3).unwrap();
+        assert_eq!(queue.pop(), 2);
+        assert_eq!(queue.try_pop().unwrap(), 3);
+        assert!(queue.is_empty());
+    }
+
+    #[test]
+    fn multiple_producers_and_consumers() {
+        let queue = BoundedQueue::new(4);
+
+        let sum: u64 = thread::scope(|scope| {
+            for p in 0..4 {
+                let queue = &queue;
+                scope.spawn(move || {
+                    for i in 0..250 {
+                        queue.push(p * 250 + i);
+                    }
+                });
+            }
+
+            let consumers: Vec<_> = (0..4)
+                .map(|_| scope.spawn(|| (0..250).map(|_| queue.pop()).sum::<u64>()))
+                .collect();
+            consumers.into_iter().map(|c| c.join().unwrap()).sum()
+        });
+
+        assert_eq!(sum, (0..1000).sum());
+        assert!(queue.is_empty());
+    }
+}
@@ -0,0 +1,154 @@
+use crate::sync::loom_const_fn;
+use crate::Semaphore;
+use rsevents::TimeoutError;
+use std::sync::Mutex;
+use std::time::Duration;
+
+/// A fixed-capacity, blocking, multi-producer/multi-consumer LIFO stack.
+///
+/// `BoundedStack` is the last-in, first-out counterpart of [`BoundedQueue`](crate::BoundedQueue),
+/// built from the same pair of counting semaphores. Popping always returns the most recently
+/// pushed value, which is the one most likely to still be in the CPU cache: this makes it the
+/// better choice for pools of reusable buffers, where a queue would cycle through every buffer
+/// in turn.
+///
+/// ## Example:
+///
+/// ```rust
+/// use rsevents_extra::BoundedStack;
+///
+/// // A pool of at most 4 reusable buffers.
+/// static BUFFERS: BoundedStack<Vec<u8>> = BoundedStack::new(4);
+///
+/// for _ in 0..4 {
+///     BUFFERS.push(Vec::with_capacity(4096));
+/// }
+///
+/// let mut buffer = BUFFERS.pop();
+/// // <fill and send the buffer here>
+/// buffer.clear();
+/// BUFFERS.push(buffer);
+/// ```
+pub struct BoundedStack<T> {
+    /// The number of free slots, taken by producers and returned by consumers.
+    empty: Semaphore,
+    /// The number of filled slots, taken by consumers and returned by producers.
+    filled: Semaphore,
+    values: Mutex<Vec<T>>,
+}
+
+impl<T> BoundedStack<T> {
+    loom_const_fn! {
+        /// Creates a new, empty stack that can hold up to `capacity` values.
+        pub fn new(capacity: u16) -> Self {
+            BoundedStack {
+                empty: Semaphore::new(capacity, capacity),
+                filled: Semaphore::new(0, capacity),
+                values: Mutex::new(Vec::new()),
+            }
+        }
+    }
+
+    /// Pushes `value` onto the top of the stack, blocking until there is room for it.
+    pub fn push(&self, value: T) {
+        self.empty.wait().forget();
+        self.push_internal(value);
+    }
+
+    /// Attempts to push `value` onto the top of the stack, handing it back as `Err(value)` if
+    /// there is no room for it within the time limit.
+    pub fn push_for(&self, value: T, limit: Duration) -> Result<(), T> {
+        match self.empty.wait_for(limit) {
+            Ok(slot) => slot.forget(),
+            Err(TimeoutError) => return Err(value),
+        }
+        self.push_internal(value);
+        Ok(())
+    }
+
+    /// Attempts to push `value` onto the top of the stack without blocking, handing it back as
+    /// `Err(value)` if the stack is full.
+    pub fn try_push(&self, value: T) -> Result<(), T> {
+        self.push_for(value, Duration::ZERO)
+    }
+
+    fn push_internal(&self, value: T) {
+        self.values.lock().unwrap().push(value);
+        self.filled.release(1);
+    }
+
+    /// Removes the value at the top of the stack, blocking until there is one.
+    pub fn pop(&self) -> T {
+        self.filled.wait().forget();
+        self.pop_internal()
+    }
+
+    /// Attempts to remove the value at the top of the stack, returning a [`TimeoutError`] if the
+    /// stack remains empty for the duration of the time limit.
+    pub fn pop_for(&self, limit: Duration) -> Result<T, TimeoutError> {
+        self.filled.wait_for(limit)?.forget();
+        Ok(self.pop_internal())
+    }
+
+    /// Attempts to remove the value at the top of the stack without blocking, returning a
+    /// [`TimeoutError`] if the stack is empty.
+    pub fn try_pop(&self) -> Result<T, TimeoutError> {
+        self.pop_for(Duration::ZERO)
+    }
+
+    fn pop_internal(&self) -> T {
+        // Holding a filled slot guarantees there's a value for us.
+        let value = self.values.lock().unwrap().pop().unwrap();
+        self.empty.release(1);
+        value
+    }
+
+    /// Returns the number of values currently on the stack.
+    ///
+    /// Note that this may race with other calls such as `push()` or `pop()`.
+    pub fn len(&self) -> usize {
+        self.values.lock().unwrap().len()
+    }
+
+    /// Returns whether the stack is currently empty.
+    ///
+    /// Note that this may race with other calls such as `push()` or `pop()`.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::BoundedStack;
+    use std::thread;
+    use std::time::Duration;
+
+    #[test]
+    fn last_in_first_out() {
+        let stack = BoundedStack::new(2);
+        stack.try_pop().unwrap_err();
+        stack.push(1);
+        stack.try_push(2).unwrap();
+        assert_eq!(stack.push_for(3, Duration::from_millis(10)), Err(3));
+        assert_eq!(stack.len(), 2);
+
+        assert_eq!(stack.pop(), 2);
+        stack.try_push(3).unwrap();
+        assert_eq!(stack.pop(), 3);
+        assert_eq!(stack.try_pop().unwrap(), 1);
+        assert!(stack.is_empty());
+    }
+
+    #[test]
+    fn blocks_until_pushed() {
+        let stack = BoundedStack::new(1);
+
+        thread::scope(|scope| {
+            let consumer = scope.spawn(|| stack.pop());
+            thread::sleep(Duration::from_millis(10));
+            stack.push(7);
+            assert_eq!(consumer.join().unwrap(), 7);
+        });
+    }
+}
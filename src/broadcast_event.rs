@@ -0,0 +1,213 @@
+use crate::waiter_queue::WaiterQueue;
+use rsevents::{Awaitable, TimeoutError};
+use std::convert::Infallible;
+use std::sync::Mutex;
+use std::time::Duration;
+
+/// A broadcast event that counts its signals, so that each waiter is released by exactly those
+/// signals that are newer than the last one it saw.
+///
+/// Reusing a [`ManualResetEvent`](rsevents::ManualResetEvent) for repeated broadcasts is prone to
+/// missed wakeups (a waiter that starts waiting just after the event is reset misses the signal it
+/// meant to wait for) and double wakeups (a waiter that comes back before the event is reset sees
+/// the same signal twice). A `BroadcastEvent` avoids both by numbering its signals: a waiter
+/// names the last generation it has seen (or holds a [`BroadcastSubscription`] that tracks it),
+/// and waits for a newer one.
+///
+/// ## Example:
+///
+/// ```rust
+/// use rsevents_extra::BroadcastEvent;
+///
+/// static CONFIG_CHANGED: BroadcastEvent = BroadcastEvent::new();
+///
+/// std::thread::scope(|scope| {
+///     let mut subscription = CONFIG_CHANGED.subscribe();
+///     let listener = scope.spawn(move || {
+///         // Sees the signal even if it was sent before we started waiting.
+///         subscription.wait()
+///     });
+///
+///     assert_eq!(CONFIG_CHANGED.signal(), 1);
+///     assert_eq!(listener.join().unwrap(), 1);
+/// });
+/// ```
+pub struct BroadcastEvent {
+    state: Mutex<BroadcastState>,
+}
+
+struct BroadcastState {
+    /// The number of signals sent so far.
+    generation: usize,
+    /// The threads waiting for the next signal.
+    waiters: WaiterQueue,
+}
+
+impl BroadcastEvent {
+    /// Creates a new `BroadcastEvent` at generation zero.
+    ///
+    /// This is a `const` function and can be used in a `static` context.
+    pub const fn new() -> Self {
+        BroadcastEvent {
+            state: Mutex::new(BroadcastState {
+                generation: 0,
+                waiters: WaiterQueue::new(),
+            }),
+        }
+    }
+
+    /// Returns the current generation, i.e. the number of signals sent so far.
+    pub fn generation(&self) -> usize {
+        self.state.lock().unwrap().generation
+    }
+
+    /// Sends a signal, releasing all threads waiting for a generation newer than the previous one.
+    /// Returns the new generation.
+    pub fn signal(&self) -> usize {
+        let mut state = self.state.lock().unwrap();
+        state.generation = state.generation.wrapping_add(1);
+        state.waiters.release_all();
+        state.generation
+    }
+
+    /// Returns a subscription that waits for signals sent after this call.
+    pub fn subscribe(&self) -> BroadcastSubscription<'_> {
+        BroadcastSubscription {
+            event: self,
+            seen: self.generation(),
+        }
+    }
+
+    /// Waits for a signal newer than `generation`, returning immediately if one has already been
+    /// sent. Returns the current generation.
+    pub fn wait_since(&self, generation: usize) -> usize {
+        match self.wait_since_inner(generation, None) {
+            Ok(generation) => generation,
+            Err(_) => unreachable!(),
+        }
+    }
+
+    /// Waits for up to `limit` for a signal newer than `generation`, returning immediately if one
+    /// has already been sent. Returns the current generation or an error in case of a timeout.
+    pub fn wait_since_for(
+        &self,
+        generation: usize,
+        limit: Duration,
+    ) -> Result<usize, TimeoutError> {
+        self.wait_since_inner(generation, Some(limit))
+    }
+
+    fn wait_since_inner(
+        &self,
+        generation: usize,
+        limit: Option<Duration>,
+    ) -> Result<usize, TimeoutError> {
+        let mut state = self.state.lock().unwrap();
+        if state.generation != generation {
+            return Ok(state.generation);
+        }
+        let waiter = state.waiters.push();
+        drop(state);
+
+        match limit {
+            None => waiter.wait(),
+            Some(limit) => {
+                let signalled = waiter.wait_for(limit, |waiter| {
+                    self.state.lock().unwrap().waiters.withdraw(waiter)
+                });
+                if !signalled {
+                    return Err(TimeoutError);
+                }
+            }
+        }
+        Ok(self.generation())
+    }
+}
+
+impl Default for BroadcastEvent {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Awaitable<'_> for BroadcastEvent {
+    type T = usize;
+    type Error = TimeoutError;
+
+    /// Waits for the next signal, returning its generation.
+    fn try_wait(&self) -> Result<usize, Infallible> {
+        Ok(self.wait_since(self.generation()))
+    }
+
+    /// Waits for up to `limit` for the next signal, returning its generation or an error in case
+    /// of a timeout.
+    fn try_wait_for(&self, limit: Duration) -> Result<usize, TimeoutError> {
+        self.wait_since_for(self.generation(), limit)
+    }
+
+    /// Always fails, as only signals sent after a wait begins release it.
+    fn try_wait0(&self) -> Result<usize, TimeoutError> {
+        Err(TimeoutError)
+    }
+}
+
+/// A subscription to a [`BroadcastEvent`], returned by [`BroadcastEvent::subscribe()`], which
+/// remembers the last generation it has seen.
+///
+/// Each wait returns once a signal newer than the last one seen has been sent, whether it was sent
+/// before or during the wait, so no signal is missed and none is seen twice. Several signals sent
+/// between waits are observed together.
+pub struct BroadcastSubscription<'a> {
+    event: &'a BroadcastEvent,
+    seen: usize,
+}
+
+impl BroadcastSubscription<'_> {
+    /// Waits for a signal newer than the last one seen, returning the current generation.
+    pub fn wait(&mut self) -> usize {
+        self.seen = self.event.wait_since(self.seen);
+        self.seen
+    }
+
+    /// Waits for up to `limit` for a signal newer than the last one seen, returning the current
+    /// generation or an error in case of a timeout.
+    pub fn wait_for(&mut self, limit: Duration) -> Result<usize, TimeoutError> {
+        self.seen = self.event.wait_since_for(self.seen, limit)?;
+        Ok(self.seen)
+    }
+
+    /// Returns whether a signal newer than the last one seen has been sent, without blocking or
+    /// marking it as seen.
+    pub fn is_pending(&self) -> bool {
+        self.event.generation() != self.seen
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::BroadcastEvent;
+    use std::thread;
+    use std::time::Duration;
+
+    #[test]
+    fn no_missed_or_double_wakeups() {
+        let event = BroadcastEvent::new();
+        let mut subscription = event.subscribe();
+        assert!(subscription.wait_for(Duration::from_millis(1)).is_err());
+
+        event.signal();
+        event.signal();
+        assert!(subscription.is_pending());
+        assert_eq!(subscription.wait(), 2);
+        assert!(!subscription.is_pending());
+        assert!(subscription.wait_for(Duration::from_millis(1)).is_err());
+
+        thread::scope(|scope| {
+            let waiter = scope.spawn(|| event.wait_since(2));
+            thread::sleep(Duration::from_millis(10));
+            assert!(!waiter.is_finished());
+            assert_eq!(event.signal(), 3);
+            assert_eq!(waiter.join().unwrap(), 3);
+        });
+    }
+}
@@ -0,0 +1,214 @@
+use crate::clock::{self, Clock, SystemClock};
+use rsevents::{Awaitable, TimeoutError};
+use std::convert::Infallible;
+use std::sync::{Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+
+/// A quota of `amount` units that is replenished to its cap once per `refill_interval`, modelling
+/// the fixed-window quotas (e.g. 10,000 API calls per day) enforced by many services.
+///
+/// Unlike a [`RateLimiter`](crate::RateLimiter), which refills continuously at a steady rate, a
+/// `Budget` is topped back up all at once at the start of each window, and any units left unspent
+/// at the end of a window are lost rather than carried over. The first window starts the first
+/// time the budget is used. [`Budget::spend()`] blocks until the budget can cover the requested
+/// amount, waiting for the next window if need be; waiting on the budget via the [`Awaitable`] API
+/// spends a single unit.
+///
+/// ## Example:
+///
+/// ```rust
+/// use rsevents_extra::Budget;
+/// use std::time::Duration;
+///
+/// // The geocoding API allows 2,500 requests per day.
+/// static GEOCODING: Budget = Budget::new(2_500, Duration::from_secs(24 * 60 * 60));
+///
+/// for _ in 0..10 {
+///     GEOCODING.spend(1);
+///     // <geocode the next address here>
+/// }
+/// assert_eq!(GEOCODING.remaining(), 2_490);
+/// ```
+pub struct Budget {
+    amount: u32,
+    refill_interval: Duration,
+    state: Mutex<BudgetState>,
+    clock: &'static dyn Clock,
+}
+
+struct BudgetState {
+    remaining: u32,
+    /// The start of the current window, or `None` if the budget hasn't been used yet.
+    window_start: Option<Instant>,
+}
+
+impl Budget {
+    /// Creates a new `Budget` of `amount` units, replenished to `amount` once per
+    /// `refill_interval`.
+    ///
+    /// Panics if `refill_interval` is zero.
+    ///
+    /// This is a `const` function and can be used in a `static` context.
+    pub const fn new(amount: u32, refill_interval: Duration) -> Self {
+        assert!(
+            !refill_interval.is_zero(),
+            "Budget refill interval must be non-zero!"
+        );
+        Budget {
+            amount,
+            refill_interval,
+            state: Mutex::new(BudgetState {
+                remaining: amount,
+                window_start: None,
+            }),
+            clock: &SystemClock,
+        }
+    }
+
+    /// Replaces the [`SystemClock`] used to schedule refills and time waits with `clock`. This is
+    /// primarily useful in tests, where a [`MockClock`](crate::MockClock) makes the refill
+    /// schedule deterministic.
+    pub const fn with_clock(mut self, clock: &'static dyn Clock) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Spends `n` units, blocking until the budget can cover them.
+    ///
+    /// Panics if `n` exceeds the budget's cap, as it could never cover that many.
+    pub fn spend(&self, n: u32) {
+        match self.spend_until(n, None) {
+            Ok(()) => {}
+            Err(_) => unreachable!(),
+        }
+    }
+
+    /// Spends `n` units, blocking for up to `limit` until the budget can cover them. Returns an
+    /// error without spending anything (and without waiting) if the budget won't be replenished
+    /// within the time limit.
+    ///
+    /// Panics if `n` exceeds the budget's cap, as it could never cover that many.
+    pub fn spend_for(&self, n: u32, limit: Duration) -> Result<(), TimeoutError> {
+        self.spend_until(n, Some(self.clock.now() + limit))
+    }
+
+    /// Spends `n` units if the budget can cover them right away, returning whether it could.
+    ///
+    /// Panics if `n` exceeds the budget's cap, as it could never cover that many.
+    pub fn try_spend(&self, n: u32) -> bool {
+        self.spend_until(n, Some(self.clock.now())).is_ok()
+    }
+
+    /// Returns the number of units left in the current window.
+    pub fn remaining(&self) -> u32 {
+        self.lock(self.clock.now()).remaining
+    }
+
+    /// Returns the time until the budget is next replenished, or `None` if the first window
+    /// hasn't started yet.
+    pub fn refills_in(&self) -> Option<Duration> {
+        let now = self.clock.now();
+        let state = self.lock(now);
+        let refill_at = state.window_start? + self.refill_interval;
+        Some(refill_at.saturating_duration_since(now))
+    }
+
+    fn spend_until(&self, n: u32, deadline: Option<Instant>) -> Result<(), TimeoutError> {
+        assert!(n <= self.amount, "Budget spend exceeds its cap!");
+
+        loop {
+            let now = self.clock.now();
+            let refill_at = {
+                let mut state = self.lock(now);
+                let window_start = *state.window_start.get_or_insert(now);
+                if state.remaining >= n {
+                    state.remaining -= n;
+                    return Ok(());
+                }
+                window_start + self.refill_interval
+            };
+
+            if matches!(deadline, Some(deadline) if refill_at > deadline) {
+                return Err(TimeoutError);
+            }
+            clock::sleep(self.clock, refill_at.saturating_duration_since(now));
+        }
+    }
+
+    /// Locks the state, first moving on to a new window (and replenishing the budget) if the
+    /// current one has ended by `now`.
+    fn lock(&self, now: Instant) -> MutexGuard<'_, BudgetState> {
+        let mut state = self.state.lock().unwrap();
+        if let Some(start) = state.window_start {
+            let elapsed = now.saturating_duration_since(start);
+            let windows = elapsed.as_nanos() / self.refill_interval.as_nanos();
+            if windows > 0 {
+                // Windows stay aligned to the first one, however long the budget sat unused.
+                let skipped = self.refill_interval.as_nanos() * windows;
+                state.window_start = Some(start + Duration::from_nanos(skipped as u64));
+                state.remaining = self.amount;
+            }
+        }
+        state
+    }
+}
+
+impl Awaitable<'_> for Budget {
+    type T = ();
+    type Error = TimeoutError;
+
+    /// Spends a single unit, blocking until the budget can cover it.
+    fn try_wait(&self) -> Result<(), Infallible> {
+        self.spend(1);
+        Ok(())
+    }
+
+    /// Spends a single unit, blocking for up to `limit` until the budget can cover it.
+    fn try_wait_for(&self, limit: Duration) -> Result<(), TimeoutError> {
+        self.spend_for(1, limit)
+    }
+
+    /// Spends a single unit if the budget can cover it right away.
+    fn try_wait0(&self) -> Result<(), TimeoutError> {
+        match self.try_spend(1) {
+            true => Ok(()),
+            false => Err(TimeoutError),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{Budget, MockClock};
+    use std::thread;
+    use std::time::Duration;
+
+    #[test]
+    fn replenishes_each_window() {
+        static CLOCK: MockClock = MockClock::new();
+        let budget = Budget::new(5, Duration::from_secs(60)).with_clock(&CLOCK);
+        assert_eq!(budget.refills_in(), None);
+
+        assert!(budget.try_spend(3));
+        assert!(!budget.try_spend(3));
+        assert_eq!(budget.remaining(), 2);
+        assert!(budget.spend_for(3, Duration::from_secs(30)).is_err());
+
+        // Unspent units are not carried over into the next window.
+        CLOCK.advance(Duration::from_secs(90));
+        assert_eq!(budget.remaining(), 5);
+        assert_eq!(budget.refills_in(), Some(Duration::from_secs(30)));
+        budget.spend(5);
+
+        thread::scope(|scope| {
+            let waiter = scope.spawn(|| budget.spend(4));
+            thread::sleep(Duration::from_millis(10));
+            assert!(!waiter.is_finished());
+            while !waiter.is_finished() {
+                CLOCK.advance(Duration::from_secs(10));
+                thread::sleep(Duration::from_millis(1));
+            }
+        });
+        assert_eq!(budget.remaining(), 1);
+    }
+}
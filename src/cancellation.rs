@@ -0,0 +1,158 @@
+use crate::subscribe::{Subscribe, Subscription};
+use crate::waker_list::WakerList;
+use rsevents::{Awaitable, EventState, ManualResetEvent, TimeoutError};
+use std::convert::Infallible;
+use std::sync::Arc;
+use std::task::Waker;
+use std::time::Duration;
+
+/// The controlling side of a cancellation pair, which hands out [`CancellationToken`]s and
+/// cancels them all at once with [`CancellationTokenSource::cancel()`].
+///
+/// ## Example:
+///
+/// ```rust
+/// use rsevents_extra::{AcquireError, CancellationTokenSource, Semaphore};
+///
+/// let shutdown = CancellationTokenSource::new();
+/// let jobs = Semaphore::new(0, 16);
+///
+/// std::thread::scope(|scope| {
+///     let token = shutdown.token();
+///     let worker = scope.spawn(move || loop {
+///         // Wait for the next job, unless we are shutting down.
+///         match jobs.wait_cancellable(&token) {
+///             Ok(job) => job.forget(),
+///             Err(AcquireError::Cancelled) => break,
+///             Err(error) => panic!("{}", error),
+///         }
+///     });
+///
+///     shutdown.cancel();
+///     worker.join().unwrap();
+/// });
+/// ```
+pub struct CancellationTokenSource {
+    shared: Arc<Shared>,
+}
+
+/// A cheaply cloneable handle for observing the cancellation of a [`CancellationTokenSource`].
+///
+/// Tokens implement [`Awaitable`], becoming set once cancelled, so they can be waited on directly
+/// or passed to the cancellable waits of the crate's other primitives (such as
+/// [`Semaphore::wait_cancellable()`](crate::Semaphore::wait_cancellable) and
+/// [`CountdownEvent::wait_cancellable()`](crate::CountdownEvent::wait_cancellable)).
+#[derive(Clone)]
+pub struct CancellationToken {
+    shared: Arc<Shared>,
+}
+
+/// The state shared between a [`CancellationTokenSource`] and its tokens.
+struct Shared {
+    /// Set once cancelled.
+    event: ManualResetEvent,
+    /// The wakers [subscribed](Subscribe) to the cancellation.
+    wakers: WakerList,
+}
+
+impl CancellationTokenSource {
+    /// Creates a new, uncancelled `CancellationTokenSource`.
+    pub fn new() -> Self {
+        CancellationTokenSource {
+            shared: Arc::new(Shared {
+                event: ManualResetEvent::new(EventState::Unset),
+                wakers: WakerList::new(),
+            }),
+        }
+    }
+
+    /// Returns a token that becomes cancelled when this source is.
+    pub fn token(&self) -> CancellationToken {
+        CancellationToken {
+            shared: Arc::clone(&self.shared),
+        }
+    }
+
+    /// Cancels all tokens handed out by this source (including any handed out afterwards),
+    /// releasing any threads waiting on them. Cancellation is permanent.
+    pub fn cancel(&self) {
+        self.shared.event.set();
+        self.shared.wakers.wake_all();
+    }
+
+    /// Returns whether the source has been cancelled.
+    pub fn is_cancelled(&self) -> bool {
+        self.shared.event.wait0()
+    }
+}
+
+impl Default for CancellationTokenSource {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CancellationToken {
+    /// Returns whether the token has been cancelled, without blocking.
+    pub fn is_cancelled(&self) -> bool {
+        self.shared.event.wait0()
+    }
+}
+
+impl Awaitable<'_> for CancellationToken {
+    type T = ();
+    type Error = TimeoutError;
+
+    /// Waits for the token to be cancelled.
+    fn try_wait(&self) -> Result<(), Infallible> {
+        self.shared.event.try_wait()
+    }
+
+    /// Waits for the token to be cancelled or returns an error in case of a timeout.
+    fn try_wait_for(&self, limit: Duration) -> Result<(), TimeoutError> {
+        self.shared.event.try_wait_for(limit)
+    }
+
+    /// Checks whether the token has been cancelled without blocking.
+    fn try_wait0(&self) -> Result<(), TimeoutError> {
+        self.shared.event.try_wait0()
+    }
+}
+
+impl Subscribe for CancellationToken {
+    fn subscribe(&self, waker: &Waker) -> Option<Subscription<'_>> {
+        Some(Subscription::new(&self.shared.wakers, waker))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{Awaitable, CancellationTokenSource};
+    use std::thread;
+    use std::time::Duration;
+
+    #[test]
+    fn cancel_releases_tokens() {
+        let source = CancellationTokenSource::new();
+        let token = source.token();
+        assert!(!token.is_cancelled());
+        assert!(!token.wait_for(Duration::from_millis(1)));
+
+        thread::scope(|scope| {
+            let waiters: Vec<_> = (0..3)
+                .map(|_| {
+                    let token = token.clone();
+                    scope.spawn(move || token.wait())
+                })
+                .collect();
+            thread::sleep(Duration::from_millis(10));
+            source.cancel();
+            for waiter in waiters {
+                waiter.join().unwrap();
+            }
+        });
+
+        assert!(source.is_cancelled());
+        assert!(source.token().is_cancelled());
+    }
+}
@@ -0,0 +1,123 @@
+use crate::subscribe::{Subscribe, Subscription};
+use crate::sync::loom_const_fn;
+use crate::{CountdownEvent, OverTickPolicy};
+use rsevents::{Awaitable, TimeoutError};
+use std::convert::Infallible;
+use std::task::Waker;
+use std::time::Duration;
+
+/// A [`CountdownEvent`] linked to a parent countdown, which it ticks once when it reaches zero,
+/// for modelling multi-stage fan-outs (e.g. _n_ shards, each made up of _m_ chunks) without
+/// needing a glue thread per stage.
+///
+/// ## Example:
+///
+/// ```rust
+/// use rsevents_extra::{Awaitable, ChildCountdown, CountdownEvent};
+///
+/// const SHARDS: usize = 4;
+/// const CHUNKS: usize = 8;
+///
+/// let all_shards = CountdownEvent::new(SHARDS);
+/// std::thread::scope(|scope| {
+///     for _ in 0..SHARDS {
+///         let shard = ChildCountdown::new(&all_shards, CHUNKS);
+///         scope.spawn(move || {
+///             for _ in 0..CHUNKS {
+///                 // <load a chunk here>
+///                 shard.tick();
+///             }
+///         });
+///     }
+///
+///     all_shards.wait();
+/// });
+/// ```
+pub struct ChildCountdown<'a> {
+    countdown: CountdownEvent,
+    parent: &'a CountdownEvent,
+}
+
+impl<'a> ChildCountdown<'a> {
+    loom_const_fn! {
+        /// Creates a new countdown with its count initialized to `count`, which ticks `parent` once
+        /// when it reaches zero.
+        ///
+        /// Unlike a regular `CountdownEvent`, a count of zero does not tick the parent, as the
+        /// parent is only ever ticked by a call to [`ChildCountdown::tick()`].
+        pub fn new(parent: &'a CountdownEvent, count: usize) -> Self {
+            ChildCountdown {
+                countdown: CountdownEvent::new(count).with_over_tick_policy(OverTickPolicy::Count),
+                parent,
+            }
+        }
+    }
+
+    /// Decrements the internal countdown, ticking the parent countdown if this brings the count
+    /// down to zero.
+    pub fn tick(&self) {
+        if self.countdown.decrement_by(1) {
+            self.parent.tick();
+        }
+    }
+
+    /// Gets the current internal countdown value.
+    pub fn count(&self) -> usize {
+        self.countdown.count()
+    }
+
+    /// Returns the parent countdown that is ticked when this countdown reaches zero.
+    pub fn parent(&self) -> &'a CountdownEvent {
+        self.parent
+    }
+}
+
+impl Awaitable<'_> for ChildCountdown<'_> {
+    type T = ();
+    type Error = TimeoutError;
+
+    /// Waits for the child's own countdown to reach zero.
+    fn try_wait(&self) -> Result<(), Infallible> {
+        self.countdown.try_wait()
+    }
+
+    /// Waits for the child's own countdown to reach zero or returns an error in case of a timeout.
+    fn try_wait_for(&self, limit: Duration) -> Result<(), TimeoutError> {
+        self.countdown.try_wait_for(limit)
+    }
+
+    /// Checks whether the child's own countdown has reached zero without blocking.
+    fn try_wait0(&self) -> Result<(), TimeoutError> {
+        self.countdown.try_wait0()
+    }
+}
+
+impl Subscribe for ChildCountdown<'_> {
+    fn subscribe(&self, waker: &Waker) -> Option<Subscription<'_>> {
+        self.countdown.subscribe(waker)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{Awaitable, ChildCountdown, CountdownEvent};
+
+    #[test]
+    fn ticks_parent_once() {
+        let parent = CountdownEvent::new(2);
+        let first = ChildCountdown::new(&parent, 2);
+        let second = ChildCountdown::new(&parent, 1);
+
+        first.tick();
+        assert_eq!(parent.count(), 2);
+        first.tick();
+        assert!(first.wait0());
+        assert_eq!(parent.count(), 1);
+        // Extra ticks of a child that has already completed don't reach the parent.
+        first.tick();
+        assert_eq!(parent.count(), 1);
+
+        second.tick();
+        assert!(parent.wait0());
+    }
+}
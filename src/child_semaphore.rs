@@ -0,0 +1,225 @@
+use crate::sync::loom_const_fn;
+use crate::{Semaphore, SemaphoreGuard};
+use rsevents::{Awaitable, TimeoutError};
+use std::convert::Infallible;
+use std::fmt::Debug;
+use std::time::{Duration, Instant};
+
+/// A [`Semaphore`] with its own local concurrency limit that also draws from the budget of a
+/// parent semaphore, for modelling nested limits such as per-tenant quotas sharing a global cap.
+///
+/// Waiting on a `ChildSemaphore` obtains a slot from the child itself and then from each of its
+/// ancestors in turn (so a thread is admitted only if both the local and the global limits allow
+/// it), and dropping the returned [`ChildSemaphoreGuard`] gives every slot back. Since slots are
+/// always obtained from the bottom of the hierarchy up, threads acquiring from different children
+/// of the same parent can't deadlock against one another.
+///
+/// ## Example:
+///
+/// ```rust
+/// use rsevents_extra::{ChildSemaphore, Semaphore};
+///
+/// // At most 8 requests may be in flight in total...
+/// static GLOBAL: Semaphore = Semaphore::new(8, 8);
+/// // ...of which no single tenant may use more than 5.
+/// static TENANT_A: ChildSemaphore = ChildSemaphore::new(&GLOBAL, 5, 5);
+/// static TENANT_B: ChildSemaphore = ChildSemaphore::new(&GLOBAL, 5, 5);
+///
+/// let _a = TENANT_A.wait();
+/// let _b = TENANT_B.wait();
+/// assert_eq!(GLOBAL.count(), 6);
+/// ```
+pub struct ChildSemaphore<'a> {
+    /// The child's own (local) concurrency limit.
+    local: Semaphore,
+    parent: Parent<'a>,
+}
+
+enum Parent<'a> {
+    Root(&'a Semaphore),
+    Child(&'a ChildSemaphore<'a>),
+}
+
+impl<'a> ChildSemaphore<'a> {
+    loom_const_fn! {
+        /// Creates a new `ChildSemaphore` drawing from `parent`, with a local maximum concurrency
+        /// count of `max_count` and an initial local concurrency count of `initial_count`.
+        pub fn new(parent: &'a Semaphore, initial_count: u16, max_count: u16) -> Self {
+            ChildSemaphore {
+                local: Semaphore::new(initial_count, max_count),
+                parent: Parent::Root(parent),
+            }
+        }
+    }
+
+    loom_const_fn! {
+        /// Creates a new `ChildSemaphore` drawing from another `ChildSemaphore` (and, in turn, from
+        /// all of its ancestors), with the specified local maximum and initial concurrency counts.
+        pub fn nested(
+            parent: &'a ChildSemaphore<'a>,
+            initial_count: u16,
+            max_count: u16,
+        ) -> Self {
+            ChildSemaphore {
+                local: Semaphore::new(initial_count, max_count),
+                parent: Parent::Child(parent),
+            }
+        }
+    }
+
+    /// Obtains a slot from this semaphore and from each of its ancestors, blocking until all are
+    /// available.
+    #[must_use = "The semaphore slots are immediately released if the guard is dropped"]
+    pub fn wait(&self) -> ChildSemaphoreGuard<'_> {
+        self.wait_internal(None).unwrap()
+    }
+
+    /// Attempts to obtain a slot from this semaphore and each of its ancestors within the time
+    /// limit, returning a [`TimeoutError`] (and holding none of them) otherwise.
+    #[must_use = "The semaphore slots are immediately released if the guard is dropped"]
+    pub fn wait_for(&self, limit: Duration) -> Result<ChildSemaphoreGuard<'_>, TimeoutError> {
+        self.wait_internal(Some(Instant::now() + limit))
+    }
+
+    fn wait_internal(
+        &self,
+        deadline: Option<Instant>,
+    ) -> Result<ChildSemaphoreGuard<'_>, TimeoutError> {
+        let mut guards = Vec::new();
+        let mut next = Some(&self.local);
+        let mut parent = Some(&self.parent);
+
+        while let Some(sem) = next {
+            guards.push(match deadline {
+                None => sem.wait(),
+                Some(deadline) => {
+                    // Previously obtained guards are dropped (and the slots returned) on failure.
+                    sem.wait_for(deadline.saturating_duration_since(Instant::now()))?
+                }
+            });
+
+            (next, parent) = match parent {
+                None => (None, None),
+                Some(Parent::Root(root)) => (Some(*root), None),
+                Some(Parent::Child(child)) => (Some(&child.local), Some(&child.parent)),
+            };
+        }
+
+        Ok(ChildSemaphoreGuard { guards })
+    }
+
+    /// Increments the local available concurrency by `count`, panicking if this exceeds the local
+    /// maximum. See [`Semaphore::release()`].
+    pub fn release(&self, count: u16) {
+        self.local.release(count)
+    }
+
+    /// Attempts to increment the local available concurrency by `count`, returning `false` if this
+    /// would exceed the local maximum. See [`Semaphore::try_release()`].
+    pub fn try_release(&self, count: u16) -> bool {
+        self.local.try_release(count)
+    }
+
+    /// Returns the currently available local count of this semaphore, without regard for the
+    /// availability of its ancestors.
+    ///
+    /// Note that this may race with other calls such as `release()` or `wait()`.
+    pub fn count(&self) -> u16 {
+        self.local.count()
+    }
+}
+
+impl<'a> Awaitable<'a> for ChildSemaphore<'_> {
+    type T = ChildSemaphoreGuard<'a>;
+    type Error = TimeoutError;
+
+    /// Blocks until a slot has been obtained from this semaphore and all of its ancestors.
+    fn try_wait(&'a self) -> Result<ChildSemaphoreGuard<'a>, Infallible> {
+        Ok(self.wait_internal(None).unwrap())
+    }
+
+    /// Attempts a time-bounded wait for a slot from this semaphore and all of its ancestors.
+    fn try_wait_for(&'a self, limit: Duration) -> Result<ChildSemaphoreGuard<'a>, TimeoutError> {
+        self.wait_internal(Some(Instant::now() + limit))
+    }
+
+    /// Attempts to obtain a slot from this semaphore and all of its ancestors without waiting.
+    fn try_wait0(&'a self) -> Result<ChildSemaphoreGuard<'a>, TimeoutError> {
+        self.wait_internal(Some(Instant::now()))
+    }
+}
+
+/// The concurrency token returned by [`ChildSemaphore::wait()`], holding one slot from the child
+/// semaphore and one from each of its ancestors. All slots are released when the guard is dropped.
+pub struct ChildSemaphoreGuard<'a> {
+    /// The guards for the child semaphore and its ancestors, from the bottom of the hierarchy up.
+    guards: Vec<SemaphoreGuard<'a>>,
+}
+
+impl ChildSemaphoreGuard<'_> {
+    /// Safely "forgets" the guard, permanently reducing the concurrency limit of the child
+    /// semaphore and each of its ancestors. See [`SemaphoreGuard::forget()`].
+    pub fn forget(self) {
+        for guard in self.guards {
+            guard.forget();
+        }
+    }
+}
+
+impl Debug for ChildSemaphoreGuard<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ChildSemaphoreGuard")
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{ChildSemaphore, Semaphore};
+    use rsevents::Awaitable;
+    use std::time::Duration;
+
+    #[test]
+    fn parent_budget_shared() {
+        let parent = Semaphore::new(3, 3);
+        let a = ChildSemaphore::new(&parent, 2, 2);
+        let b = ChildSemaphore::new(&parent, 2, 2);
+
+        let _a1 = a.wait();
+        let _a2 = a.wait();
+        // The child's local limit is exhausted even though the parent has room.
+        a.try_wait0().unwrap_err();
+        assert_eq!(parent.count(), 1);
+
+        let _b1 = b.wait();
+        // Now the parent's budget is exhausted even though b has room.
+        b.wait_for(Duration::from_millis(10)).unwrap_err();
+        // A failed acquisition must not hold on to the child's slot.
+        assert_eq!(b.count(), 1);
+    }
+
+    #[test]
+    fn release_propagates_up() {
+        let parent = Semaphore::new(1, 1);
+        let child = ChildSemaphore::new(&parent, 1, 1);
+
+        let guard = child.wait();
+        assert_eq!(parent.count(), 0);
+        assert_eq!(child.count(), 0);
+        drop(guard);
+        assert_eq!(parent.count(), 1);
+        assert_eq!(child.count(), 1);
+    }
+
+    #[test]
+    fn nested_children() {
+        let root = Semaphore::new(4, 4);
+        let mid = ChildSemaphore::new(&root, 2, 2);
+        let leaf = ChildSemaphore::nested(&mid, 2, 2);
+
+        let _guard = leaf.wait();
+        assert_eq!(leaf.count(), 1);
+        assert_eq!(mid.count(), 1);
+        assert_eq!(root.count(), 3);
+    }
+}
@@ -0,0 +1,374 @@
+use crate::clock::{Clock, SystemClock};
+use crate::{CircuitOpen, Condition};
+use rsevents::{Awaitable, TimeoutError};
+use std::convert::Infallible;
+use std::time::{Duration, Instant};
+
+/// A circuit breaker guarding calls to an unreliable operation (such as a remote service),
+/// limiting their concurrency while the operation is healthy and rejecting them outright after
+/// repeated failures.
+///
+/// Callers [acquire](CircuitBreaker::acquire) a [`CircuitPermit`] before each call and report the
+/// outcome through it. The breaker moves between three [states](CircuitState):
+///
+/// * **Closed**: calls are admitted as by a semaphore, blocking while `concurrency` calls are
+///   already in progress. After `failure_threshold` consecutive failures, the circuit opens.
+/// * **Open**: calls are rejected immediately with [`CircuitOpen`] until `cooldown` has elapsed,
+///   at which point the circuit becomes half-open. Waiting on the breaker via the [`Awaitable`]
+///   API parks the caller until then.
+/// * **Half-open**: a limited number of probe calls (one by default, see
+///   [`with_probes()`](CircuitBreaker::with_probes)) are admitted and any others rejected. A
+///   successful probe closes the circuit again, while a failed one re-opens it.
+///
+/// ## Example:
+///
+/// ```rust
+/// use rsevents_extra::{Awaitable, CircuitBreaker};
+/// use std::time::Duration;
+///
+/// // Up to 8 concurrent calls; open for a second after 5 consecutive failures.
+/// static PAYMENTS: CircuitBreaker = CircuitBreaker::new(8, 5, Duration::from_secs(1));
+///
+/// fn charge(amount: u32) -> Result<(), &'static str> {
+///     let permit = PAYMENTS.acquire().map_err(|_| "payment service unavailable")?;
+///     // <call the payment service here>
+///     let result: Result<(), &'static str> = Ok(());
+///     match result {
+///         Ok(_) => permit.succeed(),
+///         Err(_) => permit.fail(),
+///     }
+///     result
+/// }
+///
+/// if charge(100).is_err() {
+///     // Park until the breaker lets calls through again, then retry.
+///     PAYMENTS.wait();
+///     charge(100).unwrap();
+/// }
+/// ```
+pub struct CircuitBreaker {
+    concurrency: usize,
+    failure_threshold: usize,
+    cooldown: Duration,
+    probes: usize,
+    state: Condition<BreakerState>,
+    clock: &'static dyn Clock,
+}
+
+/// The state of a [`CircuitBreaker`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum CircuitState {
+    /// Calls are admitted, up to the breaker's concurrency limit.
+    Closed,
+    /// Calls are rejected until the cooldown has elapsed.
+    Open,
+    /// A limited number of probe calls are admitted to test whether the operation has recovered.
+    HalfOpen,
+}
+
+struct BreakerState {
+    mode: Mode,
+    /// The number of permits currently held, probes included.
+    in_flight: usize,
+}
+
+enum Mode {
+    Closed { failures: usize },
+    Open { until: Instant },
+    HalfOpen { probes: usize },
+}
+
+impl BreakerState {
+    /// Moves an open circuit whose cooldown has elapsed to half-open.
+    fn refresh(&mut self, now: Instant) {
+        if matches!(self.mode, Mode::Open { until } if now >= until) {
+            self.mode = Mode::HalfOpen { probes: 0 };
+        }
+    }
+}
+
+impl CircuitBreaker {
+    /// Creates a new, closed `CircuitBreaker` admitting up to `concurrency` calls at a time, which
+    /// opens after `failure_threshold` consecutive failures and stays open for `cooldown`.
+    ///
+    /// Panics if `concurrency` or `failure_threshold` is zero.
+    ///
+    /// This is a `const` function and can be used in a `static` context.
+    pub const fn new(concurrency: usize, failure_threshold: usize, cooldown: Duration) -> Self {
+        assert!(
+            concurrency > 0,
+            "CircuitBreaker concurrency must be non-zero!"
+        );
+        assert!(
+            failure_threshold > 0,
+            "CircuitBreaker failure threshold must be non-zero!"
+        );
+        CircuitBreaker {
+            concurrency,
+            failure_threshold,
+            cooldown,
+            probes: 1,
+            state: Condition::new(BreakerState {
+                mode: Mode::Closed { failures: 0 },
+                in_flight: 0,
+            }),
+            clock: &SystemClock,
+        }
+    }
+
+    /// Sets the number of probe calls admitted at a time while the circuit is half-open (one by
+    /// default).
+    ///
+    /// Panics if `probes` is zero.
+    pub const fn with_probes(mut self, probes: usize) -> Self {
+        assert!(probes > 0, "CircuitBreaker probes must be non-zero!");
+        self.probes = probes;
+        self
+    }
+
+    /// Replaces the [`SystemClock`] used to time the cooldown with `clock`. This is primarily
+    /// useful in tests, where a [`MockClock`](crate::MockClock) makes the cooldown deterministic.
+    pub const fn with_clock(mut self, clock: &'static dyn Clock) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Returns the current state of the circuit.
+    pub fn state(&self) -> CircuitState {
+        let now = self.clock.now();
+        self.state.update(|state| {
+            state.refresh(now);
+            match state.mode {
+                Mode::Closed { .. } => CircuitState::Closed,
+                Mode::Open { .. } => CircuitState::Open,
+                Mode::HalfOpen { .. } => CircuitState::HalfOpen,
+            }
+        })
+    }
+
+    /// Obtains a permit to make a call. While the circuit is closed, this blocks until fewer than
+    /// `concurrency` calls are in progress; while it is open (or half-open with all probes in
+    /// use), this fails immediately with [`CircuitOpen`].
+    #[must_use = "the outcome of the call should be recorded through the permit"]
+    pub fn acquire(&self) -> Result<CircuitPermit<'_>, CircuitOpen> {
+        loop {
+            let now = self.clock.now();
+            let admitted = self.state.update(|state| {
+                state.refresh(now);
+                if state.in_flight >= self.concurrency {
+                    return match state.mode {
+                        Mode::Closed { .. } => None,
+                        _ => Some(Err(CircuitOpen)),
+                    };
+                }
+                let probe = match &mut state.mode {
+                    Mode::Closed { .. } => false,
+                    Mode::Open { .. } => return Some(Err(CircuitOpen)),
+                    Mode::HalfOpen { probes } if *probes < self.probes => {
+                        *probes += 1;
+                        true
+                    }
+                    Mode::HalfOpen { .. } => return Some(Err(CircuitOpen)),
+                };
+                state.in_flight += 1;
+                Some(Ok(probe))
+            });
+
+            match admitted {
+                Some(admitted) => {
+                    return admitted.map(|probe| CircuitPermit {
+                        breaker: self,
+                        probe,
+                        outcome: None,
+                    })
+                }
+                None => {
+                    self.state.wait_until(|state| {
+                        state.in_flight < self.concurrency
+                            || !matches!(state.mode, Mode::Closed { .. })
+                    });
+                }
+            }
+        }
+    }
+
+    /// Returns how long until an open circuit becomes half-open, or `None` if it isn't open.
+    fn retry_in(&self) -> Option<Duration> {
+        match self.state.read().mode {
+            Mode::Open { until } => Some(until.saturating_duration_since(self.clock.now())),
+            _ => None,
+        }
+    }
+
+    /// Returns whether calls may be made (or probed) without waiting for a cooldown.
+    // `Option::is_none_or()` would need Rust 1.82.
+    #[allow(clippy::unnecessary_map_or)]
+    fn can_retry(&self) -> bool {
+        self.retry_in()
+            .map_or(true, |remaining| remaining.is_zero())
+    }
+
+    /// Blocks for up to `limit` (in real time) until the circuit is updated to no longer be open or
+    /// its cooldown elapses, returning whether calls may be retried.
+    fn wait_until_retry(&self, limit: Duration) -> bool {
+        let limit = self
+            .retry_in()
+            .map_or(limit, |remaining| limit.min(remaining));
+        let closed = self
+            .state
+            .wait_until_for(|state| !matches!(state.mode, Mode::Open { .. }), limit);
+        drop(closed);
+        self.can_retry()
+    }
+
+    fn release(&self, probe: bool, outcome: Option<bool>) {
+        let now = self.clock.now();
+        self.state.update(|state| {
+            state.in_flight -= 1;
+            let mode = &mut state.mode;
+            match (mode, probe, outcome) {
+                (Mode::Closed { failures }, false, Some(true)) => *failures = 0,
+                (Mode::Closed { failures }, false, Some(false)) => {
+                    *failures += 1;
+                    if *failures >= self.failure_threshold {
+                        state.mode = Mode::Open {
+                            until: now + self.cooldown,
+                        };
+                    }
+                }
+                (Mode::HalfOpen { .. }, true, Some(true)) => {
+                    state.mode = Mode::Closed { failures: 0 };
+                }
+                (Mode::HalfOpen { .. }, true, Some(false)) => {
+                    state.mode = Mode::Open {
+                        until: now + self.cooldown,
+                    };
+                }
+                (Mode::HalfOpen { probes }, true, None) => *probes -= 1,
+                // Outcomes of calls admitted under an earlier state no longer apply.
+                _ => {}
+            }
+        });
+    }
+}
+
+impl Awaitable<'_> for CircuitBreaker {
+    type T = ();
+    type Error = TimeoutError;
+
+    /// Waits until the circuit is no longer open, i.e. until calls may be retried.
+    fn try_wait(&self) -> Result<(), Infallible> {
+        while let Some(remaining) = self.retry_in().filter(|remaining| !remaining.is_zero()) {
+            self.clock
+                .wait_for(remaining, &mut |limit| self.wait_until_retry(limit));
+        }
+        Ok(())
+    }
+
+    /// Waits until the circuit is no longer open, or returns an error in case of a timeout.
+    fn try_wait_for(&self, limit: Duration) -> Result<(), TimeoutError> {
+        if self.can_retry() {
+            return Ok(());
+        }
+        let ready = self
+            .clock
+            .wait_for(limit, &mut |limit| self.wait_until_retry(limit));
+        match ready {
+            true => Ok(()),
+            false => Err(TimeoutError),
+        }
+    }
+
+    /// Checks whether the circuit is open without blocking.
+    fn try_wait0(&self) -> Result<(), TimeoutError> {
+        match self.state() {
+            CircuitState::Open => Err(TimeoutError),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Permission to make a call through a [`CircuitBreaker`], returned by
+/// [`CircuitBreaker::acquire()`]. The outcome of the call is recorded with
+/// [`succeed()`](Self::succeed) or [`fail()`](Self::fail); dropping the permit without doing
+/// either releases it without affecting the state of the circuit.
+pub struct CircuitPermit<'a> {
+    breaker: &'a CircuitBreaker,
+    /// Whether this permit was admitted as a half-open probe.
+    probe: bool,
+    outcome: Option<bool>,
+}
+
+impl CircuitPermit<'_> {
+    /// Records that the call succeeded, closing a half-open circuit.
+    pub fn succeed(mut self) {
+        self.outcome = Some(true);
+    }
+
+    /// Records that the call failed, which counts towards opening a closed circuit and re-opens a
+    /// half-open one.
+    pub fn fail(mut self) {
+        self.outcome = Some(false);
+    }
+}
+
+impl Drop for CircuitPermit<'_> {
+    fn drop(&mut self) {
+        self.breaker.release(self.probe, self.outcome);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{Awaitable, CircuitBreaker, CircuitState, MockClock};
+    use std::thread;
+    use std::time::Duration;
+
+    #[test]
+    fn opens_and_recovers() {
+        static CLOCK: MockClock = MockClock::new();
+        let breaker = CircuitBreaker::new(2, 2, Duration::from_secs(10)).with_clock(&CLOCK);
+
+        breaker.acquire().unwrap().fail();
+        breaker.acquire().unwrap().succeed();
+        breaker.acquire().unwrap().fail();
+        assert_eq!(breaker.state(), CircuitState::Closed);
+        breaker.acquire().unwrap().fail();
+        assert_eq!(breaker.state(), CircuitState::Open);
+        assert!(breaker.acquire().is_err());
+        assert!(breaker.try_wait0().is_err());
+
+        thread::scope(|scope| {
+            let waiter = scope.spawn(|| breaker.wait());
+            thread::sleep(Duration::from_millis(10));
+            assert!(!waiter.is_finished());
+            CLOCK.advance(Duration::from_secs(10));
+            waiter.join().unwrap();
+        });
+
+        // Only one probe at a time; a failed probe re-opens the circuit.
+        let probe = breaker.acquire().unwrap();
+        assert_eq!(breaker.state(), CircuitState::HalfOpen);
+        assert!(breaker.acquire().is_err());
+        probe.fail();
+        assert_eq!(breaker.state(), CircuitState::Open);
+
+        CLOCK.advance(Duration::from_secs(10));
+        breaker.acquire().unwrap().succeed();
+        assert_eq!(breaker.state(), CircuitState::Closed);
+    }
+
+    #[test]
+    fn limits_concurrency_while_closed() {
+        let breaker = CircuitBreaker::new(1, 1, Duration::from_secs(10));
+        let permit = breaker.acquire().unwrap();
+
+        thread::scope(|scope| {
+            let waiter = scope.spawn(|| breaker.acquire().map(|permit| permit.succeed()));
+            thread::sleep(Duration::from_millis(10));
+            assert!(!waiter.is_finished());
+            drop(permit);
+            assert!(waiter.join().unwrap().is_ok());
+        });
+    }
+}
@@ -0,0 +1,162 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::OnceLock;
+use std::time::{Duration, Instant};
+
+/// The source of time used by the timed wait paths of a [`Semaphore`](crate::Semaphore) and by
+/// rate limiters such as [`RateLimiter`](crate::RateLimiter), letting tests substitute a
+/// [`MockClock`] for the [`SystemClock`] and control the passage of time.
+///
+/// A clock is responsible both for telling the time and for carrying out time-bounded waits, as a
+/// mock clock needs to keep waiting (however long that takes in reality) until its own notion of
+/// time has caught up with the time limit.
+pub trait Clock: Sync {
+    /// Returns the current time according to this clock.
+    fn now(&self) -> Instant;
+
+    /// Waits for up to `limit` (as measured by this clock) for a condition to be met, returning
+    /// whether it was. `wait` performs an actual bounded wait for the condition, lasting at most
+    /// the real-time duration it is passed, and returns `true` if the condition was met.
+    fn wait_for(&self, limit: Duration, wait: &mut dyn FnMut(Duration) -> bool) -> bool;
+}
+
+/// The default [`Clock`], measuring real time.
+#[derive(Debug, Default, Copy, Clone)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+
+    fn wait_for(&self, limit: Duration, wait: &mut dyn FnMut(Duration) -> bool) -> bool {
+        wait(limit)
+    }
+}
+
+/// A [`Clock`] for tests that only advances when told to, making timed waits deterministic.
+///
+/// Threads blocked in a timed wait against a `MockClock` keep waiting until the clock has been
+/// [advanced](MockClock::advance) past their time limit, no matter how much real time elapses.
+///
+/// ## Example:
+///
+/// ```rust
+/// use rsevents_extra::{MockClock, Semaphore, SemaphoreOptions};
+/// use std::time::Duration;
+///
+/// static CLOCK: MockClock = MockClock::new();
+/// static OPTIONS: SemaphoreOptions = SemaphoreOptions::new().clock(&CLOCK);
+/// static SEM: Semaphore = Semaphore::new(0, 1).with_options(&OPTIONS);
+///
+/// std::thread::scope(|scope| {
+///     let waiter = scope.spawn(|| SEM.wait_for(Duration::from_secs(60)).is_ok());
+///     // Time out the waiter without actually waiting a minute. The clock is
+///     // advanced until the waiter gives up, as the waiter may not have read the
+///     // time yet when the clock is first advanced.
+///     while !waiter.is_finished() {
+///         CLOCK.advance(Duration::from_secs(60));
+///         std::thread::sleep(Duration::from_millis(1));
+///     }
+///     assert_eq!(waiter.join().unwrap(), false);
+/// });
+/// ```
+#[derive(Debug)]
+pub struct MockClock {
+    /// The real time the clock was first read at, from which the mock time is offset.
+    base: OnceLock<Instant>,
+    /// The amount of time the clock has been advanced by, in nanoseconds.
+    elapsed: AtomicU64,
+}
+
+impl MockClock {
+    /// How often threads waiting against a mock clock check whether it has been advanced.
+    const POLL_INTERVAL: Duration = Duration::from_millis(1);
+
+    /// Creates a new `MockClock`, which stays at the time it is first read until advanced.
+    pub const fn new() -> Self {
+        MockClock {
+            base: OnceLock::new(),
+            elapsed: AtomicU64::new(0),
+        }
+    }
+
+    /// Moves the clock forward by `duration`, timing out any waits whose limit has now elapsed.
+    pub fn advance(&self, duration: Duration) {
+        self.elapsed
+            .fetch_add(duration.as_nanos() as u64, Ordering::SeqCst);
+    }
+}
+
+impl Default for MockClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Clock for MockClock {
+    fn now(&self) -> Instant {
+        let base = *self.base.get_or_init(Instant::now);
+        base + Duration::from_nanos(self.elapsed.load(Ordering::SeqCst))
+    }
+
+    fn wait_for(&self, limit: Duration, wait: &mut dyn FnMut(Duration) -> bool) -> bool {
+        let deadline = self.now() + limit;
+        loop {
+            let expired = self.now() >= deadline;
+            // Still check the condition (without blocking) once the time is up.
+            if wait(if expired {
+                Duration::ZERO
+            } else {
+                Self::POLL_INTERVAL
+            }) {
+                return true;
+            }
+            if expired {
+                return false;
+            }
+        }
+    }
+}
+
+/// Sleeps for `duration` as measured by `clock`.
+pub(crate) fn sleep(clock: &dyn Clock, duration: Duration) {
+    if !duration.is_zero() {
+        clock.wait_for(duration, &mut |limit| {
+            std::thread::sleep(limit);
+            false
+        });
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{Clock, MockClock};
+    use std::thread;
+    use std::time::Duration;
+
+    #[test]
+    fn mock_clock_only_moves_when_advanced() {
+        let clock = MockClock::new();
+        let start = clock.now();
+        thread::sleep(Duration::from_millis(5));
+        assert_eq!(clock.now(), start);
+
+        clock.advance(Duration::from_secs(3));
+        assert_eq!(clock.now() - start, Duration::from_secs(3));
+    }
+
+    #[test]
+    fn mock_wait_times_out_on_advance() {
+        let clock = MockClock::new();
+
+        thread::scope(|scope| {
+            let waiter = scope.spawn(|| clock.wait_for(Duration::from_secs(10), &mut |_| false));
+            thread::sleep(Duration::from_millis(20));
+            assert!(!waiter.is_finished());
+            clock.advance(Duration::from_secs(10));
+            assert!(!waiter.join().unwrap());
+        });
+
+        assert!(clock.wait_for(Duration::from_secs(1), &mut |_| true));
+    }
+}
@@ -0,0 +1,135 @@
+use crate::subscribe::{Subscribe, Subscription};
+use crate::sync::loom_const_fn;
+use crate::CountdownEvent;
+use rsevents::{Awaitable, TimeoutError};
+use std::convert::Infallible;
+use std::sync::Mutex;
+use std::task::Waker;
+use std::time::Duration;
+
+/// A [`CountdownEvent`] whose ticks carry a value, combining "wait for _n_ tasks to complete" with
+/// "gather the results of those tasks" in a single primitive.
+///
+/// Each task reports its result with [`CollectingCountdown::tick_with()`] and waiting on the
+/// countdown (via the [`Awaitable`] trait) blocks until all tasks have completed, then returns the
+/// collected values in the order they were reported.
+///
+/// ## Example:
+///
+/// ```rust
+/// use rsevents_extra::{Awaitable, CollectingCountdown};
+///
+/// let lengths = CollectingCountdown::new(3);
+/// std::thread::scope(|scope| {
+///     for word in ["one", "three", "eleven"] {
+///         let lengths = &lengths;
+///         scope.spawn(move || lengths.tick_with(word.len()));
+///     }
+///
+///     let mut lengths = lengths.wait();
+///     lengths.sort();
+///     assert_eq!(lengths, [3, 5, 6]);
+/// });
+/// ```
+pub struct CollectingCountdown<T> {
+    countdown: CountdownEvent,
+    /// The values reported so far and not yet returned to a waiter.
+    values: Mutex<Vec<T>>,
+}
+
+impl<T> CollectingCountdown<T> {
+    loom_const_fn! {
+        /// Creates a new collecting countdown waiting on `count` values. If a count of zero is
+        /// specified, the countdown is immediately set.
+        ///
+        /// This is a `const` function and can be used in a `static` context.
+        pub fn new(count: usize) -> Self {
+            Self {
+                countdown: CountdownEvent::new(count),
+                values: Mutex::new(Vec::new()),
+            }
+        }
+    }
+
+    /// Reports the result of a completed task, ticking the countdown.
+    pub fn tick_with(&self, value: T) {
+        // The value must be in place before the final tick wakes any waiters.
+        self.values.lock().unwrap().push(value);
+        self.countdown.tick();
+    }
+
+    /// Gets the number of values still outstanding.
+    pub fn count(&self) -> usize {
+        self.countdown.count()
+    }
+
+    /// Resets the countdown to wait on `count` new values, discarding any values not yet returned
+    /// to a waiter.
+    pub fn reset(&self, count: usize) {
+        let mut values = self.values.lock().unwrap();
+        values.clear();
+        self.countdown.reset(count);
+    }
+
+    /// Takes the values collected so far.
+    fn take(&self) -> Vec<T> {
+        std::mem::take(&mut *self.values.lock().unwrap())
+    }
+}
+
+impl<T> Awaitable<'_> for CollectingCountdown<T> {
+    type T = Vec<T>;
+    type Error = TimeoutError;
+
+    /// Waits for all values to be reported, then returns them. The values are returned to only one
+    /// waiter, with any other waiters receiving the values reported since (if any).
+    fn try_wait(&self) -> Result<Vec<T>, Infallible> {
+        self.countdown.wait();
+        Ok(self.take())
+    }
+
+    /// Waits for all values to be reported and returns them, or returns an error in case of a
+    /// timeout.
+    fn try_wait_for(&self, limit: Duration) -> Result<Vec<T>, TimeoutError> {
+        self.countdown.try_wait_for(limit)?;
+        Ok(self.take())
+    }
+
+    /// Returns the reported values if all of them have been reported, without blocking.
+    fn try_wait0(&self) -> Result<Vec<T>, TimeoutError> {
+        self.countdown.try_wait0()?;
+        Ok(self.take())
+    }
+}
+
+impl<T> Subscribe for CollectingCountdown<T> {
+    fn subscribe(&self, waker: &Waker) -> Option<Subscription<'_>> {
+        self.countdown.subscribe(waker)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{Awaitable, CollectingCountdown};
+    use std::time::Duration;
+
+    #[test]
+    fn collects_values() {
+        let countdown = CollectingCountdown::new(2);
+        countdown.tick_with("a");
+        assert!(countdown.try_wait0().is_err());
+        countdown.tick_with("b");
+        assert_eq!(countdown.wait(), ["a", "b"]);
+        assert!(countdown.wait().is_empty());
+    }
+
+    #[test]
+    fn reset_discards_values() {
+        let countdown = CollectingCountdown::new(1);
+        countdown.tick_with(1);
+        countdown.reset(1);
+        assert!(countdown.try_wait_for(Duration::from_millis(1)).is_err());
+        countdown.tick_with(2);
+        assert_eq!(countdown.wait(), [2]);
+    }
+}
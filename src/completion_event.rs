@@ -0,0 +1,128 @@
+use rsevents::{Awaitable, EventState, ManualResetEvent, TimeoutError};
+use std::convert::Infallible;
+use std::sync::OnceLock;
+use std::time::Duration;
+
+/// A write-once event carrying a result, completed by a producer and waited on by any number of
+/// consumers (a blocking promise/future).
+///
+/// The producer publishes its result with [`CompletionEvent::complete()`], which releases all
+/// current and future waiters. Waiting on the event returns a reference to the result, which
+/// remains available for the lifetime of the event and can be cloned if an owned copy is needed.
+///
+/// ## Example:
+///
+/// ```rust
+/// use rsevents_extra::{Awaitable, CompletionEvent};
+///
+/// static CHECKSUM: CompletionEvent<u64> = CompletionEvent::new();
+///
+/// std::thread::scope(|scope| {
+///     for _ in 0..4 {
+///         scope.spawn(|| {
+///             let checksum: &u64 = CHECKSUM.wait();
+///             assert_eq!(*checksum, 0xC0FFEE);
+///         });
+///     }
+///
+///     // <compute the checksum here>
+///     CHECKSUM.complete(0xC0FFEE).unwrap();
+/// });
+/// ```
+pub struct CompletionEvent<T> {
+    value: OnceLock<T>,
+    /// Set once `value` has been initialized.
+    event: ManualResetEvent,
+}
+
+impl<T> CompletionEvent<T> {
+    /// Creates a new, incomplete `CompletionEvent`.
+    ///
+    /// This is a `const` function and can be used in a `static` context.
+    pub const fn new() -> Self {
+        CompletionEvent {
+            value: OnceLock::new(),
+            event: ManualResetEvent::new(EventState::Unset),
+        }
+    }
+
+    /// Completes the event with `value`, releasing all current and future waiters. If the event
+    /// has already been completed, `value` is handed back as `Err(value)` and the original result
+    /// is left in place.
+    pub fn complete(&self, value: T) -> Result<(), T> {
+        self.value.set(value)?;
+        self.event.set();
+        Ok(())
+    }
+
+    /// Returns the result if the event has been completed, without blocking.
+    pub fn get(&self) -> Option<&T> {
+        self.value.get()
+    }
+
+    /// Returns whether the event has been completed, without blocking.
+    pub fn is_complete(&self) -> bool {
+        self.value.get().is_some()
+    }
+
+    fn result(&self) -> &T {
+        // The event is only set once the value has been initialized.
+        self.value
+            .get()
+            .expect("CompletionEvent set without a value!")
+    }
+}
+
+impl<T> Default for CompletionEvent<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'a, T: 'a> Awaitable<'a> for CompletionEvent<T> {
+    type T = &'a T;
+    type Error = TimeoutError;
+
+    /// Waits for the event to be completed, then returns its result.
+    fn try_wait(&'a self) -> Result<&'a T, Infallible> {
+        self.event.wait();
+        Ok(self.result())
+    }
+
+    /// Waits for the event to be completed, then returns its result, or returns an error in case
+    /// of a timeout.
+    fn try_wait_for(&'a self, limit: Duration) -> Result<&'a T, TimeoutError> {
+        self.event.try_wait_for(limit)?;
+        Ok(self.result())
+    }
+
+    /// Returns the event's result if it has been completed, without blocking.
+    fn try_wait0(&'a self) -> Result<&'a T, TimeoutError> {
+        self.get().ok_or(TimeoutError)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{Awaitable, CompletionEvent};
+    use std::thread;
+    use std::time::Duration;
+
+    #[test]
+    fn completes_once() {
+        let event = CompletionEvent::<String>::new();
+        assert!(event.try_wait0().is_err());
+        assert!(event.try_wait_for(Duration::from_millis(1)).is_err());
+
+        thread::scope(|scope| {
+            let waiter = scope.spawn(|| event.wait().clone());
+            thread::sleep(Duration::from_millis(10));
+            assert_eq!(event.complete("done".to_owned()), Ok(()));
+            assert_eq!(waiter.join().unwrap(), "done");
+        });
+
+        assert_eq!(event.complete("again".to_owned()), Err("again".to_owned()));
+        assert_eq!(event.get().map(String::as_str), Some("done"));
+        assert!(event.is_complete());
+    }
+}
@@ -0,0 +1,149 @@
+use crate::BroadcastEvent;
+use rsevents::TimeoutError;
+use std::ops::Deref;
+use std::sync::{Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+
+/// Shared state paired with change notification, letting threads block until the state satisfies
+/// a predicate.
+///
+/// All changes to the state are made through [`Condition::update()`], which wakes any threads in
+/// [`Condition::wait_until()`] to re-evaluate their predicates. This takes care of the error-prone
+/// loop of re-checking shared state around event waits (and of the missed wakeups that come from
+/// getting it wrong).
+///
+/// ## Example:
+///
+/// ```rust
+/// use rsevents_extra::Condition;
+///
+/// static CONNECTIONS: Condition<Vec<String>> = Condition::new(Vec::new());
+///
+/// std::thread::scope(|scope| {
+///     for id in 0..3 {
+///         scope.spawn(move || {
+///             // <connect to the database here>
+///             CONNECTIONS.update(|connections| connections.push(format!("db-{}", id)));
+///         });
+///     }
+///
+///     let connections = CONNECTIONS.wait_until(|connections| connections.len() == 3);
+///     assert_eq!(connections.len(), 3);
+/// });
+/// ```
+pub struct Condition<T> {
+    state: Mutex<T>,
+    /// Signalled after each update.
+    changed: BroadcastEvent,
+}
+
+impl<T> Condition<T> {
+    /// Creates a new `Condition` with the given initial state.
+    ///
+    /// This is a `const` function and can be used in a `static` context.
+    pub const fn new(state: T) -> Self {
+        Condition {
+            state: Mutex::new(state),
+            changed: BroadcastEvent::new(),
+        }
+    }
+
+    /// Modifies the state via `f`, then wakes all threads waiting on the condition so that they
+    /// re-evaluate their predicates. Returns the result of `f`.
+    pub fn update<R>(&self, f: impl FnOnce(&mut T) -> R) -> R {
+        let result = f(&mut self.lock());
+        self.changed.signal();
+        result
+    }
+
+    /// Blocks until `predicate` returns `true` for the state, which is re-evaluated after every
+    /// [update](Self::update). Returns a guard giving read-only access to the state that
+    /// satisfied the predicate, which can't change until the guard is dropped.
+    pub fn wait_until(&self, mut predicate: impl FnMut(&T) -> bool) -> ConditionGuard<'_, T> {
+        loop {
+            // Read before checking the state, so an update made after the check is not missed.
+            let generation = self.changed.generation();
+            let state = self.lock();
+            if predicate(&state) {
+                return ConditionGuard { state };
+            }
+            drop(state);
+            self.changed.wait_since(generation);
+        }
+    }
+
+    /// Blocks for up to `limit` until `predicate` returns `true` for the state (see
+    /// [`wait_until()`](Self::wait_until)), returning an error in case of a timeout.
+    pub fn wait_until_for(
+        &self,
+        mut predicate: impl FnMut(&T) -> bool,
+        limit: Duration,
+    ) -> Result<ConditionGuard<'_, T>, TimeoutError> {
+        let deadline = Instant::now() + limit;
+        loop {
+            let generation = self.changed.generation();
+            let state = self.lock();
+            if predicate(&state) {
+                return Ok(ConditionGuard { state });
+            }
+            drop(state);
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            self.changed.wait_since_for(generation, remaining)?;
+        }
+    }
+
+    /// Returns a guard giving read-only access to the current state.
+    pub fn read(&self) -> ConditionGuard<'_, T> {
+        ConditionGuard { state: self.lock() }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, T> {
+        self.state.lock().unwrap()
+    }
+}
+
+impl<T: Default> Default for Condition<T> {
+    fn default() -> Self {
+        Self::new(T::default())
+    }
+}
+
+/// Read-only access to the state of a [`Condition`], returned by [`Condition::wait_until()`] and
+/// [`Condition::read()`]. The state can't be updated while the guard is held.
+pub struct ConditionGuard<'a, T> {
+    state: MutexGuard<'a, T>,
+}
+
+impl<T> Deref for ConditionGuard<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.state
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::Condition;
+    use std::thread;
+    use std::time::Duration;
+
+    #[test]
+    fn waits_for_predicate() {
+        let counter = Condition::new(0);
+        assert!(counter
+            .wait_until_for(|&n| n > 0, Duration::from_millis(1))
+            .is_err());
+
+        thread::scope(|scope| {
+            let waiter = scope.spawn(|| *counter.wait_until(|&n| n >= 100));
+            for _ in 0..100 {
+                counter.update(|n| *n += 1);
+            }
+            assert_eq!(waiter.join().unwrap(), 100);
+        });
+
+        assert_eq!(counter.update(|n| std::mem::replace(n, 0)), 100);
+        assert_eq!(*counter.read(), 0);
+    }
+}
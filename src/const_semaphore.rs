@@ -0,0 +1,189 @@
+use crate::semaphore::{Count, ICount, RawSemaphore, Timeout};
+use crate::subscribe::{Subscribe, Subscription};
+use crate::sync::loom_const_fn;
+use crate::SemaphoreGuard;
+use rsevents::{Awaitable, TimeoutError};
+use std::convert::Infallible;
+use std::task::Waker;
+use std::time::Duration;
+
+/// A [`Semaphore`](crate::Semaphore) with its maximum concurrency count fixed at compile time.
+///
+/// A `ConstSemaphore<MAX>` behaves identically to a `Semaphore` created with a `max_count` of
+/// `MAX`, but does not need to store the maximum at runtime and lets the compiler fold the bounds
+/// checks in [`ConstSemaphore::release()`] and co. Semaphores with different maximums are also
+/// different types, so a pool sized for four connections can't be mistaken for one sized for
+/// eight.
+///
+/// Waiting on a `ConstSemaphore` yields the same [`SemaphoreGuard`] as waiting on a `Semaphore`.
+///
+/// ## Example:
+///
+/// ```rust
+/// use rsevents_extra::ConstSemaphore;
+///
+/// // Allow at most four threads to write to the disk at the same time, all
+/// // of which are available from the start.
+/// static DISK_WRITERS: ConstSemaphore<4> = ConstSemaphore::new(4);
+///
+/// std::thread::scope(|scope| {
+///     for _ in 0..16 {
+///         scope.spawn(|| {
+///             let _guard = DISK_WRITERS.wait();
+///             // <write to the disk here>
+///         });
+///     }
+/// });
+/// ```
+pub struct ConstSemaphore<const MAX: Count> {
+    raw: RawSemaphore,
+}
+
+impl<const MAX: Count> ConstSemaphore<MAX> {
+    /// The maximum available concurrency count of this semaphore type.
+    pub const MAX: Count = MAX;
+
+    loom_const_fn! {
+        /// Create a new [`ConstSemaphore`] with an initial available concurrency count of
+        /// `initial_count`, which may not exceed `MAX`.
+        pub fn new(initial_count: Count) -> Self {
+            if initial_count > MAX {
+                panic!("Invalid initial_count > MAX");
+            }
+
+            ConstSemaphore {
+                raw: RawSemaphore::new(initial_count),
+            }
+        }
+    }
+
+    /// Obtains a concurrency token from the semaphore, blocking until one becomes available. See
+    /// [`Semaphore::wait()`](crate::Semaphore::wait) for more info.
+    #[must_use = "The semaphore count is immediately re-incremented if the guard is dropped"]
+    pub fn wait(&self) -> SemaphoreGuard<'_> {
+        self.raw.try_wait(1, Timeout::Infinite, false).unwrap();
+        SemaphoreGuard::new(&self.raw, 1)
+    }
+
+    /// Attempts a time-bounded wait against the semaphore, returning a [`TimeoutError`] if the
+    /// specified time limit elapses without the semaphore becoming available.
+    #[must_use = "The semaphore count is immediately re-incremented if the guard is dropped"]
+    pub fn wait_for(&self, limit: Duration) -> Result<SemaphoreGuard<'_>, TimeoutError> {
+        match limit {
+            Duration::ZERO => self.raw.try_wait(1, Timeout::None, false),
+            timeout => self.raw.try_wait(1, Timeout::Bounded(timeout), false),
+        }
+        .map_err(|_| TimeoutError)?;
+        Ok(SemaphoreGuard::new(&self.raw, 1))
+    }
+
+    /// Directly increments or decrements the current availability limit, panicking if the result
+    /// would lie outside of `0..=MAX`. See [`Semaphore::modify()`](crate::Semaphore::modify).
+    pub fn modify(&mut self, count: ICount) {
+        if !self.raw.try_modify(count, MAX) {
+            panic!("An invalid count was supplied to ConstSemaphore::modify()");
+        }
+    }
+
+    /// Directly increments or decrements the current availability limit, returning `false` if the
+    /// result would lie outside of `0..=MAX`. See
+    /// [`Semaphore::try_modify()`](crate::Semaphore::try_modify).
+    pub fn try_modify(&mut self, count: ICount) -> bool {
+        self.raw.try_modify(count, MAX)
+    }
+
+    /// Increments the available concurrency by `count`, panicking if this would exceed `MAX`. See
+    /// [`Semaphore::release()`](crate::Semaphore::release).
+    pub fn release(&self, count: Count) {
+        self.raw.release(count, MAX)
+    }
+
+    /// Attempts to increment the available concurrency by `count`, returning `false` if this would
+    /// exceed `MAX`. See [`Semaphore::try_release()`](crate::Semaphore::try_release).
+    pub fn try_release(&self, count: Count) -> bool {
+        self.raw.try_release(count, MAX)
+    }
+
+    /// Returns the currently available count of the semaphore.
+    ///
+    /// Note that this may race with other calls such as `release()` or `wait()`.
+    pub fn count(&self) -> Count {
+        self.raw.count()
+    }
+}
+
+impl<'a, const MAX: Count> Awaitable<'a> for ConstSemaphore<MAX> {
+    type T = SemaphoreGuard<'a>;
+    type Error = TimeoutError;
+
+    /// Blocks until the semaphore becomes available, then obtains a concurrency token.
+    fn try_wait(&'a self) -> Result<SemaphoreGuard<'a>, Infallible> {
+        self.raw.try_wait(1, Timeout::Infinite, false).unwrap();
+        Ok(SemaphoreGuard::new(&self.raw, 1))
+    }
+
+    /// Attempts a time-bounded wait for a concurrency token.
+    fn try_wait_for(&'a self, limit: Duration) -> Result<SemaphoreGuard<'a>, TimeoutError> {
+        self.raw
+            .try_wait(1, Timeout::Bounded(limit), false)
+            .map_err(|_| TimeoutError)?;
+        Ok(SemaphoreGuard::new(&self.raw, 1))
+    }
+
+    /// Attempts to obtain a concurrency token without waiting.
+    fn try_wait0(&'a self) -> Result<SemaphoreGuard<'a>, TimeoutError> {
+        self.raw
+            .try_wait(1, Timeout::None, false)
+            .map_err(|_| TimeoutError)?;
+        Ok(SemaphoreGuard::new(&self.raw, 1))
+    }
+}
+
+impl<const MAX: Count> Subscribe for ConstSemaphore<MAX> {
+    fn subscribe(&self, waker: &Waker) -> Option<Subscription<'_>> {
+        Some(self.raw.subscribe(waker))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::ConstSemaphore;
+    use rsevents::Awaitable;
+
+    #[test]
+    fn const_max() {
+        let sem = ConstSemaphore::<2>::new(1);
+        assert_eq!(ConstSemaphore::<2>::MAX, 2);
+        assert!(sem.try_release(1));
+        assert!(!sem.try_release(1));
+        assert_eq!(sem.count(), 2);
+    }
+
+    #[test]
+    fn forget_lowers_current() {
+        let sem = ConstSemaphore::<1>::new(1);
+        sem.wait().forget();
+        sem.try_wait0().unwrap_err();
+        // The forgotten slot may be released again without exceeding MAX
+        sem.release(1);
+        let _guard = sem.try_wait0().unwrap();
+    }
+
+    #[test]
+    fn modify_bounds() {
+        let mut sem = ConstSemaphore::<3>::new(1);
+        assert!(!sem.try_modify(3));
+        assert!(!sem.try_modify(-2));
+        assert!(sem.try_modify(2));
+        assert_eq!(sem.count(), 3);
+        assert!(sem.try_modify(-3));
+        assert_eq!(sem.count(), 0);
+    }
+
+    #[test]
+    #[should_panic]
+    fn release_over_max() {
+        let sem = ConstSemaphore::<1>::new(1);
+        sem.release(1);
+    }
+}
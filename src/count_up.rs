@@ -0,0 +1,143 @@
+#![allow(clippy::bool_assert_comparison)]
+
+use crate::countdown::to_count;
+use crate::subscribe::{Subscribe, Subscription};
+use crate::sync::loom_const_fn;
+use crate::{CountdownEvent, OverTickPolicy};
+use rsevents::{Awaitable, TimeoutError};
+use std::convert::{Infallible, TryFrom};
+use std::task::Waker;
+use std::time::Duration;
+
+/// An `Awaitable` type that becomes set once an accumulated total reaches a target, for workflows
+/// that naturally count up (e.g. "bytes received reaches the total size") rather than down.
+///
+/// A `CountUpEvent` is the mirror image of a [`CountdownEvent`] (and shares its waiting
+/// machinery): each call to [`CountUpEvent::add()`] adds to the running total, and the event is
+/// set as soon as the total reaches the target passed to [`CountUpEvent::new()`]. Totals past the
+/// target are still tracked and reported by [`CountUpEvent::total()`].
+///
+/// ## Example:
+///
+/// ```rust
+/// use rsevents_extra::{Awaitable, CountUpEvent};
+///
+/// const FILE_SIZE: usize = 4096;
+/// static RECEIVED: CountUpEvent = CountUpEvent::new(FILE_SIZE);
+///
+/// std::thread::scope(|scope| {
+///     for _ in 0..4 {
+///         scope.spawn(|| {
+///             for _ in 0..8 {
+///                 // <receive the next 128-byte chunk here>
+///                 RECEIVED.add(128);
+///             }
+///         });
+///     }
+///
+///     RECEIVED.wait();
+///     assert_eq!(RECEIVED.total(), FILE_SIZE);
+/// });
+/// ```
+pub struct CountUpEvent {
+    /// Counts down the amount remaining until the target is reached.
+    countdown: CountdownEvent,
+}
+
+impl CountUpEvent {
+    loom_const_fn! {
+        /// Creates a new event that becomes set once a total of `target` has been
+        /// [added](Self::add). If a target of zero is specified, the event is immediately set.
+        ///
+        /// This is a `const` function and can be used in a `static` context.
+        pub fn new(target: usize) -> Self {
+            Self {
+                countdown: CountdownEvent::new(target).with_over_tick_policy(OverTickPolicy::Count),
+            }
+        }
+    }
+
+    /// Adds `n` to the running total, setting the event if this brings the total up to the target.
+    pub fn add(&self, n: usize) {
+        if n != 0 {
+            self.countdown.decrement_by(to_count(n));
+        }
+    }
+
+    /// Returns the total accumulated so far, which may exceed the target.
+    pub fn total(&self) -> usize {
+        let total = self.target() as i128 - i128::from(self.countdown.raw_count());
+        usize::try_from(total).unwrap_or(usize::MAX)
+    }
+
+    /// Returns the target at which the event becomes set.
+    pub fn target(&self) -> usize {
+        self.countdown.initial_count()
+    }
+
+    /// Returns whether the total has reached the target, without blocking.
+    pub fn is_set(&self) -> bool {
+        self.countdown.is_set()
+    }
+
+    /// Resets the running total to zero, unsetting the event (unless the target is zero).
+    pub fn reset(&self) {
+        self.countdown.reset_to_initial();
+    }
+}
+
+impl Awaitable<'_> for CountUpEvent {
+    type T = ();
+    type Error = TimeoutError;
+
+    /// Waits for the total to reach the target.
+    fn try_wait(&self) -> Result<(), Infallible> {
+        self.countdown.try_wait()
+    }
+
+    /// Waits for the total to reach the target or returns an error in case of a timeout.
+    fn try_wait_for(&self, limit: Duration) -> Result<(), TimeoutError> {
+        self.countdown.try_wait_for(limit)
+    }
+
+    /// Checks whether the total has reached the target without blocking.
+    fn try_wait0(&self) -> Result<(), TimeoutError> {
+        self.countdown.try_wait0()
+    }
+}
+
+impl Subscribe for CountUpEvent {
+    fn subscribe(&self, waker: &Waker) -> Option<Subscription<'_>> {
+        self.countdown.subscribe(waker)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{Awaitable, CountUpEvent};
+
+    #[test]
+    fn counts_up_to_target() {
+        let event = CountUpEvent::new(10);
+        event.add(4);
+        event.add(0);
+        assert_eq!(event.total(), 4);
+        assert_eq!(event.wait0(), false);
+        event.add(6);
+        assert_eq!(event.wait0(), true);
+
+        event.add(5);
+        assert_eq!(event.total(), 15);
+        assert_eq!(event.is_set(), true);
+
+        event.reset();
+        assert_eq!(event.total(), 0);
+        assert_eq!(event.is_set(), false);
+    }
+
+    #[test]
+    fn zero_target() {
+        let event = CountUpEvent::new(0);
+        assert_eq!(event.wait0(), true);
+    }
+}
@@ -1,7 +1,100 @@
+#![allow(clippy::bool_assert_comparison)]
+
+use crate::backoff::Backoff;
+use crate::error::{Cancelled, CountdownError};
+use crate::lazy_box::LazyBox;
+use crate::subscribe::{polled, subscribed, Parker, Subscribe, SubscribeFn, Subscription};
+#[cfg(not(target_has_atomic = "64"))]
+use crate::sync::AtomicIsize;
+use crate::sync::{fence, loom_const_fn, Arc, AtomicBool, AtomicUsize, Mutex, Ordering};
+#[cfg(target_has_atomic = "64")]
+use crate::sync::{AtomicI64, AtomicU64};
+use crate::waker_list::WakerList;
 use rsevents::{AutoResetEvent, Awaitable, EventState, ManualResetEvent, TimeoutError};
-use std::convert::{Infallible, TryInto};
-use std::sync::atomic::{AtomicIsize, Ordering};
-use std::time::Duration;
+use std::convert::{Infallible, TryFrom};
+use std::fmt::Debug;
+#[cfg(feature = "async")]
+use std::future::Future;
+#[cfg(feature = "async")]
+use std::pin::Pin;
+use std::task::Waker;
+#[cfg(feature = "async")]
+use std::task::{Context, Poll};
+use std::thread::{Scope, ScopedJoinHandle};
+use std::time::{Duration, Instant};
+
+/// The type of the internal count, which is 64 bits wide wherever the target supports 64-bit
+/// atomics so that large counts work the same on 32-bit and 64-bit targets.
+#[cfg(target_has_atomic = "64")]
+type Count = i64;
+#[cfg(target_has_atomic = "64")]
+type AtomicCount = AtomicI64;
+#[cfg(not(target_has_atomic = "64"))]
+type Count = isize;
+#[cfg(not(target_has_atomic = "64"))]
+type AtomicCount = AtomicIsize;
+
+/// The count packed together with the low bits of the generation and the `ABORTED` and `SYNCING`
+/// flags, so that a tick can complete a cycle (or a reset replace the count) in a single atomic
+/// operation. The count takes up the low `COUNT_BITS` bits, in two's complement.
+#[cfg(target_has_atomic = "64")]
+type State = u64;
+#[cfg(target_has_atomic = "64")]
+type AtomicState = AtomicU64;
+#[cfg(not(target_has_atomic = "64"))]
+type State = usize;
+#[cfg(not(target_has_atomic = "64"))]
+type AtomicState = AtomicUsize;
+
+/// Set while a thread is bringing `event` in line with the state. See `sync_event()`.
+const SYNCING: State = 1 << (State::BITS - 1);
+/// Set once the countdown is aborted, until it is next reset.
+const ABORTED: State = 1 << (State::BITS - 2);
+/// The number of low bits of the generation kept in the state, which only needs to tell apart the
+/// cycles completed since `CountdownEvent::generation` was last brought up to date.
+const GENERATION_BITS: u32 = State::BITS / 4;
+const GENERATION_MASK: State = (1 << GENERATION_BITS) - 1;
+const COUNT_BITS: u32 = State::BITS - 2 - GENERATION_BITS;
+const COUNT_MASK: State = (1 << COUNT_BITS) - 1;
+/// The largest count supported, with as much room again for extra ticks below zero.
+const MAX_COUNT: Count = (1 << (COUNT_BITS - 1)) - 1;
+
+/// Converts a count passed to the public API to the internal count type.
+pub(crate) fn to_count(count: usize) -> Count {
+    match Count::try_from(count) {
+        Ok(count) if count <= MAX_COUNT => count,
+        _ => panic!("count exceeds the maximum supported count"),
+    }
+}
+
+/// Extracts the (sign-extended) count from `state`.
+const fn count_of(state: State) -> Count {
+    ((state << (State::BITS - COUNT_BITS)) as Count) >> (State::BITS - COUNT_BITS)
+}
+
+/// Replaces the count in `state`, panicking if it is out of range.
+fn with_count(state: State, count: Count) -> State {
+    if !(-MAX_COUNT - 1..=MAX_COUNT).contains(&count) {
+        panic!("count exceeds the maximum supported count");
+    }
+    (state & !COUNT_MASK) | (count as State & COUNT_MASK)
+}
+
+/// Extracts the low bits of the generation from `state`.
+const fn generation_of(state: State) -> usize {
+    ((state >> COUNT_BITS) & GENERATION_MASK) as usize
+}
+
+/// Whether `event` should be set for `state`, i.e. whether the count has reached zero or the
+/// countdown has been aborted.
+const fn should_be_set(state: State) -> bool {
+    count_of(state) <= 0 || state & ABORTED != 0
+}
+
+/// Converts an internal count to a `usize` for the public API, clamping negative counts to zero.
+fn to_usize(count: Count) -> usize {
+    usize::try_from(count.max(0)).unwrap_or(usize::MAX)
+}
 
 /// An `Awaitable` type that can be used to block until _n_ parallel tasks have completed.
 ///
@@ -17,11 +110,13 @@ use std::time::Duration;
 /// ## Example:
 ///
 /// ```rust
-/// use rsevents_extra::{Awaitable, CountdownEvent};
+/// use rsevents_extra::{Awaitable, CountdownEvent, OverTickPolicy};
 ///
 /// // CountdownEvent::new() is const and can be used directly in a static
-/// // context without needing lazy_static or once_cell:
-/// static ALMOST_DONE: CountdownEvent = CountdownEvent::new(0);
+/// // context without needing lazy_static or once_cell. Ticks past zero are
+/// // expected here, so they are counted rather than treated as a bug.
+/// static ALMOST_DONE: CountdownEvent =
+///     CountdownEvent::new(0).with_over_tick_policy(OverTickPolicy::Count);
 ///
 /// fn worker_thread() {
 ///     for _ in 0..250 {
@@ -57,189 +152,2271 @@ use std::time::Duration;
 /// }
 /// ```
 pub struct CountdownEvent {
-    /// The internal count tracking the number of events left. While we could use an unsigned type
-    /// and just wrap on under/overflow and that would be fine (since we only set the event in
-    /// response to a `tick()` call and never reset it), it means calls to `CountdownEvent::count()`
-    /// would report the overflow and we couldn't intercept it.
-    count: AtomicIsize,
-    /// The core synchronization event, waited on by calls to `wait()` but only accessed on the
-    /// final call to `tick()`.
+    /// The internal count tracking the number of events left, packed with the low bits of the
+    /// generation and the abort flag (see [`State`]). The count is signed so that extra ticks can be
+    /// counted and `CountdownEvent::count()` can clamp them rather than report an underflow.
+    state: AtomicState,
+    /// The count the event was created with or last [reset](CountdownEvent::reset()) to.
+    initial: AtomicCount,
+    /// The core synchronization event, waited on by calls to `wait()` but only set or reset by the
+    /// thread holding `SYNCING` (see `sync_event()`).
     event: ManualResetEvent,
-    /// The event used to adjudicate disputes between calls to `reset()` or `increment()` coinciding
-    /// with the final call to `tick()`.
+    /// Serializes calls to `reset()` (and its variants) with each other. Ticks never wait on it: a
+    /// tick completing a cycle refills the count in the same atomic operation that takes it to
+    /// zero, so a reset can't slip in between.
     event2: AutoResetEvent,
+    /// What to do when the event is ticked while the count is already zero.
+    policy: OverTickPolicy,
+    /// Whether the event automatically resets to its initial count each time it reaches zero.
+    cyclic: bool,
+    /// The number of cycles completed by a [cyclic](CountdownEvent::cyclic()) event, as of the
+    /// last tick to complete one. This lags the low bits of the generation in `state` by the
+    /// cycles whose ticks have yet to record them here. See `generation()`.
+    generation: AtomicUsize,
+    /// Twice the number of times the event has been [reset](CountdownEvent::reset()), plus one
+    /// while a reset is replacing the count. See `round_state()`.
+    round: AtomicUsize,
+    /// The state only needed once the countdown is aborted with a reason, watched, observed or
+    /// subscribed to.
+    extras: LazyBox<Extras>,
+}
+
+struct Extras {
+    /// The reason passed to [`CountdownEvent::abort_with()`], if any.
+    abort_reason: Mutex<Option<String>>,
+    /// Threads blocked in [`CountdownEvent::wait_for_count()`], waiting for the count to drop to
+    /// their threshold.
+    watchers: Mutex<Vec<Arc<Watcher>>>,
+    /// The length of `watchers`, letting `tick()` skip taking the lock when nobody is watching.
+    watching: AtomicUsize,
+    /// Callbacks registered via [`CountdownEvent::on_tick()`].
+    observers: Mutex<Vec<TickCallback>>,
+    /// Whether `observers` is non-empty, letting `tick()` skip taking the lock otherwise.
+    observed: AtomicBool,
+    /// The wakers of tasks awaiting [`CountdownEvent::wait_async()`] and of
+    /// [subscribers](Subscribe), woken whenever the event is set or a cycle completes.
+    wakers: WakerList,
+}
+
+impl Default for Extras {
+    fn default() -> Self {
+        Extras {
+            abort_reason: Mutex::new(None),
+            watchers: Mutex::new(Vec::new()),
+            watching: AtomicUsize::new(0),
+            observers: Mutex::new(Vec::new()),
+            observed: AtomicBool::new(false),
+            wakers: WakerList::new(),
+        }
+    }
+}
+
+type TickCallback = Box<dyn Fn(usize) + Send + Sync>;
+
+/// A thread waiting for the count to drop to `threshold`, signalled via `event`.
+struct Watcher {
+    threshold: Count,
+    /// For watchers in [`CountdownEvent::wait_round()`], the round being waited on. Such watchers
+    /// are signalled whenever the round may have completed or been reset, and check which it was
+    /// themselves.
+    round: Option<usize>,
+    /// For watchers in [`CountdownEvent::wait_for_change()`], the last count seen by the caller.
+    /// Such watchers are signalled once the count differs from it.
+    changed_from: Option<usize>,
+    /// Set (before `event`) if the watcher was released by an abort of its round rather than by
+    /// its condition being met, so that the abort is still reported after a later reset.
+    aborted: AtomicBool,
+    event: ManualResetEvent,
+}
+
+/// Determines how a [`CountdownEvent`] treats calls to [`CountdownEvent::tick()`] made after the
+/// count has already reached zero. See [`CountdownEvent::with_over_tick_policy()`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum OverTickPolicy {
+    /// Panic, treating extra ticks as a bug in the caller. This is the default in debug builds.
+    Panic,
+    /// Silently ignore extra ticks, leaving the count at zero.
+    Saturate,
+    /// Keep track of extra ticks, which can be retrieved via [`CountdownEvent::extra_ticks()`].
+    /// Subsequent increments first offset any extra ticks before the count rises above zero. This is
+    /// the default in release builds.
+    Count,
+}
+
+impl OverTickPolicy {
+    /// The policy of events created without [`CountdownEvent::with_over_tick_policy()`].
+    const DEFAULT: OverTickPolicy = match cfg!(debug_assertions) {
+        true => OverTickPolicy::Panic,
+        false => OverTickPolicy::Count,
+    };
 }
 
 impl CountdownEvent {
-    /// Creates a new countdown event with the internal count initialized to `count`. If a count of
-    /// zero is specified, the event is immediately set.
-    ///
-    /// This is a `const` function and can be used in a `static` context, (e.g. to declare a shared,
-    /// static variable without using lazy_static or once_cell).
-    pub const fn new(count: usize) -> Self {
-        const MAX: usize = isize::MAX as usize;
-        let count: isize = match count {
-            0..=MAX => count as isize,
-            _ => panic!("count cannot exceeed isize::MAX"),
-        };
+    loom_const_fn! {
+        /// Creates a new countdown event with the internal count initialized to `count`. If a count
+        /// of zero is specified, the event is immediately set.
+        ///
+        /// This is a `const` function and can be used in a `static` context, (e.g. to declare a
+        /// shared, static variable without using lazy_static or once_cell).
+        pub fn new(count: usize) -> Self {
+            if count as u128 > MAX_COUNT as u128 {
+                panic!("count exceeds the maximum supported count");
+            }
+            let count = count as Count;
 
-        Self {
-            count: AtomicIsize::new(count),
-            event: ManualResetEvent::new(if count == 0 {
-                EventState::Set
-            } else {
-                EventState::Unset
-            }),
-            event2: AutoResetEvent::new(EventState::Set),
+            Self {
+                state: AtomicState::new(count as State),
+                initial: AtomicCount::new(count),
+                event: ManualResetEvent::new(if count == 0 {
+                    EventState::Set
+                } else {
+                    EventState::Unset
+                }),
+                event2: AutoResetEvent::new(EventState::Set),
+                policy: OverTickPolicy::DEFAULT,
+                cyclic: false,
+                generation: AtomicUsize::new(0),
+                round: AtomicUsize::new(0),
+                extras: LazyBox::new(),
+            }
+        }
+    }
+
+    /// Sets the policy for ticks made after the count has already reached zero, which by default
+    /// [panic](OverTickPolicy::Panic) in debug builds and are [counted](OverTickPolicy::Count) in
+    /// release builds. Useful when fanning out work that may legitimately produce more completions
+    /// than the number being waited for.
+    ///
+    /// ```rust
+    /// use rsevents_extra::{CountdownEvent, OverTickPolicy};
+    ///
+    /// let countdown = CountdownEvent::new(1).with_over_tick_policy(OverTickPolicy::Saturate);
+    /// countdown.tick();
+    /// countdown.tick();
+    /// // The extra tick was ignored, so a single increment unsets the event again.
+    /// countdown.increment();
+    /// assert_eq!(countdown.count(), 1);
+    /// ```
+    pub const fn with_over_tick_policy(mut self, policy: OverTickPolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    /// Makes the countdown cyclic: each time the count reaches zero, all waiters are woken and the
+    /// count automatically resets to its [initial count](Self::initial_count()) for the next
+    /// round, turning the event into a reusable barrier.
+    ///
+    /// Waiters on a cyclic event block until the current cycle completes, and can find out which
+    /// cycle that was via [`wait_cycle()`](Self::wait_cycle) and
+    /// [`generation()`](Self::generation).
+    ///
+    /// ```rust
+    /// use rsevents_extra::CountdownEvent;
+    ///
+    /// let round = CountdownEvent::new(3).cyclic();
+    /// std::thread::scope(|scope| {
+    ///     for _ in 0..3 {
+    ///         scope.spawn(|| {
+    ///             for _ in 0..5 {
+    ///                 // <do this round's work here>
+    ///                 round.tick();
+    ///             }
+    ///         });
+    ///     }
+    /// });
+    /// assert_eq!(round.generation(), 5);
+    /// assert_eq!(round.count(), 3);
+    /// ```
+    pub const fn cyclic(mut self) -> Self {
+        self.cyclic = true;
+        self
+    }
+
+    /// Returns the current round of the countdown, which starts at zero and advances each time the
+    /// event is [reset](Self::reset()). Pass it to [`wait_round()`](Self::wait_round) to wait for
+    /// the work of a particular round, without being caught out by resets made in the meantime.
+    pub fn round(&self) -> usize {
+        self.round.load(Ordering::SeqCst) / 2
+    }
+
+    /// Waits for the countdown to reach zero in the given `round`, returning `true` if it did or
+    /// `false` if the round was superseded by a [reset](Self::reset()) (or the countdown was
+    /// [aborted](Self::abort)) first. Unlike [`wait()`](Awaitable::wait), a waiter is never left
+    /// waiting on the work of a later round than the one it meant to wait for.
+    ///
+    /// ```rust
+    /// use rsevents_extra::CountdownEvent;
+    ///
+    /// let countdown = CountdownEvent::new(1);
+    /// let round = countdown.round();
+    /// std::thread::scope(|scope| {
+    ///     let waiter = scope.spawn(|| countdown.wait_round(round));
+    ///     // The outstanding work is abandoned and a new round started.
+    ///     countdown.reset(3);
+    ///     assert_eq!(waiter.join().unwrap(), false);
+    /// });
+    /// ```
+    pub fn wait_round(&self, round: usize) -> bool {
+        loop {
+            if let Some(completed) = self.round_state(round) {
+                return completed;
+            }
+            let watcher = self.register_watcher(Watcher {
+                threshold: 0,
+                round: Some(round),
+                changed_from: None,
+                aborted: AtomicBool::new(false),
+                event: ManualResetEvent::new(EventState::Unset),
+            });
+            watcher.event.wait();
+        }
+    }
+
+    /// Returns whether `round` has completed (`Some(true)`), has been superseded by a reset or
+    /// abort (`Some(false)`), or is still outstanding (`None`).
+    fn round_state(&self, round: usize) -> Option<bool> {
+        loop {
+            if self.is_aborted() {
+                return Some(false);
+            }
+            // `round` is odd while a reset is replacing the count, and is read before and after
+            // the count (as with a seqlock) so that a count is never attributed to the wrong round.
+            let before = self.round.load(Ordering::SeqCst);
+            if before % 2 == 1 {
+                std::hint::spin_loop();
+                continue;
+            }
+            if before / 2 != round {
+                return Some(false);
+            }
+            let count = self.count_raw();
+            if self.round.load(Ordering::SeqCst) != before {
+                continue;
+            }
+            return match count <= 0 {
+                true => Some(true),
+                false => None,
+            };
+        }
+    }
+
+    /// Waits for the countdown to reach zero in the current [round](Self::round), returning `false`
+    /// if the event is reset (or aborted) before then. See [`wait_round()`](Self::wait_round).
+    pub fn wait_current_round(&self) -> bool {
+        self.wait_round(self.round())
+    }
+
+    /// Returns the number of cycles a [cyclic](Self::cyclic()) event has completed. Always zero
+    /// for a regular countdown event.
+    pub fn generation(&self) -> usize {
+        // Read in this order, the state includes every cycle recorded in `generation` and any
+        // completed since, which can't be as many as `GENERATION_BITS` can count.
+        let recorded = self.generation.load(Ordering::SeqCst);
+        let state = self.state.load(Ordering::SeqCst);
+        recorded + (generation_of(state).wrapping_sub(recorded) & GENERATION_MASK as usize)
+    }
+
+    /// Waits for the current cycle of a [cyclic](Self::cyclic()) event to complete, returning its
+    /// generation (i.e. the value [`generation()`](Self::generation) took on as it completed).
+    ///
+    /// Panics if the event is not cyclic.
+    pub fn wait_cycle(&self) -> usize {
+        match self.watch_cycle(None) {
+            Some(_) if self.is_aborted() => panic!("CountdownEvent was aborted!"),
+            Some(generation) => generation,
+            None => unreachable!(),
+        }
+    }
+
+    /// Waits for up to `limit` for the current cycle of a [cyclic](Self::cyclic()) event to
+    /// complete, returning its generation or an error in case of a timeout.
+    ///
+    /// Panics if the event is not cyclic.
+    pub fn wait_cycle_for(&self, limit: Duration) -> Result<usize, TimeoutError> {
+        match self.watch_cycle(Some(limit)) {
+            Some(generation) if !self.is_aborted() => Ok(generation),
+            _ => Err(TimeoutError),
+        }
+    }
+
+    /// Waits (for up to `limit`, if specified) for the current cycle to complete, returning the
+    /// generation it completed.
+    fn watch_cycle(&self, limit: Option<Duration>) -> Option<usize> {
+        assert!(self.cyclic, "CountdownEvent is not cyclic");
+
+        let generation = self.generation();
+        let watcher = self.add_watcher(0);
+        // A cycle is completed before its watchers are notified.
+        if self.generation() != generation && self.remove_watcher(&watcher) {
+            return Some(generation + 1);
+        }
+
+        match self.await_watcher(&watcher, limit) {
+            true => Some(generation + 1),
+            false => None,
         }
     }
 
+    /// Returns `state` with the count refilled from the initial count after it dropped to `count`
+    /// (zero or below), completing a cycle of a cyclic event, along with the number of cycles
+    /// completed. Ticks racing the end of a cycle count toward the next one, so a tick taking the
+    /// count far enough below zero completes several.
+    fn complete_cycles(&self, state: State, count: Count) -> (State, usize) {
+        let initial = self.initial.load(Ordering::SeqCst);
+        let cycles = match initial {
+            0 => 1,
+            initial => (-count / initial + 1) as usize,
+        };
+        let count = count + initial * cycles as Count;
+        let generation = generation_of(state).wrapping_add(cycles) as State & GENERATION_MASK;
+        let state = (state & !(GENERATION_MASK << COUNT_BITS)) | (generation << COUNT_BITS);
+        (with_count(state, count), cycles)
+    }
+
     /// Decrements the internal countdown. When the internal countdown reaches zero, the countdown
     /// event enters a [set](EventState::Set) state and any outstanding or future calls to
     /// [`CountdownEvent::wait()`] will be let through without blocking (until [the event is
     /// reset](CountdownEvent::reset()) [or incremented](Self::increment())).
-    pub fn decrement(&self) {
-        let prev = self.count.fetch_sub(1, Ordering::Relaxed);
-        if prev == 1 {
-            self.event2.wait();
-            if self.count.load(Ordering::Relaxed) == 0 {
-                self.event.set();
+    ///
+    /// Calling this after the count has already reached zero is handled according to the event's
+    /// [`OverTickPolicy`].
+    ///
+    /// Returns `true` if this call brought the count down to zero (and so set the event), which
+    /// happens for exactly one of the calls that complete the countdown, making it a convenient
+    /// place to run any finalization that should only happen once.
+    pub fn decrement(&self) -> bool {
+        self.decrement_by(1)
+    }
+
+    /// Decrements the internal countdown by `n`, setting the event if this brings the count down to
+    /// zero. Ticks past zero are handled according to the event's [`OverTickPolicy`].
+    ///
+    /// Returns whether this call completed the countdown (or the current cycle).
+    pub(crate) fn decrement_by(&self, n: Count) -> bool {
+        let mut cycles = 0;
+        let prev = match self.update(|state| {
+            let prev = count_of(state);
+            let count = match self.policy {
+                OverTickPolicy::Count => prev - n,
+                OverTickPolicy::Saturate if prev > 0 => (prev - n).max(0),
+                _ if prev >= n => prev - n,
+                _ => return None,
+            };
+            cycles = 0;
+            if self.cyclic && prev > 0 && count <= 0 {
+                let (state, completed) = self.complete_cycles(state, count);
+                cycles = completed;
+                return Some(state);
+            }
+            Some(with_count(state, count))
+        }) {
+            Ok(prev) => count_of(prev),
+            Err(_) if self.policy == OverTickPolicy::Panic => {
+                panic!("CountdownEvent ticked past zero!")
+            }
+            Err(_) => return false,
+        };
+        let count = prev - n;
+
+        let completed = prev > 0 && count <= 0;
+        if cycles > 0 {
+            self.generation.fetch_add(cycles, Ordering::SeqCst);
+            self.wake_subscribers();
+        }
+
+        self.notify_watchers(count);
+
+        match self.extras.get() {
+            Some(extras) if extras.observed.load(Ordering::Acquire) => {
+                let count = to_usize(count);
+                for callback in extras.observers.lock().unwrap().iter() {
+                    callback(count);
+                }
+            }
+            _ => (),
+        }
+
+        completed
+    }
+
+    /// Registers a callback to be invoked on every call to [`tick()`](Self::tick) with the new
+    /// count, letting a reporting thread observe progress without polling
+    /// [`count()`](Self::count). Callbacks remain registered for the lifetime of the event.
+    ///
+    /// Callbacks are run on the ticking thread (and may be run concurrently if multiple threads
+    /// tick the event at the same time), so they should be quick, e.g. forwarding the count over a
+    /// channel. A callback must not tick the event or register further callbacks itself.
+    ///
+    /// ```rust
+    /// use rsevents_extra::CountdownEvent;
+    /// use std::sync::mpsc;
+    ///
+    /// let countdown = CountdownEvent::new(3);
+    /// let (sender, progress) = mpsc::channel();
+    /// countdown.on_tick(move |count| sender.send(count).unwrap());
+    ///
+    /// countdown.tick();
+    /// countdown.tick();
+    /// assert_eq!(progress.try_iter().collect::<Vec<_>>(), [2, 1]);
+    /// ```
+    pub fn on_tick(&self, callback: impl Fn(usize) + Send + Sync + 'static) {
+        let extras = self.extras.get_or_init();
+        extras.observers.lock().unwrap().push(Box::new(callback));
+        extras.observed.store(true, Ordering::Release);
+    }
+
+    /// Atomically replaces the state with `f(state)`, returning the previous state, or the current
+    /// one as an error if `f` returns `None`.
+    ///
+    /// The call that changes whether the event should be set (by taking the count across zero, or
+    /// by aborting or resetting the countdown) takes `SYNCING` along with it and brings the event
+    /// in line, unless another thread already holds `SYNCING`: that thread then picks up the
+    /// change before letting go. This way only one thread sets or resets the event at a time, and
+    /// the event is never left out of step with the state once `SYNCING` is released.
+    fn update(&self, mut f: impl FnMut(State) -> Option<State>) -> Result<State, State> {
+        let mut syncing = false;
+        let result = self
+            .state
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |state| {
+                let new = f(state)?;
+                syncing = state & SYNCING == 0 && should_be_set(state) != should_be_set(new);
+                Some(match syncing {
+                    true => new | SYNCING,
+                    false => new,
+                })
+            });
+        if syncing && result.is_ok() {
+            self.sync_event();
+        }
+        result
+    }
+
+    /// Brings the event in line with the state, on behalf of the call that took `SYNCING` (and of
+    /// any calls that change the state before it is released).
+    fn sync_event(&self) {
+        let mut state = self.state.load(Ordering::SeqCst);
+        loop {
+            let set = should_be_set(state);
+            match set {
+                true => self.event.set(),
+                false => self.event.reset(),
+            }
+            // Let go of `SYNCING`, unless the event has to be brought in line again first.
+            loop {
+                match self.state.compare_exchange_weak(
+                    state,
+                    state & !SYNCING,
+                    Ordering::SeqCst,
+                    Ordering::SeqCst,
+                ) {
+                    Ok(_) if set => return self.wake_subscribers(),
+                    Ok(_) => return,
+                    Err(actual) => state = actual,
+                }
+                if should_be_set(state) != set {
+                    break;
+                }
             }
-            self.event2.set();
         }
-        #[cfg(debug_assertions)]
-        if prev == 0 {
-            panic!("tick() called more times than outstanding jobs!");
+    }
+
+    /// Wakes the tasks awaiting the event and its subscribers.
+    fn wake_subscribers(&self) {
+        // Pairs with the fence in `WakerList::register()`: either the subscriber sees the new
+        // state, or we see the extras it allocated to register its waker with.
+        fence(Ordering::SeqCst);
+        if let Some(extras) = self.extras.get() {
+            extras.wakers.wake_all();
+        }
+    }
+
+    /// Wakes any threads in [`wait_for_count()`](Self::wait_for_count) whose threshold has been
+    /// reached by the count changing to `count`, any threads in
+    /// [`wait_for_change()`](Self::wait_for_change) that last saw a different count, and any threads
+    /// in [`wait_round()`](Self::wait_round) if the round may have completed.
+    fn notify_watchers(&self, count: Count) {
+        self.signal_watchers(|watcher| Self::is_signalled(watcher, count));
+    }
+
+    /// Returns whether `watcher`'s condition is met by the count being `count`. Round watchers
+    /// check what became of their round themselves, so are woken whenever it may have completed.
+    fn is_signalled(watcher: &Watcher, count: Count) -> bool {
+        match (watcher.round, watcher.changed_from) {
+            (Some(_), _) => count <= 0,
+            (None, Some(seen)) => to_usize(count) != seen,
+            (None, None) => count <= watcher.threshold,
         }
     }
 
+    /// Signals (and unregisters) the watchers matching `predicate`.
+    fn signal_watchers(&self, mut predicate: impl FnMut(&Watcher) -> bool) {
+        // Pairs with the fence in `add_watcher()`: either the watcher sees the new state, or we see
+        // the watcher.
+        fence(Ordering::SeqCst);
+        let extras = match self.extras.get() {
+            Some(extras) if extras.watching.load(Ordering::Relaxed) != 0 => extras,
+            _ => return,
+        };
+
+        let mut watchers = extras.watchers.lock().unwrap();
+        watchers.retain(|watcher| {
+            if predicate(watcher) {
+                watcher.event.set();
+                false
+            } else {
+                true
+            }
+        });
+        extras.watching.store(watchers.len(), Ordering::Relaxed);
+    }
+
     /// An alias for [`decrement()`](Self::decrement) for backwards compatibility purposes.
     #[inline(always)]
-    pub fn tick(&self) {
+    pub fn tick(&self) -> bool {
         self.decrement()
     }
 
+    /// Decrements the internal countdown by `weight` rather than by one, for countdowns tracking a
+    /// weighted total (such as a number of bytes) instead of a number of work items. The event
+    /// becomes set once the count reaches zero, exactly as with [`tick()`](Self::tick).
+    ///
+    /// A weight that takes the count past zero is handled according to the event's
+    /// [`OverTickPolicy`]: the excess is tracked as extra ticks, saturated to zero or causes a
+    /// panic (in which case the count is left unchanged).
+    ///
+    /// Returns `true` if this call brought the count down to zero.
+    ///
+    /// ## Example:
+    ///
+    /// ```rust
+    /// use rsevents_extra::CountdownEvent;
+    ///
+    /// // Track the download of a 4 KiB file, one chunk at a time.
+    /// let remaining = CountdownEvent::new(4096);
+    /// assert_eq!(remaining.tick_weight(1500), false);
+    /// assert_eq!(remaining.tick_weight(1500), false);
+    /// assert_eq!(remaining.tick_weight(1096), true);
+    /// assert_eq!(remaining.is_set(), true);
+    /// ```
+    pub fn tick_weight(&self, weight: usize) -> bool {
+        self.decrement_by(to_count(weight))
+    }
+
     /// Increment the internal count (e.g. to add a work item).
     ///
     /// This resets the event (makes it unavailable) if the previous count was zero.
     pub fn increment(&self) {
-        let prev = self.count.fetch_add(1, Ordering::Relaxed);
-        if prev == 0 {
-            self.event2.wait();
-            if self.count.load(Ordering::Relaxed) == 0 {
-                self.event.set();
+        self.increment_by(1);
+    }
+
+    /// Increments the internal count by `n` in a single atomic operation (e.g. to add a batch of
+    /// newly discovered work items), which is cheaper than calling
+    /// [`increment()`](Self::increment) `n` times.
+    ///
+    /// This resets the event (makes it unavailable) if the count was previously zero (or below, if
+    /// [extra ticks](Self::extra_ticks) have been counted) and is now above zero.
+    pub fn increment_by(&self, n: usize) {
+        let n = to_count(n);
+
+        let prev = match self.update(|state| Some(with_count(state, count_of(state) + n))) {
+            Ok(prev) => count_of(prev),
+            Err(_) => unreachable!(),
+        };
+        self.notify_watchers(prev + n);
+    }
+
+    /// Runs `f` with a [`CountdownScope`] for spawning tracked scoped threads, then waits for the
+    /// countdown to reach zero before returning `f`'s result.
+    ///
+    /// Each thread spawned via [`CountdownScope::spawn()`] is [registered](Self::register) with
+    /// the countdown before it starts and ticks it when it completes (or panics), packaging
+    /// WaitGroup-style structured concurrency on top of the countdown event.
+    ///
+    /// ```rust
+    /// use rsevents_extra::CountdownEvent;
+    /// use std::sync::atomic::{AtomicUsize, Ordering};
+    ///
+    /// let pending = CountdownEvent::new(0);
+    /// let done = AtomicUsize::new(0);
+    /// pending.scope(|s| {
+    ///     for _ in 0..4 {
+    ///         s.spawn(|| done.fetch_add(1, Ordering::Relaxed));
+    ///     }
+    /// });
+    /// assert_eq!(done.load(Ordering::Relaxed), 4);
+    /// ```
+    pub fn scope<'env, F, T>(&'env self, f: F) -> T
+    where
+        F: for<'scope> FnOnce(CountdownScope<'scope, 'env>) -> T,
+    {
+        std::thread::scope(|scope| {
+            let result = f(CountdownScope {
+                scope,
+                countdown: self,
+            });
+            self.wait();
+            result
+        })
+    }
+
+    /// Increments the internal count only if the event hasn't already been set, returning whether
+    /// the count was incremented.
+    ///
+    /// Unlike [`increment()`](Self::increment), this never resurrects an event that has already
+    /// counted down to zero (and may have already been observed as complete by its waiters), making
+    /// it safe for late producers to race the final call to [`tick()`](Self::tick).
+    pub fn try_add_count(&self) -> bool {
+        let prev = self.update(|state| match count_of(state) {
+            c if c > 0 => Some(with_count(state, c + 1)),
+            _ => None,
+        });
+        match prev {
+            // The count was already above zero, so the event's state is unchanged, but watchers
+            // still need to hear about the new count (as with `increment_by()`).
+            Ok(prev) => {
+                self.notify_watchers(count_of(prev) + 1);
+                true
             }
-            self.event2.set();
+            Err(_) => false,
         }
     }
 
-    /// Resets a countdown event to the specified `count`. If a count of zero is specified, the
-    /// countdown event is immediately set.
-    pub fn reset(&self, count: usize) {
-        let count: isize = match count.try_into() {
-            Ok(count) => count,
-            Err(_) => panic!("count cannot exceeed isize::MAX"),
-        };
+    /// Registers a new work item by incrementing the count, returning a [`TickGuard`] that ticks
+    /// the countdown when dropped. As the guard is also dropped if the thread panics, a work item
+    /// tracked this way can never be left outstanding.
+    ///
+    /// ```rust
+    /// use rsevents_extra::{Awaitable, CountdownEvent};
+    ///
+    /// let pending = CountdownEvent::new(0);
+    ///
+    /// std::thread::scope(|scope| {
+    ///     for _ in 0..4 {
+    ///         let guard = pending.register();
+    ///         scope.spawn(move || {
+    ///             let _guard = guard;
+    ///             // <do some work here>
+    ///         });
+    ///     }
+    ///
+    ///     pending.wait();
+    /// });
+    /// ```
+    #[must_use = "The countdown is immediately ticked if the guard is dropped"]
+    pub fn register(&self) -> TickGuard<'_> {
+        self.increment();
+        TickGuard { countdown: self }
+    }
+
+    /// Aborts the countdown, e.g. because a worker hit a fatal error and the remaining ticks will
+    /// never come. All current and future waiters are released: the checked waits (such as
+    /// [`wait_checked()`](Self::wait_checked)) return [`CountdownError::Aborted`], other timed waits
+    /// return a [`TimeoutError`], and the infallible [`wait()`](Awaitable::wait) panics.
+    ///
+    /// The countdown remains aborted until it is [reset](Self::reset()).
+    ///
+    /// ```rust
+    /// use rsevents_extra::{CountdownError, CountdownEvent};
+    ///
+    /// let countdown = CountdownEvent::new(4);
+    /// std::thread::scope(|scope| {
+    ///     scope.spawn(|| countdown.abort_with("disk full"));
+    ///     let error = countdown.wait_checked().unwrap_err();
+    ///     assert_eq!(error, CountdownError::Aborted(Some("disk full".into())));
+    /// });
+    /// ```
+    pub fn abort(&self) {
+        self.abort_inner(None)
+    }
+
+    /// Aborts the countdown with the given `reason`, which is reported to waiters via
+    /// [`CountdownError::Aborted`]. See [`abort()`](Self::abort).
+    pub fn abort_with(&self, reason: impl Into<String>) {
+        self.abort_inner(Some(reason.into()))
+    }
+
+    fn abort_inner(&self, reason: Option<String>) {
+        // Only allocate the extras to store a reason, or to clear the reason of a previous abort.
+        if reason.is_some() || self.extras.get().is_some() {
+            *self.extras.get_or_init().abort_reason.lock().unwrap() = reason;
+        }
+        let _ = self.update(|state| Some(state | ABORTED));
 
-        self.count.store(count, Ordering::Relaxed);
-        if self.count.load(Ordering::Relaxed) == 0 {
-            self.event2.wait();
-            if self.count.load(Ordering::Relaxed) == 0 {
-                self.event.set();
+        // Any watchers registered from here on are signalled immediately by `register_watcher()`.
+        // Pairs with the fence there: either the watcher sees the abort, or we see the extras it
+        // allocated to register with.
+        fence(Ordering::SeqCst);
+        if let Some(extras) = self.extras.get() {
+            let mut watchers = extras.watchers.lock().unwrap();
+            for watcher in watchers.drain(..) {
+                watcher.aborted.store(true, Ordering::Release);
+                watcher.event.set();
             }
-            self.event2.set();
-            self.event.set();
+            extras.watching.store(0, Ordering::Relaxed);
         }
     }
 
-    /// Get the current internal countdown value.
-    pub fn count(&self) -> usize {
-        match self.count.load(Ordering::Relaxed) {
-            count @ 0.. => count as usize,
-            _ => 0,
+    /// Returns whether the countdown has been [aborted](Self::abort) since it was last reset.
+    pub fn is_aborted(&self) -> bool {
+        self.state.load(Ordering::SeqCst) & ABORTED != 0
+    }
+
+    /// Returns the error waiters should report if the countdown has been aborted.
+    fn check_aborted(&self) -> Result<(), CountdownError> {
+        match self.is_aborted() {
+            false => Ok(()),
+            true => {
+                Err(CountdownError::Aborted(self.extras.get().and_then(
+                    |extras| extras.abort_reason.lock().unwrap().clone(),
+                )))
+            }
         }
     }
-}
 
-impl Awaitable<'_> for CountdownEvent {
-    type T = ();
-    type Error = TimeoutError;
+    /// Waits for the countdown to reach zero (or for the current cycle to complete, if the event
+    /// is [cyclic](Self::cyclic())), returning [`CountdownError::Aborted`] if the countdown is
+    /// [aborted](Self::abort) instead.
+    pub fn wait_checked(&self) -> Result<(), CountdownError> {
+        self.wait_raw(None);
+        self.check_aborted()
+    }
 
-    /// Waits for the internal countdown of the [`CountdownEvent`] to reach zero.
-    fn try_wait(&self) -> Result<(), Infallible> {
-        self.event.try_wait()
+    /// Waits for up to `limit` for the countdown to reach zero (or for the current cycle to
+    /// complete, if the event is [cyclic](Self::cyclic())), returning an error if the time limit
+    /// elapses first or the countdown is [aborted](Self::abort).
+    pub fn wait_checked_for(&self, limit: Duration) -> Result<(), CountdownError> {
+        let completed = self.wait_raw(Some(limit));
+        self.check_aborted()?;
+        match completed {
+            true => Ok(()),
+            false => Err(CountdownError::TimedOut {
+                remaining: self.count(),
+            }),
+        }
     }
 
-    /// Waits for the internal countdown of the [`CountdownEvent`] to reach zero or returns an error
-    /// in case of a timeout.
-    fn try_wait_for(&self, limit: Duration) -> Result<(), TimeoutError> {
-        self.event.try_wait_for(limit)
+    /// Returns a [`Future`] that completes once the countdown reaches zero (or the current cycle
+    /// completes, if the event is [cyclic](Self::cyclic())), with the same result as
+    /// [`wait_checked()`](Self::wait_checked). Only available with the `async` feature enabled.
+    ///
+    /// The future registers its waker with the event, which wakes it directly (on whichever
+    /// executor it runs) when the countdown completes or is [aborted](Self::abort).
+    ///
+    /// ```rust
+    /// use rsevents_extra::CountdownEvent;
+    /// # fn block_on<F: std::future::Future>(future: F) -> F::Output {
+    /// #     struct Unparker(std::thread::Thread);
+    /// #     impl std::task::Wake for Unparker {
+    /// #         fn wake(self: std::sync::Arc<Self>) {
+    /// #             self.0.unpark();
+    /// #         }
+    /// #     }
+    /// #     let waker = std::sync::Arc::new(Unparker(std::thread::current())).into();
+    /// #     let mut context = std::task::Context::from_waker(&waker);
+    /// #     let mut future = std::pin::pin!(future);
+    /// #     loop {
+    /// #         match future.as_mut().poll(&mut context) {
+    /// #             std::task::Poll::Ready(result) => return result,
+    /// #             std::task::Poll::Pending => std::thread::park(),
+    /// #         }
+    /// #     }
+    /// # }
+    ///
+    /// let uploads = CountdownEvent::new(3);
+    /// std::thread::scope(|scope| {
+    ///     for _ in 0..3 {
+    ///         scope.spawn(|| uploads.tick());
+    ///     }
+    ///
+    ///     // e.g. from within an async fn
+    ///     block_on(async {
+    ///         uploads.wait_async().await.unwrap();
+    ///     });
+    /// });
+    /// ```
+    #[cfg(feature = "async")]
+    pub fn wait_async(&self) -> CountdownWait<'_> {
+        CountdownWait {
+            countdown: self,
+            generation: self.generation(),
+            waker_id: None,
+        }
     }
 
-    /// An optimized (wait-free, lock-free) check to see if the `CountdownEvent` has reached zero or
-    /// not.
-    fn try_wait0(&self) -> Result<(), TimeoutError> {
-        self.event.try_wait0()
+    /// Returns whether the countdown has reached zero or, for a cyclic event, whether the cycle
+    /// with the given `generation` has completed.
+    fn is_complete(&self, generation: usize) -> bool {
+        match self.cyclic {
+            true => self.generation() != generation,
+            false => self.count_raw() <= 0,
+        }
     }
-}
 
-#[test]
-fn basic_countdown() {
-    let countdown = CountdownEvent::new(1);
-    assert_eq!(countdown.wait0(), false);
-    countdown.tick();
-    assert_eq!(countdown.wait0(), true);
-}
+    /// Waits (for up to `limit`, if specified) for the countdown to reach zero or the current cycle
+    /// to complete, without checking whether the wait was released by an abort.
+    fn wait_raw(&self, limit: Option<Duration>) -> bool {
+        match (self.cyclic, limit) {
+            (true, limit) => self.watch_cycle(limit).is_some(),
+            (false, None) => loop {
+                self.event.wait();
+                if self.is_complete(0) || self.is_aborted() {
+                    return true;
+                }
+                // The count has risen above zero again, and the event is about to be reset.
+                std::thread::yield_now();
+            },
+            (false, Some(limit)) => {
+                let deadline = Instant::now() + limit;
+                loop {
+                    let remaining = deadline.saturating_duration_since(Instant::now());
+                    if !self.event.wait_for(remaining) {
+                        return false;
+                    }
+                    if self.is_complete(0) || self.is_aborted() {
+                        return true;
+                    }
+                    std::thread::yield_now();
+                }
+            }
+        }
+    }
 
-#[test]
-fn reset_countdown() {
-    let countdown = CountdownEvent::new(1);
-    assert_eq!(countdown.wait0(), false);
-    countdown.tick();
-    assert_eq!(countdown.wait0(), true);
-    countdown.reset(1);
-    assert_eq!(countdown.wait0(), false);
-}
+    /// Resets a countdown event to the specified `count`, which also becomes the event's
+    /// [initial count](Self::initial_count()). If a count of zero is specified, the countdown event
+    /// is immediately set; otherwise it is un-set if a previous round had set it, so waiters block
+    /// until the new count is reached.
+    pub fn reset(&self, count: usize) {
+        let count = to_count(count);
 
-#[test]
-fn start_at_zero() {
-    let countdown = CountdownEvent::new(0);
-    assert_eq!(countdown.wait0(), true);
-}
+        self.event2.wait();
+        self.initial.store(count, Ordering::Relaxed);
+        self.replace(count, false);
+        self.event2.set();
+    }
 
-#[test]
-fn threaded_countdown() {
-    use std::thread;
+    /// Atomically resets the countdown event to the specified `count` (as with
+    /// [`reset()`](Self::reset())), but only if the countdown has already reached zero. Returns
+    /// whether the event was reset.
+    ///
+    /// This lets a periodic manager re-arm a countdown without the risk of discarding work that is
+    /// still outstanding.
+    ///
+    /// ```rust
+    /// use rsevents_extra::CountdownEvent;
+    ///
+    /// let countdown = CountdownEvent::new(1);
+    /// assert!(!countdown.reset_if_set(5));
+    /// countdown.tick();
+    /// assert!(countdown.reset_if_set(5));
+    /// assert_eq!(countdown.count(), 5);
+    /// ```
+    pub fn reset_if_set(&self, count: usize) -> bool {
+        let count = to_count(count);
 
-    static COUNTDOWN: CountdownEvent = CountdownEvent::new(2);
+        self.event2.wait();
+        let set = self.replace(count, true);
+        if set {
+            self.initial.store(count, Ordering::Relaxed);
+        }
+        self.event2.set();
+        set
+    }
 
-    assert_eq!(COUNTDOWN.wait0(), false);
+    /// Resets a countdown event to its [initial count](Self::initial_count()), making it easy to
+    /// reuse the same event for each round of a repeated fan-out/fan-in loop.
+    ///
+    /// ```rust
+    /// use rsevents_extra::{Awaitable, CountdownEvent};
+    ///
+    /// let round = CountdownEvent::new(4);
+    /// for _ in 0..3 {
+    ///     std::thread::scope(|scope| {
+    ///         for _ in 0..4 {
+    ///             scope.spawn(|| round.tick());
+    ///         }
+    ///         round.wait();
+    ///     });
+    ///     round.reset_to_initial();
+    /// }
+    /// ```
+    pub fn reset_to_initial(&self) {
+        self.event2.wait();
+        self.replace(self.initial.load(Ordering::Relaxed), false);
+        self.event2.set();
+    }
 
-    let thread1 = thread::spawn(move || {
-        assert_eq!(COUNTDOWN.wait0(), false);
-        COUNTDOWN.tick();
-    });
+    /// Replaces the count (only if the event is currently set, if `only_if_set`), starting a new
+    /// round. Returns whether the count was replaced. Must be called with `event2` held.
+    fn replace(&self, count: Count, only_if_set: bool) -> bool {
+        // Mark the round as in progress while the count is replaced. See `round_state()`.
+        self.round.fetch_add(1, Ordering::SeqCst);
+        let replaced = self.update(|state| match only_if_set && count_of(state) > 0 {
+            true => None,
+            false => Some(with_count(state & !ABORTED, count)),
+        });
+        if replaced.is_err() {
+            self.round.fetch_sub(1, Ordering::SeqCst);
+            return false;
+        }
+        self.round.fetch_add(1, Ordering::SeqCst);
 
-    let thread2 = thread::spawn(move || {
-        assert_eq!(COUNTDOWN.wait0(), false);
-        COUNTDOWN.tick();
-    });
+        // Wake any threads waiting on the previous round.
+        self.signal_watchers(|watcher| watcher.round.is_some());
+        self.notify_watchers(count);
+        true
+    }
 
-    COUNTDOWN.wait();
+    /// Blocks until the count drops to `threshold` or below, e.g. to start the next phase of work
+    /// once most (but not all) tasks have completed. Waiting for a count of zero is equivalent to
+    /// [`wait()`](Awaitable::wait).
+    ///
+    /// ```rust
+    /// use rsevents_extra::CountdownEvent;
+    ///
+    /// let shards = CountdownEvent::new(10);
+    /// std::thread::scope(|scope| {
+    ///     for _ in 0..10 {
+    ///         scope.spawn(|| {
+    ///             // <load a shard here>
+    ///             shards.tick();
+    ///         });
+    ///     }
+    ///
+    ///     // Start serving requests once 80% of the shards are loaded.
+    ///     shards.wait_for_count(2);
+    ///     assert!(shards.count() <= 2);
+    /// });
+    /// ```
+    pub fn wait_for_count(&self, threshold: usize) {
+        self.watch(threshold, None);
+        if self.is_aborted() {
+            panic!("CountdownEvent was aborted!");
+        }
+    }
 
-    // To catch any panics
-    thread1.join().unwrap();
-    thread2.join().unwrap();
-}
+    /// Blocks for up to `limit` until the count drops to `threshold` or below, returning an error
+    /// if the time limit elapses first. See [`wait_for_count()`](Self::wait_for_count).
+    pub fn wait_for_count_timeout(
+        &self,
+        threshold: usize,
+        limit: Duration,
+    ) -> Result<(), TimeoutError> {
+        match self.watch(threshold, Some(limit)) {
+            true if !self.is_aborted() => Ok(()),
+            _ => Err(TimeoutError),
+        }
+    }
 
-#[test]
-fn negative_countdown() {
-    let countdown = CountdownEvent::new(1);
-    assert_eq!(false, countdown.wait0());
-    countdown.tick();
-    assert_eq!(countdown.count(), 0);
-    assert_eq!(true, countdown.wait0());
-    countdown.tick();
-    assert_eq!(countdown.count(), 0);
-    assert_eq!(true, countdown.wait0());
+    /// Waits (for up to `limit`, if specified) for the count to drop to `threshold`, returning
+    /// whether it did.
+    fn watch(&self, threshold: usize, limit: Option<Duration>) -> bool {
+        let threshold = Count::try_from(threshold).unwrap_or(Count::MAX);
+        if self.count_raw() <= threshold {
+            return true;
+        }
+
+        let watcher = self.add_watcher(threshold);
+        self.await_watcher(&watcher, limit)
+    }
+
+    /// Waits (for up to `limit`, if specified) for `watcher` to be signalled, returning whether it
+    /// was.
+    fn await_watcher(&self, watcher: &Arc<Watcher>, limit: Option<Duration>) -> bool {
+        match limit {
+            None => {
+                watcher.event.wait();
+                true
+            }
+            // Withdraw the watcher on timeout, unless it was signalled in the meantime.
+            Some(limit) => watcher.event.wait_for(limit) || !self.remove_watcher(watcher),
+        }
+    }
+
+    /// Registers a watcher for the count dropping to `threshold`, which is signalled immediately
+    /// if the count is already there.
+    fn add_watcher(&self, threshold: Count) -> Arc<Watcher> {
+        self.register_watcher(Watcher {
+            threshold,
+            round: None,
+            changed_from: None,
+            aborted: AtomicBool::new(false),
+            event: ManualResetEvent::new(EventState::Unset),
+        })
+    }
+
+    /// Registers `watcher`, signalling it immediately if the condition it watches for has already
+    /// been met.
+    fn register_watcher(&self, watcher: Watcher) -> Arc<Watcher> {
+        let watcher = Arc::new(watcher);
+        let extras = self.extras.get_or_init();
+        let mut watchers = extras.watchers.lock().unwrap();
+        watchers.push(Arc::clone(&watcher));
+        extras.watching.store(watchers.len(), Ordering::Relaxed);
+        // Pairs with the fence in `signal_watchers()`.
+        fence(Ordering::SeqCst);
+        let signalled = match watcher.round {
+            None => Self::is_signalled(&watcher, self.count_raw()),
+            Some(round) => self.round_state(round).is_some(),
+        };
+        if signalled || self.is_aborted() {
+            watchers.pop();
+            extras.watching.store(watchers.len(), Ordering::Relaxed);
+            watcher.aborted.store(!signalled, Ordering::Release);
+            watcher.event.set();
+        }
+
+        watcher
+    }
+
+    /// Unregisters `watcher`, returning whether it was still registered (i.e. not yet signalled).
+    fn remove_watcher(&self, watcher: &Arc<Watcher>) -> bool {
+        let extras = self.extras.get_or_init();
+        let mut watchers = extras.watchers.lock().unwrap();
+        let len = watchers.len();
+        watchers.retain(|w| !Arc::ptr_eq(w, watcher));
+        extras.watching.store(watchers.len(), Ordering::Relaxed);
+        watchers.len() != len
+    }
+
+    /// Returns a [`Milestone`] that becomes set once the count drops to `threshold` or below,
+    /// letting a progress bar or the next stage of a pipeline wait on partial progress alongside
+    /// the countdown itself.
+    ///
+    /// ```rust
+    /// use rsevents_extra::{Awaitable, CountdownEvent};
+    ///
+    /// let countdown = CountdownEvent::new(4);
+    /// let halfway = countdown.milestone_percent(50);
+    /// countdown.tick();
+    /// assert!(!halfway.wait0());
+    /// countdown.tick();
+    /// assert!(halfway.wait0());
+    /// ```
+    pub fn milestone(&self, threshold: usize) -> Milestone<'_> {
+        let threshold = Count::try_from(threshold).unwrap_or(Count::MAX);
+        Milestone {
+            countdown: self,
+            watcher: self.add_watcher(threshold),
+        }
+    }
+
+    /// Returns a [`Milestone`] that becomes set once `percent` percent of the
+    /// [initial count](Self::initial_count) has been ticked off.
+    ///
+    /// Panics if `percent` is greater than 100.
+    pub fn milestone_percent(&self, percent: u8) -> Milestone<'_> {
+        assert!(percent <= 100, "percent cannot exceed 100");
+        let initial = self.initial_count() as u128;
+        let done = (initial * u128::from(percent)).div_ceil(100);
+        self.milestone((initial - done) as usize)
+    }
+
+    /// Waits up to `limit` for the countdown to reach zero (or for the current cycle to complete,
+    /// if the event is [cyclic](Self::cyclic())). Unlike [`wait_for()`](Awaitable::wait_for), a
+    /// timeout reports the count that was observed as the wait gave up, so progress can be logged
+    /// without a separate (and potentially stale) call to [`count()`](Self::count).
+    ///
+    /// As with [`wait_checked_for()`](Self::wait_checked_for), an
+    /// [aborted](Self::abort) countdown is reported as [`CountdownError::Aborted`].
+    ///
+    /// ```rust
+    /// use rsevents_extra::CountdownEvent;
+    /// use std::time::Duration;
+    ///
+    /// let countdown = CountdownEvent::new(3);
+    /// countdown.tick();
+    /// if let Err(e) = countdown.wait_for_remaining(Duration::from_millis(10)) {
+    ///     if let Some(remaining) = e.remaining() {
+    ///         eprintln!("Still waiting on {} tasks", remaining);
+    ///     }
+    /// }
+    /// ```
+    pub fn wait_for_remaining(&self, limit: Duration) -> Result<(), CountdownError> {
+        let completed = self.wait_raw(Some(limit));
+        self.check_aborted()?;
+        match (completed, self.count()) {
+            (true, _) => Ok(()),
+            // The countdown completed just as the wait timed out.
+            (false, 0) if !self.cyclic => Ok(()),
+            (false, remaining) => Err(CountdownError::TimedOut { remaining }),
+        }
+    }
+
+    /// Waits for the countdown to reach zero until `deadline`, returning `false` if the deadline
+    /// passes first. Equivalent to [`wait_for()`](Awaitable::wait_for) with the time remaining
+    /// until the deadline, which lets shutdown code share a single deadline across multiple waits.
+    ///
+    /// ```rust
+    /// use rsevents_extra::CountdownEvent;
+    /// use std::time::{Duration, Instant};
+    ///
+    /// let readers = CountdownEvent::new(0);
+    /// let writers = CountdownEvent::new(1);
+    ///
+    /// let deadline = Instant::now() + Duration::from_millis(10);
+    /// assert!(readers.wait_until(deadline));
+    /// assert!(!writers.wait_until(deadline));
+    /// ```
+    pub fn wait_until(&self, deadline: Instant) -> bool {
+        self.wait_for(deadline.saturating_duration_since(Instant::now()))
+    }
+
+    /// Blocks until the countdown reaches zero, invoking `progress` with the remaining count each
+    /// time `interval` elapses without the countdown completing. Like [`wait()`](Awaitable::wait),
+    /// this panics if the countdown is [aborted](Self::abort).
+    ///
+    /// ```rust
+    /// use rsevents_extra::CountdownEvent;
+    /// use std::time::Duration;
+    ///
+    /// let countdown = CountdownEvent::new(2);
+    /// std::thread::scope(|scope| {
+    ///     scope.spawn(|| {
+    ///         std::thread::sleep(Duration::from_millis(5));
+    ///         countdown.tick();
+    ///         countdown.tick();
+    ///     });
+    ///
+    ///     countdown.wait_with_progress(Duration::from_millis(1), |remaining| {
+    ///         eprintln!("Work in progress. {} items remaining.", remaining);
+    ///     });
+    /// });
+    /// ```
+    pub fn wait_with_progress(&self, interval: Duration, mut progress: impl FnMut(usize)) {
+        while !self.wait_raw(Some(interval)) {
+            progress(self.count());
+        }
+        if self.is_aborted() {
+            panic!("CountdownEvent was aborted!");
+        }
+    }
+
+    /// Blocks until the count (as reported by [`count()`](Self::count)) differs from `last_seen`,
+    /// returning the new count. This lets a progress reporting thread sleep between updates
+    /// instead of polling `count()` on a timer. Like [`wait()`](Awaitable::wait), this panics if
+    /// the countdown is [aborted](Self::abort).
+    ///
+    /// Changes that are undone before the waiting thread gets to observe them (such as an
+    /// increment immediately followed by a tick) may be missed.
+    ///
+    /// ```rust
+    /// use rsevents_extra::CountdownEvent;
+    ///
+    /// let countdown = CountdownEvent::new(3);
+    /// std::thread::scope(|scope| {
+    ///     scope.spawn(|| {
+    ///         for _ in 0..3 {
+    ///             countdown.tick();
+    ///         }
+    ///     });
+    ///
+    ///     let mut remaining = countdown.count();
+    ///     while remaining > 0 {
+    ///         remaining = countdown.wait_for_change(remaining);
+    ///         eprintln!("{} items remaining", remaining);
+    ///     }
+    /// });
+    /// ```
+    pub fn wait_for_change(&self, last_seen: usize) -> usize {
+        loop {
+            if self.is_aborted() {
+                panic!("CountdownEvent was aborted!");
+            }
+            let count = self.count();
+            if count != last_seen {
+                return count;
+            }
+            let watcher = self.register_watcher(Watcher {
+                threshold: 0,
+                round: None,
+                changed_from: Some(last_seen),
+                aborted: AtomicBool::new(false),
+                event: ManualResetEvent::new(EventState::Unset),
+            });
+            watcher.event.wait();
+        }
+    }
+
+    /// Blocks until the countdown reaches zero (or the current cycle completes, if the event is
+    /// [cyclic](Self::cyclic())), returning [`Cancelled`] if `token` (any [`Awaitable`], such as a
+    /// [`CancellationToken`](crate::CancellationToken) or a [`ManualResetEvent`]) becomes set
+    /// first. Like [`wait()`](Awaitable::wait), this panics if the countdown is
+    /// [aborted](Self::abort).
+    ///
+    /// The thread sleeps until woken by the countdown, polling the token with a backoff capped at
+    /// a few milliseconds in the meantime; use
+    /// [`wait_cancellable_subscribed()`](Self::wait_cancellable_subscribed) to have the token wake
+    /// it too.
+    ///
+    /// ```rust
+    /// use rsevents_extra::{CancellationTokenSource, Cancelled, CountdownEvent};
+    ///
+    /// let countdown = CountdownEvent::new(1);
+    /// let cancel = CancellationTokenSource::new();
+    /// let token = cancel.token();
+    ///
+    /// std::thread::scope(|scope| {
+    ///     let monitor = scope.spawn(|| countdown.wait_cancellable(&token));
+    ///     cancel.cancel();
+    ///     assert_eq!(monitor.join().unwrap(), Err(Cancelled));
+    /// });
+    /// ```
+    pub fn wait_cancellable<'t, C>(&self, token: &'t C) -> Result<(), Cancelled>
+    where
+        C: Awaitable<'t> + ?Sized,
+    {
+        self.wait_cancellable_with(token, polled)
+    }
+
+    /// Blocks until the countdown completes like [`wait_cancellable()`](Self::wait_cancellable),
+    /// but sleeps until woken by either the countdown or (through its [`Subscribe`]
+    /// implementation) the token, instead of polling the token.
+    pub fn wait_cancellable_subscribed<'t, C>(&self, token: &'t C) -> Result<(), Cancelled>
+    where
+        C: Awaitable<'t> + Subscribe + ?Sized,
+    {
+        self.wait_cancellable_with(token, subscribed)
+    }
+
+    fn wait_cancellable_with<'t, C>(
+        &self,
+        token: &'t C,
+        subscribe: SubscribeFn<C>,
+    ) -> Result<(), Cancelled>
+    where
+        C: Awaitable<'t> + ?Sized,
+    {
+        let generation = self.generation();
+        let (parker, waker) = Parker::new();
+        let mut backoff = Backoff::new();
+        loop {
+            // Subscribe before checking, so that neither source can fire unnoticed in between.
+            let _completion = Subscription::new(&self.extras.get_or_init().wakers, &waker);
+            let cancellation = subscribe(token, &waker);
+            if token.try_wait0().is_ok() {
+                return Err(Cancelled);
+            }
+            if self.is_aborted() {
+                panic!("CountdownEvent was aborted!");
+            }
+            if self.is_complete(generation) {
+                return Ok(());
+            }
+            parker.park(match cancellation {
+                Some(_) => None,
+                None => Some(backoff.next()),
+            });
+        }
+    }
+
+    /// Returns whether the countdown has reached zero and the event is set, without blocking.
+    /// Equivalent to [`wait0()`](Awaitable::wait0).
+    pub fn is_set(&self) -> bool {
+        self.wait0()
+    }
+
+    /// Returns the signed internal count, which (unlike [`count()`](Self::count)) is not clamped
+    /// to zero and so goes negative if the event is ticked after the count has already reached
+    /// zero. Useful for asserting in tests that no task ticked the countdown more than once.
+    ///
+    /// ```rust
+    /// use rsevents_extra::{CountdownEvent, OverTickPolicy};
+    ///
+    /// let countdown = CountdownEvent::new(1).with_over_tick_policy(OverTickPolicy::Count);
+    /// countdown.tick();
+    /// countdown.tick();
+    /// assert_eq!(countdown.count(), 0);
+    /// assert_eq!(countdown.raw_count(), -1);
+    /// ```
+    #[allow(clippy::unnecessary_cast)] // The internal count may be narrower than 64 bits.
+    pub fn raw_count(&self) -> i64 {
+        self.count_raw() as i64
+    }
+
+    /// Returns the count the event was created with or was last [reset](Self::reset()) to.
+    pub fn initial_count(&self) -> usize {
+        to_usize(self.initial.load(Ordering::Relaxed))
+    }
+
+    /// Get the current internal countdown value.
+    pub fn count(&self) -> usize {
+        to_usize(self.count_raw())
+    }
+
+    /// Returns the signed internal count.
+    fn count_raw(&self) -> Count {
+        count_of(self.state.load(Ordering::SeqCst))
+    }
+
+    /// Returns the number of ticks made after the count reached zero (and not since offset by
+    /// calls to `increment()` or cleared by `reset()`). Always zero unless the event's
+    /// [`OverTickPolicy`] is [`OverTickPolicy::Count`].
+    pub fn extra_ticks(&self) -> usize {
+        to_usize(self.count_raw().saturating_neg())
+    }
+}
+
+impl Debug for CountdownEvent {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CountdownEvent")
+            .field("count", &self.count())
+            .field("initial_count", &self.initial_count())
+            .field("is_set", &self.is_set())
+            .finish_non_exhaustive()
+    }
+}
+
+/// The [`Future`] returned by [`CountdownEvent::wait_async()`], completing once the countdown
+/// reaches zero (or the current cycle completes). Only available with the `async` feature enabled.
+#[cfg(feature = "async")]
+#[must_use = "futures do nothing unless polled"]
+pub struct CountdownWait<'a> {
+    countdown: &'a CountdownEvent,
+    /// For a cyclic event, the generation of the cycle being waited on.
+    generation: usize,
+    /// The id our waker is registered under, once we've been polled.
+    waker_id: Option<usize>,
+}
+
+#[cfg(feature = "async")]
+impl Future for CountdownWait<'_> {
+    type Output = Result<(), CountdownError>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.get_mut();
+        let countdown = this.countdown;
+        // Register before checking, so that a completion racing this poll still wakes us.
+        let wakers = &countdown.extras.get_or_init().wakers;
+        let id = *this.waker_id.get_or_insert_with(|| wakers.next_id());
+        wakers.register(id, cx.waker());
+
+        if countdown.is_complete(this.generation) || countdown.is_aborted() {
+            wakers.remove(id);
+            this.waker_id = None;
+            return Poll::Ready(countdown.check_aborted());
+        }
+        Poll::Pending
+    }
+}
+
+#[cfg(feature = "async")]
+impl Drop for CountdownWait<'_> {
+    fn drop(&mut self) {
+        if let (Some(id), Some(extras)) = (self.waker_id, self.countdown.extras.get()) {
+            extras.wakers.remove(id);
+        }
+    }
+}
+
+#[cfg(feature = "async")]
+impl Debug for CountdownWait<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CountdownWait")
+            .field("countdown", self.countdown)
+            .finish_non_exhaustive()
+    }
+}
+
+/// A scope for spawning threads tracked by a [`CountdownEvent`], created by
+/// [`CountdownEvent::scope()`].
+#[derive(Copy, Clone)]
+pub struct CountdownScope<'scope, 'env> {
+    scope: &'scope Scope<'scope, 'env>,
+    countdown: &'env CountdownEvent,
+}
+
+impl<'scope, 'env> CountdownScope<'scope, 'env> {
+    /// Spawns a scoped thread running `f`, which is registered with the countdown before the thread
+    /// is spawned and ticks it when `f` returns (or panics).
+    pub fn spawn<F, T>(&self, f: F) -> ScopedJoinHandle<'scope, T>
+    where
+        F: FnOnce() -> T + Send + 'scope,
+        T: Send + 'scope,
+    {
+        let guard = self.countdown.register();
+        self.scope.spawn(move || {
+            // The countdown is ticked when `f` completes or unwinds.
+            let _guard = guard;
+            f()
+        })
+    }
+
+    /// Returns the countdown tracking the scope's threads.
+    pub fn countdown(&self) -> &'env CountdownEvent {
+        self.countdown
+    }
+}
+
+impl Debug for CountdownScope<'_, '_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CountdownScope")
+            .field("countdown", self.countdown)
+            .finish_non_exhaustive()
+    }
+}
+
+/// A registered work item, returned by [`CountdownEvent::register()`]. Ticks the countdown when
+/// dropped.
+pub struct TickGuard<'a> {
+    countdown: &'a CountdownEvent,
+}
+
+impl Debug for TickGuard<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TickGuard").finish_non_exhaustive()
+    }
+}
+
+impl Drop for TickGuard<'_> {
+    fn drop(&mut self) {
+        self.countdown.tick();
+    }
+}
+
+/// An [`Awaitable`] that becomes set once a [`CountdownEvent`] has dropped to a given count,
+/// returned by [`CountdownEvent::milestone()`] and [`CountdownEvent::milestone_percent()`].
+///
+/// Once set, a milestone remains set even if the countdown is later incremented or reset. Likewise,
+/// a milestone released by an [abort](CountdownEvent::abort) keeps reporting the abort after the
+/// countdown is reset.
+pub struct Milestone<'a> {
+    countdown: &'a CountdownEvent,
+    watcher: Arc<Watcher>,
+}
+
+impl Milestone<'_> {
+    /// The count at or below which the milestone is reached.
+    pub fn threshold(&self) -> usize {
+        to_usize(self.watcher.threshold)
+    }
+}
+
+impl Debug for Milestone<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Milestone")
+            .field("threshold", &self.watcher.threshold)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Drop for Milestone<'_> {
+    fn drop(&mut self) {
+        self.countdown.remove_watcher(&self.watcher);
+    }
+}
+
+impl Awaitable<'_> for Milestone<'_> {
+    type T = ();
+    type Error = TimeoutError;
+
+    /// Waits for the countdown to reach the milestone. Panics if the countdown is
+    /// [aborted](CountdownEvent::abort) first.
+    fn try_wait(&self) -> Result<(), Infallible> {
+        self.watcher.event.wait();
+        if self.watcher.aborted.load(Ordering::Acquire) {
+            panic!("CountdownEvent was aborted!");
+        }
+        Ok(())
+    }
+
+    /// Waits for the countdown to reach the milestone or returns an error in case of a timeout (or
+    /// if the countdown is aborted).
+    fn try_wait_for(&self, limit: Duration) -> Result<(), TimeoutError> {
+        self.watcher.event.try_wait_for(limit)?;
+        match self.watcher.aborted.load(Ordering::Acquire) {
+            false => Ok(()),
+            true => Err(TimeoutError),
+        }
+    }
+
+    /// Checks whether the countdown has reached the milestone without blocking.
+    fn try_wait0(&self) -> Result<(), TimeoutError> {
+        self.try_wait_for(Duration::ZERO)
+    }
+}
+
+impl Awaitable<'_> for CountdownEvent {
+    type T = ();
+    type Error = TimeoutError;
+
+    /// Waits for the internal countdown of the [`CountdownEvent`] to reach zero (or for the
+    /// current cycle to complete, if the event is [cyclic](CountdownEvent::cyclic())). Panics if
+    /// the countdown is [aborted](CountdownEvent::abort) first.
+    fn try_wait(&self) -> Result<(), Infallible> {
+        self.wait_raw(None);
+        if self.is_aborted() {
+            panic!("CountdownEvent was aborted!");
+        }
+        Ok(())
+    }
+
+    /// Waits for the internal countdown of the [`CountdownEvent`] to reach zero (or for the
+    /// current cycle to complete, if the event is [cyclic](CountdownEvent::cyclic())) or returns
+    /// an error in case of a timeout (or if the countdown is aborted).
+    fn try_wait_for(&self, limit: Duration) -> Result<(), TimeoutError> {
+        match self.wait_raw(Some(limit)) {
+            true if !self.is_aborted() => Ok(()),
+            _ => Err(TimeoutError),
+        }
+    }
+
+    /// An optimized (wait-free, lock-free) check to see if the `CountdownEvent` has reached zero or
+    /// not. A cyclic event never stays at zero, as the tick completing a cycle starts the next one.
+    fn try_wait0(&self) -> Result<(), TimeoutError> {
+        match self.state.load(Ordering::SeqCst) {
+            state if state & ABORTED != 0 => Err(TimeoutError),
+            state if count_of(state) <= 0 => Ok(()),
+            _ => Err(TimeoutError),
+        }
+    }
+}
+
+impl Subscribe for CountdownEvent {
+    fn subscribe(&self, waker: &Waker) -> Option<Subscription<'_>> {
+        Some(Subscription::new(&self.extras.get_or_init().wakers, waker))
+    }
+}
+
+#[test]
+fn small_footprint() {
+    // Watchers, tick callbacks, wakers and abort reasons are only allocated once used.
+    assert!(std::mem::size_of::<CountdownEvent>() <= 64);
+}
+
+#[test]
+fn basic_countdown() {
+    let countdown = CountdownEvent::new(1);
+    assert_eq!(countdown.wait0(), false);
+    countdown.tick();
+    assert_eq!(countdown.wait0(), true);
+    assert_eq!(countdown.is_set(), true);
+    countdown.increment();
+    assert_eq!(countdown.is_set(), false);
+}
+
+#[cfg(not(loom))]
+#[test]
+fn tick_reports_completion() {
+    let countdown = CountdownEvent::new(100).with_over_tick_policy(OverTickPolicy::Count);
+    let completions = std::sync::atomic::AtomicUsize::new(0);
+    std::thread::scope(|scope| {
+        for _ in 0..4 {
+            scope.spawn(|| {
+                for _ in 0..25 {
+                    if countdown.tick() {
+                        completions.fetch_add(1, Ordering::Relaxed);
+                    }
+                }
+            });
+        }
+    });
+    assert_eq!(completions.load(Ordering::Relaxed), 1);
+    assert_eq!(countdown.tick(), false);
+}
+
+#[test]
+fn reset_countdown() {
+    let countdown = CountdownEvent::new(1);
+    assert_eq!(countdown.wait0(), false);
+    countdown.tick();
+    assert_eq!(countdown.wait0(), true);
+    countdown.reset(1);
+    assert_eq!(countdown.wait0(), false);
+}
+
+#[test]
+fn reset_unsets_event() {
+    let countdown = CountdownEvent::new(0);
+    assert_eq!(countdown.wait0(), true);
+    countdown.reset(2);
+    assert_eq!(countdown.is_set(), false);
+    assert_eq!(countdown.wait_for(Duration::from_millis(10)), false);
+    countdown.reset(0);
+    assert_eq!(countdown.wait0(), true);
+}
+
+#[test]
+fn increment_unsets_event() {
+    let countdown = CountdownEvent::new(1);
+    countdown.tick();
+    assert_eq!(countdown.wait0(), true);
+    countdown.increment();
+    assert_eq!(countdown.is_set(), false);
+    assert_eq!(countdown.wait0(), false);
+    countdown.tick();
+    assert_eq!(countdown.wait0(), true);
+}
+
+#[test]
+fn reset_to_initial() {
+    let countdown = CountdownEvent::new(2);
+    countdown.tick();
+    countdown.increment();
+    countdown.increment();
+    assert_eq!(countdown.initial_count(), 2);
+    countdown.reset_to_initial();
+    assert_eq!(countdown.count(), 2);
+
+    countdown.reset(3);
+    countdown.tick();
+    countdown.reset_to_initial();
+    assert_eq!(countdown.initial_count(), 3);
+    assert_eq!(countdown.count(), 3);
+    assert_eq!(countdown.wait0(), false);
+}
+
+#[test]
+fn timeout_reports_remaining() {
+    let countdown = CountdownEvent::new(3);
+    countdown.tick();
+    let err = countdown
+        .wait_for_remaining(Duration::from_millis(1))
+        .unwrap_err();
+    assert_eq!(err, CountdownError::TimedOut { remaining: 2 });
+    assert_eq!(err.remaining(), Some(2));
+
+    countdown.tick();
+    countdown.tick();
+    assert_eq!(countdown.wait_for_remaining(Duration::ZERO), Ok(()));
+
+    let countdown = CountdownEvent::new(3);
+    countdown.abort();
+    assert_eq!(
+        countdown.wait_for_remaining(Duration::from_millis(1)),
+        Err(CountdownError::Aborted(None))
+    );
+}
+
+#[cfg(not(loom))]
+#[test]
+fn remaining_after_cycle() {
+    let countdown = CountdownEvent::new(2).cyclic();
+    std::thread::scope(|scope| {
+        let waiter = scope.spawn(|| countdown.wait_for_remaining(Duration::from_secs(10)));
+        std::thread::sleep(Duration::from_millis(20));
+        countdown.tick();
+        countdown.tick();
+        // The count is back at 2 for the next cycle, but the one being waited on completed.
+        assert_eq!(waiter.join().unwrap(), Ok(()));
+    });
+}
+
+#[test]
+fn wait_for_count_threshold() {
+    let countdown = CountdownEvent::new(4);
+    assert!(countdown
+        .wait_for_count_timeout(2, Duration::from_millis(1))
+        .is_err());
+
+    std::thread::scope(|scope| {
+        let waiter = scope.spawn(|| countdown.wait_for_count(2));
+        std::thread::sleep(Duration::from_millis(10));
+        countdown.tick();
+        assert!(!waiter.is_finished());
+        countdown.tick();
+        waiter.join().unwrap();
+    });
+    assert_eq!(countdown.count(), 2);
+    assert!(countdown.wait_for_count_timeout(3, Duration::ZERO).is_ok());
+}
+
+#[test]
+fn tick_callbacks() {
+    use std::sync::Arc;
+
+    let countdown = CountdownEvent::new(2).with_over_tick_policy(OverTickPolicy::Count);
+    let seen = Arc::new(std::sync::Mutex::new(Vec::new()));
+    let observer = Arc::clone(&seen);
+    countdown.on_tick(move |count| observer.lock().unwrap().push(count));
+
+    countdown.tick();
+    countdown.increment();
+    countdown.tick();
+    countdown.tick();
+    countdown.tick();
+    assert_eq!(*seen.lock().unwrap(), [1, 1, 0, 0]);
+}
+
+#[test]
+fn milestones() {
+    let countdown = CountdownEvent::new(10);
+    let quarter = countdown.milestone_percent(25);
+    let half = countdown.milestone_percent(50);
+    let last = countdown.milestone(1);
+    assert_eq!(quarter.threshold(), 7);
+    assert_eq!(half.threshold(), 5);
+
+    for _ in 0..3 {
+        countdown.tick();
+    }
+    assert_eq!(quarter.wait0(), true);
+    assert_eq!(half.wait0(), false);
+    for _ in 0..6 {
+        countdown.tick();
+    }
+    assert_eq!(half.wait0(), true);
+    assert_eq!(last.wait0(), true);
+
+    // Milestones stay set, and new milestones that have already been reached start out set.
+    countdown.reset(10);
+    assert_eq!(quarter.wait0(), true);
+    assert_eq!(countdown.milestone(10).wait0(), true);
+    assert_eq!(countdown.milestone_percent(25).wait0(), false);
+}
+
+#[test]
+fn cyclic_generations() {
+    let countdown = CountdownEvent::new(2).cyclic();
+    assert!(countdown.wait_cycle_for(Duration::from_millis(1)).is_err());
+
+    std::thread::scope(|scope| {
+        let waiter = scope.spawn(|| countdown.wait_cycle());
+        std::thread::sleep(Duration::from_millis(10));
+        countdown.tick();
+        countdown.tick();
+        assert_eq!(waiter.join().unwrap(), 1);
+    });
+    assert_eq!(countdown.generation(), 1);
+    assert_eq!(countdown.count(), 2);
+    assert_eq!(countdown.wait0(), false);
+
+    std::thread::scope(|scope| {
+        let waiter = scope.spawn(|| countdown.wait());
+        std::thread::sleep(Duration::from_millis(10));
+        countdown.tick();
+        countdown.tick();
+        waiter.join().unwrap();
+    });
+    assert_eq!(countdown.generation(), 2);
+}
+
+#[test]
+fn abort_releases_waiters() {
+    let countdown = CountdownEvent::new(3);
+    let milestone = countdown.milestone(1);
+    std::thread::scope(|scope| {
+        let waiter = scope.spawn(|| countdown.wait_checked());
+        let watcher = scope.spawn(|| countdown.wait_for_count_timeout(1, Duration::from_secs(10)));
+        std::thread::sleep(Duration::from_millis(10));
+        countdown.tick();
+        countdown.abort();
+        assert_eq!(waiter.join().unwrap(), Err(CountdownError::Aborted(None)));
+        assert!(watcher.join().unwrap().is_err());
+    });
+    assert_eq!(countdown.is_set(), false);
+    assert!(!milestone.wait_for(Duration::ZERO));
+    assert_eq!(
+        countdown.wait_checked_for(Duration::ZERO),
+        Err(CountdownError::Aborted(None))
+    );
+
+    countdown.reset(1);
+    assert_eq!(countdown.is_aborted(), false);
+    assert_eq!(
+        countdown.wait_checked_for(Duration::ZERO),
+        Err(CountdownError::TimedOut { remaining: 1 })
+    );
+    countdown.tick();
+    assert_eq!(countdown.wait_checked(), Ok(()));
+}
+
+#[test]
+fn milestone_reports_abort_after_reset() {
+    let countdown = CountdownEvent::new(3);
+    let reached = countdown.milestone(3);
+    let aborted = countdown.milestone(1);
+    countdown.abort();
+    let late = countdown.milestone(1);
+
+    // The abort is reported even once the countdown has moved on to a new round.
+    countdown.reset(3);
+    assert_eq!(reached.wait0(), true);
+    assert_eq!(aborted.wait0(), false);
+    assert_eq!(late.wait_for(Duration::ZERO), false);
+}
+
+#[test]
+#[should_panic]
+fn wait_panics_when_aborted() {
+    let countdown = CountdownEvent::new(1);
+    countdown.abort_with("failed");
+    countdown.wait();
+}
+
+#[test]
+fn debug_format() {
+    let countdown = CountdownEvent::new(3);
+    countdown.tick();
+    assert_eq!(
+        format!("{:?}", countdown),
+        "CountdownEvent { count: 2, initial_count: 3, is_set: false, .. }"
+    );
+}
+
+#[test]
+fn increment_by() {
+    let countdown = CountdownEvent::new(0).with_over_tick_policy(OverTickPolicy::Count);
+    countdown.increment_by(3);
+    assert_eq!(countdown.count(), 3);
+    assert_eq!(countdown.wait0(), false);
+    countdown.increment_by(0);
+    for _ in 0..5 {
+        countdown.tick();
+    }
+    assert_eq!(countdown.extra_ticks(), 2);
+    // Only the increment that brings the count back above zero resets the event.
+    countdown.increment_by(2);
+    assert_eq!(countdown.wait0(), true);
+    countdown.increment_by(1);
+    assert_eq!(countdown.wait0(), false);
+}
+
+#[test]
+fn countdown_scope() {
+    let countdown = CountdownEvent::new(0);
+    let total = countdown.scope(|s| {
+        let handles: Vec<_> = (1..=3).map(|i| s.spawn(move || i * 2)).collect();
+        assert!(s.countdown().count() <= 3);
+        handles.into_iter().map(|h| h.join().unwrap()).sum::<i32>()
+    });
+    assert_eq!(total, 12);
+    assert_eq!(countdown.wait0(), true);
+}
+
+#[test]
+fn wait_with_progress() {
+    let countdown = CountdownEvent::new(2);
+    let mut reports = Vec::new();
+    std::thread::scope(|scope| {
+        scope.spawn(|| {
+            std::thread::sleep(Duration::from_millis(20));
+            countdown.tick();
+            countdown.tick();
+        });
+        countdown.wait_with_progress(Duration::from_millis(1), |count| reports.push(count));
+    });
+    assert!(!reports.is_empty());
+    assert!(reports.iter().all(|&count| count > 0));
+}
+
+#[test]
+fn wait_until_deadline() {
+    let countdown = CountdownEvent::new(1);
+    let start = Instant::now();
+    assert_eq!(
+        countdown.wait_until(start + Duration::from_millis(10)),
+        false
+    );
+    assert!(start.elapsed() >= Duration::from_millis(10));
+    // A deadline in the past doesn't block.
+    countdown.tick();
+    assert_eq!(countdown.wait_until(start), true);
+}
+
+#[test]
+fn wait_round() {
+    let countdown = CountdownEvent::new(1);
+    let round = countdown.round();
+
+    std::thread::scope(|scope| {
+        let waiter = scope.spawn(|| countdown.wait_round(round));
+        std::thread::sleep(Duration::from_millis(10));
+        countdown.tick();
+        assert_eq!(waiter.join().unwrap(), true);
+    });
+    assert_eq!(countdown.wait_current_round(), true);
+
+    countdown.reset(1);
+    assert_eq!(countdown.round(), round + 1);
+    // The earlier round has been superseded.
+    assert_eq!(countdown.wait_round(round), false);
+    std::thread::scope(|scope| {
+        let waiter = scope.spawn(|| countdown.wait_current_round());
+        std::thread::sleep(Duration::from_millis(10));
+        countdown.reset_to_initial();
+        assert_eq!(waiter.join().unwrap(), false);
+    });
+}
+
+#[test]
+fn reset_if_set() {
+    let countdown = CountdownEvent::new(2).with_over_tick_policy(OverTickPolicy::Count);
+    countdown.tick();
+    assert_eq!(countdown.reset_if_set(3), false);
+    assert_eq!(countdown.count(), 1);
+    countdown.tick();
+    countdown.tick();
+    assert_eq!(countdown.reset_if_set(3), true);
+    assert_eq!(countdown.count(), 3);
+    assert_eq!(countdown.initial_count(), 3);
+    assert_eq!(countdown.wait0(), false);
+}
+
+#[test]
+fn start_at_zero() {
+    let countdown = CountdownEvent::new(0);
+    assert_eq!(countdown.wait0(), true);
+}
+
+// Loom's synchronization types can't be used in a static.
+#[cfg(not(loom))]
+#[test]
+fn threaded_countdown() {
+    use std::thread;
+
+    static COUNTDOWN: CountdownEvent = CountdownEvent::new(2);
+
+    assert_eq!(COUNTDOWN.wait0(), false);
+
+    let thread1 = thread::spawn(move || {
+        assert_eq!(COUNTDOWN.wait0(), false);
+        COUNTDOWN.tick();
+    });
+
+    let thread2 = thread::spawn(move || {
+        assert_eq!(COUNTDOWN.wait0(), false);
+        COUNTDOWN.tick();
+    });
+
+    COUNTDOWN.wait();
+
+    // To catch any panics
+    thread1.join().unwrap();
+    thread2.join().unwrap();
+}
+
+#[test]
+#[cfg_attr(debug_assertions, should_panic(expected = "ticked past zero"))]
+fn negative_countdown() {
+    let countdown = CountdownEvent::new(1);
+    assert_eq!(false, countdown.wait0());
+    countdown.tick();
+    assert_eq!(countdown.count(), 0);
+    assert_eq!(true, countdown.wait0());
+    countdown.tick();
+    assert_eq!(countdown.count(), 0);
+    assert_eq!(true, countdown.wait0());
+}
+
+#[test]
+fn over_tick_policies() {
+    let counted = CountdownEvent::new(1).with_over_tick_policy(OverTickPolicy::Count);
+    counted.tick();
+    counted.tick();
+    counted.tick();
+    assert_eq!(counted.extra_ticks(), 2);
+    counted.increment();
+    assert_eq!(counted.extra_ticks(), 1);
+    assert_eq!(counted.wait0(), true);
+
+    let saturated = CountdownEvent::new(1).with_over_tick_policy(OverTickPolicy::Saturate);
+    saturated.tick();
+    saturated.tick();
+    assert_eq!(saturated.extra_ticks(), 0);
+    saturated.increment();
+    assert_eq!(saturated.count(), 1);
+    assert_eq!(saturated.wait0(), false);
+}
+
+#[test]
+fn try_add_count() {
+    let countdown = CountdownEvent::new(1);
+    assert_eq!(countdown.try_add_count(), true);
+    assert_eq!(countdown.count(), 2);
+    countdown.tick();
+    countdown.tick();
+    assert_eq!(countdown.try_add_count(), false);
+    assert_eq!(countdown.count(), 0);
+    assert_eq!(countdown.wait0(), true);
+}
+
+#[test]
+fn tick_guard_ticks_on_panic() {
+    let countdown = CountdownEvent::new(0);
+    let guard = countdown.register();
+    assert_eq!(countdown.count(), 1);
+    assert_eq!(countdown.wait0(), false);
+
+    std::thread::scope(|scope| {
+        let worker = scope.spawn(move || {
+            let _guard = guard;
+            panic!("worker failed");
+        });
+        worker.join().unwrap_err();
+    });
+    assert_eq!(countdown.wait0(), true);
+}
+
+#[cfg(not(loom))]
+#[test]
+fn concurrent_increments_and_ticks() {
+    let countdown = CountdownEvent::new(1);
+    std::thread::scope(|scope| {
+        for _ in 0..4 {
+            scope.spawn(|| {
+                for _ in 0..1000 {
+                    countdown.increment();
+                    countdown.tick();
+                }
+            });
+        }
+    });
+    assert_eq!(countdown.wait0(), false);
+    countdown.tick();
+    assert_eq!(countdown.wait0(), true);
+}
+
+#[test]
+#[should_panic]
+fn over_tick_panics() {
+    let countdown = CountdownEvent::new(1).with_over_tick_policy(OverTickPolicy::Panic);
+    countdown.tick();
+    countdown.tick();
+}
+
+#[cfg(not(loom))]
+#[test]
+fn ticks_never_wait_for_resets() {
+    let countdown = CountdownEvent::new(2);
+    let cyclic = CountdownEvent::new(1).cyclic();
+
+    // Take the lock a concurrent reset would hold.
+    countdown.event2.wait();
+    cyclic.event2.wait();
+
+    // Neither the tick completing a countdown nor the one completing a cycle waits for it.
+    assert!(!countdown.tick());
+    assert!(countdown.tick());
+    assert!(countdown.is_set());
+    assert!(cyclic.tick());
+    assert_eq!((cyclic.generation(), cyclic.count()), (1, 1));
+
+    countdown.event2.set();
+    cyclic.event2.set();
+}
+
+#[test]
+#[should_panic(expected = "count exceeds the maximum supported count")]
+fn increment_past_max_count_panics() {
+    let countdown = CountdownEvent::new(MAX_COUNT as usize);
+    assert_eq!(countdown.count(), MAX_COUNT as usize);
+    countdown.increment();
+}
+
+#[cfg(not(loom))]
+#[test]
+fn cycle_completion_races_resets() {
+    let cyclic = CountdownEvent::new(2).cyclic();
+    std::thread::scope(|scope| {
+        for _ in 0..4 {
+            scope.spawn(|| {
+                for _ in 0..1000 {
+                    cyclic.tick();
+                }
+            });
+        }
+        scope.spawn(|| {
+            for i in 0..1000 {
+                cyclic.reset(1 + i % 3);
+            }
+        });
+    });
+
+    // Each cycle was refilled from the initial count in force at the time, never a stale one.
+    cyclic.reset(3);
+    let generation = cyclic.generation();
+    for _ in 0..3 {
+        cyclic.tick();
+    }
+    assert_eq!((cyclic.generation(), cyclic.count()), (generation + 1, 3));
+}
+
+#[cfg(not(loom))]
+#[test]
+fn cancellable_wait() {
+    use rsevents::{EventState, ManualResetEvent};
+
+    let countdown = CountdownEvent::new(1);
+    let token = ManualResetEvent::new(EventState::Unset);
+    std::thread::scope(|scope| {
+        let waiter = scope.spawn(|| countdown.wait_cancellable(&token));
+        std::thread::sleep(Duration::from_millis(20));
+        countdown.tick();
+        assert_eq!(waiter.join().unwrap(), Ok(()));
+    });
+
+    countdown.reset(1);
+    token.set();
+    assert_eq!(countdown.wait_cancellable(&token), Err(Cancelled));
+}
+
+#[cfg(not(loom))]
+#[test]
+fn cancellable_wait_blocks_on_both() {
+    use crate::CancellationTokenSource;
+
+    // A cyclic countdown is only momentarily at zero, so the waiter must not miss the cycle.
+    let countdown = CountdownEvent::new(1).cyclic();
+    let source = CancellationTokenSource::new();
+    let token = source.token();
+    std::thread::scope(|scope| {
+        let waiter = scope.spawn(|| countdown.wait_cancellable(&token));
+        std::thread::sleep(Duration::from_millis(20));
+        countdown.tick();
+        assert_eq!(waiter.join().unwrap(), Ok(()));
+        assert_eq!(countdown.generation(), 1);
+
+        let waiter = scope.spawn(|| countdown.wait_cancellable_subscribed(&token));
+        std::thread::sleep(Duration::from_millis(20));
+        source.cancel();
+        assert_eq!(waiter.join().unwrap(), Err(Cancelled));
+    });
+}
+
+#[cfg(not(loom))]
+#[test]
+fn wait_for_change() {
+    let countdown = CountdownEvent::new(2);
+    assert_eq!(countdown.wait_for_change(5), 2);
+
+    std::thread::scope(|scope| {
+        let waiter = scope.spawn(|| countdown.wait_for_change(2));
+        std::thread::sleep(Duration::from_millis(20));
+        assert!(!waiter.is_finished());
+        countdown.increment_by(3);
+        assert_eq!(waiter.join().unwrap(), 5);
+
+        let waiter = scope.spawn(|| countdown.wait_for_change(5));
+        std::thread::sleep(Duration::from_millis(20));
+        countdown.reset(1);
+        assert_eq!(waiter.join().unwrap(), 1);
+
+        let waiter = scope.spawn(|| countdown.wait_for_change(1));
+        std::thread::sleep(Duration::from_millis(20));
+        assert!(countdown.try_add_count());
+        assert_eq!(waiter.join().unwrap(), 2);
+    });
+}
+
+#[test]
+fn weighted_ticks() {
+    let countdown = CountdownEvent::new(100).with_over_tick_policy(OverTickPolicy::Count);
+    assert_eq!(countdown.tick_weight(60), false);
+    assert_eq!(countdown.count(), 40);
+    assert_eq!(countdown.tick_weight(0), false);
+    assert_eq!(countdown.tick_weight(50), true);
+    assert_eq!(countdown.extra_ticks(), 10);
+
+    let countdown = CountdownEvent::new(100).with_over_tick_policy(OverTickPolicy::Saturate);
+    assert_eq!(countdown.tick_weight(150), true);
+    assert_eq!(countdown.count(), 0);
+    assert_eq!(countdown.extra_ticks(), 0);
+}
+
+#[cfg(feature = "async")]
+#[test]
+fn async_wait() {
+    use std::sync::atomic::AtomicUsize;
+
+    struct CountingWaker(AtomicUsize);
+    impl std::task::Wake for CountingWaker {
+        fn wake(self: std::sync::Arc<Self>) {
+            self.0.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+        }
+    }
+
+    let wakes = std::sync::Arc::new(CountingWaker(AtomicUsize::new(0)));
+    let waker = wakes.clone().into();
+    let mut cx = Context::from_waker(&waker);
+    let woken = || wakes.0.load(std::sync::atomic::Ordering::SeqCst);
+
+    let countdown = CountdownEvent::new(2);
+    let mut wait = std::pin::pin!(countdown.wait_async());
+    assert!(wait.as_mut().poll(&mut cx).is_pending());
+    countdown.tick();
+    assert_eq!(woken(), 0);
+    countdown.tick();
+    assert_eq!(woken(), 1);
+    assert_eq!(wait.as_mut().poll(&mut cx), Poll::Ready(Ok(())));
+
+    let cyclic = CountdownEvent::new(1).cyclic();
+    let mut cycle = std::pin::pin!(cyclic.wait_async());
+    assert!(cycle.as_mut().poll(&mut cx).is_pending());
+    cyclic.tick();
+    assert_eq!(woken(), 2);
+    assert_eq!(cycle.as_mut().poll(&mut cx), Poll::Ready(Ok(())));
+
+    let mut aborted = std::pin::pin!(cyclic.wait_async());
+    assert!(aborted.as_mut().poll(&mut cx).is_pending());
+    cyclic.abort();
+    assert_eq!(woken(), 3);
+    assert_eq!(
+        aborted.as_mut().poll(&mut cx),
+        Poll::Ready(Err(CountdownError::Aborted(None)))
+    );
+}
+
+#[cfg(loom)]
+#[test]
+fn loom_concurrent_ticks() {
+    use loom::sync::Arc;
+
+    loom::model(|| {
+        let countdown = Arc::new(CountdownEvent::new(2));
+        let handles: Vec<_> = (0..2)
+            .map(|_| {
+                let countdown = Arc::clone(&countdown);
+                loom::thread::spawn(move || countdown.tick())
+            })
+            .collect();
+        for handle in handles {
+            handle.join().unwrap();
+        }
+
+        assert_eq!(countdown.count(), 0);
+        assert_eq!(countdown.wait0(), true);
+    });
+}
+
+#[cfg(loom)]
+#[test]
+fn loom_increment_races_final_tick() {
+    use loom::sync::Arc;
+
+    loom::model(|| {
+        let countdown = Arc::new(CountdownEvent::new(1));
+        let incrementer = {
+            let countdown = Arc::clone(&countdown);
+            loom::thread::spawn(move || countdown.increment())
+        };
+        countdown.tick();
+        incrementer.join().unwrap();
+
+        // Whichever call lands last, the event must agree with the count.
+        assert_eq!(countdown.count(), 1);
+        assert_eq!(countdown.wait0(), false);
+        assert!(countdown.event.try_wait0().is_err());
+    });
+}
+
+#[cfg(loom)]
+#[test]
+fn loom_event_set_only_at_zero() {
+    use loom::sync::Arc;
+
+    loom::model(|| {
+        let countdown = Arc::new(CountdownEvent::new(1));
+        let incrementer = {
+            let countdown = Arc::clone(&countdown);
+            loom::thread::spawn(move || {
+                countdown.increment();
+                countdown.tick();
+            })
+        };
+        countdown.tick();
+        incrementer.join().unwrap();
+
+        assert_eq!(countdown.count(), 0);
+        assert!(countdown.event.try_wait0().is_ok());
+    });
 }
@@ -0,0 +1,262 @@
+use crate::clock::{Clock, SystemClock};
+use crate::BroadcastEvent;
+use rsevents::{Awaitable, TimeoutError};
+use std::cmp::{Ordering, Reverse};
+use std::collections::BinaryHeap;
+use std::convert::Infallible;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// A queue of delayed values, each of which only becomes available to pop once its delay has
+/// elapsed.
+///
+/// Values are [inserted](DelayQueue::insert) with a delay (or [at](DelayQueue::insert_at) a
+/// deadline), and [`DelayQueue::pop()`] blocks until the earliest deadline has passed, then
+/// returns that value. Values with the same deadline are popped in the order they were inserted.
+/// A value inserted with an earlier deadline than those already queued wakes any blocked poppers
+/// so that they wait for the new deadline instead. Waiting on the queue via the [`Awaitable`] API
+/// pops the next matured value.
+///
+/// ## Example:
+///
+/// ```rust
+/// use rsevents_extra::DelayQueue;
+/// use std::time::Duration;
+///
+/// static RETRIES: DelayQueue<&str> = DelayQueue::new();
+///
+/// RETRIES.insert("slow request", Duration::from_millis(20));
+/// RETRIES.insert("fast request", Duration::from_millis(10));
+///
+/// // Each request is retried once its backoff has elapsed.
+/// assert_eq!(RETRIES.pop(), "fast request");
+/// assert_eq!(RETRIES.pop(), "slow request");
+/// ```
+pub struct DelayQueue<T> {
+    state: Mutex<DelayState<T>>,
+    /// Signalled after each insertion.
+    inserted: BroadcastEvent,
+    clock: &'static dyn Clock,
+}
+
+struct DelayState<T> {
+    entries: BinaryHeap<Reverse<Entry<T>>>,
+    /// The number of values inserted so far, used to order values with the same deadline.
+    sequence: u64,
+}
+
+struct Entry<T> {
+    deadline: Instant,
+    sequence: u64,
+    value: T,
+}
+
+impl<T> PartialEq for Entry<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl<T> Eq for Entry<T> {}
+
+impl<T> PartialOrd for Entry<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<T> Ord for Entry<T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (self.deadline, self.sequence).cmp(&(other.deadline, other.sequence))
+    }
+}
+
+impl<T> DelayQueue<T> {
+    /// Creates a new, empty `DelayQueue`.
+    ///
+    /// This is a `const` function and can be used in a `static` context.
+    pub const fn new() -> Self {
+        DelayQueue {
+            state: Mutex::new(DelayState {
+                entries: BinaryHeap::new(),
+                sequence: 0,
+            }),
+            inserted: BroadcastEvent::new(),
+            clock: &SystemClock,
+        }
+    }
+
+    /// Replaces the [`SystemClock`] used to time delays and waits with `clock`. This is primarily
+    /// useful in tests, where a [`MockClock`](crate::MockClock) makes the delays deterministic.
+    pub const fn with_clock(mut self, clock: &'static dyn Clock) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Inserts `value`, to become available once `delay` has elapsed.
+    pub fn insert(&self, value: T, delay: Duration) {
+        self.insert_at(value, self.clock.now() + delay);
+    }
+
+    /// Inserts `value`, to become available once `deadline` has passed.
+    pub fn insert_at(&self, value: T, deadline: Instant) {
+        {
+            let mut state = self.state.lock().unwrap();
+            let sequence = state.sequence;
+            state.sequence += 1;
+            state.entries.push(Reverse(Entry {
+                deadline,
+                sequence,
+                value,
+            }));
+        }
+        self.inserted.signal();
+    }
+
+    /// Removes the value with the earliest deadline, blocking until that deadline has passed.
+    pub fn pop(&self) -> T {
+        match self.pop_until(None) {
+            Ok(value) => value,
+            Err(_) => unreachable!(),
+        }
+    }
+
+    /// Removes the value with the earliest deadline, blocking for up to `limit` until that
+    /// deadline has passed, and returning an error in case of a timeout.
+    pub fn pop_for(&self, limit: Duration) -> Result<T, TimeoutError> {
+        self.pop_until(Some(self.clock.now() + limit))
+    }
+
+    /// Removes the value with the earliest deadline if that deadline has passed, returning an
+    /// error without blocking otherwise.
+    pub fn try_pop(&self) -> Result<T, TimeoutError> {
+        self.pop_until(Some(self.clock.now()))
+    }
+
+    /// Returns the earliest deadline of the queued values, or `None` if the queue is empty.
+    pub fn next_deadline(&self) -> Option<Instant> {
+        let state = self.state.lock().unwrap();
+        state.entries.peek().map(|Reverse(entry)| entry.deadline)
+    }
+
+    /// Returns the number of values in the queue, whether or not their deadline has passed.
+    pub fn len(&self) -> usize {
+        self.state.lock().unwrap().entries.len()
+    }
+
+    /// Returns whether the queue is empty.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn pop_until(&self, deadline: Option<Instant>) -> Result<T, TimeoutError> {
+        loop {
+            // Read before checking the queue, so an insertion made after the check is not missed.
+            let generation = self.inserted.generation();
+            let now = self.clock.now();
+            let next = {
+                let mut state = self.state.lock().unwrap();
+                match state.entries.peek() {
+                    Some(Reverse(entry)) if entry.deadline <= now => {
+                        let Reverse(entry) = state.entries.pop().unwrap();
+                        return Ok(entry.value);
+                    }
+                    Some(Reverse(entry)) => Some(entry.deadline),
+                    None => None,
+                }
+            };
+
+            // Sleep until the next value matures or our own deadline passes, whichever is first.
+            let wake_at = match (next, deadline) {
+                (Some(next), Some(deadline)) => Some(next.min(deadline)),
+                (next, deadline) => next.or(deadline),
+            };
+            match wake_at {
+                None => {
+                    self.inserted.wait_since(generation);
+                }
+                Some(wake_at) => {
+                    if matches!(deadline, Some(deadline) if deadline <= now) {
+                        return Err(TimeoutError);
+                    }
+                    let duration = wake_at.saturating_duration_since(now);
+                    self.clock.wait_for(duration, &mut |limit| {
+                        self.inserted.wait_since_for(generation, limit).is_ok()
+                    });
+                }
+            }
+        }
+    }
+}
+
+impl<T> Default for DelayQueue<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'a, T: 'a> Awaitable<'a> for DelayQueue<T> {
+    type T = T;
+    type Error = TimeoutError;
+
+    /// Pops the value with the earliest deadline, blocking until that deadline has passed.
+    fn try_wait(&'a self) -> Result<T, Infallible> {
+        Ok(self.pop())
+    }
+
+    /// Pops the value with the earliest deadline, blocking for up to `limit` until that deadline
+    /// has passed.
+    fn try_wait_for(&'a self, limit: Duration) -> Result<T, TimeoutError> {
+        self.pop_for(limit)
+    }
+
+    /// Pops the value with the earliest deadline if that deadline has passed, without blocking.
+    fn try_wait0(&'a self) -> Result<T, TimeoutError> {
+        self.try_pop()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{DelayQueue, MockClock};
+    use std::thread;
+    use std::time::Duration;
+
+    #[test]
+    fn pops_in_deadline_order() {
+        static CLOCK: MockClock = MockClock::new();
+        let queue = DelayQueue::new().with_clock(&CLOCK);
+        queue.insert("c", Duration::from_secs(3));
+        queue.insert("a", Duration::from_secs(1));
+        queue.insert("b", Duration::from_secs(1));
+        assert_eq!(queue.len(), 3);
+        assert!(queue.try_pop().is_err());
+
+        CLOCK.advance(Duration::from_secs(1));
+        assert_eq!(queue.pop(), "a");
+        assert_eq!(queue.try_pop().ok(), Some("b"));
+        assert!(queue.try_pop().is_err());
+
+        thread::scope(|scope| {
+            let popper = scope.spawn(|| queue.pop());
+            while !popper.is_finished() {
+                CLOCK.advance(Duration::from_millis(100));
+                thread::sleep(Duration::from_millis(1));
+            }
+            assert_eq!(popper.join().unwrap(), "c");
+        });
+    }
+
+    #[test]
+    fn earlier_insertion_wakes_popper() {
+        let queue = DelayQueue::new();
+        thread::scope(|scope| {
+            let popper = scope.spawn(|| queue.pop());
+            thread::sleep(Duration::from_millis(10));
+            queue.insert(2, Duration::from_secs(60));
+            queue.insert(1, Duration::ZERO);
+            assert_eq!(popper.join().unwrap(), 1);
+        });
+        assert_eq!(queue.len(), 1);
+    }
+}
@@ -0,0 +1,155 @@
+use crate::{Condition, ShuttingDown};
+use rsevents::TimeoutError;
+use std::time::Duration;
+
+/// Tracks in-flight operations for a graceful shutdown, combining an admission gate with a count
+/// of the operations that were admitted.
+///
+/// Each operation calls [`DrainTracker::enter()`] and holds on to the returned [`DrainGuard`] for
+/// as long as it runs. Once shutdown has begun (with [`DrainTracker::shutdown()`] or
+/// [`DrainTracker::shutdown_and_wait()`]), `enter()` fails for all new operations, and the
+/// shutdown path can wait until all in-flight guards have been dropped. Admission and the count
+/// are updated together, so no operation can slip in after the shutdown has started waiting.
+///
+/// ## Example:
+///
+/// ```rust
+/// use rsevents_extra::DrainTracker;
+///
+/// static REQUESTS: DrainTracker = DrainTracker::new();
+///
+/// fn handle_request() -> Result<(), &'static str> {
+///     let _guard = REQUESTS.enter().map_err(|_| "503 Service Unavailable")?;
+///     // <handle the request here>
+///     Ok(())
+/// }
+///
+/// std::thread::scope(|scope| {
+///     for _ in 0..4 {
+///         scope.spawn(|| {
+///             while handle_request().is_ok() {}
+///         });
+///     }
+///
+///     // Stop accepting requests, then wait for the ones being handled to finish.
+///     REQUESTS.shutdown_and_wait();
+///     assert_eq!(REQUESTS.in_flight(), 0);
+/// });
+/// ```
+pub struct DrainTracker {
+    state: Condition<DrainState>,
+}
+
+struct DrainState {
+    shutting_down: bool,
+    in_flight: usize,
+}
+
+impl DrainTracker {
+    /// Creates a new `DrainTracker` that admits operations.
+    ///
+    /// This is a `const` function and can be used in a `static` context.
+    pub const fn new() -> Self {
+        DrainTracker {
+            state: Condition::new(DrainState {
+                shutting_down: false,
+                in_flight: 0,
+            }),
+        }
+    }
+
+    /// Admits a new operation, returning a guard that marks it in flight until dropped, or an
+    /// error if shutdown has begun.
+    #[must_use = "the operation is only tracked while the guard is held"]
+    pub fn enter(&self) -> Result<DrainGuard<'_>, ShuttingDown> {
+        self.state.update(|state| {
+            if state.shutting_down {
+                return Err(ShuttingDown);
+            }
+            state.in_flight += 1;
+            Ok(DrainGuard { tracker: self })
+        })
+    }
+
+    /// Begins shutdown, so that all further calls to [`enter()`](Self::enter) fail, without
+    /// waiting for in-flight operations.
+    pub fn shutdown(&self) {
+        self.state.update(|state| state.shutting_down = true);
+    }
+
+    /// Begins shutdown (see [`shutdown()`](Self::shutdown)), then blocks until all in-flight
+    /// operations have dropped their guards.
+    pub fn shutdown_and_wait(&self) {
+        self.shutdown();
+        self.state.wait_until(|state| state.in_flight == 0);
+    }
+
+    /// Begins shutdown (see [`shutdown()`](Self::shutdown)), then blocks for up to `limit` until
+    /// all in-flight operations have dropped their guards, returning an error in case of a
+    /// timeout. Shutdown remains in effect either way.
+    pub fn shutdown_and_wait_for(&self, limit: Duration) -> Result<(), TimeoutError> {
+        self.shutdown();
+        self.state
+            .wait_until_for(|state| state.in_flight == 0, limit)
+            .map(drop)
+    }
+
+    /// Returns whether shutdown has begun.
+    pub fn is_shutting_down(&self) -> bool {
+        self.state.read().shutting_down
+    }
+
+    /// Returns the number of operations currently in flight.
+    pub fn in_flight(&self) -> usize {
+        self.state.read().in_flight
+    }
+}
+
+impl Default for DrainTracker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Marks an operation admitted by [`DrainTracker::enter()`] as in flight until dropped.
+pub struct DrainGuard<'a> {
+    tracker: &'a DrainTracker,
+}
+
+impl Drop for DrainGuard<'_> {
+    fn drop(&mut self) {
+        self.tracker.state.update(|state| state.in_flight -= 1);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{DrainTracker, ShuttingDown};
+    use std::thread;
+    use std::time::Duration;
+
+    #[test]
+    fn drains_in_flight_operations() {
+        let tracker = DrainTracker::new();
+        let first = tracker.enter().unwrap();
+        let second = tracker.enter().unwrap();
+        assert_eq!(tracker.in_flight(), 2);
+
+        assert!(tracker
+            .shutdown_and_wait_for(Duration::from_millis(1))
+            .is_err());
+        assert!(tracker.is_shutting_down());
+        assert_eq!(tracker.enter().err(), Some(ShuttingDown));
+        drop(first);
+
+        thread::scope(|scope| {
+            let waiter = scope.spawn(|| tracker.shutdown_and_wait());
+            thread::sleep(Duration::from_millis(10));
+            assert!(!waiter.is_finished());
+            drop(second);
+            waiter.join().unwrap();
+        });
+
+        assert_eq!(tracker.in_flight(), 0);
+    }
+}
@@ -0,0 +1,116 @@
+use crate::Condition;
+use rsevents::TimeoutError;
+use std::time::Duration;
+
+/// A monotonically increasing epoch (or sequence number) that threads can wait on until it
+/// reaches a given value.
+///
+/// This is useful for tracking progress through an ordered sequence, such as the index of the last
+/// replicated log entry or the number of the last rendered frame: a thread interested in a
+/// particular point in the sequence [waits until](Self::wait_until) the epoch reaches it, while
+/// the thread making progress [advances](Self::advance) the epoch. As the epoch never goes
+/// backwards, a waiter can't miss the point it is waiting for.
+///
+/// ## Example:
+///
+/// ```rust
+/// use rsevents_extra::EpochEvent;
+///
+/// // The index of the last log entry replicated to a follower.
+/// static REPLICATED: EpochEvent = EpochEvent::new();
+///
+/// std::thread::scope(|scope| {
+///     scope.spawn(|| {
+///         for index in 1..=10 {
+///             // <send log entry `index` to the follower here>
+///             REPLICATED.advance_to(index);
+///         }
+///     });
+///
+///     // Acknowledge the write once the follower has it.
+///     REPLICATED.wait_until(7);
+///     assert!(REPLICATED.epoch() >= 7);
+/// });
+/// ```
+pub struct EpochEvent {
+    epoch: Condition<u64>,
+}
+
+impl EpochEvent {
+    /// Creates a new `EpochEvent` at epoch zero.
+    ///
+    /// This is a `const` function and can be used in a `static` context.
+    pub const fn new() -> Self {
+        EpochEvent {
+            epoch: Condition::new(0),
+        }
+    }
+
+    /// Returns the current epoch.
+    pub fn epoch(&self) -> u64 {
+        *self.epoch.read()
+    }
+
+    /// Moves on to the next epoch, releasing any threads waiting for it. Returns the new epoch.
+    pub fn advance(&self) -> u64 {
+        self.epoch.update(|current| {
+            *current += 1;
+            *current
+        })
+    }
+
+    /// Moves the epoch forward to `epoch`, releasing any threads waiting for it or an earlier
+    /// epoch. Returns the new epoch, which is left unchanged if it had already reached `epoch`.
+    pub fn advance_to(&self, epoch: u64) -> u64 {
+        self.epoch.update(|current| {
+            *current = (*current).max(epoch);
+            *current
+        })
+    }
+
+    /// Blocks until the epoch has reached `epoch`, returning the current epoch (which may have
+    /// moved past it).
+    pub fn wait_until(&self, epoch: u64) -> u64 {
+        *self.epoch.wait_until(|&current| current >= epoch)
+    }
+
+    /// Blocks for up to `limit` until the epoch has reached `epoch`, returning the current epoch
+    /// or an error in case of a timeout.
+    pub fn wait_until_for(&self, epoch: u64, limit: Duration) -> Result<u64, TimeoutError> {
+        self.epoch
+            .wait_until_for(|&current| current >= epoch, limit)
+            .map(|current| *current)
+    }
+}
+
+impl Default for EpochEvent {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::EpochEvent;
+    use std::thread;
+    use std::time::Duration;
+
+    #[test]
+    fn waits_for_epoch() {
+        let event = EpochEvent::new();
+        assert_eq!(event.wait_until(0), 0);
+        assert!(event.wait_until_for(1, Duration::from_millis(1)).is_err());
+
+        thread::scope(|scope| {
+            let waiter = scope.spawn(|| event.wait_until(3));
+            assert_eq!(event.advance(), 1);
+            assert_eq!(event.advance(), 2);
+            assert_eq!(event.advance_to(5), 5);
+            assert_eq!(waiter.join().unwrap(), 5);
+        });
+
+        // The epoch never goes backwards.
+        assert_eq!(event.advance_to(4), 5);
+        assert_eq!(event.wait_until_for(5, Duration::ZERO).ok(), Some(5));
+    }
+}
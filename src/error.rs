@@ -0,0 +1,184 @@
+use std::fmt::Display;
+
+/// The error returned by cancellable waits (such as [`CountdownEvent::wait_cancellable()`]) when
+/// the cancellation token fires before the wait completes.
+///
+/// [`CountdownEvent::wait_cancellable()`]: crate::CountdownEvent::wait_cancellable
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Cancelled;
+
+impl Display for Cancelled {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("The wait call was cancelled")
+    }
+}
+
+impl std::error::Error for Cancelled {}
+
+/// The error returned by [`CircuitBreaker::acquire()`] while the circuit is open (or half-open
+/// with all of its probes in use), indicating that the protected operation shouldn't be attempted.
+///
+/// [`CircuitBreaker::acquire()`]: crate::CircuitBreaker::acquire
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct CircuitOpen;
+
+impl Display for CircuitOpen {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("The circuit is open")
+    }
+}
+
+impl std::error::Error for CircuitOpen {}
+
+/// The error returned by [`DrainTracker::enter()`] and [`ShutdownToken::register()`] once
+/// shutdown has begun, indicating that no new operations or workers are being admitted.
+///
+/// [`DrainTracker::enter()`]: crate::DrainTracker::enter
+/// [`ShutdownToken::register()`]: crate::ShutdownToken::register
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct ShuttingDown;
+
+impl Display for ShuttingDown {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("Shutdown has begun")
+    }
+}
+
+impl std::error::Error for ShuttingDown {}
+
+/// The error returned by blocking or time-bounded [`Semaphore`] acquisitions such as
+/// [`Semaphore::acquire()`], indicating why the acquisition failed.
+///
+/// [`Semaphore`]: crate::Semaphore
+/// [`Semaphore::acquire()`]: crate::Semaphore::acquire
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum AcquireError {
+    /// The time limit elapsed without the semaphore becoming available.
+    TimedOut,
+    /// The semaphore was closed before it became available.
+    Closed,
+    /// The cancellation token passed to
+    /// [`Semaphore::wait_cancellable()`](crate::Semaphore::wait_cancellable) fired before the
+    /// semaphore became available.
+    Cancelled,
+}
+
+impl Display for AcquireError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AcquireError::TimedOut => f.write_str("The wait call timed out"),
+            AcquireError::Closed => f.write_str("The semaphore was closed"),
+            AcquireError::Cancelled => f.write_str("The wait call was cancelled"),
+        }
+    }
+}
+
+impl std::error::Error for AcquireError {}
+
+/// The error returned by non-blocking [`Semaphore`] acquisitions such as
+/// [`Semaphore::try_acquire()`], indicating why the acquisition failed.
+///
+/// [`Semaphore`]: crate::Semaphore
+/// [`Semaphore::try_acquire()`]: crate::Semaphore::try_acquire
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum TryAcquireError {
+    /// The semaphore has no slots available right now.
+    NoPermits,
+    /// The semaphore has been closed.
+    Closed,
+}
+
+impl Display for TryAcquireError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TryAcquireError::NoPermits => f.write_str("The semaphore has no slots available"),
+            TryAcquireError::Closed => f.write_str("The semaphore was closed"),
+        }
+    }
+}
+
+impl std::error::Error for TryAcquireError {}
+
+/// The error returned by the checked [`CountdownEvent`] waits such as
+/// [`CountdownEvent::wait_checked()`] and [`CountdownEvent::wait_for_remaining()`], indicating why
+/// the wait failed.
+///
+/// [`CountdownEvent`]: crate::CountdownEvent
+/// [`CountdownEvent::wait_checked()`]: crate::CountdownEvent::wait_checked
+/// [`CountdownEvent::wait_for_remaining()`]: crate::CountdownEvent::wait_for_remaining
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CountdownError {
+    /// The time limit elapsed before the countdown reached zero, with the number of ticks the
+    /// countdown was still waiting on at the time of the timeout.
+    TimedOut { remaining: usize },
+    /// The countdown was [aborted](crate::CountdownEvent::abort), with the reason passed to
+    /// [`CountdownEvent::abort_with()`](crate::CountdownEvent::abort_with) (if any).
+    Aborted(Option<String>),
+}
+
+impl Display for CountdownError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CountdownError::TimedOut { remaining } => write!(
+                f,
+                "The wait call timed out with {} ticks remaining",
+                remaining
+            ),
+            CountdownError::Aborted(None) => f.write_str("The countdown was aborted"),
+            CountdownError::Aborted(Some(reason)) => {
+                write!(f, "The countdown was aborted: {}", reason)
+            }
+        }
+    }
+}
+
+impl CountdownError {
+    /// The number of ticks the countdown was still waiting on if the wait timed out, or `None` if
+    /// it was aborted.
+    pub fn remaining(&self) -> Option<usize> {
+        match self {
+            CountdownError::TimedOut { remaining } => Some(*remaining),
+            CountdownError::Aborted(_) => None,
+        }
+    }
+}
+
+impl std::error::Error for CountdownError {}
+
+/// The error returned by waits on a [`FallibleCountdown`] when one or more tasks reported a
+/// failure, holding the errors they reported.
+///
+/// [`FallibleCountdown`]: crate::FallibleCountdown
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TaskErrors<E> {
+    pub(crate) errors: Vec<E>,
+}
+
+impl<E> TaskErrors<E> {
+    /// The errors reported by the failed tasks, in the order they were reported.
+    pub fn errors(&self) -> &[E] {
+        &self.errors
+    }
+
+    /// Consumes the error, returning the errors reported by the failed tasks.
+    pub fn into_errors(self) -> Vec<E> {
+        self.errors
+    }
+}
+
+impl<E: Display> Display for TaskErrors<E> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.errors.as_slice() {
+            [] => f.write_str("No tasks failed"),
+            [error] => write!(f, "A task failed: {}", error),
+            [first, rest @ ..] => write!(
+                f,
+                "{} tasks failed, the first with: {}",
+                rest.len() + 1,
+                first
+            ),
+        }
+    }
+}
+
+impl<E: std::fmt::Debug + Display> std::error::Error for TaskErrors<E> {}
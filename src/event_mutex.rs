@@ -0,0 +1,154 @@
+use rsevents::{AutoResetEvent, Awaitable, EventState, TimeoutError};
+use std::cell::UnsafeCell;
+use std::ops::{Deref, DerefMut};
+use std::time::Duration;
+
+/// A mutual-exclusion lock built on an [`AutoResetEvent`], which (unlike [`std::sync::Mutex`])
+/// supports time-bounded acquisition with [`EventMutex::lock_timeout()`].
+///
+/// The lock is held by at most one thread at a time, and is released when the guard returned by
+/// a successful acquisition is dropped. An `EventMutex` can be created in a `static` context.
+/// Unlike `std::sync::Mutex`, it is not poisoned if a thread panics while holding the lock.
+///
+/// ## Example:
+///
+/// ```rust
+/// use rsevents_extra::EventMutex;
+/// use std::time::Duration;
+///
+/// static LOG: EventMutex<Vec<String>> = EventMutex::new(Vec::new());
+///
+/// std::thread::scope(|scope| {
+///     for i in 0..4 {
+///         scope.spawn(move || {
+///             match LOG.lock_timeout(Duration::from_secs(1)) {
+///                 Ok(mut log) => log.push(format!("thread {} checking in", i)),
+///                 Err(_) => eprintln!("log is stuck, dropping message"),
+///             }
+///         });
+///     }
+/// });
+///
+/// assert_eq!(LOG.lock().len(), 4);
+/// ```
+pub struct EventMutex<T: ?Sized> {
+    /// Set while the lock is available.
+    event: AutoResetEvent,
+    value: UnsafeCell<T>,
+}
+
+unsafe impl<T: ?Sized + Send> Send for EventMutex<T> {}
+unsafe impl<T: ?Sized + Send> Sync for EventMutex<T> {}
+
+impl<T> EventMutex<T> {
+    /// Creates a new, unlocked `EventMutex` protecting `value`.
+    ///
+    /// This is a `const` function and can be used in a `static` context.
+    pub const fn new(value: T) -> Self {
+        EventMutex {
+            event: AutoResetEvent::new(EventState::Set),
+            value: UnsafeCell::new(value),
+        }
+    }
+
+    /// Consumes the mutex, returning the protected value.
+    pub fn into_inner(self) -> T {
+        self.value.into_inner()
+    }
+}
+
+impl<T: ?Sized> EventMutex<T> {
+    /// Acquires the lock, blocking until it is available.
+    #[must_use = "the lock is immediately released if the guard is dropped"]
+    pub fn lock(&self) -> EventMutexGuard<'_, T> {
+        self.event.wait();
+        EventMutexGuard { mutex: self }
+    }
+
+    /// Acquires the lock if it is available right away, returning `None` otherwise.
+    #[must_use = "the lock is immediately released if the guard is dropped"]
+    pub fn try_lock(&self) -> Option<EventMutexGuard<'_, T>> {
+        match self.event.wait0() {
+            true => Some(EventMutexGuard { mutex: self }),
+            false => None,
+        }
+    }
+
+    /// Acquires the lock, blocking for up to `limit` until it is available, and returning an
+    /// error in case of a timeout.
+    #[must_use = "the lock is immediately released if the guard is dropped"]
+    pub fn lock_timeout(&self, limit: Duration) -> Result<EventMutexGuard<'_, T>, TimeoutError> {
+        self.event.try_wait_for(limit)?;
+        Ok(EventMutexGuard { mutex: self })
+    }
+
+    /// Returns a mutable reference to the protected value. No locking is needed, as the mutable
+    /// borrow statically guarantees exclusive access.
+    pub fn get_mut(&mut self) -> &mut T {
+        self.value.get_mut()
+    }
+}
+
+impl<T: Default> Default for EventMutex<T> {
+    fn default() -> Self {
+        Self::new(T::default())
+    }
+}
+
+/// Exclusive access to the value protected by an [`EventMutex`], releasing the lock when dropped.
+pub struct EventMutexGuard<'a, T: ?Sized> {
+    mutex: &'a EventMutex<T>,
+}
+
+unsafe impl<T: ?Sized + Sync> Sync for EventMutexGuard<'_, T> {}
+
+impl<T: ?Sized> Deref for EventMutexGuard<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        // The guard holds the lock, so nothing else can be accessing the value.
+        unsafe { &*self.mutex.value.get() }
+    }
+}
+
+impl<T: ?Sized> DerefMut for EventMutexGuard<'_, T> {
+    fn deref_mut(&mut self) -> &mut T {
+        // The guard holds the lock, so nothing else can be accessing the value.
+        unsafe { &mut *self.mutex.value.get() }
+    }
+}
+
+impl<T: ?Sized> Drop for EventMutexGuard<'_, T> {
+    fn drop(&mut self) {
+        self.mutex.event.set();
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::EventMutex;
+    use std::thread;
+    use std::time::Duration;
+
+    #[test]
+    fn excludes_and_times_out() {
+        let mutex = EventMutex::new(0);
+        let guard = mutex.lock();
+        assert!(mutex.try_lock().is_none());
+        assert!(mutex.lock_timeout(Duration::from_millis(1)).is_err());
+        drop(guard);
+
+        thread::scope(|scope| {
+            for _ in 0..4 {
+                scope.spawn(|| {
+                    for _ in 0..100 {
+                        *mutex.lock() += 1;
+                    }
+                });
+            }
+        });
+
+        assert_eq!(*mutex.try_lock().unwrap(), 400);
+        assert_eq!(mutex.into_inner(), 400);
+    }
+}
@@ -0,0 +1,223 @@
+use rsevents::{AutoResetEvent, Awaitable, EventState, ManualResetEvent};
+use std::sync::{Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+
+/// A meeting point where pairs of threads swap values.
+///
+/// The first thread to call [`Exchanger::exchange()`] offers its value and blocks until a second
+/// thread arrives, at which point each thread walks away with the other's value. This is useful
+/// for double-buffering (e.g. a producer filling one buffer while a consumer drains the other,
+/// swapping them once both are done) and other handoff patterns in which both sides have
+/// something to give.
+///
+/// ## Example:
+///
+/// ```rust
+/// use rsevents_extra::Exchanger;
+///
+/// static BUFFERS: Exchanger<Vec<u8>> = Exchanger::new();
+///
+/// std::thread::scope(|scope| {
+///     scope.spawn(|| {
+///         let mut buffer = Vec::new();
+///         for round in 0..3 {
+///             buffer.push(round);
+///             // Hand over the full buffer in return for an empty one.
+///             buffer = BUFFERS.exchange(buffer);
+///         }
+///     });
+///
+///     let mut buffer = Vec::new();
+///     for round in 0..3 {
+///         buffer = BUFFERS.exchange(buffer);
+///         assert_eq!(buffer, [round]);
+///         buffer.clear();
+///     }
+/// });
+/// ```
+pub struct Exchanger<T> {
+    slot: Mutex<Slot<T>>,
+    /// Set when the value offered by the first thread of a pair has been answered.
+    answered: AutoResetEvent,
+    /// Set while no answer is waiting to be collected, so that a new pair may start. Only set or
+    /// reset with the `slot` lock held.
+    vacant: ManualResetEvent,
+}
+
+enum Slot<T> {
+    Empty,
+    /// A value offered by a thread waiting for a partner.
+    Offered(T),
+    /// The partner's value, waiting to be collected by the thread that made the offer.
+    Answered(T),
+}
+
+impl<T> Exchanger<T> {
+    /// Creates a new `Exchanger` with no threads waiting.
+    ///
+    /// This is a `const` function and can be used in a `static` context.
+    pub const fn new() -> Self {
+        Exchanger {
+            slot: Mutex::new(Slot::Empty),
+            answered: AutoResetEvent::new(EventState::Unset),
+            vacant: ManualResetEvent::new(EventState::Set),
+        }
+    }
+
+    /// Swaps `value` with the value of another thread calling `exchange()`, blocking until one
+    /// does.
+    pub fn exchange(&self, value: T) -> T {
+        match self.exchange_inner(value, None) {
+            Ok(theirs) => theirs,
+            Err(_) => unreachable!(),
+        }
+    }
+
+    /// Swaps `value` with the value of another thread calling `exchange()`, handing it back as
+    /// `Err(value)` if no other thread arrives within the time limit.
+    pub fn exchange_for(&self, value: T, limit: Duration) -> Result<T, T> {
+        self.exchange_inner(value, Some(Instant::now() + limit))
+    }
+
+    fn exchange_inner(&self, value: T, deadline: Option<Instant>) -> Result<T, T> {
+        let remaining =
+            || deadline.map(|deadline| deadline.saturating_duration_since(Instant::now()));
+
+        let mut slot = self.lock();
+        loop {
+            match std::mem::replace(&mut *slot, Slot::Empty) {
+                Slot::Offered(theirs) => {
+                    *slot = Slot::Answered(value);
+                    self.vacant.reset();
+                    self.answered.set();
+                    return Ok(theirs);
+                }
+                Slot::Empty => break,
+                answered => {
+                    // Wait for the previous pair to finish up.
+                    *slot = answered;
+                    drop(slot);
+                    match remaining() {
+                        None => self.vacant.wait(),
+                        Some(limit) if self.vacant.wait_for(limit) => (),
+                        Some(_) => return Err(value),
+                    }
+                    slot = self.lock();
+                }
+            }
+        }
+
+        *slot = Slot::Offered(value);
+        drop(slot);
+        let answered = match remaining() {
+            None => {
+                self.answered.wait();
+                true
+            }
+            Some(limit) => self.answered.wait_for(limit),
+        };
+
+        let mut slot = self.lock();
+        if !answered {
+            // Only withdraw the offer if it is still there, leaving an answer that raced our
+            // timeout in place to be collected below.
+            if let Slot::Offered(_) = &*slot {
+                match std::mem::replace(&mut *slot, Slot::Empty) {
+                    Slot::Offered(value) => return Err(value),
+                    _ => unreachable!(),
+                }
+            }
+            // We were answered just as we timed out. The answering thread set the event while
+            // holding the lock, so it is definitely set.
+            self.answered.reset();
+        }
+        match std::mem::replace(&mut *slot, Slot::Empty) {
+            Slot::Answered(theirs) => {
+                self.vacant.set();
+                Ok(theirs)
+            }
+            _ => unreachable!("Exchanger answer went missing!"),
+        }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Slot<T>> {
+        self.slot.lock().unwrap()
+    }
+}
+
+impl<T> Default for Exchanger<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::Exchanger;
+    use std::thread;
+    use std::time::Duration;
+
+    #[test]
+    fn pairs_swap_values() {
+        let exchanger = Exchanger::new();
+        let mut received: Vec<usize> = thread::scope(|scope| {
+            let threads: Vec<_> = (0..8)
+                .map(|i| {
+                    let exchanger = &exchanger;
+                    scope.spawn(move || (i, exchanger.exchange(i)))
+                })
+                .collect();
+            threads
+                .into_iter()
+                .map(|thread| {
+                    let (mine, theirs) = thread.join().unwrap();
+                    assert_ne!(mine, theirs);
+                    theirs
+                })
+                .collect()
+        });
+
+        received.sort_unstable();
+        assert_eq!(received, (0..8).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn timed_out_offer_is_returned() {
+        let exchanger = Exchanger::new();
+        assert_eq!(exchanger.exchange_for(1, Duration::from_millis(5)), Err(1));
+
+        // The withdrawn offer must not be picked up by the next pair.
+        thread::scope(|scope| {
+            let other = scope.spawn(|| exchanger.exchange_for(2, Duration::from_secs(10)));
+            assert_eq!(exchanger.exchange_for(3, Duration::from_secs(10)), Ok(2));
+            assert_eq!(other.join().unwrap(), Ok(3));
+        });
+    }
+
+    #[test]
+    fn timed_exchanges_race_answers() {
+        let exchanger = Exchanger::new();
+        thread::scope(|scope| {
+            let threads: Vec<_> = (0..2)
+                .map(|side| {
+                    let exchanger = &exchanger;
+                    scope.spawn(move || {
+                        for i in 0..20_000 {
+                            let mine = side * 100_000 + i;
+                            match exchanger.exchange_for(mine, Duration::from_micros(1)) {
+                                Ok(theirs) => assert_ne!(theirs / 100_000, side),
+                                Err(value) => assert_eq!(value, mine),
+                            }
+                        }
+                    })
+                })
+                .collect();
+            for thread in threads {
+                thread.join().unwrap();
+            }
+        });
+
+        // Nothing was left behind in the slot.
+        assert_eq!(exchanger.exchange_for(1, Duration::ZERO), Err(1));
+    }
+}
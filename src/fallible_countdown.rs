@@ -0,0 +1,166 @@
+use crate::error::TaskErrors;
+use crate::subscribe::{Subscribe, Subscription};
+use crate::sync::loom_const_fn;
+use crate::CountdownEvent;
+use rsevents::{Awaitable, TimeoutError};
+use std::convert::Infallible;
+use std::sync::Mutex;
+use std::task::Waker;
+use std::time::Duration;
+
+/// A [`CountdownEvent`] that tracks whether each task succeeded or failed, so that waiting on it
+/// reports whether the work as a whole succeeded.
+///
+/// Each task reports its outcome with [`FallibleCountdown::tick_ok()`] or
+/// [`FallibleCountdown::tick_err()`], and waiting on the countdown (via the [`Awaitable`] trait)
+/// blocks until all tasks have completed, then returns `Ok(())` if every task succeeded or a
+/// [`TaskErrors`] holding the errors of the tasks that failed.
+///
+/// ## Example:
+///
+/// ```rust
+/// use rsevents_extra::{Awaitable, FallibleCountdown, TaskErrors};
+///
+/// fn upload_all(uploads: &FallibleCountdown<&'static str>) -> Result<(), TaskErrors<&'static str>> {
+///     uploads.wait()
+/// }
+///
+/// let uploads = FallibleCountdown::new(3);
+/// std::thread::scope(|scope| {
+///     for size in [10, 0, 20] {
+///         let uploads = &uploads;
+///         scope.spawn(move || match size {
+///             0 => uploads.tick_err("empty file"),
+///             _ => uploads.tick_ok(),
+///         });
+///     }
+///
+///     let errors = upload_all(&uploads).unwrap_err();
+///     assert_eq!(errors.errors(), ["empty file"]);
+/// });
+/// ```
+pub struct FallibleCountdown<E> {
+    countdown: CountdownEvent,
+    /// The errors reported so far and not yet returned to a waiter.
+    errors: Mutex<Vec<E>>,
+}
+
+impl<E> FallibleCountdown<E> {
+    loom_const_fn! {
+        /// Creates a new fallible countdown waiting on `count` tasks. If a count of zero is
+        /// specified, the countdown is immediately set.
+        ///
+        /// This is a `const` function and can be used in a `static` context.
+        pub fn new(count: usize) -> Self {
+            Self {
+                countdown: CountdownEvent::new(count),
+                errors: Mutex::new(Vec::new()),
+            }
+        }
+    }
+
+    /// Reports that a task completed successfully, ticking the countdown.
+    pub fn tick_ok(&self) {
+        self.countdown.tick();
+    }
+
+    /// Reports that a task failed with `error`, ticking the countdown.
+    pub fn tick_err(&self, error: E) {
+        // The error must be recorded before the final tick wakes any waiters.
+        self.errors.lock().unwrap().push(error);
+        self.countdown.tick();
+    }
+
+    /// Reports the outcome of a task, ticking the countdown.
+    pub fn tick_result(&self, result: Result<(), E>) {
+        match result {
+            Ok(()) => self.tick_ok(),
+            Err(error) => self.tick_err(error),
+        }
+    }
+
+    /// Gets the number of tasks still outstanding.
+    pub fn count(&self) -> usize {
+        self.countdown.count()
+    }
+
+    /// Gets the number of failures reported so far (and not yet returned to a waiter).
+    pub fn failures(&self) -> usize {
+        self.errors.lock().unwrap().len()
+    }
+
+    /// Resets the countdown to wait on `count` new tasks, discarding any failures not yet returned
+    /// to a waiter.
+    pub fn reset(&self, count: usize) {
+        let mut errors = self.errors.lock().unwrap();
+        errors.clear();
+        self.countdown.reset(count);
+    }
+
+    /// Takes the errors reported so far, if any.
+    fn take(&self) -> Result<(), TaskErrors<E>> {
+        let errors = std::mem::take(&mut *self.errors.lock().unwrap());
+        match errors.is_empty() {
+            true => Ok(()),
+            false => Err(TaskErrors { errors }),
+        }
+    }
+}
+
+impl<E> Awaitable<'_> for FallibleCountdown<E> {
+    type T = Result<(), TaskErrors<E>>;
+    type Error = TimeoutError;
+
+    /// Waits for all tasks to complete, then reports whether they all succeeded. The errors of any
+    /// failed tasks are returned to only one waiter.
+    fn try_wait(&self) -> Result<Self::T, Infallible> {
+        self.countdown.wait();
+        Ok(self.take())
+    }
+
+    /// Waits for all tasks to complete and reports whether they all succeeded, or returns an error
+    /// in case of a timeout.
+    fn try_wait_for(&self, limit: Duration) -> Result<Self::T, TimeoutError> {
+        self.countdown.try_wait_for(limit)?;
+        Ok(self.take())
+    }
+
+    /// Reports whether all tasks succeeded if they have all completed, without blocking.
+    fn try_wait0(&self) -> Result<Self::T, TimeoutError> {
+        self.countdown.try_wait0()?;
+        Ok(self.take())
+    }
+}
+
+impl<E> Subscribe for FallibleCountdown<E> {
+    fn subscribe(&self, waker: &Waker) -> Option<Subscription<'_>> {
+        self.countdown.subscribe(waker)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{Awaitable, FallibleCountdown};
+
+    #[test]
+    fn all_succeeded() {
+        let countdown = FallibleCountdown::<()>::new(2);
+        countdown.tick_ok();
+        assert!(countdown.try_wait0().is_err());
+        countdown.tick_result(Ok(()));
+        assert_eq!(countdown.wait(), Ok(()));
+    }
+
+    #[test]
+    fn failures_are_reported() {
+        let countdown = FallibleCountdown::new(3);
+        countdown.tick_err(1);
+        countdown.tick_ok();
+        countdown.tick_result(Err(2));
+        assert_eq!(countdown.failures(), 2);
+
+        let errors = countdown.wait().unwrap_err();
+        assert_eq!(errors.errors(), [1, 2]);
+        assert_eq!(errors.to_string(), "2 tasks failed, the first with: 1");
+    }
+}
@@ -0,0 +1,120 @@
+#![allow(clippy::bool_assert_comparison)]
+
+use rsevents::{Awaitable, EventState, ManualResetEvent, TimeoutError};
+use std::convert::Infallible;
+use std::time::Duration;
+
+/// A gate that can be repeatedly opened and closed, letting waiters through while it is open and
+/// blocking them while it is closed.
+///
+/// A `Gate` is useful for pausing a pool of workers, e.g. while it is being reconfigured: each
+/// worker waits on the gate before picking up its next job, so [closing](Gate::close) the gate
+/// holds up any worker that gets to it afterwards (without interrupting jobs already underway) and
+/// [opening](Gate::open) it again lets them all resume.
+///
+/// ## Example:
+///
+/// ```rust
+/// use rsevents_extra::{Awaitable, Gate};
+/// use std::sync::atomic::{AtomicUsize, Ordering};
+///
+/// static RUNNING: Gate = Gate::new(true);
+/// static JOBS: AtomicUsize = AtomicUsize::new(0);
+///
+/// std::thread::scope(|scope| {
+///     scope.spawn(|| {
+///         for _ in 0..100 {
+///             RUNNING.wait();
+///             // <process the next job here>
+///             JOBS.fetch_add(1, Ordering::Relaxed);
+///         }
+///     });
+///
+///     RUNNING.close();
+///     // <reconfigure the workers here>
+///     RUNNING.open();
+/// });
+///
+/// assert_eq!(JOBS.load(Ordering::Relaxed), 100);
+/// ```
+pub struct Gate {
+    event: ManualResetEvent,
+}
+
+impl Gate {
+    /// Creates a new `Gate`, which starts out open or closed according to `open`.
+    ///
+    /// This is a `const` function and can be used in a `static` context.
+    pub const fn new(open: bool) -> Self {
+        Gate {
+            event: ManualResetEvent::new(match open {
+                true => EventState::Set,
+                false => EventState::Unset,
+            }),
+        }
+    }
+
+    /// Opens the gate, releasing any blocked waiters and letting subsequent waiters through until
+    /// the gate is closed again.
+    pub fn open(&self) {
+        self.event.set();
+    }
+
+    /// Closes the gate, blocking subsequent waiters until it is opened again. Threads that have
+    /// already passed through the gate are not affected.
+    pub fn close(&self) {
+        self.event.reset();
+    }
+
+    /// Returns whether the gate is currently open.
+    pub fn is_open(&self) -> bool {
+        self.event.wait0()
+    }
+}
+
+impl Awaitable<'_> for Gate {
+    type T = ();
+    type Error = TimeoutError;
+
+    /// Waits for the gate to be open.
+    fn try_wait(&self) -> Result<(), Infallible> {
+        self.event.try_wait()
+    }
+
+    /// Waits for the gate to be open or returns an error in case of a timeout.
+    fn try_wait_for(&self, limit: Duration) -> Result<(), TimeoutError> {
+        self.event.try_wait_for(limit)
+    }
+
+    /// Checks whether the gate is open without blocking.
+    fn try_wait0(&self) -> Result<(), TimeoutError> {
+        self.event.try_wait0()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{Awaitable, Gate};
+    use std::thread;
+    use std::time::Duration;
+
+    #[test]
+    fn open_and_close() {
+        let gate = Gate::new(false);
+        assert_eq!(gate.is_open(), false);
+
+        thread::scope(|scope| {
+            let waiter = scope.spawn(|| gate.wait());
+            thread::sleep(Duration::from_millis(10));
+            assert!(!waiter.is_finished());
+            gate.open();
+            waiter.join().unwrap();
+        });
+
+        assert_eq!(gate.wait0(), true);
+        gate.close();
+        assert_eq!(gate.wait_for(Duration::from_millis(1)), false);
+        gate.open();
+        assert_eq!(gate.is_open(), true);
+    }
+}
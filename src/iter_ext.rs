@@ -0,0 +1,107 @@
+use crate::{ScopeExt, Semaphore};
+use std::thread;
+
+/// An extension trait for iterators, processing each item on its own scoped thread while never
+/// running more threads at once than a [`Semaphore`] allows.
+///
+/// Each item is handed to a new thread only once a slot has been obtained from the semaphore, and
+/// the slot is returned when the thread completes. The semaphore may be shared with other code, in
+/// which case the combined concurrency of all its users is limited.
+///
+/// ## Example:
+///
+/// ```rust
+/// use rsevents_extra::{Semaphore, SemaphoreIterExt};
+///
+/// // Download at most three urls at the same time.
+/// static DOWNLOADS: Semaphore = Semaphore::new(3, 3);
+///
+/// let urls = ["https://a.example/", "https://b.example/", "https://c.example/"];
+/// let lengths = urls.iter().map_limited(&DOWNLOADS, |url| {
+///     // <download the url here>
+///     url.len()
+/// });
+/// assert_eq!(lengths, vec![18, 18, 18]);
+/// ```
+pub trait SemaphoreIterExt: Iterator + Sized {
+    /// Calls `f` on each item of the iterator, each on its own scoped thread, with no more than
+    /// the semaphore's available slots running at the same time. Returns once all calls to `f`
+    /// have completed, propagating the first panic (if any).
+    fn for_each_limited<F>(self, semaphore: &Semaphore, f: F)
+    where
+        F: Fn(Self::Item) + Sync,
+        Self::Item: Send,
+    {
+        let f = &f;
+        thread::scope(|scope| {
+            for item in self {
+                scope.spawn_limited(semaphore, move || f(item));
+            }
+        });
+    }
+
+    /// Like [`for_each_limited()`](Self::for_each_limited), but collects the values returned by
+    /// `f` into a `Vec`, in the order of the items they were produced from.
+    fn map_limited<F, T>(self, semaphore: &Semaphore, f: F) -> Vec<T>
+    where
+        F: Fn(Self::Item) -> T + Sync,
+        Self::Item: Send,
+        T: Send,
+    {
+        let f = &f;
+        thread::scope(|scope| {
+            let handles: Vec<_> = self
+                .map(|item| scope.spawn_limited(semaphore, move || f(item)))
+                .collect();
+
+            handles
+                .into_iter()
+                .map(|handle| match handle.join() {
+                    Ok(result) => result,
+                    Err(panic) => std::panic::resume_unwind(panic),
+                })
+                .collect()
+        })
+    }
+}
+
+impl<I: Iterator> SemaphoreIterExt for I {}
+
+#[cfg(test)]
+mod test {
+    use crate::{Semaphore, SemaphoreIterExt};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::thread;
+    use std::time::Duration;
+
+    #[test]
+    fn for_each_is_limited() {
+        let sem = Semaphore::new(3, 3);
+        let running = AtomicUsize::new(0);
+        let peak = AtomicUsize::new(0);
+        let total = AtomicUsize::new(0);
+
+        (1..=10).for_each_limited(&sem, |i| {
+            let now = running.fetch_add(1, Ordering::SeqCst) + 1;
+            peak.fetch_max(now, Ordering::SeqCst);
+            thread::sleep(Duration::from_millis(5));
+            total.fetch_add(i, Ordering::SeqCst);
+            running.fetch_sub(1, Ordering::SeqCst);
+        });
+
+        assert!(peak.load(Ordering::SeqCst) <= 3);
+        assert_eq!(total.load(Ordering::SeqCst), 55);
+        assert_eq!(sem.count(), 3);
+    }
+
+    #[test]
+    fn map_preserves_order() {
+        let sem = Semaphore::new(2, 2);
+        let squares = (0..6u64).map_limited(&sem, |i| {
+            // Later items finish first
+            thread::sleep(Duration::from_millis(12 - 2 * i));
+            i * i
+        });
+        assert_eq!(squares, vec![0, 1, 4, 9, 16, 25]);
+    }
+}
@@ -0,0 +1,111 @@
+#![allow(clippy::bool_assert_comparison)]
+
+use rsevents::{Awaitable, EventState, ManualResetEvent, TimeoutError};
+use std::convert::Infallible;
+use std::time::Duration;
+
+/// A single-use gate that, once [opened](Latch::open), lets all current and future waiters
+/// through for good.
+///
+/// A `Latch` is for one-time signals such as "initialization has finished" or "shutdown has
+/// started". Unlike a [`CountdownEvent`](crate::CountdownEvent) created with a count of one (or a
+/// bare `ManualResetEvent`), it can't be reset or re-armed, so a waiter that has seen it open
+/// knows that it will stay open.
+///
+/// ## Example:
+///
+/// ```rust
+/// use rsevents_extra::{Awaitable, Latch};
+///
+/// static CONFIG_LOADED: Latch = Latch::new();
+///
+/// std::thread::scope(|scope| {
+///     for _ in 0..4 {
+///         scope.spawn(|| {
+///             CONFIG_LOADED.wait();
+///             // <serve requests using the loaded configuration>
+///         });
+///     }
+///
+///     // <load the configuration here>
+///     CONFIG_LOADED.open();
+/// });
+/// ```
+pub struct Latch {
+    event: ManualResetEvent,
+}
+
+impl Latch {
+    /// Creates a new, closed `Latch`.
+    ///
+    /// This is a `const` function and can be used in a `static` context.
+    pub const fn new() -> Self {
+        Latch {
+            event: ManualResetEvent::new(EventState::Unset),
+        }
+    }
+
+    /// Opens the latch, releasing all current and future waiters. Opening an already open latch
+    /// has no effect.
+    pub fn open(&self) {
+        self.event.set();
+    }
+
+    /// Returns whether the latch has been opened, without blocking.
+    pub fn is_open(&self) -> bool {
+        self.event.wait0()
+    }
+}
+
+impl Default for Latch {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Awaitable<'_> for Latch {
+    type T = ();
+    type Error = TimeoutError;
+
+    /// Waits for the latch to be opened.
+    fn try_wait(&self) -> Result<(), Infallible> {
+        self.event.try_wait()
+    }
+
+    /// Waits for the latch to be opened or returns an error in case of a timeout.
+    fn try_wait_for(&self, limit: Duration) -> Result<(), TimeoutError> {
+        self.event.try_wait_for(limit)
+    }
+
+    /// Checks whether the latch has been opened without blocking.
+    fn try_wait0(&self) -> Result<(), TimeoutError> {
+        self.event.try_wait0()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{Awaitable, Latch};
+    use std::thread;
+    use std::time::Duration;
+
+    #[test]
+    fn opens_for_good() {
+        let latch = Latch::new();
+        assert_eq!(latch.is_open(), false);
+        assert_eq!(latch.wait_for(Duration::from_millis(1)), false);
+
+        thread::scope(|scope| {
+            let waiters: Vec<_> = (0..4).map(|_| scope.spawn(|| latch.wait())).collect();
+            thread::sleep(Duration::from_millis(10));
+            latch.open();
+            for waiter in waiters {
+                waiter.join().unwrap();
+            }
+        });
+
+        latch.open();
+        assert_eq!(latch.is_open(), true);
+        assert_eq!(latch.wait0(), true);
+    }
+}
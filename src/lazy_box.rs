@@ -0,0 +1,71 @@
+use crate::sync::{loom_const_fn, AtomicPtr, Ordering};
+use std::marker::PhantomData;
+use std::ptr;
+
+/// Out-of-line state that a primitive only needs once certain features are used (such as waiting
+/// in line or awaiting it from async code), allocated on first use and freed along with the
+/// primitive. This keeps primitives that never use those features as small as the handful of
+/// atomics they actually need.
+pub(crate) struct LazyBox<T> {
+    ptr: AtomicPtr<T>,
+    /// The box owns a `T`, which may be allocated on one thread and dropped on another.
+    marker: PhantomData<Box<T>>,
+}
+
+unsafe impl<T: Send> Send for LazyBox<T> {}
+unsafe impl<T: Send + Sync> Sync for LazyBox<T> {}
+
+impl<T> LazyBox<T> {
+    loom_const_fn! {
+        pub(crate) fn new() -> Self {
+            LazyBox {
+                ptr: AtomicPtr::new(ptr::null_mut()),
+                marker: PhantomData,
+            }
+        }
+    }
+
+    /// Returns the state if it has been allocated.
+    pub(crate) fn get(&self) -> Option<&T> {
+        // SAFETY: a non-null pointer was published by `get_or_init()` (with release semantics)
+        // and is only freed along with `self`.
+        unsafe { self.ptr.load(Ordering::Acquire).as_ref() }
+    }
+}
+
+impl<T: Default> LazyBox<T> {
+    /// Returns the state, allocating it first if necessary.
+    pub(crate) fn get_or_init(&self) -> &T {
+        if let Some(value) = self.get() {
+            return value;
+        }
+
+        let new = Box::into_raw(Box::<T>::default());
+        match self
+            .ptr
+            .compare_exchange(ptr::null_mut(), new, Ordering::AcqRel, Ordering::Acquire)
+        {
+            // SAFETY: we just published `new`, which is only freed along with `self`.
+            Ok(_) => unsafe { &*new },
+            Err(existing) => {
+                // Another thread beat us to it.
+                // SAFETY: `new` was never shared, and `existing` is only freed along with `self`.
+                unsafe {
+                    drop(Box::from_raw(new));
+                    &*existing
+                }
+            }
+        }
+    }
+}
+
+impl<T> Drop for LazyBox<T> {
+    fn drop(&mut self) {
+        // Exclusive access means a relaxed load suffices (loom's atomics have no `get_mut()`).
+        let ptr = self.ptr.load(Ordering::Relaxed);
+        if !ptr.is_null() {
+            // SAFETY: the pointer came from `Box::into_raw()` and nobody else can access it now.
+            unsafe { drop(Box::from_raw(ptr)) };
+        }
+    }
+}
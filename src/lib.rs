@@ -1,8 +1,155 @@
+mod awaitable_ext;
+mod backoff;
+mod barrier;
+mod bounded_queue;
+mod bounded_stack;
+mod broadcast_event;
+mod budget;
+mod cancellation;
+mod child_countdown;
+mod child_semaphore;
+mod circuit_breaker;
+mod clock;
+mod collecting_countdown;
+mod completion_event;
+mod condition;
+mod const_semaphore;
+mod count_up;
 mod countdown;
+mod delay_queue;
+mod drain_tracker;
+mod epoch_event;
+mod error;
+mod event_mutex;
+mod exchanger;
+mod fallible_countdown;
+mod gate;
+mod handoff_slot;
+mod iter_ext;
+mod latch;
+mod lazy_box;
+mod monitor;
+mod multi_wait;
+mod notify;
+mod object_pool;
+mod once_event;
+mod pacer;
+mod periodic_event;
+mod phaser;
+mod pulse_event;
+mod quiescence;
+mod rate_limiter;
+#[cfg(feature = "rayon")]
+mod rayon_ext;
+mod relay;
+mod rendezvous;
+mod resettable_once;
+mod rw_event;
+mod scope_ext;
 mod semaphore;
+mod semaphore_set;
+mod seq_lock;
+mod shutdown;
+mod single_flight;
+mod small_semaphore;
+mod striped_semaphore;
+mod subscribe;
+mod sync;
+mod task_tracker;
+mod threshold_event;
+mod throttle;
+mod ticket_turn;
+mod timer_event;
+mod turnstile;
+mod unbounded_semaphore;
+mod value_event;
+mod wait_group;
+mod wait_set;
+mod waiter_queue;
+#[cfg(feature = "async")]
+mod wake_timer;
+mod waker_list;
+mod watchdog;
 
-pub use self::countdown::CountdownEvent;
-pub use self::semaphore::{Semaphore, SemaphoreGuard};
+#[cfg(feature = "async")]
+pub use self::awaitable_ext::AwaitableFuture;
+pub use self::awaitable_ext::{AllOf, AnyOf, AwaitableExt, OneOf};
+pub use self::barrier::Barrier;
+pub use self::bounded_queue::BoundedQueue;
+pub use self::bounded_stack::BoundedStack;
+pub use self::broadcast_event::{BroadcastEvent, BroadcastSubscription};
+pub use self::budget::Budget;
+pub use self::cancellation::{CancellationToken, CancellationTokenSource};
+pub use self::child_countdown::ChildCountdown;
+pub use self::child_semaphore::{ChildSemaphore, ChildSemaphoreGuard};
+pub use self::circuit_breaker::{CircuitBreaker, CircuitPermit, CircuitState};
+pub use self::clock::{Clock, MockClock, SystemClock};
+pub use self::collecting_countdown::CollectingCountdown;
+pub use self::completion_event::CompletionEvent;
+pub use self::condition::{Condition, ConditionGuard};
+pub use self::const_semaphore::ConstSemaphore;
+pub use self::count_up::CountUpEvent;
+#[cfg(feature = "async")]
+pub use self::countdown::CountdownWait;
+pub use self::countdown::{CountdownEvent, CountdownScope, Milestone, OverTickPolicy, TickGuard};
+pub use self::delay_queue::DelayQueue;
+pub use self::drain_tracker::{DrainGuard, DrainTracker};
+pub use self::epoch_event::EpochEvent;
+pub use self::error::{
+    AcquireError, Cancelled, CircuitOpen, CountdownError, ShuttingDown, TaskErrors, TryAcquireError,
+};
+pub use self::event_mutex::{EventMutex, EventMutexGuard};
+pub use self::exchanger::Exchanger;
+pub use self::fallible_countdown::FallibleCountdown;
+pub use self::gate::Gate;
+pub use self::handoff_slot::HandoffSlot;
+pub use self::iter_ext::SemaphoreIterExt;
+pub use self::latch::Latch;
+pub use self::monitor::{Monitor, MonitorGuard};
+pub use self::multi_wait::{
+    wait_all, wait_all_for, wait_all_of, wait_all_of_for, wait_any, wait_any_for, wait_any_of,
+    wait_any_of_for, wait_any_of_subscribed, wait_any_of_subscribed_for, wait_any_subscribed,
+    wait_any_subscribed_for, AwaitableTuple, DynAwaitable, DynSubscribe, SubscribeTuple,
+};
+pub use self::notify::Notify;
+pub use self::object_pool::{ObjectPool, ObjectPoolGuard};
+pub use self::once_event::{Lazy, OnceEvent};
+pub use self::pacer::Pacer;
+pub use self::periodic_event::PeriodicEvent;
+pub use self::phaser::Phaser;
+pub use self::pulse_event::PulseEvent;
+pub use self::quiescence::{QuiescenceGuard, QuiescenceTracker};
+pub use self::rate_limiter::RateLimiter;
+#[cfg(feature = "rayon")]
+pub use self::rayon_ext::{ParallelIteratorExt, Throttled};
+pub use self::relay::Relay;
+pub use self::rendezvous::Rendezvous;
+pub use self::resettable_once::ResettableOnce;
+pub use self::rw_event::{RwEvent, RwEventReadGuard, RwEventWriteGuard};
+pub use self::scope_ext::ScopeExt;
+#[cfg(feature = "async")]
+pub use self::semaphore::{Acquire, AcquireOwned};
+pub use self::semaphore::{
+    OwnedSemaphoreGuard, Semaphore, SemaphoreGuard, SemaphoreOptions, Ticket,
+};
+pub use self::semaphore_set::{acquire_all, SemaphoreSet, SemaphoreSetGuard};
+pub use self::seq_lock::SeqLock;
+pub use self::shutdown::{ShutdownToken, ShutdownWorker};
+pub use self::single_flight::SingleFlight;
+pub use self::small_semaphore::{SmallSemaphore, SmallSemaphoreGuard};
+pub use self::striped_semaphore::StripedSemaphore;
+pub use self::subscribe::{Subscribe, Subscription};
+pub use self::task_tracker::TaskTracker;
+pub use self::threshold_event::ThresholdEvent;
+pub use self::throttle::Throttle;
+pub use self::ticket_turn::TicketTurn;
+pub use self::timer_event::TimerEvent;
+pub use self::turnstile::Turnstile;
+pub use self::unbounded_semaphore::{UnboundedSemaphore, UnboundedSemaphoreGuard};
+pub use self::value_event::ValueEvent;
+pub use self::wait_group::WaitGroup;
+pub use self::wait_set::{WaitKey, WaitSet};
+pub use self::watchdog::WatchdogEvent;
 
 /// The `rsevents` abstraction over all types that can be awaited, implemented by types in this
 /// crate.
@@ -0,0 +1,209 @@
+use crate::waiter_queue::{Waiter, WaiterQueue};
+use rsevents::TimeoutError;
+use std::ops::{Deref, DerefMut};
+use std::sync::{Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+
+/// A monitor, combining a mutex protecting some data with condition-variable style waiting and
+/// notification on the same object.
+///
+/// Threads [lock](Monitor::lock) the monitor to access the data, and can then
+/// [wait](MonitorGuard::wait) on the guard, which releases the lock until another thread calls
+/// [`notify_one()`](Monitor::notify_one) or [`notify_all()`](Monitor::notify_all) and then
+/// reacquires it. There are no spurious wakeups: a wait only returns once the thread has been
+/// notified (or its time limit has elapsed), and [`MonitorGuard::wait_while()`] takes care of
+/// re-checking a condition that another thread may have invalidated in the meantime.
+///
+/// ## Example:
+///
+/// ```rust
+/// use rsevents_extra::Monitor;
+/// use std::collections::VecDeque;
+///
+/// static QUEUE: Monitor<VecDeque<u32>> = Monitor::new(VecDeque::new());
+///
+/// std::thread::scope(|scope| {
+///     scope.spawn(|| {
+///         for job in 0..10 {
+///             QUEUE.lock().push_back(job);
+///             QUEUE.notify_one();
+///         }
+///     });
+///
+///     for expected in 0..10 {
+///         let mut queue = QUEUE.lock();
+///         queue.wait_while(|queue| queue.is_empty());
+///         assert_eq!(queue.pop_front(), Some(expected));
+///     }
+/// });
+/// ```
+pub struct Monitor<T> {
+    data: Mutex<T>,
+    /// The waiting threads, in arrival order.
+    waiters: Mutex<WaiterQueue>,
+}
+
+impl<T> Monitor<T> {
+    /// Creates a new, unlocked `Monitor` protecting `data`.
+    ///
+    /// This is a `const` function and can be used in a `static` context.
+    pub const fn new(data: T) -> Self {
+        Monitor {
+            data: Mutex::new(data),
+            waiters: Mutex::new(WaiterQueue::new()),
+        }
+    }
+
+    /// Locks the monitor, blocking until the lock is available.
+    #[must_use = "the lock is immediately released if the guard is dropped"]
+    pub fn lock(&self) -> MonitorGuard<'_, T> {
+        MonitorGuard {
+            monitor: self,
+            data: Some(self.data.lock().unwrap()),
+        }
+    }
+
+    /// Wakes the longest-waiting thread, if any. The notification is lost if no thread is
+    /// waiting.
+    pub fn notify_one(&self) {
+        self.waiters.lock().unwrap().release_one();
+    }
+
+    /// Wakes all threads that are currently waiting.
+    pub fn notify_all(&self) {
+        self.waiters.lock().unwrap().release_all();
+    }
+
+    /// Consumes the monitor, returning the protected data.
+    pub fn into_inner(self) -> T {
+        self.data.into_inner().unwrap()
+    }
+}
+
+impl<T: Default> Default for Monitor<T> {
+    fn default() -> Self {
+        Self::new(T::default())
+    }
+}
+
+/// Access to the data protected by a locked [`Monitor`], releasing the lock when dropped.
+pub struct MonitorGuard<'a, T> {
+    monitor: &'a Monitor<T>,
+    /// Only `None` while waiting.
+    data: Option<MutexGuard<'a, T>>,
+}
+
+impl<T> MonitorGuard<'_, T> {
+    /// Releases the lock and blocks until notified, then reacquires the lock.
+    pub fn wait(&mut self) {
+        let waiter = self.release();
+        waiter.wait();
+        self.reacquire();
+    }
+
+    /// Releases the lock and blocks for up to `limit` until notified, then reacquires the lock.
+    /// Returns an error if the time limit elapsed without a notification.
+    pub fn wait_for(&mut self, limit: Duration) -> Result<(), TimeoutError> {
+        let waiter = self.release();
+        let notified = waiter.wait_for(limit, |waiter| {
+            self.monitor.waiters.lock().unwrap().withdraw(waiter)
+        });
+        let result = match notified {
+            true => Ok(()),
+            false => Err(TimeoutError),
+        };
+        self.reacquire();
+        result
+    }
+
+    /// Waits (as with [`wait()`](Self::wait)) for as long as `condition` holds for the data,
+    /// returning immediately if it doesn't hold to begin with.
+    pub fn wait_while(&mut self, mut condition: impl FnMut(&mut T) -> bool) {
+        while condition(self) {
+            self.wait();
+        }
+    }
+
+    /// Waits (as with [`wait_for()`](Self::wait_for)) for as long as `condition` holds for the
+    /// data, returning an error if it still holds once `limit` has elapsed.
+    pub fn wait_while_for(
+        &mut self,
+        mut condition: impl FnMut(&mut T) -> bool,
+        limit: Duration,
+    ) -> Result<(), TimeoutError> {
+        let deadline = Instant::now() + limit;
+        while condition(self) {
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            if remaining.is_zero() {
+                return Err(TimeoutError);
+            }
+            // A timeout is reported on the next iteration, if the condition still holds.
+            let _ = self.wait_for(remaining);
+        }
+        Ok(())
+    }
+
+    /// Queues up a waiter, then releases the lock. The waiter is queued first so that a
+    /// notification sent by a thread that acquires the lock after us can't be missed.
+    fn release(&mut self) -> Waiter {
+        let waiter = self.monitor.waiters.lock().unwrap().push();
+        self.data = None;
+        waiter
+    }
+
+    fn reacquire(&mut self) {
+        self.data = Some(self.monitor.data.lock().unwrap());
+    }
+}
+
+impl<T> Deref for MonitorGuard<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        self.data.as_ref().unwrap()
+    }
+}
+
+impl<T> DerefMut for MonitorGuard<'_, T> {
+    fn deref_mut(&mut self) -> &mut T {
+        self.data.as_mut().unwrap()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::Monitor;
+    use std::thread;
+    use std::time::Duration;
+
+    #[test]
+    fn waits_for_notification() {
+        let monitor = Monitor::new(false);
+        {
+            let mut ready = monitor.lock();
+            assert!(ready.wait_for(Duration::from_millis(1)).is_err());
+            assert!(ready
+                .wait_while_for(|ready| !*ready, Duration::from_millis(1))
+                .is_err());
+        }
+
+        thread::scope(|scope| {
+            let waiters: Vec<_> = (0..3)
+                .map(|_| {
+                    scope.spawn(|| {
+                        let mut ready = monitor.lock();
+                        ready.wait_while(|ready| !*ready);
+                    })
+                })
+                .collect();
+            thread::sleep(Duration::from_millis(10));
+            *monitor.lock() = true;
+            monitor.notify_all();
+            for waiter in waiters {
+                waiter.join().unwrap();
+            }
+        });
+
+        assert!(monitor.into_inner());
+    }
+}
@@ -0,0 +1,707 @@
+use crate::backoff::Backoff;
+use crate::subscribe::{Parker, Subscribe, Subscription};
+use rsevents::{Awaitable, TimeoutError};
+use std::task::Waker;
+use std::time::{Duration, Instant};
+
+/// An object-safe view of an [`Awaitable`], implemented for every `Awaitable` type whose timed waits
+/// fail with a [`TimeoutError`] (which includes all of the crate's primitives and the events of
+/// `rsevents`), so that awaitables of different types can be waited on together, e.g. by
+/// [`wait_any()`]. They must still share a result type (`T`); use the tuple forms such as
+/// [`wait_any_of()`] and [`wait_all_of()`] to mix e.g. a `CountdownEvent` with a `Semaphore`.
+///
+/// `Awaitable` itself can't be used as a trait object, as some of its methods are only available
+/// for certain result types.
+pub trait DynAwaitable<'a> {
+    /// The result of a successful wait, as with [`Awaitable::T`].
+    type T;
+
+    /// Waits without a time limit, as with [`Awaitable::wait()`].
+    fn dyn_wait(&'a self) -> Self::T;
+
+    /// Waits for up to `limit`, as with [`Awaitable::try_wait_for()`].
+    fn dyn_try_wait_for(&'a self, limit: Duration) -> Result<Self::T, TimeoutError>;
+
+    /// Attempts a wait without blocking, as with [`Awaitable::try_wait0()`].
+    fn dyn_try_wait0(&'a self) -> Result<Self::T, TimeoutError>;
+}
+
+impl<'a, A> DynAwaitable<'a> for A
+where
+    A: Awaitable<'a, Error = TimeoutError> + ?Sized,
+{
+    type T = A::T;
+
+    fn dyn_wait(&'a self) -> A::T {
+        self.wait()
+    }
+
+    fn dyn_try_wait_for(&'a self, limit: Duration) -> Result<A::T, TimeoutError> {
+        self.try_wait_for(limit)
+    }
+
+    fn dyn_try_wait0(&'a self) -> Result<A::T, TimeoutError> {
+        self.try_wait0()
+    }
+}
+
+/// A [`DynAwaitable`] that can also be [subscribed](Subscribe) to, implemented for every such
+/// awaitable that implements [`Subscribe`], to be waited on by [`wait_any_subscribed()`].
+pub trait DynSubscribe<'a>: DynAwaitable<'a> {
+    /// Subscribes to the awaitable becoming ready, as with [`Subscribe::subscribe()`].
+    fn dyn_subscribe(&self, waker: &Waker) -> Option<Subscription<'_>>;
+}
+
+impl<'a, A> DynSubscribe<'a> for A
+where
+    A: Awaitable<'a, Error = TimeoutError> + Subscribe + ?Sized,
+{
+    fn dyn_subscribe(&self, waker: &Waker) -> Option<Subscription<'_>> {
+        self.subscribe(waker)
+    }
+}
+
+/// Blocks until any one of `awaitables` becomes ready, returning its index in the slice along
+/// with the result of waiting on it (e.g. a [`SemaphoreGuard`](crate::SemaphoreGuard) when waiting
+/// on semaphores).
+///
+/// Exactly one of the awaitables is waited on successfully: if several are ready at once, the
+/// one listed first wins, and the others are left untouched (an auto-reset event stays set, a
+/// semaphore keeps its slot). An awaitable that never reports being ready, such as an
+/// [aborted](crate::CountdownEvent::abort) `CountdownEvent`, is simply never chosen.
+///
+/// The awaitables are polled, with an exponential backoff capped at a few milliseconds, so one of
+/// them may be noticed becoming ready up to that much later. Use [`wait_any_subscribed()`] to
+/// instead sleep until woken by one of them, if they all implement [`Subscribe`].
+///
+/// All of the awaitables must have the same result type; see [`wait_any_of()`] for awaitables with
+/// different ones.
+///
+/// Panics if `awaitables` is empty.
+///
+/// ## Example:
+///
+/// ```rust
+/// use rsevents::{EventState, ManualResetEvent};
+/// use rsevents_extra::{wait_any, CountdownEvent};
+///
+/// let downloads = CountdownEvent::new(3);
+/// let stop = ManualResetEvent::new(EventState::Unset);
+///
+/// std::thread::scope(|scope| {
+///     scope.spawn(|| stop.set());
+///
+///     match wait_any(&[&downloads, &stop]) {
+///         (0, ()) => println!("all downloads finished"),
+///         (1, ()) => println!("downloads stopped"),
+///         _ => unreachable!(),
+///     }
+/// });
+/// ```
+pub fn wait_any<'a, T>(awaitables: &[&'a dyn DynAwaitable<'a, T = T>]) -> (usize, T) {
+    match wait_any_until(awaitables, None, |_, _| None) {
+        Ok(ready) => ready,
+        Err(_) => unreachable!(),
+    }
+}
+
+/// Blocks for up to `limit` until any one of `awaitables` becomes ready (see [`wait_any()`]),
+/// returning its index and the result of waiting on it, or an error in case of a timeout.
+///
+/// Panics if `awaitables` is empty.
+pub fn wait_any_for<'a, T>(
+    awaitables: &[&'a dyn DynAwaitable<'a, T = T>],
+    limit: Duration,
+) -> Result<(usize, T), TimeoutError> {
+    wait_any_until(awaitables, Some(Instant::now() + limit), |_, _| None)
+}
+
+/// Blocks until any one of `awaitables` becomes ready, as with [`wait_any()`], but sleeps until
+/// woken by one of them through its [`Subscribe`] implementation instead of polling them.
+///
+/// ## Example:
+///
+/// ```rust
+/// use rsevents_extra::{wait_any_subscribed, CancellationTokenSource, CountdownEvent};
+///
+/// let downloads = CountdownEvent::new(3);
+/// let cancel = CancellationTokenSource::new();
+/// let token = cancel.token();
+///
+/// std::thread::scope(|scope| {
+///     scope.spawn(|| cancel.cancel());
+///
+///     match wait_any_subscribed(&[&downloads, &token]) {
+///         (0, ()) => println!("all downloads finished"),
+///         (1, ()) => println!("downloads cancelled"),
+///         _ => unreachable!(),
+///     }
+/// });
+/// ```
+pub fn wait_any_subscribed<'a, T>(awaitables: &[&'a dyn DynSubscribe<'a, T = T>]) -> (usize, T) {
+    let subscribe = |index: usize, waker: &Waker| awaitables[index].dyn_subscribe(waker);
+    match wait_any_until(awaitables, None, subscribe) {
+        Ok(ready) => ready,
+        Err(_) => unreachable!(),
+    }
+}
+
+/// Blocks for up to `limit` until any one of `awaitables` becomes ready (see
+/// [`wait_any_subscribed()`]), returning its index and the result of waiting on it, or an error in
+/// case of a timeout.
+///
+/// Panics if `awaitables` is empty.
+pub fn wait_any_subscribed_for<'a, T>(
+    awaitables: &[&'a dyn DynSubscribe<'a, T = T>],
+    limit: Duration,
+) -> Result<(usize, T), TimeoutError> {
+    let subscribe = |index: usize, waker: &Waker| awaitables[index].dyn_subscribe(waker);
+    wait_any_until(awaitables, Some(Instant::now() + limit), subscribe)
+}
+
+fn wait_any_until<'a, D, T>(
+    awaitables: &[&'a D],
+    deadline: Option<Instant>,
+    subscribe: impl FnMut(usize, &Waker) -> Option<Subscription<'a>>,
+) -> Result<(usize, T), TimeoutError>
+where
+    D: DynAwaitable<'a, T = T> + ?Sized,
+{
+    assert!(
+        !awaitables.is_empty(),
+        "wait_any() needs something to wait on!"
+    );
+
+    let (index, mut results) = any_until(awaitables, deadline, subscribe)?;
+    Ok((index, results[index].take().unwrap()))
+}
+
+/// Waits until the first of `count` awaitables, checked through `try_wait0`, becomes ready,
+/// returning its index and the result of waiting on it.
+///
+/// Each round, the awaitables are subscribed to through `subscribe` before being checked, after
+/// which the thread sleeps until woken by one of them. If any of them declines the subscription,
+/// the thread also wakes up to poll them, with an exponential backoff.
+pub(crate) fn wait_first<'s, T>(
+    count: usize,
+    deadline: Option<Instant>,
+    mut subscribe: impl FnMut(usize, &Waker) -> Option<Subscription<'s>>,
+    mut try_wait0: impl FnMut(usize) -> Option<T>,
+) -> Result<(usize, T), TimeoutError> {
+    let (parker, waker) = Parker::new();
+    let mut backoff = Backoff::new();
+    loop {
+        // Subscribe before checking, so that an awaitable becoming ready in between still wakes us.
+        let subscriptions: Vec<_> = (0..count).map(|index| subscribe(index, &waker)).collect();
+        for index in 0..count {
+            if let Some(result) = try_wait0(index) {
+                return Ok((index, result));
+            }
+        }
+
+        let mut limit = match subscriptions.iter().all(Option::is_some) {
+            true => None,
+            false => Some(backoff.next()),
+        };
+        if let Some(deadline) = deadline {
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            if remaining.is_zero() {
+                return Err(TimeoutError);
+            }
+            limit = Some(limit.map_or(remaining, |limit| limit.min(remaining)));
+        }
+        parker.park(limit);
+    }
+}
+
+/// Blocks until every one of `awaitables` is ready, returning the results of waiting on each of
+/// them (e.g. the [`SemaphoreGuard`](crate::SemaphoreGuard)s when waiting on semaphores) in the
+/// order they were listed.
+///
+/// Nothing is held while blocking: the awaitables are taken without waiting, and when one of them
+/// isn't ready, the results already obtained are dropped (releasing any semaphore slots) before
+/// blocking on it alone. Once it is ready, the others are taken again without waiting. A thread
+/// waiting on several semaphores thus never holds slots of some while waiting on the others,
+/// which would risk deadlocking against a thread waiting on the same semaphores in another order.
+///
+/// As dropping a result can't undo a wait on an [`AutoResetEvent`](rsevents::AutoResetEvent),
+/// auto-reset events may be consumed by an attempt that is then abandoned, and are best not mixed
+/// with other awaitables here.
+///
+/// All of the awaitables must have the same result type; see [`wait_all_of()`] for awaitables with
+/// different ones.
+///
+/// ## Example:
+///
+/// ```rust
+/// use rsevents_extra::{wait_all, Semaphore};
+///
+/// static READERS: Semaphore = Semaphore::new(4, 4);
+/// static WRITERS: Semaphore = Semaphore::new(1, 1);
+///
+/// let guards = wait_all(&[&READERS, &WRITERS]);
+/// assert_eq!(guards.len(), 2);
+/// assert_eq!((READERS.count(), WRITERS.count()), (3, 0));
+///
+/// drop(guards);
+/// assert_eq!((READERS.count(), WRITERS.count()), (4, 1));
+/// ```
+pub fn wait_all<'a, T>(awaitables: &[&'a dyn DynAwaitable<'a, T = T>]) -> Vec<T> {
+    match wait_all_until(awaitables, None) {
+        Ok(results) => results,
+        Err(_) => unreachable!(),
+    }
+}
+
+/// Blocks for up to `limit` until every one of `awaitables` is ready (see [`wait_all()`]),
+/// returning the results of waiting on each of them. In case of a timeout, any results already
+/// obtained are dropped (releasing any semaphore slots) and an error is returned.
+pub fn wait_all_for<'a, T>(
+    awaitables: &[&'a dyn DynAwaitable<'a, T = T>],
+    limit: Duration,
+) -> Result<Vec<T>, TimeoutError> {
+    wait_all_until(awaitables, Some(Instant::now() + limit))
+}
+
+fn wait_all_until<'a, T>(
+    awaitables: &[&'a dyn DynAwaitable<'a, T = T>],
+    deadline: Option<Instant>,
+) -> Result<Vec<T>, TimeoutError> {
+    all_until(awaitables, deadline)
+}
+
+/// A tuple of references to awaitables, which (unlike the slices taken by [`wait_any()`] and
+/// [`wait_all()`]) may have different result types, to be waited on with [`wait_any_of()`] or
+/// [`wait_all_of()`].
+///
+/// Implemented for tuples of up to eight `&A`, where `A` is an [`Awaitable`] whose timed waits
+/// fail with a [`TimeoutError`]. This trait is sealed and can't be implemented outside of this
+/// crate.
+pub trait AwaitableTuple<'a>: sealed::Awaitables<'a> {}
+
+/// An [`AwaitableTuple`] of awaitables that all implement [`Subscribe`], to be waited on with
+/// [`wait_any_of_subscribed()`]. This trait is sealed and can't be implemented outside of this
+/// crate.
+pub trait SubscribeTuple<'a>: AwaitableTuple<'a> + sealed::Subscribers<'a> {}
+
+mod sealed {
+    use crate::subscribe::Subscription;
+    use rsevents::TimeoutError;
+    use std::task::Waker;
+    use std::time::Instant;
+
+    /// A list of awaitables, each of which is identified by its index and waited on into its own
+    /// slot of a set of results.
+    pub trait Awaitables<'a> {
+        /// One optional result per awaitable.
+        type Results;
+        /// The results of waiting on all of the awaitables.
+        type All;
+
+        fn len(&self) -> usize;
+        fn empty(&self) -> Self::Results;
+        /// Waits on the awaitable `index` without blocking, returning whether it was ready.
+        fn try_wait0(&self, index: usize, results: &mut Self::Results) -> bool;
+        /// Waits on the awaitable `index` (until `deadline`, if specified).
+        fn wait_until(
+            &self,
+            index: usize,
+            deadline: Option<Instant>,
+            results: &mut Self::Results,
+        ) -> Result<(), TimeoutError>;
+        /// Unwraps the results of a wait on all of the awaitables.
+        fn into_all(results: Self::Results) -> Self::All;
+    }
+
+    /// A list of awaitables that can each be subscribed to.
+    pub trait Subscribers<'a>: Awaitables<'a> {
+        fn subscribe(&self, index: usize, waker: &Waker) -> Option<Subscription<'a>>;
+    }
+}
+
+impl<'a, D, T> sealed::Awaitables<'a> for [&'a D]
+where
+    D: DynAwaitable<'a, T = T> + ?Sized,
+{
+    type Results = Vec<Option<T>>;
+    type All = Vec<T>;
+
+    fn len(&self) -> usize {
+        <[_]>::len(self)
+    }
+
+    fn empty(&self) -> Vec<Option<T>> {
+        self.iter().map(|_| None).collect()
+    }
+
+    fn try_wait0(&self, index: usize, results: &mut Vec<Option<T>>) -> bool {
+        results[index] = self[index].dyn_try_wait0().ok();
+        results[index].is_some()
+    }
+
+    fn wait_until(
+        &self,
+        index: usize,
+        deadline: Option<Instant>,
+        results: &mut Vec<Option<T>>,
+    ) -> Result<(), TimeoutError> {
+        results[index] = Some(match deadline {
+            None => self[index].dyn_wait(),
+            Some(deadline) => {
+                self[index].dyn_try_wait_for(deadline.saturating_duration_since(Instant::now()))?
+            }
+        });
+        Ok(())
+    }
+
+    fn into_all(results: Vec<Option<T>>) -> Vec<T> {
+        results.into_iter().map(Option::unwrap).collect()
+    }
+}
+
+macro_rules! awaitable_tuple {
+    ($($index:tt $name:ident),+) => {
+        impl<'a, $($name),+> AwaitableTuple<'a> for ($(&'a $name,)+)
+        where
+            $($name: Awaitable<'a, Error = TimeoutError> + ?Sized,)+
+        {
+        }
+
+        impl<'a, $($name),+> SubscribeTuple<'a> for ($(&'a $name,)+)
+        where
+            $($name: Awaitable<'a, Error = TimeoutError> + Subscribe + ?Sized,)+
+        {
+        }
+
+        impl<'a, $($name),+> sealed::Awaitables<'a> for ($(&'a $name,)+)
+        where
+            $($name: Awaitable<'a, Error = TimeoutError> + ?Sized,)+
+        {
+            type Results = ($(Option<$name::T>,)+);
+            type All = ($($name::T,)+);
+
+            fn len(&self) -> usize {
+                [$($index),+].len()
+            }
+
+            fn empty(&self) -> Self::Results {
+                ($(None::<$name::T>,)+)
+            }
+
+            fn try_wait0(&self, index: usize, results: &mut Self::Results) -> bool {
+                match index {
+                    $($index => {
+                        results.$index = self.$index.try_wait0().ok();
+                        results.$index.is_some()
+                    })+
+                    _ => unreachable!(),
+                }
+            }
+
+            fn wait_until(
+                &self,
+                index: usize,
+                deadline: Option<Instant>,
+                results: &mut Self::Results,
+            ) -> Result<(), TimeoutError> {
+                match index {
+                    $($index => {
+                        results.$index = Some(match deadline {
+                            None => self.$index.wait(),
+                            Some(deadline) => self.$index.try_wait_for(
+                                deadline.saturating_duration_since(Instant::now()),
+                            )?,
+                        });
+                    })+
+                    _ => unreachable!(),
+                }
+                Ok(())
+            }
+
+            fn into_all(results: Self::Results) -> Self::All {
+                ($(results.$index.unwrap(),)+)
+            }
+        }
+
+        impl<'a, $($name),+> sealed::Subscribers<'a> for ($(&'a $name,)+)
+        where
+            $($name: Awaitable<'a, Error = TimeoutError> + Subscribe + ?Sized,)+
+        {
+            fn subscribe(&self, index: usize, waker: &Waker) -> Option<Subscription<'a>> {
+                match index {
+                    $($index => self.$index.subscribe(waker),)+
+                    _ => unreachable!(),
+                }
+            }
+        }
+    };
+}
+
+awaitable_tuple!(0 A, 1 B);
+awaitable_tuple!(0 A, 1 B, 2 C);
+awaitable_tuple!(0 A, 1 B, 2 C, 3 D);
+awaitable_tuple!(0 A, 1 B, 2 C, 3 D, 4 E);
+awaitable_tuple!(0 A, 1 B, 2 C, 3 D, 4 E, 5 F);
+awaitable_tuple!(0 A, 1 B, 2 C, 3 D, 4 E, 5 F, 6 G);
+awaitable_tuple!(0 A, 1 B, 2 C, 3 D, 4 E, 5 F, 6 G, 7 H);
+
+/// Blocks until any one of the differently-typed `awaitables` becomes ready, as with
+/// [`wait_any()`], returning one `Option` per awaitable, of which only that of the awaitable that
+/// was waited on is `Some`.
+///
+/// ## Example:
+///
+/// ```rust
+/// use rsevents_extra::{wait_any_of, CountdownEvent, Semaphore};
+///
+/// let downloads = CountdownEvent::new(3);
+/// let slots = Semaphore::new(1, 1);
+///
+/// match wait_any_of((&downloads, &slots)) {
+///     (Some(()), _) => println!("all downloads finished"),
+///     (_, Some(_guard)) => println!("got a slot"),
+///     _ => unreachable!(),
+/// }
+/// assert_eq!(slots.count(), 1);
+/// ```
+pub fn wait_any_of<'a, T: AwaitableTuple<'a>>(awaitables: T) -> T::Results {
+    match any_until(&awaitables, None, |_, _| None) {
+        Ok((_, results)) => results,
+        Err(_) => unreachable!(),
+    }
+}
+
+/// Blocks for up to `limit` until any one of the differently-typed `awaitables` becomes ready (see
+/// [`wait_any_of()`]), or returns an error in case of a timeout.
+pub fn wait_any_of_for<'a, T: AwaitableTuple<'a>>(
+    awaitables: T,
+    limit: Duration,
+) -> Result<T::Results, TimeoutError> {
+    let (_, results) = any_until(&awaitables, Some(Instant::now() + limit), |_, _| None)?;
+    Ok(results)
+}
+
+/// Blocks until any one of the differently-typed `awaitables` becomes ready, as with
+/// [`wait_any_of()`], but sleeps until woken by one of them through its [`Subscribe`]
+/// implementation instead of polling them (see [`wait_any_subscribed()`]).
+pub fn wait_any_of_subscribed<'a, T: SubscribeTuple<'a>>(awaitables: T) -> T::Results {
+    let subscribe = |index: usize, waker: &Waker| awaitables.subscribe(index, waker);
+    match any_until(&awaitables, None, subscribe) {
+        Ok((_, results)) => results,
+        Err(_) => unreachable!(),
+    }
+}
+
+/// Blocks for up to `limit` until any one of the differently-typed `awaitables` becomes ready (see
+/// [`wait_any_of_subscribed()`]), or returns an error in case of a timeout.
+pub fn wait_any_of_subscribed_for<'a, T: SubscribeTuple<'a>>(
+    awaitables: T,
+    limit: Duration,
+) -> Result<T::Results, TimeoutError> {
+    let subscribe = |index: usize, waker: &Waker| awaitables.subscribe(index, waker);
+    let (_, results) = any_until(&awaitables, Some(Instant::now() + limit), subscribe)?;
+    Ok(results)
+}
+
+/// Blocks until every one of the differently-typed `awaitables` is ready, as with [`wait_all()`],
+/// returning a tuple of the results of waiting on each of them.
+///
+/// ## Example:
+///
+/// ```rust
+/// use rsevents_extra::{wait_all_of, CountdownEvent, Semaphore};
+///
+/// let warmup = CountdownEvent::new(0);
+/// let slots = Semaphore::new(1, 1);
+///
+/// let ((), guard) = wait_all_of((&warmup, &slots));
+/// assert_eq!(slots.count(), 0);
+/// drop(guard);
+/// ```
+pub fn wait_all_of<'a, T: AwaitableTuple<'a>>(awaitables: T) -> T::All {
+    match all_until(&awaitables, None) {
+        Ok(results) => results,
+        Err(_) => unreachable!(),
+    }
+}
+
+/// Blocks for up to `limit` until every one of the differently-typed `awaitables` is ready (see
+/// [`wait_all_of()`]). In case of a timeout, any results already obtained are dropped (releasing
+/// any semaphore slots) and an error is returned.
+pub fn wait_all_of_for<'a, T: AwaitableTuple<'a>>(
+    awaitables: T,
+    limit: Duration,
+) -> Result<T::All, TimeoutError> {
+    all_until(&awaitables, Some(Instant::now() + limit))
+}
+
+/// Waits until the first of `awaitables` becomes ready, subscribing to them through `subscribe`,
+/// returning its index along with the results holding the result of waiting on it.
+fn any_until<'a, S>(
+    awaitables: &S,
+    deadline: Option<Instant>,
+    subscribe: impl FnMut(usize, &Waker) -> Option<Subscription<'a>>,
+) -> Result<(usize, S::Results), TimeoutError>
+where
+    S: sealed::Awaitables<'a> + ?Sized,
+{
+    let mut results = awaitables.empty();
+    let (index, ()) = wait_first(awaitables.len(), deadline, subscribe, |index| {
+        awaitables.try_wait0(index, &mut results).then_some(())
+    })?;
+    Ok((index, results))
+}
+
+/// Waits until all of `awaitables` are ready, holding nothing while blocked (see [`wait_all()`]).
+fn all_until<'a, S>(awaitables: &S, deadline: Option<Instant>) -> Result<S::All, TimeoutError>
+where
+    S: sealed::Awaitables<'a> + ?Sized,
+{
+    let mut blocked_on: Option<usize> = None;
+    'retry: loop {
+        let mut results = awaitables.empty();
+        if let Some(index) = blocked_on {
+            // Nothing else is held, so we can block on this one for as long as it takes.
+            awaitables.wait_until(index, deadline, &mut results)?;
+        }
+
+        for index in 0..awaitables.len() {
+            if blocked_on == Some(index) {
+                continue;
+            }
+            if !awaitables.try_wait0(index, &mut results) {
+                // Release everything obtained so far before blocking.
+                drop(results);
+                blocked_on = Some(index);
+                continue 'retry;
+            }
+        }
+
+        return Ok(S::into_all(results));
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        wait_all, wait_all_for, wait_all_of, wait_all_of_for, wait_any, wait_any_for, wait_any_of,
+        wait_any_of_subscribed, wait_any_subscribed, wait_any_subscribed_for, CountdownEvent,
+        Semaphore,
+    };
+    use rsevents::{AutoResetEvent, EventState, ManualResetEvent};
+    use std::thread;
+    use std::time::Duration;
+
+    #[test]
+    fn reports_which_is_ready() {
+        let first = AutoResetEvent::new(EventState::Unset);
+        let second = ManualResetEvent::new(EventState::Unset);
+        assert!(wait_any_for(&[&first, &second], Duration::from_millis(10)).is_err());
+
+        thread::scope(|scope| {
+            let waiter = scope.spawn(|| wait_any(&[&first, &second]).0);
+            thread::sleep(Duration::from_millis(10));
+            second.set();
+            assert_eq!(waiter.join().unwrap(), 1);
+        });
+
+        // The first listed wins, and only it is consumed.
+        first.set();
+        assert_eq!(wait_any(&[&first, &second]).0, 0);
+        assert_eq!(wait_any(&[&first, &second]).0, 1);
+    }
+
+    #[test]
+    fn acquires_one_semaphore() {
+        let empty = Semaphore::new(0, 1);
+        let full = Semaphore::new(1, 1);
+        let (index, guard) = wait_any(&[&empty, &full]);
+        assert_eq!(index, 1);
+        assert_eq!((empty.count(), full.count()), (0, 0));
+        drop(guard);
+        assert_eq!(full.count(), 1);
+    }
+
+    #[test]
+    fn wait_all_holds_nothing_while_blocked() {
+        let first = Semaphore::new(1, 1);
+        let second = Semaphore::new(0, 1);
+        assert!(wait_all_for(&[&first, &second], Duration::from_millis(10)).is_err());
+        assert_eq!(first.count(), 1);
+
+        thread::scope(|scope| {
+            let waiter = scope.spawn(|| wait_all(&[&first, &second]).len());
+            thread::sleep(Duration::from_millis(10));
+            // The waiter is blocked on `second` without holding `first`.
+            drop(first.wait_for(Duration::from_secs(1)).unwrap());
+            second.release(1);
+            assert_eq!(waiter.join().unwrap(), 2);
+        });
+        assert_eq!((first.count(), second.count()), (1, 1));
+    }
+
+    #[test]
+    fn woken_by_subscriptions() {
+        let first = Semaphore::new(0, 1);
+        let second = Semaphore::new(0, 1);
+        assert!(wait_any_subscribed_for(&[&first, &second], Duration::from_millis(10)).is_err());
+
+        thread::scope(|scope| {
+            let waiter = scope.spawn(|| wait_any_subscribed(&[&first, &second]).0);
+            thread::sleep(Duration::from_millis(10));
+            second.release(1);
+            assert_eq!(waiter.join().unwrap(), 1);
+        });
+        assert_eq!((first.count(), second.count()), (0, 1));
+
+        let countdown = CountdownEvent::new(1);
+        thread::scope(|scope| {
+            let waiter = scope.spawn(|| wait_any_of_subscribed((&first, &countdown)).1.is_some());
+            thread::sleep(Duration::from_millis(10));
+            countdown.tick();
+            assert!(waiter.join().unwrap());
+        });
+    }
+
+    #[test]
+    fn skips_aborted_countdown() {
+        let aborted = CountdownEvent::new(1);
+        aborted.abort();
+        let pending = CountdownEvent::new(1);
+        assert!(wait_any_for(&[&aborted, &pending], Duration::from_millis(10)).is_err());
+
+        thread::scope(|scope| {
+            let waiter = scope.spawn(|| wait_any(&[&aborted, &pending]).0);
+            thread::sleep(Duration::from_millis(10));
+            pending.tick();
+            assert_eq!(waiter.join().unwrap(), 1);
+        });
+    }
+
+    #[test]
+    fn mixes_result_types() {
+        let countdown = CountdownEvent::new(1);
+        let sem = Semaphore::new(1, 1);
+
+        // The semaphore is ready first, and only it is waited on.
+        let (done, guard) = wait_any_of((&countdown, &sem));
+        assert!(done.is_none() && guard.is_some());
+        assert!(wait_all_of_for((&countdown, &sem), Duration::from_millis(10)).is_err());
+        drop(guard);
+        assert_eq!(sem.count(), 1);
+
+        thread::scope(|scope| {
+            let waiter = scope.spawn(|| {
+                let ((), guard) = wait_all_of((&countdown, &sem));
+                guard.forget();
+            });
+            thread::sleep(Duration::from_millis(10));
+            // The waiter holds nothing while blocked on the countdown.
+            assert_eq!(sem.count(), 1);
+            countdown.tick();
+            waiter.join().unwrap();
+        });
+        assert_eq!(sem.count(), 0);
+        assert!(matches!(wait_any_of((&sem, &countdown)), (None, Some(()))));
+    }
+}
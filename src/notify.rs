@@ -0,0 +1,170 @@
+use crate::waiter_queue::{Waiter, WaiterQueue};
+use rsevents::{Awaitable, TimeoutError};
+use std::convert::Infallible;
+use std::sync::Mutex;
+use std::time::Duration;
+
+/// A blocking equivalent of tokio's `Notify`, for waking one or all waiting threads without losing
+/// notifications sent while nobody is waiting.
+///
+/// [`Notify::notify_one()`] wakes the longest-waiting thread or, if no thread is waiting, stores
+/// a single permit that lets the next waiter through straight away (so a notification sent just
+/// before a thread starts waiting is not lost). [`Notify::notify_all()`] wakes every thread that
+/// is currently waiting but stores no permit.
+///
+/// ## Example:
+///
+/// ```rust
+/// use rsevents_extra::{Awaitable, Notify};
+/// use std::sync::Mutex;
+///
+/// static QUEUE: Mutex<Vec<u32>> = Mutex::new(Vec::new());
+/// static ITEM_ADDED: Notify = Notify::new();
+///
+/// std::thread::scope(|scope| {
+///     let consumer = scope.spawn(|| loop {
+///         if let Some(item) = QUEUE.lock().unwrap().pop() {
+///             return item;
+///         }
+///         ITEM_ADDED.wait();
+///     });
+///
+///     QUEUE.lock().unwrap().push(42);
+///     // Not lost even if the consumer isn't waiting yet.
+///     ITEM_ADDED.notify_one();
+///     assert_eq!(consumer.join().unwrap(), 42);
+/// });
+/// ```
+pub struct Notify {
+    state: Mutex<NotifyState>,
+}
+
+struct NotifyState {
+    /// Whether a `notify_one()` call found nobody waiting.
+    permit: bool,
+    /// The waiting threads, in arrival order.
+    waiters: WaiterQueue,
+}
+
+impl Notify {
+    /// Creates a new `Notify` with no stored permit.
+    ///
+    /// This is a `const` function and can be used in a `static` context.
+    pub const fn new() -> Self {
+        Notify {
+            state: Mutex::new(NotifyState {
+                permit: false,
+                waiters: WaiterQueue::new(),
+            }),
+        }
+    }
+
+    /// Wakes the longest-waiting thread, or stores a permit for the next thread to wait if none is
+    /// waiting. At most one permit is stored, no matter how many times this is called.
+    pub fn notify_one(&self) {
+        let mut state = self.state.lock().unwrap();
+        if !state.waiters.release_one() {
+            state.permit = true;
+        }
+    }
+
+    /// Wakes all threads that are currently waiting. No permit is stored for threads that start
+    /// waiting afterwards.
+    pub fn notify_all(&self) {
+        self.state.lock().unwrap().waiters.release_all();
+    }
+
+    /// Consumes the stored permit, if any, or otherwise queues up a waiter.
+    fn enqueue(&self) -> Option<Waiter> {
+        let mut state = self.state.lock().unwrap();
+        if std::mem::replace(&mut state.permit, false) {
+            return None;
+        }
+        Some(state.waiters.push())
+    }
+}
+
+impl Default for Notify {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Awaitable<'_> for Notify {
+    type T = ();
+    type Error = TimeoutError;
+
+    /// Waits to be notified, returning immediately if a permit was stored.
+    fn try_wait(&self) -> Result<(), Infallible> {
+        if let Some(waiter) = self.enqueue() {
+            waiter.wait();
+        }
+        Ok(())
+    }
+
+    /// Waits to be notified or returns an error in case of a timeout, returning immediately if a
+    /// permit was stored.
+    fn try_wait_for(&self, limit: Duration) -> Result<(), TimeoutError> {
+        let waiter = match self.enqueue() {
+            Some(waiter) => waiter,
+            None => return Ok(()),
+        };
+        let notified = waiter.wait_for(limit, |waiter| {
+            self.state.lock().unwrap().waiters.withdraw(waiter)
+        });
+        match notified {
+            true => Ok(()),
+            false => Err(TimeoutError),
+        }
+    }
+
+    /// Consumes the stored permit, if any, without blocking.
+    fn try_wait0(&self) -> Result<(), TimeoutError> {
+        match std::mem::replace(&mut self.state.lock().unwrap().permit, false) {
+            true => Ok(()),
+            false => Err(TimeoutError),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{Awaitable, Notify};
+    use std::thread;
+    use std::time::Duration;
+
+    #[test]
+    fn permit_is_stored_once() {
+        let notify = Notify::new();
+        assert!(!notify.wait0());
+        notify.notify_one();
+        notify.notify_one();
+        assert!(notify.wait0());
+        assert!(!notify.wait0());
+
+        // notify_all() doesn't store a permit.
+        notify.notify_all();
+        assert!(!notify.wait_for(Duration::from_millis(1)));
+    }
+
+    #[test]
+    fn notify_one_and_all() {
+        let notify = Notify::new();
+
+        thread::scope(|scope| {
+            let waiters: Vec<_> = (0..3).map(|_| scope.spawn(|| notify.wait())).collect();
+            while notify.state.lock().unwrap().waiters.len() != 3 {
+                thread::yield_now();
+            }
+            notify.notify_one();
+            thread::sleep(Duration::from_millis(10));
+            assert_eq!(waiters.iter().filter(|w| w.is_finished()).count(), 1);
+
+            notify.notify_all();
+            for waiter in waiters {
+                waiter.join().unwrap();
+            }
+        });
+        assert!(!notify.wait0());
+    }
+}
@@ -0,0 +1,175 @@
+use crate::{Semaphore, SemaphoreGuard};
+use rsevents::TimeoutError;
+use std::convert::TryFrom;
+use std::fmt::Debug;
+use std::ops::{Deref, DerefMut};
+use std::sync::Mutex;
+use std::time::Duration;
+
+/// A fixed-size pool of reusable objects (such as database connections), handing out each object
+/// to at most one thread at a time.
+///
+/// Threads [check out](ObjectPool::checkout()) an object from the pool, blocking until one is
+/// available, and obtain an [`ObjectPoolGuard`] that dereferences to the object. The object is
+/// returned to the pool (and made available to the next waiting thread) when the guard is dropped.
+///
+/// ## Example:
+///
+/// ```rust
+/// use rsevents_extra::ObjectPool;
+///
+/// struct Connection { id: usize }
+///
+/// let pool = ObjectPool::new((0..4).map(|id| Connection { id }));
+///
+/// std::thread::scope(|scope| {
+///     for _ in 0..16 {
+///         scope.spawn(|| {
+///             let conn = pool.checkout();
+///             // <use the connection here>
+///             assert!(conn.id < 4);
+///         });
+///     }
+/// });
+/// ```
+pub struct ObjectPool<T> {
+    /// Tracks the number of objects currently in `objects`, so that waiting for an object doesn't
+    /// require holding the lock.
+    semaphore: Semaphore,
+    objects: Mutex<Vec<T>>,
+}
+
+impl<T> ObjectPool<T> {
+    /// Creates a new pool containing the specified objects. Panics if there are more than
+    /// `u16::MAX` objects.
+    pub fn new(objects: impl IntoIterator<Item = T>) -> Self {
+        let objects: Vec<T> = objects.into_iter().collect();
+        let count = match u16::try_from(objects.len()) {
+            Ok(count) => count,
+            Err(_) => panic!("ObjectPool::new() called with more than u16::MAX objects!"),
+        };
+
+        ObjectPool {
+            semaphore: Semaphore::new(count, count),
+            objects: Mutex::new(objects),
+        }
+    }
+
+    /// Checks out an object from the pool, blocking until one becomes available.
+    #[must_use = "The object is immediately returned to the pool if the guard is dropped"]
+    pub fn checkout(&self) -> ObjectPoolGuard<'_, T> {
+        self.take(self.semaphore.wait())
+    }
+
+    /// Attempts to check out an object from the pool, returning a [`TimeoutError`] if none becomes
+    /// available within the time limit.
+    #[must_use = "The object is immediately returned to the pool if the guard is dropped"]
+    pub fn checkout_for(&self, limit: Duration) -> Result<ObjectPoolGuard<'_, T>, TimeoutError> {
+        Ok(self.take(self.semaphore.wait_for(limit)?))
+    }
+
+    /// Attempts to check out an object from the pool without blocking, returning a
+    /// [`TimeoutError`] if none is currently available.
+    #[must_use = "The object is immediately returned to the pool if the guard is dropped"]
+    pub fn try_checkout(&self) -> Result<ObjectPoolGuard<'_, T>, TimeoutError> {
+        self.checkout_for(Duration::ZERO)
+    }
+
+    fn take<'a>(&'a self, slot: SemaphoreGuard<'a>) -> ObjectPoolGuard<'a, T> {
+        // Holding a slot guarantees there's an object for us.
+        let object = self.objects.lock().unwrap().pop().unwrap();
+        ObjectPoolGuard {
+            pool: self,
+            object: Some(object),
+            _slot: slot,
+        }
+    }
+
+    /// Returns the number of objects currently available in the pool.
+    ///
+    /// Note that this may race with other calls such as `checkout()`.
+    pub fn available(&self) -> usize {
+        self.semaphore.count().into()
+    }
+}
+
+/// An object checked out from an [`ObjectPool`], returned to the pool when dropped.
+pub struct ObjectPoolGuard<'a, T> {
+    pool: &'a ObjectPool<T>,
+    object: Option<T>,
+    /// Dropped after the object has been returned to the pool, making it available again.
+    _slot: SemaphoreGuard<'a>,
+}
+
+impl<T> Deref for ObjectPoolGuard<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        self.object.as_ref().unwrap()
+    }
+}
+
+impl<T> DerefMut for ObjectPoolGuard<'_, T> {
+    fn deref_mut(&mut self) -> &mut T {
+        self.object.as_mut().unwrap()
+    }
+}
+
+impl<T: Debug> Debug for ObjectPoolGuard<'_, T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ObjectPoolGuard")
+            .field("object", &self.object)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<T> Drop for ObjectPoolGuard<'_, T> {
+    fn drop(&mut self) {
+        if let Some(object) = self.object.take() {
+            self.pool.objects.lock().unwrap().push(object);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::ObjectPool;
+    use std::thread;
+    use std::time::Duration;
+
+    #[test]
+    fn checkout_and_return() {
+        let pool = ObjectPool::new(vec![String::from("a")]);
+        let mut obj = pool.checkout();
+        obj.push('b');
+        assert_eq!(pool.available(), 0);
+        pool.try_checkout().unwrap_err();
+
+        drop(obj);
+        assert_eq!(pool.available(), 1);
+        assert_eq!(*pool.try_checkout().unwrap(), "ab");
+    }
+
+    #[test]
+    fn objects_are_exclusive() {
+        let pool = ObjectPool::new(vec![0u32; 2]);
+
+        thread::scope(|scope| {
+            for _ in 0..8 {
+                scope.spawn(|| {
+                    for _ in 0..50 {
+                        let mut obj = pool.checkout_for(Duration::from_secs(5)).unwrap();
+                        // Not atomic, so a lost update means two threads shared an object.
+                        let value = *obj;
+                        thread::yield_now();
+                        *obj = value + 1;
+                    }
+                });
+            }
+        });
+
+        let a = pool.checkout();
+        let b = pool.checkout();
+        assert_eq!(*a + *b, 400);
+    }
+}
@@ -0,0 +1,229 @@
+use rsevents::{Awaitable, EventState, ManualResetEvent};
+use std::ops::Deref;
+use std::sync::{Mutex, OnceLock};
+
+/// A cell initialized exactly once by the first caller of [`OnceEvent::get_or_init()`], with any
+/// concurrent callers blocking until the value is ready.
+///
+/// Only the first caller runs its initializer; the others park on an internal event until the
+/// value has been stored, then all return a reference to the same value. `OnceEvent` can be
+/// created in a `static` context. See [`Lazy`] for a wrapper that carries its own initializer.
+///
+/// If the initializer panics, the `OnceEvent` is poisoned: the threads waiting on it and all
+/// later callers of `get_or_init()` panic rather than run an initializer of their own.
+///
+/// ## Example:
+///
+/// ```rust
+/// use rsevents_extra::OnceEvent;
+/// use std::collections::HashMap;
+///
+/// static CONFIG: OnceEvent<HashMap<&str, &str>> = OnceEvent::new();
+///
+/// std::thread::scope(|scope| {
+///     for _ in 0..4 {
+///         scope.spawn(|| {
+///             let config = CONFIG.get_or_init(|| {
+///                 // <parse the configuration file here>
+///                 HashMap::from([("log_level", "debug")])
+///             });
+///             assert_eq!(config["log_level"], "debug");
+///         });
+///     }
+/// });
+/// ```
+pub struct OnceEvent<T> {
+    value: OnceLock<T>,
+    state: Mutex<OnceState>,
+    /// Set once the initializer has finished, whether or not it panicked.
+    done: ManualResetEvent,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum OnceState {
+    Uninitialized,
+    Running,
+    Poisoned,
+}
+
+impl<T> OnceEvent<T> {
+    /// Creates a new, uninitialized `OnceEvent`.
+    ///
+    /// This is a `const` function and can be used in a `static` context.
+    pub const fn new() -> Self {
+        OnceEvent {
+            value: OnceLock::new(),
+            state: Mutex::new(OnceState::Uninitialized),
+            done: ManualResetEvent::new(EventState::Unset),
+        }
+    }
+
+    /// Returns the value if it has been initialized, without blocking.
+    pub fn get(&self) -> Option<&T> {
+        self.value.get()
+    }
+
+    /// Returns whether an initializer panicked, poisoning the `OnceEvent`.
+    pub fn is_poisoned(&self) -> bool {
+        *self.state.lock().unwrap() == OnceState::Poisoned
+    }
+
+    /// Returns the value, initializing it with `init` if this is the first call. If another thread
+    /// is already initializing the value, blocks until it is ready.
+    ///
+    /// Panics if the `OnceEvent` has been poisoned by a panicking initializer.
+    pub fn get_or_init(&self, init: impl FnOnce() -> T) -> &T {
+        if let Some(value) = self.value.get() {
+            return value;
+        }
+
+        let state = {
+            let mut state = self.state.lock().unwrap();
+            let previous = *state;
+            if previous == OnceState::Uninitialized {
+                *state = OnceState::Running;
+            }
+            previous
+        };
+
+        match state {
+            OnceState::Uninitialized => {
+                let initializer = Initializer { once: self };
+                let _ = self.value.set(init());
+                std::mem::forget(initializer);
+                self.done.set();
+            }
+            OnceState::Running => self.done.wait(),
+            OnceState::Poisoned => {}
+        }
+
+        match self.value.get() {
+            Some(value) => value,
+            None => panic!("OnceEvent initializer panicked!"),
+        }
+    }
+}
+
+impl<T> Default for OnceEvent<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Poisons the `OnceEvent` and releases its waiters if dropped, which only happens when the
+/// initializer panics.
+struct Initializer<'a, T> {
+    once: &'a OnceEvent<T>,
+}
+
+impl<T> Drop for Initializer<'_, T> {
+    fn drop(&mut self) {
+        if let Ok(mut state) = self.once.state.lock() {
+            *state = OnceState::Poisoned;
+        }
+        self.once.done.set();
+    }
+}
+
+/// A value initialized on first access by its own initializer, with any threads accessing it
+/// concurrently blocking until it is ready. Built on [`OnceEvent`], and shares its poisoning
+/// behavior.
+///
+/// ## Example:
+///
+/// ```rust
+/// use rsevents_extra::Lazy;
+///
+/// static PRIMES: Lazy<Vec<u32>> = Lazy::new(|| {
+///     (2..100).filter(|n| (2..*n).all(|d| n % d != 0)).collect()
+/// });
+///
+/// assert_eq!(PRIMES[..5], [2, 3, 5, 7, 11]);
+/// ```
+pub struct Lazy<T, F = fn() -> T> {
+    once: OnceEvent<T>,
+    init: F,
+}
+
+impl<T, F: Fn() -> T> Lazy<T, F> {
+    /// Creates a new `Lazy`, to be initialized by `init` on first access.
+    ///
+    /// This is a `const` function and can be used in a `static` context.
+    pub const fn new(init: F) -> Self {
+        Lazy {
+            once: OnceEvent::new(),
+            init,
+        }
+    }
+
+    /// Returns the value, initializing it first if needed.
+    pub fn force(this: &Self) -> &T {
+        this.once.get_or_init(&this.init)
+    }
+}
+
+impl<T, F: Fn() -> T> Deref for Lazy<T, F> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        Lazy::force(self)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{Lazy, OnceEvent};
+    use std::panic::{catch_unwind, AssertUnwindSafe};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::thread;
+    use std::time::Duration;
+
+    #[test]
+    fn initializes_once() {
+        let once = OnceEvent::new();
+        let runs = AtomicUsize::new(0);
+        assert_eq!(once.get(), None);
+
+        thread::scope(|scope| {
+            for _ in 0..4 {
+                scope.spawn(|| {
+                    let value = once.get_or_init(|| {
+                        runs.fetch_add(1, Ordering::SeqCst);
+                        thread::sleep(Duration::from_millis(10));
+                        42
+                    });
+                    assert_eq!(*value, 42);
+                });
+            }
+        });
+
+        assert_eq!(runs.load(Ordering::SeqCst), 1);
+        assert_eq!(once.get(), Some(&42));
+
+        let lazy = Lazy::new(|| "value".len());
+        assert_eq!(*lazy, 5);
+    }
+
+    #[test]
+    fn poisoned_by_panic() {
+        let once = OnceEvent::<u32>::new();
+
+        thread::scope(|scope| {
+            let initializer = scope.spawn(|| {
+                catch_unwind(AssertUnwindSafe(|| {
+                    once.get_or_init(|| {
+                        thread::sleep(Duration::from_millis(50));
+                        panic!("initializer failed")
+                    });
+                }))
+            });
+            thread::sleep(Duration::from_millis(10));
+            let waiter = catch_unwind(AssertUnwindSafe(|| *once.get_or_init(|| 1)));
+            assert!(waiter.is_err());
+            assert!(initializer.join().unwrap().is_err());
+        });
+
+        assert!(once.is_poisoned());
+        assert!(catch_unwind(AssertUnwindSafe(|| *once.get_or_init(|| 1))).is_err());
+    }
+}
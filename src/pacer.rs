@@ -0,0 +1,102 @@
+#![allow(clippy::bool_assert_comparison)]
+
+use crate::clock::Clock;
+use crate::RateLimiter;
+use rsevents::{Awaitable, TimeoutError};
+use std::convert::Infallible;
+use std::time::Duration;
+
+/// A leaky-bucket limiter that paces acquisitions evenly, letting at most one through per
+/// interval with no bursts.
+///
+/// Where a [`RateLimiter`] lets a burst of acquisitions through at once after a quiet period, a
+/// `Pacer` strictly spaces them out, which suits APIs that enforce a minimum interval between
+/// requests. Waiting on a pacer (via the [`Awaitable`] API) blocks until at least the pacer's
+/// interval has elapsed since the previous acquisition was let through, with waiters let through
+/// in the order they arrived.
+///
+/// ## Example:
+///
+/// ```rust
+/// use rsevents_extra::{Awaitable, Pacer};
+/// use std::time::{Duration, Instant};
+///
+/// // The API rejects requests made less than 10ms apart.
+/// static API: Pacer = Pacer::new(Duration::from_millis(10));
+///
+/// let start = Instant::now();
+/// for _ in 0..3 {
+///     API.wait();
+///     // <make the next request here>
+/// }
+/// assert!(start.elapsed() >= Duration::from_millis(20));
+/// ```
+pub struct Pacer {
+    limiter: RateLimiter,
+}
+
+impl Pacer {
+    /// Creates a new `Pacer` letting one acquisition through per `interval`. The first
+    /// acquisition is let through immediately.
+    ///
+    /// This is a `const` function and can be used in a `static` context.
+    pub const fn new(interval: Duration) -> Self {
+        Pacer {
+            limiter: RateLimiter::with_interval(interval, 1),
+        }
+    }
+
+    /// Replaces the [`SystemClock`](crate::SystemClock) used to pace acquisitions with `clock`.
+    /// This is primarily useful in tests, where a [`MockClock`](crate::MockClock) makes the pacing
+    /// deterministic.
+    pub const fn with_clock(self, clock: &'static dyn Clock) -> Self {
+        Pacer {
+            limiter: self.limiter.with_clock(clock),
+        }
+    }
+}
+
+impl Awaitable<'_> for Pacer {
+    type T = ();
+    type Error = TimeoutError;
+
+    /// Blocks until the pacer lets the caller through.
+    fn try_wait(&self) -> Result<(), Infallible> {
+        self.limiter.try_wait()
+    }
+
+    /// Blocks for up to `limit` until the pacer lets the caller through, returning an error right
+    /// away if it would not do so within the time limit.
+    fn try_wait_for(&self, limit: Duration) -> Result<(), TimeoutError> {
+        self.limiter.try_wait_for(limit)
+    }
+
+    /// Passes through the pacer if it would let the caller through right away.
+    fn try_wait0(&self) -> Result<(), TimeoutError> {
+        self.limiter.try_wait0()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{Awaitable, MockClock, Pacer};
+    use std::time::Duration;
+
+    #[test]
+    fn spaces_acquisitions() {
+        static CLOCK: MockClock = MockClock::new();
+        let pacer = Pacer::new(Duration::from_secs(1)).with_clock(&CLOCK);
+
+        assert_eq!(pacer.wait0(), true);
+        assert_eq!(pacer.wait0(), false);
+        // A quiet period does not build up a burst allowance.
+        CLOCK.advance(Duration::from_secs(10));
+        assert_eq!(pacer.wait0(), true);
+        assert_eq!(pacer.wait0(), false);
+
+        CLOCK.advance(Duration::from_millis(500));
+        assert_eq!(pacer.wait_for(Duration::from_millis(100)), false);
+        CLOCK.advance(Duration::from_millis(500));
+        assert_eq!(pacer.wait0(), true);
+    }
+}
@@ -0,0 +1,205 @@
+use crate::clock::{self, Clock, SystemClock};
+use crate::Cancelled;
+use rsevents::{Awaitable, TimeoutError};
+use std::convert::Infallible;
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+/// An event that becomes set once every period and is reset again by the wait that observes it,
+/// like an [`AutoResetEvent`](rsevents::AutoResetEvent) set by a metronome.
+///
+/// A worker loop waiting on a `PeriodicEvent` runs once per period without any sleep or deadline
+/// arithmetic of its own. Periods are measured from when the event is first used, so the loop
+/// doesn't drift if an iteration takes a while; if an iteration overruns a whole period, the next
+/// wait returns immediately but missed periods are not made up for. Each period releases a single
+/// waiter.
+///
+/// [`PeriodicEvent::wait_cancellable()`] waits for the next period or a cancellation token,
+/// whichever comes first, so that a periodic loop can be shut down promptly.
+///
+/// ## Example:
+///
+/// ```rust
+/// use rsevents_extra::{Awaitable, CancellationTokenSource, Cancelled, PeriodicEvent};
+/// use std::time::Duration;
+///
+/// static HEARTBEAT: PeriodicEvent = PeriodicEvent::new(Duration::from_millis(5));
+/// let shutdown = CancellationTokenSource::new();
+///
+/// std::thread::scope(|scope| {
+///     let token = shutdown.token();
+///     let worker = scope.spawn(move || {
+///         let mut beats = 0;
+///         while HEARTBEAT.wait_cancellable(&token).is_ok() {
+///             // <send a heartbeat here>
+///             beats += 1;
+///         }
+///         beats
+///     });
+///
+///     std::thread::sleep(Duration::from_millis(20));
+///     shutdown.cancel();
+///     assert!(worker.join().unwrap() > 0);
+/// });
+/// ```
+pub struct PeriodicEvent {
+    period: Duration,
+    /// When the event was first used, from which periods are measured.
+    start: OnceLock<Instant>,
+    /// The number of the last period that has been observed by a waiter.
+    observed: Mutex<u128>,
+    clock: &'static dyn Clock,
+}
+
+impl PeriodicEvent {
+    /// Creates a new `PeriodicEvent` that becomes set every `period`, starting one period after
+    /// the event is first used.
+    ///
+    /// Panics if `period` is zero.
+    ///
+    /// This is a `const` function and can be used in a `static` context.
+    pub const fn new(period: Duration) -> Self {
+        assert!(!period.is_zero(), "PeriodicEvent period must be non-zero!");
+        PeriodicEvent {
+            period,
+            start: OnceLock::new(),
+            observed: Mutex::new(0),
+            clock: &SystemClock,
+        }
+    }
+
+    /// Replaces the [`SystemClock`] used to measure periods with `clock`. This is primarily useful
+    /// in tests, where a [`MockClock`](crate::MockClock) makes the periods deterministic.
+    pub const fn with_clock(mut self, clock: &'static dyn Clock) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Returns the interval at which the event becomes set.
+    pub fn period(&self) -> Duration {
+        self.period
+    }
+
+    /// Waits for the next period, returning [`Cancelled`] if `token` (any [`Awaitable`], such as a
+    /// [`CancellationToken`](crate::CancellationToken)) becomes set first.
+    pub fn wait_cancellable<'t, C>(&self, token: &'t C) -> Result<(), Cancelled>
+    where
+        C: Awaitable<'t> + ?Sized,
+    {
+        loop {
+            if token.try_wait0().is_ok() {
+                return Err(Cancelled);
+            }
+            let wait = match self.try_observe() {
+                Ok(()) => return Ok(()),
+                Err(wait) => wait,
+            };
+            // Sleep on the token itself, so that cancellation cuts the wait short.
+            let cancelled = self
+                .clock
+                .wait_for(wait, &mut |limit| token.try_wait_for(limit).is_ok());
+            if cancelled {
+                return Err(Cancelled);
+            }
+        }
+    }
+
+    /// Observes (and so resets) the event if a new period has started since it was last observed,
+    /// or otherwise returns how long it will be until the next period starts.
+    fn try_observe(&self) -> Result<(), Duration> {
+        let now = self.clock.now();
+        let start = *self.start.get_or_init(|| now);
+        let elapsed = now.saturating_duration_since(start).as_nanos();
+        let current = elapsed / self.period.as_nanos();
+
+        let mut observed = self.observed.lock().unwrap();
+        if current > *observed {
+            *observed = current;
+            return Ok(());
+        }
+        let next = (current + 1) * self.period.as_nanos();
+        Err(Duration::from_nanos((next - elapsed) as u64))
+    }
+}
+
+impl Awaitable<'_> for PeriodicEvent {
+    type T = ();
+    type Error = TimeoutError;
+
+    /// Waits for the next period to start, or returns immediately if one has started since the
+    /// event was last waited on.
+    fn try_wait(&self) -> Result<(), Infallible> {
+        while let Err(wait) = self.try_observe() {
+            clock::sleep(self.clock, wait);
+        }
+        Ok(())
+    }
+
+    /// Waits for up to `limit` for the next period to start, or returns immediately if one has
+    /// started since the event was last waited on.
+    fn try_wait_for(&self, limit: Duration) -> Result<(), TimeoutError> {
+        let deadline = self.clock.now() + limit;
+        loop {
+            let wait = match self.try_observe() {
+                Ok(()) => return Ok(()),
+                Err(wait) => wait,
+            };
+            let remaining = deadline.saturating_duration_since(self.clock.now());
+            if wait > remaining {
+                clock::sleep(self.clock, remaining);
+                return self.try_observe().map_err(|_| TimeoutError);
+            }
+            clock::sleep(self.clock, wait);
+        }
+    }
+
+    /// Checks whether a period has started since the event was last waited on, without blocking.
+    fn try_wait0(&self) -> Result<(), TimeoutError> {
+        self.try_observe().map_err(|_| TimeoutError)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{Awaitable, CancellationTokenSource, Cancelled, MockClock, PeriodicEvent};
+    use std::thread;
+    use std::time::Duration;
+
+    #[test]
+    fn set_once_per_period() {
+        static CLOCK: MockClock = MockClock::new();
+        let event = PeriodicEvent::new(Duration::from_secs(1)).with_clock(&CLOCK);
+        assert!(!event.wait0());
+
+        CLOCK.advance(Duration::from_millis(1500));
+        assert!(event.wait0());
+        assert!(!event.wait0());
+
+        // Missed periods are collapsed into one.
+        CLOCK.advance(Duration::from_secs(3));
+        assert!(event.wait0());
+        assert!(!event.wait0());
+
+        thread::scope(|scope| {
+            let waiter = scope.spawn(|| event.wait());
+            while !waiter.is_finished() {
+                CLOCK.advance(Duration::from_millis(100));
+                thread::sleep(Duration::from_millis(1));
+            }
+        });
+    }
+
+    #[test]
+    fn cancellable() {
+        let event = PeriodicEvent::new(Duration::from_secs(3600));
+        let source = CancellationTokenSource::new();
+        let token = source.token();
+
+        thread::scope(|scope| {
+            let waiter = scope.spawn(|| event.wait_cancellable(&token));
+            thread::sleep(Duration::from_millis(10));
+            source.cancel();
+            assert_eq!(waiter.join().unwrap(), Err(Cancelled));
+        });
+    }
+}
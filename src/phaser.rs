@@ -0,0 +1,216 @@
+use crate::waiter_queue::{Waiter, WaiterQueue};
+use std::sync::Mutex;
+
+/// A reusable synchronization point for a varying number of parties, which advances through
+/// numbered phases as the registered parties arrive.
+///
+/// A `Phaser` is a generalization of a [`Barrier`](crate::Barrier) for thread pools whose
+/// membership fluctuates: parties join with [`Phaser::register()`] and leave with
+/// [`Phaser::deregister()`] at any time, and the current phase completes (and the next one
+/// starts) as soon as every registered party has arrived. A party may either
+/// [arrive](Phaser::arrive) without waiting for the others, or
+/// [arrive and wait](Phaser::arrive_and_await) for the phase to complete.
+///
+/// ## Example:
+///
+/// ```rust
+/// use rsevents_extra::Phaser;
+///
+/// static PHASER: Phaser = Phaser::new(0);
+///
+/// std::thread::scope(|scope| {
+///     for _ in 0..4 {
+///         PHASER.register();
+///     }
+///     for id in 0..4 {
+///         scope.spawn(move || {
+///             // Each worker stays for a different number of phases.
+///             for _ in 0..=id {
+///                 // <work on the current phase here>
+///                 PHASER.arrive_and_await();
+///             }
+///             PHASER.deregister();
+///         });
+///     }
+/// });
+///
+/// assert_eq!(PHASER.phase(), 4);
+/// assert_eq!(PHASER.parties(), 0);
+/// ```
+pub struct Phaser {
+    state: Mutex<PhaserState>,
+}
+
+struct PhaserState {
+    /// The number of registered parties.
+    parties: usize,
+    /// The number of parties that have arrived in the current phase.
+    arrived: usize,
+    /// The number of the current phase, which starts at zero.
+    phase: usize,
+    /// Threads waiting for the current phase to complete.
+    waiters: WaiterQueue,
+}
+
+impl PhaserState {
+    /// Completes the current phase, releasing any waiting threads.
+    fn advance(&mut self) {
+        self.arrived = 0;
+        self.phase = self.phase.wrapping_add(1);
+        self.waiters.release_all();
+    }
+
+    /// Registers a waiter for the current phase to complete.
+    fn add_waiter(&mut self) -> Waiter {
+        self.waiters.push()
+    }
+}
+
+impl Phaser {
+    /// Creates a new `Phaser` starting at phase zero, with `parties` parties already registered.
+    ///
+    /// This is a `const` function and can be used in a `static` context.
+    pub const fn new(parties: usize) -> Self {
+        Phaser {
+            state: Mutex::new(PhaserState {
+                parties,
+                arrived: 0,
+                phase: 0,
+                waiters: WaiterQueue::new(),
+            }),
+        }
+    }
+
+    /// Returns the number of the current phase, which starts at zero and advances each time all
+    /// registered parties have arrived.
+    pub fn phase(&self) -> usize {
+        self.state.lock().unwrap().phase
+    }
+
+    /// Returns the number of registered parties.
+    pub fn parties(&self) -> usize {
+        self.state.lock().unwrap().parties
+    }
+
+    /// Registers a new party, which must arrive before the current phase can complete. Returns
+    /// the number of the current phase.
+    pub fn register(&self) -> usize {
+        let mut state = self.state.lock().unwrap();
+        state.parties += 1;
+        state.phase
+    }
+
+    /// Deregisters a party that has not yet arrived in the current phase, completing the phase if
+    /// all remaining parties have already arrived. Returns the number of the phase the party left
+    /// in.
+    ///
+    /// Panics if no parties are registered.
+    pub fn deregister(&self) -> usize {
+        let mut state = self.state.lock().unwrap();
+        assert!(state.parties > 0, "Phaser has no registered parties!");
+        state.parties -= 1;
+        let phase = state.phase;
+        if state.arrived > 0 && state.arrived >= state.parties {
+            state.advance();
+        }
+        phase
+    }
+
+    /// Marks a party as having arrived in the current phase without waiting for the others,
+    /// completing the phase if it was the last to arrive. Returns the number of the phase arrived
+    /// in.
+    ///
+    /// Panics if more parties arrive than are registered.
+    pub fn arrive(&self) -> usize {
+        let mut state = self.state.lock().unwrap();
+        let phase = state.phase;
+        Self::arrive_locked(&mut state);
+        phase
+    }
+
+    /// Marks a party as having arrived in the current phase and waits for all other registered
+    /// parties to arrive. Returns the number of the phase that has now started.
+    ///
+    /// Panics if more parties arrive than are registered.
+    pub fn arrive_and_await(&self) -> usize {
+        let mut state = self.state.lock().unwrap();
+        let next = state.phase.wrapping_add(1);
+        if Self::arrive_locked(&mut state) {
+            return next;
+        }
+        let waiter = state.add_waiter();
+        drop(state);
+
+        waiter.wait();
+        next
+    }
+
+    /// Waits for `phase` to complete without arriving in it (e.g. from a coordinating thread that
+    /// is not a registered party), returning immediately if the phaser has already moved past it.
+    /// Returns the number of the current phase.
+    pub fn await_advance(&self, phase: usize) -> usize {
+        let mut state = self.state.lock().unwrap();
+        if state.phase != phase {
+            return state.phase;
+        }
+        let waiter = state.add_waiter();
+        drop(state);
+
+        waiter.wait();
+        self.phase()
+    }
+
+    /// Records an arrival, returning whether it completed the current phase.
+    fn arrive_locked(state: &mut PhaserState) -> bool {
+        assert!(
+            state.arrived < state.parties,
+            "Phaser arrived at by more parties than are registered!"
+        );
+        state.arrived += 1;
+        if state.arrived == state.parties {
+            state.advance();
+            return true;
+        }
+        false
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::Phaser;
+    use std::thread;
+    use std::time::Duration;
+
+    #[test]
+    fn dynamic_registration() {
+        let phaser = Phaser::new(3);
+        assert_eq!(phaser.arrive(), 0);
+
+        thread::scope(|scope| {
+            let waiter = scope.spawn(|| phaser.arrive_and_await());
+            thread::sleep(Duration::from_millis(10));
+            assert_eq!(phaser.register(), 0);
+            assert_eq!(phaser.deregister(), 0);
+            assert!(!waiter.is_finished());
+            // The last outstanding party leaving completes the phase.
+            assert_eq!(phaser.deregister(), 0);
+            assert_eq!(waiter.join().unwrap(), 1);
+        });
+        assert_eq!(phaser.phase(), 1);
+        assert_eq!(phaser.parties(), 2);
+    }
+
+    #[test]
+    fn await_advance() {
+        let phaser = Phaser::new(1);
+        assert_eq!(phaser.await_advance(5), 0);
+
+        thread::scope(|scope| {
+            let observer = scope.spawn(|| phaser.await_advance(0));
+            thread::sleep(Duration::from_millis(10));
+            assert!(!observer.is_finished());
+            assert_eq!(phaser.arrive_and_await(), 1);
+            assert_eq!(observer.join().unwrap(), 1);
+        });
+    }
+}
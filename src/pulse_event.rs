@@ -0,0 +1,123 @@
+use crate::waiter_queue::{Waiter, WaiterQueue};
+use rsevents::{Awaitable, TimeoutError};
+use std::convert::Infallible;
+use std::sync::Mutex;
+use std::time::Duration;
+
+/// An event that, when [pulsed](PulseEvent::pulse), releases every thread currently waiting on it
+/// while remaining unset for threads that only start waiting afterwards.
+///
+/// This provides the semantics of the Win32 `PulseEvent()` function (or of notifying all waiters
+/// of a condition variable), which neither an [`AutoResetEvent`](rsevents::AutoResetEvent) (which
+/// releases a single waiter) nor a [`ManualResetEvent`](rsevents::ManualResetEvent) (which stays
+/// set until reset, letting later waiters through too) provides. Unlike the Win32 function, a
+/// pulse is guaranteed to release every thread that was already waiting when it was pulsed.
+///
+/// ## Example:
+///
+/// ```rust
+/// use rsevents_extra::{Awaitable, PulseEvent};
+///
+/// static REFRESH: PulseEvent = PulseEvent::new();
+///
+/// std::thread::scope(|scope| {
+///     let waiter = scope.spawn(|| REFRESH.wait());
+///     while REFRESH.waiting() == 0 {
+///         std::thread::yield_now();
+///     }
+///     assert_eq!(REFRESH.pulse(), 1);
+///     waiter.join().unwrap();
+/// });
+///
+/// // The pulse isn't remembered for later waiters.
+/// assert_eq!(REFRESH.wait0(), false);
+/// ```
+pub struct PulseEvent {
+    /// The threads waiting for the next pulse.
+    waiters: Mutex<WaiterQueue>,
+}
+
+impl PulseEvent {
+    /// Creates a new `PulseEvent` with no waiters.
+    ///
+    /// This is a `const` function and can be used in a `static` context.
+    pub const fn new() -> Self {
+        PulseEvent {
+            waiters: Mutex::new(WaiterQueue::new()),
+        }
+    }
+
+    /// Releases all threads currently waiting on the event, returning how many were released.
+    pub fn pulse(&self) -> usize {
+        self.waiters.lock().unwrap().release_all()
+    }
+
+    /// Returns the number of threads currently waiting on the event.
+    pub fn waiting(&self) -> usize {
+        self.waiters.lock().unwrap().len()
+    }
+
+    fn register(&self) -> Waiter {
+        self.waiters.lock().unwrap().push()
+    }
+}
+
+impl Default for PulseEvent {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Awaitable<'_> for PulseEvent {
+    type T = ();
+    type Error = TimeoutError;
+
+    /// Waits for the event to be pulsed.
+    fn try_wait(&self) -> Result<(), Infallible> {
+        self.register().wait();
+        Ok(())
+    }
+
+    /// Waits for the event to be pulsed or returns an error in case of a timeout.
+    fn try_wait_for(&self, limit: Duration) -> Result<(), TimeoutError> {
+        let pulsed = self.register().wait_for(limit, |waiter| {
+            self.waiters.lock().unwrap().withdraw(waiter)
+        });
+        match pulsed {
+            true => Ok(()),
+            false => Err(TimeoutError),
+        }
+    }
+
+    /// Always fails, as a pulse only releases threads that are already waiting.
+    fn try_wait0(&self) -> Result<(), TimeoutError> {
+        Err(TimeoutError)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{Awaitable, PulseEvent};
+    use std::thread;
+    use std::time::Duration;
+
+    #[test]
+    fn releases_current_waiters_only() {
+        let event = PulseEvent::new();
+        assert_eq!(event.pulse(), 0);
+
+        thread::scope(|scope| {
+            let waiters: Vec<_> = (0..3).map(|_| scope.spawn(|| event.wait())).collect();
+            while event.waiting() != 3 {
+                thread::yield_now();
+            }
+            assert_eq!(event.pulse(), 3);
+            for waiter in waiters {
+                waiter.join().unwrap();
+            }
+        });
+
+        assert!(!event.wait_for(Duration::from_millis(5)));
+        assert_eq!(event.waiting(), 0);
+    }
+}
@@ -0,0 +1,167 @@
+use crate::Condition;
+use rsevents::TimeoutError;
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+/// An RCU-style tracker that lets a writer wait for the readers that may still be looking at old
+/// data to finish, without blocking new readers.
+///
+/// Readers [pin](QuiescenceTracker::pin) the tracker for as long as they use the shared data. After
+/// swapping in a new version of the data, a writer calls
+/// [`QuiescenceTracker::wait_for_quiescence()`], which blocks until every guard pinned *before*
+/// the call has been dropped, at which point no reader can still hold a reference to the old
+/// version and it can be freed or reused. Readers pinning the tracker after the call started
+/// (and who can therefore only see the new version) don't hold up the writer.
+///
+/// ## Example:
+///
+/// ```rust
+/// use rsevents_extra::QuiescenceTracker;
+/// use std::sync::atomic::{AtomicPtr, Ordering};
+///
+/// static READERS: QuiescenceTracker = QuiescenceTracker::new();
+/// static CONFIG: AtomicPtr<String> = AtomicPtr::new(std::ptr::null_mut());
+///
+/// CONFIG.store(Box::into_raw(Box::new("v1".to_owned())), Ordering::Release);
+///
+/// std::thread::scope(|scope| {
+///     scope.spawn(|| {
+///         let _guard = READERS.pin();
+///         let config = unsafe { &*CONFIG.load(Ordering::Acquire) };
+///         assert!(config.starts_with('v'));
+///     });
+///
+///     let new = Box::into_raw(Box::new("v2".to_owned()));
+///     let old = CONFIG.swap(new, Ordering::AcqRel);
+///     // Once all readers that might have seen the old configuration are done, free it.
+///     READERS.wait_for_quiescence();
+///     drop(unsafe { Box::from_raw(old) });
+/// });
+/// # drop(unsafe { Box::from_raw(CONFIG.load(Ordering::Acquire)) });
+/// ```
+pub struct QuiescenceTracker {
+    state: Condition<QuiescenceState>,
+}
+
+struct QuiescenceState {
+    /// The epoch new pins are recorded under, advanced by each wait for quiescence.
+    epoch: u64,
+    /// The number of live pins recorded under each epoch (epochs without pins are removed).
+    pinned: BTreeMap<u64, usize>,
+}
+
+impl QuiescenceTracker {
+    /// Creates a new `QuiescenceTracker` with no pinned readers.
+    ///
+    /// This is a `const` function and can be used in a `static` context.
+    pub const fn new() -> Self {
+        QuiescenceTracker {
+            state: Condition::new(QuiescenceState {
+                epoch: 0,
+                pinned: BTreeMap::new(),
+            }),
+        }
+    }
+
+    /// Pins the tracker for the lifetime of the returned guard, holding up any
+    /// [waits for quiescence](Self::wait_for_quiescence) that start before the guard is dropped.
+    #[must_use = "the reader is only pinned while the guard is held"]
+    pub fn pin(&self) -> QuiescenceGuard<'_> {
+        let epoch = self.state.update(|state| {
+            *state.pinned.entry(state.epoch).or_insert(0) += 1;
+            state.epoch
+        });
+        QuiescenceGuard {
+            tracker: self,
+            epoch,
+        }
+    }
+
+    /// Blocks until all guards pinned before this call have been dropped. Guards pinned after the
+    /// call started are not waited for.
+    pub fn wait_for_quiescence(&self) {
+        let epoch = self.advance();
+        self.state
+            .wait_until(|state| state.pinned.range(..=epoch).next().is_none());
+    }
+
+    /// Blocks for up to `limit` until all guards pinned before this call have been dropped,
+    /// returning an error in case of a timeout.
+    pub fn wait_for_quiescence_for(&self, limit: Duration) -> Result<(), TimeoutError> {
+        let epoch = self.advance();
+        self.state
+            .wait_until_for(|state| state.pinned.range(..=epoch).next().is_none(), limit)
+            .map(drop)
+    }
+
+    /// Returns the number of currently pinned guards.
+    pub fn pinned(&self) -> usize {
+        self.state.read().pinned.values().sum()
+    }
+
+    /// Moves new pins on to the next epoch, returning the epoch of the pins to wait for.
+    fn advance(&self) -> u64 {
+        self.state.update(|state| {
+            state.epoch += 1;
+            state.epoch - 1
+        })
+    }
+}
+
+impl Default for QuiescenceTracker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Keeps a [`QuiescenceTracker`] pinned until dropped. Returned by [`QuiescenceTracker::pin()`].
+pub struct QuiescenceGuard<'a> {
+    tracker: &'a QuiescenceTracker,
+    epoch: u64,
+}
+
+impl Drop for QuiescenceGuard<'_> {
+    fn drop(&mut self) {
+        self.tracker.state.update(|state| {
+            let count = state.pinned.get_mut(&self.epoch).unwrap();
+            *count -= 1;
+            if *count == 0 {
+                state.pinned.remove(&self.epoch);
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::QuiescenceTracker;
+    use std::thread;
+    use std::time::Duration;
+
+    #[test]
+    fn waits_only_for_earlier_pins() {
+        let tracker = QuiescenceTracker::new();
+        tracker.wait_for_quiescence();
+
+        let old = tracker.pin();
+        thread::scope(|scope| {
+            let writer = scope.spawn(|| tracker.wait_for_quiescence());
+            thread::sleep(Duration::from_millis(10));
+            assert!(!writer.is_finished());
+
+            // Pins taken after the wait started don't hold it up.
+            let new = tracker.pin();
+            assert_eq!(tracker.pinned(), 2);
+            drop(old);
+            writer.join().unwrap();
+
+            assert!(tracker
+                .wait_for_quiescence_for(Duration::from_millis(1))
+                .is_err());
+            drop(new);
+        });
+
+        assert_eq!(tracker.pinned(), 0);
+        assert!(tracker.wait_for_quiescence_for(Duration::ZERO).is_ok());
+    }
+}
@@ -0,0 +1,208 @@
+use crate::clock::{self, Clock, SystemClock};
+use rsevents::{Awaitable, TimeoutError};
+use std::convert::{Infallible, TryFrom};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// A token-bucket rate limiter, limiting the throughput of an operation rather than its
+/// concurrency (which is what a [`Semaphore`](crate::Semaphore) limits).
+///
+/// The bucket holds up to `burst` tokens and is refilled at `rate` tokens per second, starting out
+/// full. [`RateLimiter::acquire()`] takes tokens from the bucket, blocking until enough have been
+/// refilled if need be. Waiting on the limiter via the [`Awaitable`] API acquires a single token.
+///
+/// Tokens are handed out in the order they are asked for: a thread that has to wait reserves its
+/// tokens up front (so that later callers queue up behind it) and then sleeps until they have been
+/// refilled.
+///
+/// ## Example:
+///
+/// ```rust
+/// use rsevents_extra::{Awaitable, RateLimiter};
+///
+/// // Send at most 100 requests per second, in bursts of up to 10 requests.
+/// static REQUESTS: RateLimiter = RateLimiter::new(100, 10);
+///
+/// for _ in 0..20 {
+///     REQUESTS.wait();
+///     // <send the next request here>
+/// }
+/// ```
+pub struct RateLimiter {
+    /// The time it takes to refill a single token.
+    interval: Duration,
+    burst: u32,
+    /// The time at which the bucket will next be full, given the tokens handed out so far (as in
+    /// the generic cell rate algorithm), or `None` if no tokens have been handed out yet.
+    full_at: Mutex<Option<Instant>>,
+    clock: &'static dyn Clock,
+}
+
+impl RateLimiter {
+    /// Creates a new `RateLimiter` refilled at `rate` tokens per second, which holds up to
+    /// `burst` tokens and starts out full.
+    ///
+    /// Panics if `rate` or `burst` is zero.
+    ///
+    /// This is a `const` function and can be used in a `static` context.
+    pub const fn new(rate: u32, burst: u32) -> Self {
+        assert!(rate > 0, "RateLimiter rate must be non-zero!");
+        Self::with_interval(Duration::from_nanos(1_000_000_000 / rate as u64), burst)
+    }
+
+    /// Creates a new `RateLimiter` refilled with a token every `interval`, which holds up to
+    /// `burst` tokens and starts out full.
+    pub(crate) const fn with_interval(interval: Duration, burst: u32) -> Self {
+        assert!(burst > 0, "RateLimiter burst must be non-zero!");
+        RateLimiter {
+            interval,
+            burst,
+            full_at: Mutex::new(None),
+            clock: &SystemClock,
+        }
+    }
+
+    /// Replaces the [`SystemClock`] used to refill the bucket and time waits with `clock`. This is
+    /// primarily useful in tests, where a [`MockClock`](crate::MockClock) makes the refill rate
+    /// deterministic.
+    pub const fn with_clock(mut self, clock: &'static dyn Clock) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Takes `n` tokens from the bucket, blocking until enough tokens have been refilled.
+    ///
+    /// Panics if `n` exceeds the limiter's burst size, as the bucket can never hold that many.
+    pub fn acquire(&self, n: u32) {
+        let wait = match self.reserve(n, None) {
+            Ok(wait) => wait,
+            Err(_) => unreachable!(),
+        };
+        clock::sleep(self.clock, wait);
+    }
+
+    /// Takes `n` tokens from the bucket, blocking for up to `limit` until enough tokens have been
+    /// refilled. Returns an error without taking any tokens (and without waiting) if they would not
+    /// be available within the time limit.
+    ///
+    /// Panics if `n` exceeds the limiter's burst size, as the bucket can never hold that many.
+    pub fn acquire_for(&self, n: u32, limit: Duration) -> Result<(), TimeoutError> {
+        let wait = self.reserve(n, Some(limit))?;
+        clock::sleep(self.clock, wait);
+        Ok(())
+    }
+
+    /// Takes `n` tokens from the bucket if they are available right away, returning whether they
+    /// were.
+    ///
+    /// Panics if `n` exceeds the limiter's burst size, as the bucket can never hold that many.
+    pub fn try_acquire(&self, n: u32) -> bool {
+        self.reserve(n, Some(Duration::ZERO)).is_ok()
+    }
+
+    /// Returns the number of tokens currently in the bucket.
+    pub fn available(&self) -> u32 {
+        let now = self.clock.now();
+        let full_at = match *self.full_at.lock().unwrap() {
+            Some(full_at) => full_at,
+            None => return self.burst,
+        };
+        let missing = full_at.saturating_duration_since(now).as_nanos();
+        let missing = missing.div_ceil(self.interval.as_nanos());
+        self.burst
+            .saturating_sub(u32::try_from(missing).unwrap_or(u32::MAX))
+    }
+
+    /// Reserves `n` tokens, returning how long the caller must wait before they are available, or
+    /// an error (without reserving anything) if that would take longer than `limit`.
+    fn reserve(&self, n: u32, limit: Option<Duration>) -> Result<Duration, TimeoutError> {
+        assert!(
+            n <= self.burst,
+            "RateLimiter acquisition exceeds the burst size!"
+        );
+
+        let now = self.clock.now();
+        let mut full_at = self.full_at.lock().unwrap();
+        // Taking tokens out of a full bucket pushes back the time at which it will be full again.
+        let next_full_at = full_at.map_or(now, |full_at| full_at.max(now)) + self.interval * n;
+        let available_at = next_full_at
+            .checked_sub(self.interval * self.burst)
+            .unwrap_or(now);
+        let wait = available_at.saturating_duration_since(now);
+        if matches!(limit, Some(limit) if wait > limit) {
+            return Err(TimeoutError);
+        }
+
+        *full_at = Some(next_full_at);
+        Ok(wait)
+    }
+}
+
+impl Awaitable<'_> for RateLimiter {
+    type T = ();
+    type Error = TimeoutError;
+
+    /// Takes a single token from the bucket, blocking until one is available.
+    fn try_wait(&self) -> Result<(), Infallible> {
+        self.acquire(1);
+        Ok(())
+    }
+
+    /// Takes a single token from the bucket, blocking for up to `limit` until one is available.
+    fn try_wait_for(&self, limit: Duration) -> Result<(), TimeoutError> {
+        self.acquire_for(1, limit)
+    }
+
+    /// Takes a single token from the bucket if one is available right away.
+    fn try_wait0(&self) -> Result<(), TimeoutError> {
+        match self.try_acquire(1) {
+            true => Ok(()),
+            false => Err(TimeoutError),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{Awaitable, MockClock, RateLimiter};
+    use std::thread;
+    use std::time::Duration;
+
+    #[test]
+    fn bursts_then_refills() {
+        static CLOCK: MockClock = MockClock::new();
+        let limiter = RateLimiter::new(10, 3).with_clock(&CLOCK);
+
+        assert_eq!(limiter.available(), 3);
+        assert!(limiter.try_acquire(3));
+        assert_eq!(limiter.available(), 0);
+        assert!(!limiter.try_acquire(1));
+        assert!(limiter.try_wait0().is_err());
+
+        CLOCK.advance(Duration::from_millis(100));
+        assert_eq!(limiter.available(), 1);
+        assert!(limiter.try_acquire(1));
+
+        // Refilling stops once the bucket is full.
+        CLOCK.advance(Duration::from_secs(10));
+        assert_eq!(limiter.available(), 3);
+    }
+
+    #[test]
+    fn blocks_until_refilled() {
+        static CLOCK: MockClock = MockClock::new();
+        let limiter = RateLimiter::new(1, 1).with_clock(&CLOCK);
+        limiter.wait();
+        assert!(limiter.acquire_for(1, Duration::from_millis(500)).is_err());
+
+        thread::scope(|scope| {
+            let waiter = scope.spawn(|| limiter.acquire(1));
+            thread::sleep(Duration::from_millis(10));
+            assert!(!waiter.is_finished());
+            while !waiter.is_finished() {
+                CLOCK.advance(Duration::from_millis(100));
+                thread::sleep(Duration::from_millis(1));
+            }
+        });
+    }
+}
@@ -0,0 +1,191 @@
+use crate::Semaphore;
+use rayon::iter::plumbing::{Consumer, Folder, UnindexedConsumer};
+use rayon::iter::ParallelIterator;
+
+/// An extension trait for rayon's [`ParallelIterator`], limiting how many items are processed at
+/// the same time by a [`Semaphore`]. Only available with the `rayon` feature enabled.
+///
+/// This lets the size of a rayon thread pool (typically tuned for CPU-bound work) be decoupled
+/// from the limits of an external resource, such as the number of connections a server accepts.
+///
+/// ## Example:
+///
+/// ```rust
+/// use rayon::prelude::*;
+/// use rsevents_extra::{ParallelIteratorExt, Semaphore};
+///
+/// // The database only accepts two connections at a time.
+/// static DB_CONNECTIONS: Semaphore = Semaphore::new(2, 2);
+///
+/// let ids: Vec<u32> = (0..100).collect();
+/// ids.par_iter()
+///     .throttle(&DB_CONNECTIONS)
+///     .for_each(|id| {
+///         // <query the database for `id` here>
+///     });
+/// ```
+pub trait ParallelIteratorExt: ParallelIterator {
+    /// Limits the processing of the iterator's items by obtaining a slot from `semaphore` for
+    /// each item, which is held while the item passes through the rest of the iterator chain
+    /// (e.g. the closures passed to any subsequent `map()` or `for_each()` calls).
+    ///
+    /// Waiting for a slot blocks the rayon worker thread, so the semaphore shouldn't be held across
+    /// other rayon operations running in the same pool.
+    fn throttle(self, semaphore: &Semaphore) -> Throttled<'_, Self> {
+        Throttled {
+            base: self,
+            semaphore,
+        }
+    }
+}
+
+impl<I: ParallelIterator> ParallelIteratorExt for I {}
+
+/// The parallel iterator adapter returned by [`ParallelIteratorExt::throttle()`].
+pub struct Throttled<'a, I> {
+    base: I,
+    semaphore: &'a Semaphore,
+}
+
+impl<I: ParallelIterator> ParallelIterator for Throttled<'_, I> {
+    type Item = I::Item;
+
+    fn drive_unindexed<C>(self, consumer: C) -> C::Result
+    where
+        C: UnindexedConsumer<Self::Item>,
+    {
+        self.base.drive_unindexed(ThrottledConsumer {
+            base: consumer,
+            semaphore: self.semaphore,
+        })
+    }
+
+    fn opt_len(&self) -> Option<usize> {
+        self.base.opt_len()
+    }
+}
+
+struct ThrottledConsumer<'a, C> {
+    base: C,
+    semaphore: &'a Semaphore,
+}
+
+impl<'a, T, C: Consumer<T>> Consumer<T> for ThrottledConsumer<'a, C> {
+    type Folder = ThrottledFolder<'a, C::Folder>;
+    type Reducer = C::Reducer;
+    type Result = C::Result;
+
+    fn split_at(self, index: usize) -> (Self, Self, Self::Reducer) {
+        let (left, right, reducer) = self.base.split_at(index);
+        (
+            ThrottledConsumer {
+                base: left,
+                semaphore: self.semaphore,
+            },
+            ThrottledConsumer {
+                base: right,
+                semaphore: self.semaphore,
+            },
+            reducer,
+        )
+    }
+
+    fn into_folder(self) -> Self::Folder {
+        ThrottledFolder {
+            base: self.base.into_folder(),
+            semaphore: self.semaphore,
+        }
+    }
+
+    fn full(&self) -> bool {
+        self.base.full()
+    }
+}
+
+impl<T, C: UnindexedConsumer<T>> UnindexedConsumer<T> for ThrottledConsumer<'_, C> {
+    fn split_off_left(&self) -> Self {
+        ThrottledConsumer {
+            base: self.base.split_off_left(),
+            semaphore: self.semaphore,
+        }
+    }
+
+    fn to_reducer(&self) -> Self::Reducer {
+        self.base.to_reducer()
+    }
+}
+
+struct ThrottledFolder<'a, F> {
+    base: F,
+    semaphore: &'a Semaphore,
+}
+
+impl<T, F: Folder<T>> Folder<T> for ThrottledFolder<'_, F> {
+    type Result = F::Result;
+
+    fn consume(self, item: T) -> Self {
+        // The downstream folder runs the rest of the chain for this item.
+        let _guard = self.semaphore.wait();
+        ThrottledFolder {
+            base: self.base.consume(item),
+            semaphore: self.semaphore,
+        }
+    }
+
+    fn complete(self) -> Self::Result {
+        self.base.complete()
+    }
+
+    fn full(&self) -> bool {
+        self.base.full()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{ParallelIteratorExt, Semaphore};
+    use rayon::prelude::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::thread;
+    use std::time::Duration;
+
+    #[test]
+    fn throttled_concurrency() {
+        let sem = Semaphore::new(2, 2);
+        let running = AtomicUsize::new(0);
+        let peak = AtomicUsize::new(0);
+
+        let pool = rayon::ThreadPoolBuilder::new()
+            .num_threads(8)
+            .build()
+            .unwrap();
+        let sum: usize = pool.install(|| {
+            (0..64usize)
+                .into_par_iter()
+                .throttle(&sem)
+                .map(|i| {
+                    let now = running.fetch_add(1, Ordering::SeqCst) + 1;
+                    peak.fetch_max(now, Ordering::SeqCst);
+                    thread::sleep(Duration::from_millis(1));
+                    running.fetch_sub(1, Ordering::SeqCst);
+                    i
+                })
+                .sum()
+        });
+
+        assert_eq!(sum, (0..64).sum());
+        assert!(peak.load(Ordering::SeqCst) <= 2);
+        assert_eq!(sem.count(), 2);
+    }
+
+    #[test]
+    fn short_circuits() {
+        let sem = Semaphore::new(1, 1);
+        let found = (0..1000)
+            .into_par_iter()
+            .throttle(&sem)
+            .find_any(|&i| i == 500);
+        assert_eq!(found, Some(500));
+        assert_eq!(sem.count(), 1);
+    }
+}
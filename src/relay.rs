@@ -0,0 +1,167 @@
+use crate::Condition;
+use rsevents::TimeoutError;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// One end of a two-party baton relay, which strictly alternates two threads.
+///
+/// A relay is created as a pair of ends with [`Relay::pair()`], the first of which starts out
+/// holding the baton. The holder does its share of the work and then [passes](Relay::pass) the
+/// baton to the other end, which was blocked in [`Relay::wait()`] until it arrived. This replaces
+/// the error-prone pairing of two [`AutoResetEvent`](rsevents::AutoResetEvent)s (where a signal
+/// sent twice is lost and a thread can end up waiting on its own event), and catches protocol
+/// violations by panicking:
+///
+/// * passing the baton while the other end holds it, and
+/// * waiting for the baton after the other end has been dropped without passing it back (which
+///   would otherwise block forever).
+///
+/// ## Example:
+///
+/// ```rust
+/// use rsevents_extra::Relay;
+///
+/// let (ping, pong) = Relay::pair();
+///
+/// std::thread::scope(|scope| {
+///     scope.spawn(move || {
+///         for _ in 0..3 {
+///             pong.wait();
+///             println!("pong");
+///             pong.pass();
+///         }
+///     });
+///
+///     for _ in 0..3 {
+///         println!("ping");
+///         ping.pass();
+///         ping.wait();
+///     }
+/// });
+/// ```
+pub struct Relay {
+    state: Arc<Condition<RelayState>>,
+    /// Whether this is the first end of the pair.
+    first: bool,
+}
+
+struct RelayState {
+    /// Whether the baton is held by the first end of the pair.
+    first_holds: bool,
+    /// The number of ends that haven't been dropped.
+    ends: usize,
+}
+
+impl Relay {
+    /// Creates the two ends of a new relay. The first end starts out holding the baton.
+    pub fn pair() -> (Relay, Relay) {
+        let state = Arc::new(Condition::new(RelayState {
+            first_holds: true,
+            ends: 2,
+        }));
+        let first = Relay {
+            state: Arc::clone(&state),
+            first: true,
+        };
+        (
+            first,
+            Relay {
+                state,
+                first: false,
+            },
+        )
+    }
+
+    /// Returns whether this end currently holds the baton.
+    pub fn has_baton(&self) -> bool {
+        self.state.read().first_holds == self.first
+    }
+
+    /// Passes the baton to the other end, releasing it if it is waiting.
+    ///
+    /// Panics if this end doesn't hold the baton.
+    pub fn pass(&self) {
+        // Only the holder can move the baton, so it stays put between the check and the update.
+        assert!(
+            self.has_baton(),
+            "Relay passed by the end not holding the baton!"
+        );
+        self.state.update(|state| state.first_holds = !self.first);
+    }
+
+    /// Blocks until this end holds the baton, returning immediately if it already does.
+    ///
+    /// Panics if the other end is dropped without passing the baton back.
+    pub fn wait(&self) {
+        let state = self
+            .state
+            .wait_until(|state| state.first_holds == self.first || state.ends < 2);
+        self.check(&state);
+    }
+
+    /// Blocks for up to `limit` until this end holds the baton, returning an error in case of a
+    /// timeout.
+    ///
+    /// Panics if the other end is dropped without passing the baton back.
+    pub fn wait_for(&self, limit: Duration) -> Result<(), TimeoutError> {
+        let state = self.state.wait_until_for(
+            |state| state.first_holds == self.first || state.ends < 2,
+            limit,
+        )?;
+        self.check(&state);
+        Ok(())
+    }
+
+    fn check(&self, state: &RelayState) {
+        assert!(
+            state.first_holds == self.first,
+            "Relay peer dropped without passing the baton!"
+        );
+    }
+}
+
+impl Drop for Relay {
+    fn drop(&mut self) {
+        self.state.update(|state| state.ends -= 1);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::Relay;
+    use std::thread;
+    use std::time::Duration;
+
+    #[test]
+    fn alternates_ends() {
+        let (a, b) = Relay::pair();
+        assert!(a.has_baton());
+        assert!(b.wait_for(Duration::from_millis(1)).is_err());
+
+        let turns = std::sync::Mutex::new(Vec::new());
+        thread::scope(|scope| {
+            scope.spawn(|| {
+                for _ in 0..3 {
+                    b.wait();
+                    turns.lock().unwrap().push('b');
+                    b.pass();
+                }
+            });
+            for _ in 0..3 {
+                a.wait();
+                turns.lock().unwrap().push('a');
+                a.pass();
+            }
+            a.wait();
+        });
+
+        assert_eq!(*turns.lock().unwrap(), ['a', 'b', 'a', 'b', 'a', 'b']);
+    }
+
+    #[test]
+    #[should_panic(expected = "not holding the baton")]
+    fn pass_without_baton() {
+        let (_a, b) = Relay::pair();
+        b.pass();
+    }
+}
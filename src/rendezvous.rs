@@ -0,0 +1,171 @@
+use rsevents::{AutoResetEvent, Awaitable, EventState, TimeoutError};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// A zero-capacity channel for handing values directly from one thread to another.
+///
+/// Unlike a buffered queue, a `Rendezvous` never holds on to a value: a call to
+/// [`Rendezvous::give()`] blocks until a thread calls [`Rendezvous::take()`] to receive the value,
+/// and a call to `take()` blocks until a thread calls `give()`. The two parties are thus always in
+/// lockstep, which is useful for strict handoff scheduling between a producer and a consumer.
+///
+/// ## Example:
+///
+/// ```rust
+/// use rsevents_extra::Rendezvous;
+///
+/// static HANDOFF: Rendezvous<String> = Rendezvous::new();
+///
+/// std::thread::scope(|scope| {
+///     scope.spawn(|| {
+///         // Returns only once the main thread has taken the value.
+///         HANDOFF.give("ping".to_owned());
+///     });
+///
+///     assert_eq!(HANDOFF.take(), "ping");
+/// });
+/// ```
+pub struct Rendezvous<T> {
+    /// The value currently on offer, if any.
+    slot: Mutex<Option<T>>,
+    /// Held by the giver whose value is in `slot`, serializing concurrent givers.
+    can_give: AutoResetEvent,
+    /// Set when a value has been placed in `slot`, waking a single taker.
+    offered: AutoResetEvent,
+    /// Set when the value in `slot` has been taken, waking the giver.
+    taken: AutoResetEvent,
+}
+
+impl<T> Rendezvous<T> {
+    /// Creates a new `Rendezvous` with no parties waiting.
+    pub const fn new() -> Self {
+        Rendezvous {
+            slot: Mutex::new(None),
+            can_give: AutoResetEvent::new(EventState::Set),
+            offered: AutoResetEvent::new(EventState::Unset),
+            taken: AutoResetEvent::new(EventState::Unset),
+        }
+    }
+
+    /// Hands `value` to a thread calling [`Rendezvous::take()`], blocking until it has been taken.
+    pub fn give(&self, value: T) {
+        self.can_give.wait();
+        self.offer(value);
+        self.taken.wait();
+        self.can_give.set();
+    }
+
+    /// Attempts to hand `value` to a thread calling [`Rendezvous::take()`], handing it back as
+    /// `Err(value)` if it isn't taken within the time limit.
+    pub fn give_for(&self, value: T, limit: Duration) -> Result<(), T> {
+        let deadline = Instant::now() + limit;
+        if self.can_give.try_wait_for(limit).is_err() {
+            return Err(value);
+        }
+
+        self.offer(value);
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        if self.taken.try_wait_for(remaining).is_err() {
+            // Withdraw our offer, unless a taker beat us to it.
+            let mut slot = self.slot.lock().unwrap();
+            if let Some(value) = slot.take() {
+                self.offered.reset();
+                drop(slot);
+                self.can_give.set();
+                return Err(value);
+            }
+            drop(slot);
+            // The taker sets the event right after emptying the slot.
+            self.taken.wait();
+        }
+
+        self.can_give.set();
+        Ok(())
+    }
+
+    fn offer(&self, value: T) {
+        *self.slot.lock().unwrap() = Some(value);
+        self.offered.set();
+    }
+
+    /// Receives a value from a thread calling [`Rendezvous::give()`], blocking until one is given.
+    pub fn take(&self) -> T {
+        loop {
+            self.offered.wait();
+            if let Some(value) = self.try_take_offered() {
+                return value;
+            }
+        }
+    }
+
+    /// Attempts to receive a value from a thread calling [`Rendezvous::give()`], returning a
+    /// [`TimeoutError`] if none is given within the time limit.
+    pub fn take_for(&self, limit: Duration) -> Result<T, TimeoutError> {
+        let deadline = Instant::now() + limit;
+        loop {
+            self.offered
+                .try_wait_for(deadline.saturating_duration_since(Instant::now()))?;
+            if let Some(value) = self.try_take_offered() {
+                return Ok(value);
+            }
+        }
+    }
+
+    fn try_take_offered(&self) -> Option<T> {
+        // The offer may have been withdrawn after `offered` was set.
+        let value = self.slot.lock().unwrap().take()?;
+        self.taken.set();
+        Some(value)
+    }
+}
+
+impl<T> Default for Rendezvous<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::Rendezvous;
+    use std::thread;
+    use std::time::Duration;
+
+    #[test]
+    fn give_blocks_until_taken() {
+        let rendezvous = Rendezvous::new();
+        assert_eq!(rendezvous.give_for(1, Duration::from_millis(10)), Err(1));
+        rendezvous.take_for(Duration::from_millis(10)).unwrap_err();
+
+        thread::scope(|scope| {
+            let giver = scope.spawn(|| rendezvous.give(2));
+            thread::sleep(Duration::from_millis(20));
+            assert!(!giver.is_finished());
+            assert_eq!(rendezvous.take(), 2);
+            giver.join().unwrap();
+        });
+    }
+
+    #[test]
+    fn every_value_handed_off_once() {
+        let rendezvous = Rendezvous::new();
+
+        let sum: u32 = thread::scope(|scope| {
+            for i in 0..4 {
+                let rendezvous = &rendezvous;
+                scope.spawn(move || {
+                    for j in 0..50 {
+                        rendezvous.give(i * 50 + j);
+                    }
+                });
+            }
+
+            let takers: Vec<_> = (0..2)
+                .map(|_| scope.spawn(|| (0..100).map(|_| rendezvous.take()).sum::<u32>()))
+                .collect();
+            takers.into_iter().map(|t| t.join().unwrap()).sum()
+        });
+
+        assert_eq!(sum, (0..200).sum());
+    }
+}
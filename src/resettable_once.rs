@@ -0,0 +1,203 @@
+use crate::Condition;
+
+/// A once-only initialization that can be re-armed with [`ResettableOnce::reset()`], for
+/// subsystems that can be torn down and reinitialized at runtime.
+///
+/// [`ResettableOnce::call_once()`] runs its closure only if the `ResettableOnce` is armed (as it
+/// is when created, and after each reset); calls made while another thread's closure is running
+/// block until it finishes. The rules for threads racing with a reset are:
+///
+/// * A reset made while a closure is running blocks until it has finished, then re-arms the
+///   `ResettableOnce`, so a reset never interrupts an initialization.
+/// * Threads that were blocked waiting for a closure return once it finishes, even if a reset
+///   re-arms the `ResettableOnce` before they wake up: they never run a second initialization on
+///   behalf of the round they waited on.
+/// * If the closure panics, the `ResettableOnce` remains armed and the next waiting (or later)
+///   caller runs its own closure instead.
+///
+/// ## Example:
+///
+/// ```rust
+/// use rsevents_extra::ResettableOnce;
+///
+/// static DRIVER_INIT: ResettableOnce = ResettableOnce::new();
+///
+/// fn send_packet() {
+///     DRIVER_INIT.call_once(|| {
+///         // <load the network driver here>
+///     });
+///     // <send the packet here>
+/// }
+///
+/// send_packet();
+/// assert!(DRIVER_INIT.is_completed());
+///
+/// // The network adapter was reset, so the driver needs to be reloaded on next use.
+/// DRIVER_INIT.reset();
+/// assert!(!DRIVER_INIT.is_completed());
+/// send_packet();
+/// ```
+pub struct ResettableOnce {
+    state: Condition<OnceState>,
+}
+
+#[derive(Clone, Copy)]
+struct OnceState {
+    status: Status,
+    /// The number of closures that have run to completion.
+    completions: u64,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Status {
+    Armed,
+    Running,
+    Completed,
+}
+
+impl ResettableOnce {
+    /// Creates a new, armed `ResettableOnce`.
+    ///
+    /// This is a `const` function and can be used in a `static` context.
+    pub const fn new() -> Self {
+        ResettableOnce {
+            state: Condition::new(OnceState {
+                status: Status::Armed,
+                completions: 0,
+            }),
+        }
+    }
+
+    /// Runs `f` if the `ResettableOnce` is armed, returning whether this call ran it. If another
+    /// thread is running its closure, blocks until it has finished (see the
+    /// [type-level documentation](Self) for the rules around resets).
+    pub fn call_once(&self, f: impl FnOnce()) -> bool {
+        loop {
+            let seen = *self.state.read();
+            match seen.status {
+                Status::Completed => return false,
+                Status::Armed if self.claim() => {
+                    let running = Running { once: self };
+                    f();
+                    std::mem::forget(running);
+                    self.state.update(|state| {
+                        state.status = Status::Completed;
+                        state.completions += 1;
+                    });
+                    return true;
+                }
+                _ => {}
+            }
+
+            // Someone else is running their closure: wait for it to finish (or panic).
+            let state = *self.state.wait_until(|state| {
+                state.status != Status::Running || state.completions != seen.completions
+            });
+            if state.completions != seen.completions {
+                return false;
+            }
+        }
+    }
+
+    /// Moves from armed to running, returning whether this call did so.
+    fn claim(&self) -> bool {
+        self.state.update(|state| {
+            if state.status == Status::Armed {
+                state.status = Status::Running;
+                true
+            } else {
+                false
+            }
+        })
+    }
+
+    /// Re-arms the `ResettableOnce` so that the next call to [`call_once()`](Self::call_once) runs
+    /// its closure again. If a closure is currently running, blocks until it has finished first.
+    /// Returns whether the `ResettableOnce` had completed (rather than already being armed).
+    pub fn reset(&self) -> bool {
+        loop {
+            self.state
+                .wait_until(|state| state.status != Status::Running);
+            let reset = self.state.update(|state| match state.status {
+                Status::Running => None,
+                Status::Armed => Some(false),
+                Status::Completed => {
+                    state.status = Status::Armed;
+                    Some(true)
+                }
+            });
+            if let Some(was_completed) = reset {
+                return was_completed;
+            }
+        }
+    }
+
+    /// Returns whether a closure has run to completion since the `ResettableOnce` was created or
+    /// last reset.
+    pub fn is_completed(&self) -> bool {
+        self.state.read().status == Status::Completed
+    }
+}
+
+impl Default for ResettableOnce {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Re-arms the `ResettableOnce` if dropped, which only happens when the closure panics.
+struct Running<'a> {
+    once: &'a ResettableOnce,
+}
+
+impl Drop for Running<'_> {
+    fn drop(&mut self) {
+        self.once.state.update(|state| state.status = Status::Armed);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::ResettableOnce;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::thread;
+    use std::time::Duration;
+
+    #[test]
+    fn runs_once_per_round() {
+        let once = ResettableOnce::new();
+        let runs = AtomicUsize::new(0);
+
+        for round in 1..=3 {
+            thread::scope(|scope| {
+                for _ in 0..4 {
+                    scope.spawn(|| {
+                        once.call_once(|| {
+                            thread::sleep(Duration::from_millis(5));
+                            runs.fetch_add(1, Ordering::SeqCst);
+                        });
+                        assert!(once.is_completed());
+                    });
+                }
+            });
+            assert_eq!(runs.load(Ordering::SeqCst), round);
+            assert!(once.reset());
+        }
+
+        assert!(!once.reset());
+    }
+
+    #[test]
+    fn reset_waits_for_running_closure() {
+        let once = ResettableOnce::new();
+
+        thread::scope(|scope| {
+            scope.spawn(|| once.call_once(|| thread::sleep(Duration::from_millis(50))));
+            thread::sleep(Duration::from_millis(10));
+            // Waits for the running closure to finish, then re-arms.
+            assert!(once.reset());
+        });
+
+        assert!(once.call_once(|| ()));
+    }
+}
@@ -0,0 +1,220 @@
+use rsevents::{Awaitable, EventState, ManualResetEvent};
+use std::sync::{Mutex, MutexGuard};
+
+/// A reader-writer gate providing admission control without protecting any data: any number of
+/// readers may be inside at the same time, while a writer has the gate to itself.
+///
+/// A writer waiting in [`RwEvent::write()`] stops new readers from entering and waits for the
+/// readers already inside to leave, so a steady stream of readers can't starve it. Both sides
+/// stay inside the gate for as long as they hold the guard they were given.
+///
+/// ## Example:
+///
+/// ```rust
+/// use rsevents_extra::RwEvent;
+///
+/// // Requests may be served concurrently, but not while the cache is being rebuilt.
+/// static CACHE: RwEvent = RwEvent::new();
+///
+/// std::thread::scope(|scope| {
+///     for _ in 0..4 {
+///         scope.spawn(|| {
+///             let _reading = CACHE.read();
+///             // <serve a request from the cache here>
+///         });
+///     }
+///
+///     let _rebuilding = CACHE.write();
+///     // <rebuild the cache here>
+/// });
+/// ```
+pub struct RwEvent {
+    state: Mutex<RwState>,
+    /// Set while no writer is inside or waiting, i.e. while readers may enter.
+    readable: ManualResetEvent,
+    /// Set while nobody is inside, i.e. while a writer may enter.
+    idle: ManualResetEvent,
+}
+
+struct RwState {
+    /// The number of readers inside the gate.
+    readers: usize,
+    /// Whether a writer is inside the gate.
+    writer: bool,
+    /// The number of writers waiting to enter.
+    writers_waiting: usize,
+}
+
+impl RwEvent {
+    /// Creates a new `RwEvent` with nobody inside.
+    ///
+    /// This is a `const` function and can be used in a `static` context.
+    pub const fn new() -> Self {
+        RwEvent {
+            state: Mutex::new(RwState {
+                readers: 0,
+                writer: false,
+                writers_waiting: 0,
+            }),
+            readable: ManualResetEvent::new(EventState::Set),
+            idle: ManualResetEvent::new(EventState::Set),
+        }
+    }
+
+    /// Enters the gate as a reader, blocking while a writer is inside or waiting to enter. The
+    /// reader leaves when the returned guard is dropped.
+    #[must_use = "The reader immediately leaves the gate if the guard is dropped"]
+    pub fn read(&self) -> RwEventReadGuard<'_> {
+        loop {
+            if let Some(guard) = self.try_read() {
+                return guard;
+            }
+            self.readable.wait();
+        }
+    }
+
+    /// Enters the gate as a reader if no writer is inside or waiting to enter, without blocking.
+    #[must_use = "The reader immediately leaves the gate if the guard is dropped"]
+    pub fn try_read(&self) -> Option<RwEventReadGuard<'_>> {
+        let mut state = self.lock();
+        if state.writer || state.writers_waiting > 0 {
+            return None;
+        }
+        state.readers += 1;
+        self.idle.reset();
+        Some(RwEventReadGuard { gate: self })
+    }
+
+    /// Enters the gate as a writer, blocking new readers from entering and waiting for those
+    /// already inside (as well as any other writer) to leave. The writer leaves when the returned
+    /// guard is dropped.
+    #[must_use = "The writer immediately leaves the gate if the guard is dropped"]
+    pub fn write(&self) -> RwEventWriteGuard<'_> {
+        let mut state = self.lock();
+        state.writers_waiting += 1;
+        self.readable.reset();
+        loop {
+            if !state.writer && state.readers == 0 {
+                state.writers_waiting -= 1;
+                return self.enter_writer(state);
+            }
+            drop(state);
+            self.idle.wait();
+            state = self.lock();
+        }
+    }
+
+    /// Enters the gate as a writer if nobody is inside, without blocking.
+    #[must_use = "The writer immediately leaves the gate if the guard is dropped"]
+    pub fn try_write(&self) -> Option<RwEventWriteGuard<'_>> {
+        let state = self.lock();
+        if state.writer || state.readers > 0 {
+            return None;
+        }
+        self.readable.reset();
+        Some(self.enter_writer(state))
+    }
+
+    fn enter_writer(&self, mut state: MutexGuard<'_, RwState>) -> RwEventWriteGuard<'_> {
+        state.writer = true;
+        self.idle.reset();
+        RwEventWriteGuard { gate: self }
+    }
+
+    /// Returns the number of readers currently inside the gate.
+    pub fn readers(&self) -> usize {
+        self.lock().readers
+    }
+
+    // The events are only ever set or reset to match the state while the lock is held, so a
+    // thread that checked the state under the lock can't miss its event being set afterwards.
+    fn lock(&self) -> MutexGuard<'_, RwState> {
+        self.state.lock().unwrap()
+    }
+}
+
+impl Default for RwEvent {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The guard returned by [`RwEvent::read()`], which leaves the gate when dropped.
+pub struct RwEventReadGuard<'a> {
+    gate: &'a RwEvent,
+}
+
+impl Drop for RwEventReadGuard<'_> {
+    fn drop(&mut self) {
+        let mut state = self.gate.lock();
+        state.readers -= 1;
+        if state.readers == 0 {
+            self.gate.idle.set();
+        }
+    }
+}
+
+/// The guard returned by [`RwEvent::write()`], which leaves the gate when dropped.
+pub struct RwEventWriteGuard<'a> {
+    gate: &'a RwEvent,
+}
+
+impl Drop for RwEventWriteGuard<'_> {
+    fn drop(&mut self) {
+        let mut state = self.gate.lock();
+        state.writer = false;
+        self.gate.idle.set();
+        if state.writers_waiting == 0 {
+            self.gate.readable.set();
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::RwEvent;
+    use std::thread;
+    use std::time::Duration;
+
+    #[test]
+    fn writer_waits_for_readers() {
+        let gate = RwEvent::new();
+        let first = gate.read();
+        let second = gate.read();
+        assert_eq!(gate.readers(), 2);
+        assert!(gate.try_write().is_none());
+
+        thread::scope(|scope| {
+            let writer = scope.spawn(|| drop(gate.write()));
+            thread::sleep(Duration::from_millis(10));
+            // The waiting writer keeps new readers out.
+            assert!(gate.try_read().is_none());
+            drop(first);
+            thread::sleep(Duration::from_millis(10));
+            assert!(!writer.is_finished());
+            drop(second);
+            writer.join().unwrap();
+        });
+
+        assert!(gate.try_read().is_some());
+    }
+
+    #[test]
+    fn writer_excludes_everyone() {
+        let gate = RwEvent::new();
+        let writer = gate.try_write().unwrap();
+        assert!(gate.try_read().is_none());
+        assert!(gate.try_write().is_none());
+
+        thread::scope(|scope| {
+            let reader = scope.spawn(|| gate.read().gate.readers());
+            let other_writer = scope.spawn(|| drop(gate.write()));
+            thread::sleep(Duration::from_millis(10));
+            assert!(!reader.is_finished() && !other_writer.is_finished());
+            drop(writer);
+            other_writer.join().unwrap();
+            assert!(reader.join().unwrap() >= 1);
+        });
+        assert_eq!(gate.readers(), 0);
+    }
+}
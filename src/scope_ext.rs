@@ -0,0 +1,99 @@
+use crate::Semaphore;
+use std::thread::{Scope, ScopedJoinHandle};
+
+/// An extension trait for [`std::thread::Scope`], adding the ability to spawn scoped threads whose
+/// concurrency is limited by a [`Semaphore`].
+///
+/// ## Example:
+///
+/// ```rust
+/// use rsevents_extra::{ScopeExt, Semaphore};
+///
+/// // Never hash more than four files at the same time.
+/// let sem = Semaphore::new(4, 4);
+/// let files = ["a.txt", "b.txt", "c.txt", "d.txt", "e.txt", "f.txt"];
+///
+/// std::thread::scope(|scope| {
+///     for file in &files {
+///         scope.spawn_limited(&sem, move || {
+///             // <hash the file here>
+///             let _ = file;
+///         });
+///     }
+/// });
+/// ```
+pub trait ScopeExt<'scope> {
+    /// Obtains a slot from `semaphore`, blocking until one is available, then spawns a scoped
+    /// thread running `f` that holds the slot until `f` returns (or panics).
+    ///
+    /// The slot is obtained on the calling thread before the new thread is spawned, so no more
+    /// threads than the semaphore allows are ever alive at the same time.
+    fn spawn_limited<F, T>(
+        &'scope self,
+        semaphore: &'scope Semaphore,
+        f: F,
+    ) -> ScopedJoinHandle<'scope, T>
+    where
+        F: FnOnce() -> T + Send + 'scope,
+        T: Send + 'scope;
+}
+
+impl<'scope, 'env> ScopeExt<'scope> for Scope<'scope, 'env> {
+    fn spawn_limited<F, T>(
+        &'scope self,
+        semaphore: &'scope Semaphore,
+        f: F,
+    ) -> ScopedJoinHandle<'scope, T>
+    where
+        F: FnOnce() -> T + Send + 'scope,
+        T: Send + 'scope,
+    {
+        let guard = semaphore.wait();
+        self.spawn(move || {
+            // The guard is dropped (and the slot returned) when `f` completes or unwinds.
+            let _guard = guard;
+            f()
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{ScopeExt, Semaphore};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::thread;
+    use std::time::Duration;
+
+    #[test]
+    fn concurrency_is_limited() {
+        let sem = Semaphore::new(2, 2);
+        let running = AtomicUsize::new(0);
+        let peak = AtomicUsize::new(0);
+
+        thread::scope(|scope| {
+            for _ in 0..8 {
+                scope.spawn_limited(&sem, || {
+                    let now = running.fetch_add(1, Ordering::SeqCst) + 1;
+                    peak.fetch_max(now, Ordering::SeqCst);
+                    thread::sleep(Duration::from_millis(10));
+                    running.fetch_sub(1, Ordering::SeqCst);
+                });
+            }
+        });
+
+        assert!(peak.load(Ordering::SeqCst) <= 2);
+        assert_eq!(sem.count(), 2);
+    }
+
+    #[test]
+    fn slot_released_on_panic() {
+        let sem = Semaphore::new(1, 1);
+
+        thread::scope(|scope| {
+            let handle = scope.spawn_limited(&sem, || panic!("oops"));
+            handle.join().unwrap_err();
+        });
+
+        assert_eq!(sem.count(), 1);
+    }
+}
@@ -1,16 +1,36 @@
 #![allow(clippy::bool_assert_comparison)]
 #![allow(clippy::absurd_extreme_comparisons)]
 
-use rsevents::{AutoResetEvent, Awaitable, EventState, TimeoutError};
+use crate::backoff::Backoff;
+use crate::clock::{Clock, SystemClock};
+use crate::lazy_box::LazyBox;
+use crate::subscribe::{polled, subscribed, Parker, Subscribe, SubscribeFn, Subscription};
+use crate::sync::{
+    fence, loom_const_fn, Arc, AtomicBool, AtomicU16, AtomicU32, Mutex, MutexGuard, Ordering,
+};
+#[cfg(feature = "async")]
+use crate::wake_timer::wake_after;
+use crate::waker_list::WakerList;
+use crate::{AcquireError, TryAcquireError};
+use rsevents::{AutoResetEvent, Awaitable, EventState, ManualResetEvent, TimeoutError};
+use std::collections::VecDeque;
 use std::convert::Infallible;
 use std::fmt::Debug;
-use std::sync::atomic::{AtomicU16, Ordering};
-use std::time::Duration;
+#[cfg(feature = "async")]
+use std::future::Future;
+#[cfg(feature = "async")]
+use std::pin::Pin;
+#[cfg(feature = "async")]
+use std::task::Context;
+use std::task::{Poll, Waker};
+use std::time::{Duration, Instant};
 
-type Count = u16;
+pub(crate) type Count = u16;
 type AtomicCount = AtomicU16;
-type ICount = i16;
+pub(crate) type ICount = i16;
 type INext = i32;
+type State = u32;
+type AtomicState = AtomicU32;
 
 /// A concurrency-limiting synchronization primitive, used to limit the number of threads
 /// performing a certain operation or accessing a particular resource at the same time.
@@ -101,22 +121,215 @@ pub struct Semaphore {
     /// The maximum available concurrency for this semaphore, set at the time of initialization and
     /// static thereafter.
     max: Count,
+    /// The semaphore's internal state, shared with the other semaphore types in this crate.
+    raw: RawSemaphore,
+}
+
+/// The core of a [`Semaphore`], containing everything except the maximum concurrency count. This
+/// is shared between the semaphore types that only differ in how they store their maximum count.
+///
+/// The queue of [`Ticket`]s and the wakers of waiting tasks are allocated the first time they are
+/// needed.
+pub(crate) struct RawSemaphore {
     /// The current available concurrency for this semaphore, `> 0 && <= max`. This is like
-    /// `current` but it also includes "currently borrowed" semaphore instances. The only reason for
+    /// `count` but it also includes "currently borrowed" semaphore instances. The only reason for
     /// this field to exist is so that a truly safe `Semaphore::try_release()` method can exist (one
     /// that can guarantee not only that the new `count` won't exceed `max`, but also that the
     /// release operation will never cause `count` to exceed `max` even after all borrowed semaphore
     /// slots are returned.
     current: AtomicCount,
-    /// The currently available concurrency count, equal to `current` minus any borrowed/obtained
-    /// semaphore slots.
-    count: AtomicCount,
+    /// The currently available concurrency count (equal to `current` minus any borrowed/obtained
+    /// semaphore slots) in its low bits, along with the [`QUEUED`] flag. Sharing an atomic with the
+    /// flag lets a release find out whether tickets are waiting in line from the same operation
+    /// that returns its slots.
+    state: AtomicState,
     /// The auto-reset event used to sleep awaiting threads until a zero concurrency count is
     /// incremented, waking only one awaiter at a time.
     event: AutoResetEvent,
+    /// The state only needed once threads wait in line or tasks wait on the semaphore.
+    waiters: LazyBox<Waiters>,
+    /// The optional behaviors enabled for the semaphore, shared by reference so that semaphores
+    /// not using any of them don't pay for their storage.
+    options: &'static SemaphoreOptions,
+    /// Set by `Semaphore::close()`, after which the waits that can report it fail.
+    closed: AtomicBool,
+}
+
+/// The number of bits of [`RawSemaphore::state`] holding the available count.
+const COUNT_BITS: u32 = Count::BITS;
+/// Masks the available count out of [`RawSemaphore::state`].
+const COUNT_MASK: State = (1 << COUNT_BITS) - 1;
+/// Set in [`RawSemaphore::state`] while tickets are waiting in line, so that released slots are
+/// handed to them before they become available to regular waiters.
+const QUEUED: State = 1 << COUNT_BITS;
+
+/// The optional behaviors of a [`Semaphore`], enabled by passing them to
+/// [`Semaphore::with_options()`].
+///
+/// A semaphore only holds a reference to its options, so they are typically declared as a `static`
+/// (or `const`) item, which their `const` setters allow for:
+///
+/// ```rust
+/// use rsevents_extra::{Semaphore, SemaphoreOptions};
+/// use std::time::Duration;
+///
+/// static OPTIONS: SemaphoreOptions =
+///     SemaphoreOptions::new().starvation_limit(Duration::from_millis(250));
+/// static WORKERS: Semaphore = Semaphore::new(4, 4).with_options(&OPTIONS);
+/// ```
+#[derive(Clone, Copy)]
+pub struct SemaphoreOptions {
+    /// How long a thread may be parked waiting for a slot before it is moved to the front of the
+    /// line (by exchanging its wait for a [`Ticket`]), if at all.
+    starvation_limit: Option<Duration>,
+    /// How long a guard may be held before the callback is invoked (when it is dropped), if at all.
+    hold_limit: Option<(Duration, fn(Duration))>,
+    /// The source of time for timed waits.
+    clock: &'static dyn Clock,
+}
+
+impl SemaphoreOptions {
+    /// The options of a semaphore that wasn't given any.
+    const DEFAULT: SemaphoreOptions = SemaphoreOptions::new();
+
+    /// Creates a set of options with none of the optional behaviors enabled.
+    pub const fn new() -> Self {
+        SemaphoreOptions {
+            starvation_limit: None,
+            hold_limit: None,
+            clock: &SystemClock,
+        }
+    }
+
+    /// Enables starvation avoidance: any thread that has been blocked waiting on the semaphore for
+    /// longer than `limit` is guaranteed the next available slot, ahead of threads that have been
+    /// waiting for less time. Starved threads are served in the order they crossed the limit.
+    ///
+    /// By default, a `Semaphore` makes no fairness guarantees and a thread may be repeatedly beaten
+    /// to a released slot by other threads.
+    pub const fn starvation_limit(mut self, limit: Duration) -> Self {
+        self.starvation_limit = Some(limit);
+        self
+    }
+
+    /// Enables hold-time tracking: each [`SemaphoreGuard`] records when it was obtained, and
+    /// `on_exceeded` is called with the time the guard was held for if it is dropped after more
+    /// than `limit` has elapsed. This helps track down slow code paths that starve other threads by
+    /// holding on to their slots for too long.
+    ///
+    /// The callback runs on the thread dropping the guard, after the slot has been released. It
+    /// may log a warning, record a metric, or (in debug builds) panic; note that a panic while the
+    /// thread is already unwinding aborts the process.
+    ///
+    /// ```rust
+    /// use rsevents_extra::{Semaphore, SemaphoreOptions};
+    /// use std::time::Duration;
+    ///
+    /// static OPTIONS: SemaphoreOptions =
+    ///     SemaphoreOptions::new().hold_limit(Duration::from_secs(5), |held| {
+    ///         eprintln!("Semaphore slot held for {:?}!", held);
+    ///     });
+    /// static WORKERS: Semaphore = Semaphore::new(4, 4).with_options(&OPTIONS);
+    /// ```
+    pub const fn hold_limit(mut self, limit: Duration, on_exceeded: fn(Duration)) -> Self {
+        self.hold_limit = Some((limit, on_exceeded));
+        self
+    }
+
+    /// Replaces the [`SystemClock`] used to measure the time limits of timed waits against the
+    /// semaphore (such as [`Semaphore::wait_for()`]), the
+    /// [starvation limit](SemaphoreOptions::starvation_limit) and
+    /// [hold times](SemaphoreOptions::hold_limit) with `clock`. This is primarily useful in tests,
+    /// where a [`MockClock`](crate::MockClock) makes timeouts deterministic.
+    pub const fn clock(mut self, clock: &'static dyn Clock) -> Self {
+        self.clock = clock;
+        self
+    }
+}
+
+impl Default for SemaphoreOptions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Debug for SemaphoreOptions {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SemaphoreOptions")
+            .field("starvation_limit", &self.starvation_limit)
+            .field("hold_limit", &self.hold_limit.map(|(limit, _)| limit))
+            .finish_non_exhaustive()
+    }
+}
+
+/// The parts of a [`RawSemaphore`] that are allocated on first use.
+struct Waiters {
+    /// Outstanding [`Ticket`] reservations, in the order they were made. Released slots are handed
+    /// to the ticket at the front of the queue (by setting its event) before they are made
+    /// available to regular waiters.
+    tickets: Mutex<VecDeque<QueuedTicket>>,
+    /// The wakers of [`Acquire`] futures waiting alongside the threads parked on `event`.
+    /// Whenever a parked thread is woken, so is the oldest task.
+    #[cfg(feature = "async")]
+    wakers: WakerList,
+    /// The wakers [subscribed](Subscribe) to the semaphore, all of which are woken whenever slots
+    /// may have become available.
+    subscribers: WakerList,
+}
+
+impl Default for Waiters {
+    fn default() -> Self {
+        Waiters {
+            tickets: Mutex::new(VecDeque::new()),
+            #[cfg(feature = "async")]
+            wakers: WakerList::new(),
+            subscribers: WakerList::new(),
+        }
+    }
+}
+
+/// A [`Ticket`] waiting in line for `weight` slots to be granted to it.
+struct QueuedTicket {
+    weight: Count,
+    /// Set once the slots have been granted.
+    signal: Arc<TicketSignal>,
+}
+
+/// The signal shared between a [`Ticket`] and its entry in the semaphore's queue.
+struct TicketSignal {
+    /// Set once the slots have been granted (or, if `closable`, the semaphore has been closed).
+    event: ManualResetEvent,
+    /// Whether closing the semaphore fails the wait for this ticket, which is only the case for
+    /// the waits that can report it (i.e. [`Semaphore::acquire()`] and co.).
+    closable: bool,
+    /// The waker of the thread or task awaiting the ticket, if it is held by an [`Acquire`] future
+    /// or a cancellable wait.
+    waker: Mutex<Option<Waker>>,
+}
+
+impl TicketSignal {
+    fn new(closable: bool) -> Self {
+        TicketSignal {
+            event: ManualResetEvent::new(EventState::Unset),
+            closable,
+            waker: Mutex::new(None),
+        }
+    }
+
+    /// Sets the event, waking the thread or task waiting on the ticket.
+    fn set(&self) {
+        self.event.set();
+        if let Some(waker) = self.waker.lock().unwrap().take() {
+            waker.wake();
+        }
+    }
+
+    fn is_set(&self) -> bool {
+        self.event.try_wait0().is_ok()
+    }
 }
 
-enum Timeout {
+pub(crate) enum Timeout {
     /// Return immediately,
     None,
     /// Wait indefinitely,
@@ -125,215 +338,196 @@ enum Timeout {
     Bounded(Duration),
 }
 
-impl Semaphore {
-    /// Create a new [`Semaphore`] with a maximum available concurrency count of `max_count`
-    /// and an initial available concurrency count of `initial_count`.
-    pub const fn new(initial_count: Count, max_count: Count) -> Self {
-        #[allow(unused_comparisons)]
-        if max_count < 0 {
-            panic!("Invalid max_count < 0");
-        }
-        #[allow(unused_comparisons)]
-        if initial_count < 0 {
-            panic!("Invalid initial_count < 0");
-        }
-        if initial_count > max_count {
-            panic!("Invalid initial_count > max_count");
-        }
-
-        Semaphore {
-            max: max_count,
-            current: AtomicCount::new(initial_count),
-            count: AtomicCount::new(initial_count as Count),
-            event: AutoResetEvent::new(EventState::Unset),
+impl RawSemaphore {
+    loom_const_fn! {
+        pub(crate) fn new(initial_count: Count) -> Self {
+            RawSemaphore {
+                current: AtomicCount::new(initial_count),
+                state: AtomicState::new(initial_count as State),
+                event: AutoResetEvent::new(EventState::Unset),
+                waiters: LazyBox::new(),
+                options: &SemaphoreOptions::DEFAULT,
+                closed: AtomicBool::new(false),
+            }
         }
     }
 
-    fn try_wait(&self, timeout: Timeout) -> Result<(), TimeoutError> {
-        let mut count = self.count.load(Ordering::Relaxed);
+    /// Takes `weight` slots from the available count if that many are available, without
+    /// blocking.
+    fn try_take(&self, weight: Count) -> bool {
+        let mut state = self.state.load(Ordering::Relaxed);
 
         loop {
-            #[allow(unused_comparisons)]
-            if count < 0 {
-                debug_assert!(false, "Count cannot be less than zero!");
+            // We can't just fetch_sub() and check the result because we might underflow.
+            let count = (state & COUNT_MASK) as Count;
+            if count < weight {
+                return false;
             }
-            debug_assert!(count <= self.max);
 
-            count = if count == 0 {
-                // eprintln!("Semaphore unavailable. Sleeping until the event is signalled.");
-                match timeout {
-                    Timeout::None => return Err(TimeoutError),
-                    Timeout::Infinite => self.event.try_wait()?,
-                    Timeout::Bounded(timeout) => self.event.try_wait_for(timeout)?,
-                }
-
-                self.count.load(Ordering::Relaxed)
-            } else {
-                // We can't just fetch_sub(1) and check the result because we might underflow.
-                match self.count.compare_exchange_weak(
-                    count,
-                    count - 1,
-                    Ordering::Acquire,
-                    Ordering::Relaxed,
-                ) {
-                    Ok(_) => {
-                        // We obtained the semaphore.
-                        let new_count = count - 1;
-                        // eprintln!("Semaphore available. New count: {new_count}");
-                        if new_count > 0 {
-                            self.event.set();
-                        }
-                        break;
+            match self.state.compare_exchange_weak(
+                state,
+                state - weight as State,
+                Ordering::Acquire,
+                Ordering::Relaxed,
+            ) {
+                Ok(_) => {
+                    // We obtained the semaphore. Pass the baton to the next waiter if there's
+                    // anything left over.
+                    if count - weight > 0 {
+                        self.notify_one();
                     }
-                    Err(count) => count,
+                    return true;
                 }
+                Err(new_state) => state = new_state,
             }
         }
-
-        #[allow(unused_comparisons)]
-        if count < 0 {
-            debug_assert!(false, "Count cannot be less than zero!");
-        }
-        debug_assert!(count <= self.max);
-
-        Ok(())
-    }
-
-    /// Attempts to obtain access to the resource or code protected by the `Semaphore`, subject to
-    /// the available concurrency count. Returns immediately if the `Semaphore`'s internal
-    /// concurrency count is non-zero or blocks sleeping until the `Semaphore` becomes available
-    /// (via another thread completing its access to the controlled-concurrency region or if the
-    /// semaphore's concurrency limit is raised).
-    ///
-    /// A successful wait against the semaphore decrements its internal available concurrency
-    /// count (possibly preventing other threads from obtaining the semaphore) until
-    /// [`Semaphore::release()`] is called (which happens automatically when the `SemaphoreGuard`
-    /// concurrency token is dropped).
-    #[must_use = "The semaphore count is immediately re-incremented if the guard is dropped"]
-    pub fn wait(&self) -> SemaphoreGuard<'_> {
-        self.try_wait(Timeout::Infinite).unwrap();
-        SemaphoreGuard { semaphore: self }
     }
 
-    #[cfg_attr(not(test), allow(unused))]
-    fn wait0(&self) -> Result<SemaphoreGuard<'_>, rsevents::TimeoutError> {
-        self.try_wait(Timeout::None)?;
-        Ok(SemaphoreGuard { semaphore: self })
+    /// Returns whether tickets are waiting in line, in which case new waiters line up behind them.
+    fn is_queued(&self) -> bool {
+        self.state.load(Ordering::Relaxed) & QUEUED != 0
     }
 
-    /// Attempts a time-bounded wait against the `Semaphore`, returning `Ok(())` if and when the
-    /// semaphore becomes available or a [`TimeoutError`](rsevents::TimeoutError) if the specified
-    /// time limit elapses without the semaphore becoming available to the calling thread.
-    #[must_use = "The semaphore count is immediately re-incremented if the guard is dropped"]
-    pub fn wait_for(&self, limit: Duration) -> Result<SemaphoreGuard<'_>, rsevents::TimeoutError> {
-        match limit {
-            Duration::ZERO => self.try_wait(Timeout::None)?,
-            timeout => self.try_wait(Timeout::Bounded(timeout))?,
-        };
-        Ok(SemaphoreGuard { semaphore: self })
+    /// Locks the queue of outstanding tickets.
+    fn tickets(&self) -> MutexGuard<'_, VecDeque<QueuedTicket>> {
+        self.waiters.get_or_init().tickets.lock().unwrap()
     }
 
     #[inline]
     /// Directly increments the available concurrency count by `count`, without checking if this
     /// would violate the maximum available concurrency count.
-    unsafe fn release_internal(&self, count: Count) {
-        let prev_count = self.count.fetch_add(count, Ordering::Release);
+    pub(crate) unsafe fn release_internal(&self, count: Count) {
+        let prev_state = self.state.fetch_add(count as State, Ordering::AcqRel);
+
+        // Either we see the flag set by a ticket joining the queue, or it sees our slots (see
+        // `reserve()`).
+        if prev_state & QUEUED != 0 {
+            self.grant_tickets(&mut self.tickets());
+        }
 
         // We only need to set the AutoResetEvent if the count was previously exhausted.
         // In all other cases, the last thread to obtain the semaphore would have already set the
         // event (and auto-reset events saturate/clamp immediately).
-        if prev_count == 0 {
-            self.event.set();
+        if prev_state & COUNT_MASK == 0 {
+            self.notify_one();
         }
     }
 
-    /// Directly modifies the maximum currently available concurrency `current`, without regard for
-    /// overflow or a violation of the semaphore's maximum allowed count.
-    unsafe fn modify_current(&self, count: ICount) {
-        match count.signum() {
-            0 => return,
-            1 => self.current.fetch_add(count as Count, Ordering::Relaxed),
-            -1 => self
-                .current
-                .fetch_sub((count as INext).unsigned_abs() as Count, Ordering::Relaxed),
-            _ => unsafe { core::hint::unreachable_unchecked() },
-        };
+    /// Like [`release_internal()`](Self::release_internal), for callers already holding the
+    /// ticket lock.
+    unsafe fn release_locked(&self, count: Count, tickets: &mut VecDeque<QueuedTicket>) {
+        let prev_state = self.state.fetch_add(count as State, Ordering::AcqRel);
+        self.grant_tickets(tickets);
+        if prev_state & COUNT_MASK == 0 {
+            self.notify_one();
+        }
     }
 
-    /// Directly increments or decrements the current availability limit for a `Semaphore` without
-    /// blocking. This is only possible when the semaphore is not currently borrowed or being waited
-    /// on. Panics if the change will result in an available concurrency limit of less than zero or
-    /// greater than the semaphore's maximum. See [`Semaphore::try_modify()`] for a non-panicking
-    /// alternative.
-    ///
-    /// To increment the semaphore's concurrency limit without an `&mut Semaphore` reference, call
-    /// [`Semaphore::release()`] instead. To decrement the concurrency limit, wait on the semaphore
-    /// then call [`forget()`](SemaphoreGuard::forget) on the returned `SemaphoreGuard`:
-    ///
-    /// ```rust
-    /// use rsevents_extra::Semaphore;
-    ///
-    /// fn adjust_sem(sem: &Semaphore, count: i16) {
-    ///     if count >= 0 {
-    ///        sem.release(count as u16);
-    ///     } else {
-    ///         // Note: this will block if the semaphore isn't available!
-    ///         for _ in 0..(-1 * count) {
-    ///             let guard = sem.wait();
-    ///             guard.forget();
-    ///         }
-    ///     }
-    /// }
-    /// ```
-    pub fn modify(&mut self, count: ICount) {
-        let current = self.current.load(Ordering::Relaxed);
-        match (current as INext).checked_add(count as INext) {
-            Some(sum) if sum <= (self.max as INext) => {}
-            _ => panic!("An invalid count was supplied to Semaphore::modify()"),
-        };
+    /// Wakes a waiter to check the count: a parked thread (by setting the event) and, as the two
+    /// can't be told apart by an event, also the oldest waiting task. Whichever of the two loses
+    /// the race for the slot goes back to waiting.
+    fn notify_one(&self) {
+        self.event.set();
+        // Pairs with the fence in `WakerList::register()`: either the task sees the count we
+        // changed, or we see the waiters it allocated to register its waker with.
+        fence(Ordering::SeqCst);
+        if let Some(waiters) = self.waiters.get() {
+            #[cfg(feature = "async")]
+            waiters.wakers.wake_one();
+            waiters.subscribers.wake_all();
+        }
+    }
 
-        match count.signum() {
-            0 => (),
-            1 => {
-                self.current.fetch_add(count as Count, Ordering::Relaxed);
-                self.count.fetch_add(count as Count, Ordering::Relaxed);
+    /// Registers `waker` to be woken whenever slots may have become available.
+    pub(crate) fn subscribe(&self, waker: &Waker) -> Subscription<'_> {
+        Subscription::new(&self.waiters.get_or_init().subscribers, waker)
+    }
+
+    /// Removes the waker of the [`Acquire`] future `id`. If it was already woken (and thus
+    /// removed), the wakeup is passed on unless `consumed`, so that it isn't lost.
+    #[cfg(feature = "async")]
+    fn deregister_waker(&self, id: usize, consumed: bool) {
+        if !self.waiters.get_or_init().wakers.remove(id) && !consumed {
+            self.notify_one();
+        }
+    }
+
+    /// Hands as many available slots as possible to the oldest outstanding tickets, stopping at
+    /// the first ticket that can't be satisfied so that it isn't overtaken by lighter ones.
+    fn grant_tickets(&self, tickets: &mut VecDeque<QueuedTicket>) {
+        while let Some(front) = tickets.front() {
+            if !self.try_take(front.weight) {
+                break;
             }
-            -1 => {
-                self.current
-                    .fetch_add((count as INext).unsigned_abs() as Count, Ordering::Relaxed);
-                self.count
-                    .fetch_add((count as INext).unsigned_abs() as Count, Ordering::Relaxed);
+            tickets.pop_front().unwrap().signal.set();
+        }
+        if tickets.is_empty() {
+            let prev_state = self.state.fetch_and(!QUEUED, Ordering::Relaxed);
+            // Slots left over once the line has cleared can now be taken without queueing.
+            if prev_state & QUEUED != 0 && prev_state & COUNT_MASK != 0 {
+                fence(Ordering::SeqCst);
+                if let Some(waiters) = self.waiters.get() {
+                    waiters.subscribers.wake_all();
+                }
             }
-            _ => unsafe {
-                core::hint::unreachable_unchecked();
-            },
         }
     }
 
-    /// Directly increments or decrements the current availability limit for a `Semaphore` without
-    /// blocking. This is only possible when the semaphore is not currently borrowed or being waited
-    /// on. Returns `false` if the change will result in an available concurrency limit of less
-    /// than zero or greater than the semaphore's maximum.
-    ///
-    /// See [`Semaphore::modify()`] for more info.
-    pub fn try_modify(&mut self, count: ICount) -> bool {
+    /// Marks the semaphore as closed and wakes all waiters, which will find out that the semaphore
+    /// has been closed once they check. Waits that can't report closing carry on waiting.
+    pub(crate) fn close(&self) {
+        self.closed.store(true, Ordering::Release);
+        // Pairs with the fence in `WakerList::register()`, as in `notify_one()`.
+        fence(Ordering::SeqCst);
+
+        // Outstanding tickets are woken up but left in the queue (until they are dropped), so they
+        // can tell that they were woken because of the closure and not because they were granted
+        // their slots. A ticket joining the queue after this sees the flag itself.
+        if let Some(waiters) = self.waiters.get() {
+            let tickets = waiters.tickets.lock().unwrap();
+            for ticket in tickets.iter().filter(|ticket| ticket.signal.closable) {
+                ticket.signal.set();
+            }
+            drop(tickets);
+
+            #[cfg(feature = "async")]
+            waiters.wakers.wake_all();
+            waiters.subscribers.wake_all();
+        }
+        self.event.set();
+    }
+
+    pub(crate) fn is_closed(&self) -> bool {
+        self.closed.load(Ordering::Acquire)
+    }
+
+    /// Directly decrements the maximum currently available concurrency `current`, without regard
+    /// for underflow.
+    pub(crate) unsafe fn reduce_current(&self, count: Count) {
+        self.current.fetch_sub(count, Ordering::Relaxed);
+    }
+
+    /// Directly increments or decrements both the current availability limit and the available
+    /// count, provided the result lies within `0..=max`. Requires exclusive access, as otherwise
+    /// outstanding borrows would make the two values diverge.
+    pub(crate) fn try_modify(&mut self, count: ICount, max: Count) -> bool {
+        // Exclusive access means relaxed operations suffice (loom's atomics have no `get_mut()`).
         let current = self.current.load(Ordering::Relaxed);
         match (current as INext).checked_add(count as INext) {
-            Some(sum) if sum <= (self.max as INext) => {}
+            Some(sum) if sum >= 0 && sum <= (max as INext) => {}
             _ => return false,
         };
 
+        let delta = (count as INext).unsigned_abs() as Count;
         match count.signum() {
             0 => return true,
             1 => {
-                self.current.fetch_add(count as Count, Ordering::Relaxed);
-                self.count.fetch_add(count as Count, Ordering::Relaxed);
+                self.current.fetch_add(delta, Ordering::Relaxed);
+                self.state.fetch_add(delta as State, Ordering::Relaxed);
             }
             -1 => {
-                self.current
-                    .fetch_add((count as INext).unsigned_abs() as Count, Ordering::Relaxed);
-                self.count
-                    .fetch_add((count as INext).unsigned_abs() as Count, Ordering::Relaxed);
+                self.current.fetch_sub(delta, Ordering::Relaxed);
+                self.state.fetch_sub(delta as State, Ordering::Relaxed);
             }
             _ => unsafe {
                 core::hint::unreachable_unchecked();
@@ -343,19 +537,13 @@ impl Semaphore {
         true
     }
 
-    /// Increments the available concurrency by `count`, and panics if this results in a count that
-    /// exceeds the `max_count` the `Semaphore` was created with (see [`Semaphore::new()`]). Unlike
-    /// [`Semaphore::modify()`], this can be called with a non-mutable reference to the semaphore,
-    /// but can only increment the concurrency level.
-    ///
-    /// See [`try_release`](Self::try_release) for a non-panicking version of this function.
-    /// See the documentation for [`modify()`](Self::modify) for info on decrementing the available
-    /// concurrency level.
-    pub fn release(&self, count: Count) {
+    /// Increments the available concurrency by `count`, panicking if the result would exceed
+    /// `max`.
+    pub(crate) fn release(&self, count: Count, max: Count) {
         // Increment the "current maximum" which includes borrowed semaphore instances.
         let prev_count = self.current.fetch_add(count, Ordering::Relaxed);
         match prev_count.checked_add(count) {
-            Some(sum) if sum <= self.max => {}
+            Some(sum) if sum <= max => {}
             _ => panic!("Semaphore::release() called with an inappropriate count!"),
         }
         // Increment the actual "currently available" count to match. The two fields do not need to
@@ -366,19 +554,14 @@ impl Semaphore {
         }
     }
 
-    /// Attempts to increment the available concurrency counter by `count`, and returns `false` if
-    /// this operation would result in a count that exceeds the `max_count` the `Semaphore` was
-    /// created with (see [`Semaphore::new()`]).
-    ///
-    /// If you can guarantee that the count cannot exceed the maximum allowed, you may want to use
-    /// [`Semaphore::release()`] instead as it is both lock-free and wait-free, whereas
-    /// `try_release()` is only lock-free and may spin internally in case of contention.
-    pub fn try_release(&self, count: Count) -> bool {
+    /// Increments the available concurrency by `count`, returning `false` without making any
+    /// changes if the result would exceed `max`.
+    pub(crate) fn try_release(&self, count: Count, max: Count) -> bool {
         // Try to increment the "current maximum" which includes borrowed semaphore instances.
         let mut prev_count = self.current.load(Ordering::Relaxed);
         loop {
             match prev_count.checked_add(count) {
-                Some(sum) if sum <= self.max => {}
+                Some(sum) if sum <= max => {}
                 _ => return false,
             }
             match self.current.compare_exchange_weak(
@@ -402,181 +585,2110 @@ impl Semaphore {
         true
     }
 
-    /// Returns the currently available count of the semaphore.
-    ///
-    /// Note that this may race with other calls such as `release()` or `wait()`.
-    pub fn count(&self) -> Count {
-        self.count.load(Ordering::Relaxed)
+    pub(crate) fn count(&self) -> Count {
+        (self.state.load(Ordering::Relaxed) & COUNT_MASK) as Count
     }
-}
-
-impl<'a> Awaitable<'a> for Semaphore {
-    type T = SemaphoreGuard<'a>;
-    type Error = TimeoutError;
 
-    /// Attempts to obtain access to the resource or code protected by the `Semaphore`, subject to
-    /// the available concurrency count. Returns immediately if the `Semaphore`'s internal
-    /// concurrency count is non-zero or blocks sleeping until the `Semaphore` becomes available
-    /// (via another thread completing its access to the controlled-concurrency region or if the
-    /// semaphore's concurrency limit is raised).
+    /// Moves `count` currently available slots from `self` to `to`, provided `self` has that many
+    /// slots available and `to` can accept them without exceeding `to_max`.
     ///
-    /// A successful wait against the semaphore decrements its internal available concurrency
-    /// count (possibly preventing other threads from obtaining the semaphore) until
-    /// [`Semaphore::release()`] is called.
-    fn try_wait(&'a self) -> Result<SemaphoreGuard<'a>, Infallible> {
-        self.try_wait(Timeout::Infinite).unwrap();
-        Ok(SemaphoreGuard { semaphore: self })
-    }
-
-    /// Attempts a time-bounded wait against the `Semaphore`, returning `Ok(())` if and when the
-    /// semaphore becomes available or a [`TimeoutError`](rsevents::TimeoutError) if the specified
-    /// time limit elapses without the semaphore becoming available to the calling thread.
-    fn try_wait_for(
-        &'a self,
-        limit: Duration,
-    ) -> Result<SemaphoreGuard<'a>, rsevents::TimeoutError> {
-        self.try_wait(Timeout::Bounded(limit))?;
-        Ok(SemaphoreGuard { semaphore: self })
-    }
+    /// Room for the slots is claimed in `to` before they are taken from `self`, so that once taken
+    /// they are granted to `to` right away (while holding both semaphores' ticket locks) and are
+    /// never handed back to `self`. The slots are never available from both semaphores, and
+    /// waiters queued on either semaphore see them move in one step.
+    pub(crate) fn try_transfer(&self, to: &RawSemaphore, count: Count, to_max: Count) -> bool {
+        // Lock in address order so that opposing transfers can't deadlock.
+        let (_from_tickets, mut to_tickets) = if (self as *const Self) < (to as *const Self) {
+            let from_tickets = self.tickets();
+            (from_tickets, to.tickets())
+        } else {
+            let to_tickets = to.tickets();
+            (self.tickets(), to_tickets)
+        };
 
-    /// Attempts to obtain the `Semaphore` without waiting, returning `Ok(())` if the semaphore
-    /// is immediately available or a [`TimeoutError`](rsevents::TimeoutError) otherwise.
-    fn try_wait0(&'a self) -> Result<SemaphoreGuard<'a>, rsevents::TimeoutError> {
-        self.try_wait(Timeout::None)?;
-        Ok(SemaphoreGuard { semaphore: self })
-    }
-}
+        // Bail out early if the source doesn't have the slots, rather than claiming room in the
+        // destination only to give it back.
+        if self.count() < count {
+            return false;
+        }
 
-/// The concurrency token returned by [`Semaphore::wait()`], allowing access to the
-/// concurrency-limited region/code. Gives up its slot when dropped, allowing another thread to
-/// enter the semaphore in its place.
-///
-/// `SemaphoreGuard` instances should never be passed to `std::mem::forget()` &ndash;
-/// [`SemaphoreGuard::forget()`] should be called instead to forget a `SemaphoreGuard` and
-/// permanently decrease the available concurrency.
-pub struct SemaphoreGuard<'a> {
-    semaphore: &'a Semaphore,
-}
+        // Claim room for the slots in the destination, so that the transfer can't fail once they
+        // have been taken from the source.
+        let mut to_current = to.current.load(Ordering::Relaxed);
+        loop {
+            match to_current.checked_add(count) {
+                Some(sum) if sum <= to_max => {}
+                _ => return false,
+            }
+            match to.current.compare_exchange_weak(
+                to_current,
+                to_current + count,
+                Ordering::Relaxed,
+                Ordering::Relaxed,
+            ) {
+                Ok(_) => break,
+                Err(new_current) => to_current = new_current,
+            }
+        }
 
-impl SemaphoreGuard<'_> {
-    /// Safely "forgets" a semaphore's guard, permanently reducing the concurrency limit of the
-    /// associated `Semaphore`. `SemaphoreGuard::forget()` internally decrements the semaphore's
-    /// availablibility counter to make sure that future calls to `Semaphore::release()` or
-    /// `Semaphore::try_release()` do not incorrectly report failure.
-    ///
-    /// A `SemaphoreGuard` instance should never be passed to `std::mem::forget()` directly, as that
-    /// would violate the internal contract; this method should be used instead.
-    pub fn forget(self) {
+        // Take the slots from the source semaphore, as if `count` guards had been obtained and
+        // forgotten, and hand them straight to the destination.
+        if !self.try_take(count) {
+            to.current.fetch_sub(count, Ordering::Relaxed);
+            return false;
+        }
         unsafe {
-            self.semaphore.modify_current(-1);
+            self.reduce_current(count);
+            to.release_locked(count, &mut to_tickets);
         }
-        core::mem::forget(self);
-    }
-}
 
-impl Debug for SemaphoreGuard<'_> {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        f.debug_struct("SemaphoreGuard").finish_non_exhaustive()
+        true
     }
 }
 
-impl Drop for SemaphoreGuard<'_> {
-    fn drop(&mut self) {
-        unsafe {
-            self.semaphore.release_internal(1);
+impl RawSemaphore {
+    /// Obtains `weight` slots from the semaphore, subject to `timeout`. Only `closable` waits fail
+    /// with [`AcquireError::Closed`] once the semaphore is closed; the others keep waiting.
+    ///
+    /// Single-slot waits park on the semaphore's auto-reset event until the count is non-zero.
+    /// Weighted waits (and any wait made while tickets are outstanding, so that released slots are
+    /// handed out in order) instead take a [`Ticket`] and wait for it to be granted.
+    pub(crate) fn try_wait(
+        &self,
+        weight: Count,
+        timeout: Timeout,
+        closable: bool,
+    ) -> Result<(), AcquireError> {
+        if weight == 0 {
+            return Ok(());
         }
-    }
-}
 
-#[cfg(test)]
-mod test {
-    use super::Count;
-    use crate::Semaphore;
-    use rsevents::Awaitable;
-    use std::thread;
-    use std::time::Duration;
+        let clock = self.options.clock;
+        let mut parked_since = None;
+
+        loop {
+            let closed = self.is_closed();
+            if closed {
+                // Pass the wakeup along so the next waiter also finds out we've been closed.
+                self.event.set();
+                if closable {
+                    return Err(AcquireError::Closed);
+                }
+            }
+
+            let queued = self.is_queued();
+            if !queued && self.try_take(weight) {
+                return Ok(());
+            }
+
+            // eprintln!("Semaphore unavailable. Sleeping until the event is signalled.");
+            match (&timeout, self.options.starvation_limit) {
+                (Timeout::None, _) => return Err(AcquireError::TimedOut),
+                // Once closed, the event is passed along by every waiter that wakes, so waits that
+                // outlive the closure line up for a ticket instead of spinning on it.
+                _ if queued || weight > 1 || closed => {
+                    let parked_since = *parked_since.get_or_insert_with(|| clock.now());
+                    return self.wait_in_line(weight, &timeout, parked_since, closable);
+                }
+                (Timeout::Infinite, None) => self.event.wait(),
+                (Timeout::Bounded(timeout), None) => {
+                    // Only the remainder of the timeout is left if we've been woken before.
+                    let parked_since = *parked_since.get_or_insert_with(|| clock.now());
+                    let remaining = timeout.saturating_sub(self.elapsed_since(parked_since));
+                    if !self.park(remaining) {
+                        return Err(AcquireError::TimedOut);
+                    }
+                }
+                (_, Some(limit)) => {
+                    let parked_since = *parked_since.get_or_insert_with(|| clock.now());
+                    if self.park_or_jump_queue(&timeout, parked_since, limit, closable)? {
+                        return Ok(());
+                    }
+                }
+            }
+        }
+    }
+
+    /// Takes a [`Ticket`] for `weight` slots and waits for it to be granted, for no longer than
+    /// what remains of `timeout` after `parked_since`.
+    fn wait_in_line(
+        &self,
+        weight: Count,
+        timeout: &Timeout,
+        parked_since: Instant,
+        closable: bool,
+    ) -> Result<(), AcquireError> {
+        let mut ticket = self.reserve(weight, closable);
+        // The slots are accounted for by our caller, not a guard.
+        ticket.redeem(match timeout {
+            Timeout::Bounded(timeout) => {
+                Some(timeout.saturating_sub(self.elapsed_since(parked_since)))
+            }
+            _ => None,
+        })
+    }
+
+    /// Parks the calling thread (at most until the starvation `limit` is reached), unless the thread
+    /// has already been waiting longer than `limit`, in which case it takes a [`Ticket`] and waits
+    /// for the next slot to be handed to it ahead of any regular waiters.
+    ///
+    /// Returns `Ok(true)` if a slot was obtained via a ticket, or `Ok(false)` if the caller should
+    /// check the count and try again.
+    fn park_or_jump_queue(
+        &self,
+        timeout: &Timeout,
+        parked_since: Instant,
+        limit: Duration,
+        closable: bool,
+    ) -> Result<bool, AcquireError> {
+        let waited = self.elapsed_since(parked_since);
+        if waited >= limit {
+            self.wait_in_line(1, timeout, parked_since, closable)?;
+            return Ok(true);
+        }
+
+        let slice = limit - waited;
+        match timeout {
+            Timeout::Bounded(timeout) if timeout.saturating_sub(waited) <= slice => {
+                if !self.park(timeout.saturating_sub(waited)) {
+                    return Err(AcquireError::TimedOut);
+                }
+            }
+            // Wake up in time to be boosted if we're still waiting by then.
+            _ => {
+                self.park(slice);
+            }
+        }
+        Ok(false)
+    }
+
+    /// Waits up to `limit` (according to the semaphore's clock) for the event to be set, returning
+    /// whether it was.
+    fn park(&self, limit: Duration) -> bool {
+        self.options
+            .clock
+            .wait_for(limit, &mut |limit| self.event.try_wait_for(limit).is_ok())
+    }
+
+    fn elapsed_since(&self, instant: Instant) -> Duration {
+        self.options.clock.now().saturating_duration_since(instant)
+    }
+
+    /// Returns the `weight` slots held by a guard obtained at `acquired`, invoking the hold-limit
+    /// callback if the guard was held for too long.
+    fn return_slots(&self, weight: Count, acquired: Option<Box<Instant>>) {
+        let held = acquired.map(|acquired| self.elapsed_since(*acquired));
+        unsafe {
+            self.release_internal(weight);
+        }
+
+        if let (Some(held), Some((limit, on_exceeded))) = (held, self.options.hold_limit) {
+            if held > limit {
+                on_exceeded(held);
+            }
+        }
+    }
+
+    /// Issues a [`Ticket`] for `weight` slots, which fails to be redeemed once the semaphore is
+    /// closed only if `closable`.
+    pub(crate) fn reserve(&self, weight: Count, closable: bool) -> Ticket<'_> {
+        let signal = Arc::new(TicketSignal::new(closable));
+
+        let mut tickets = self.tickets();
+        tickets.push_back(QueuedTicket {
+            weight,
+            signal: Arc::clone(&signal),
+        });
+        // Either a concurrent release sees the flag (and hands out its slots once we unlock the
+        // queue), or we see its slots below.
+        self.state.fetch_or(QUEUED, Ordering::AcqRel);
+        // Claim the slots right away if they are available and nobody is ahead of us.
+        self.grant_tickets(&mut tickets);
+        if closable && self.is_closed() {
+            // Wake ourselves up to find out that we've been closed (see `close()`).
+            signal.set();
+        }
+        drop(tickets);
+
+        Ticket {
+            semaphore: self,
+            weight,
+            signal,
+            redeemed: false,
+        }
+    }
+}
+
+impl Semaphore {
+    loom_const_fn! {
+        /// Create a new [`Semaphore`] with a maximum available concurrency count of `max_count`
+        /// and an initial available concurrency count of `initial_count`.
+        pub fn new(initial_count: Count, max_count: Count) -> Self {
+            #[allow(unused_comparisons)]
+            if max_count < 0 {
+                panic!("Invalid max_count < 0");
+            }
+            #[allow(unused_comparisons)]
+            if initial_count < 0 {
+                panic!("Invalid initial_count < 0");
+            }
+            if initial_count > max_count {
+                panic!("Invalid initial_count > max_count");
+            }
+
+            Semaphore {
+                max: max_count,
+                raw: RawSemaphore::new(initial_count),
+            }
+        }
+    }
+
+    /// Enables the optional behaviors configured in `options`, such as a
+    /// [starvation limit](SemaphoreOptions::starvation_limit), for this `Semaphore`. See
+    /// [`SemaphoreOptions`] for more info.
+    pub const fn with_options(mut self, options: &'static SemaphoreOptions) -> Self {
+        self.raw.options = options;
+        self
+    }
+
+    /// Attempts to obtain access to the resource or code protected by the `Semaphore`, subject to
+    /// the available concurrency count. Returns immediately if the `Semaphore`'s internal
+    /// concurrency count is non-zero or blocks sleeping until the `Semaphore` becomes available
+    /// (via another thread completing its access to the controlled-concurrency region or if the
+    /// semaphore's concurrency limit is raised).
+    ///
+    /// A successful wait against the semaphore decrements its internal available concurrency
+    /// count (possibly preventing other threads from obtaining the semaphore) until
+    /// [`Semaphore::release()`] is called (which happens automatically when the `SemaphoreGuard`
+    /// concurrency token is dropped).
+    ///
+    /// [Closing](Semaphore::close) the semaphore doesn't affect this wait, which keeps waiting for
+    /// a slot to be released; see [`Semaphore::acquire()`] for a wait that gives up instead.
+    #[must_use = "The semaphore count is immediately re-incremented if the guard is dropped"]
+    pub fn wait(&self) -> SemaphoreGuard<'_> {
+        self.raw.try_wait(1, Timeout::Infinite, false).unwrap();
+        SemaphoreGuard::new(&self.raw, 1)
+    }
+
+    /// Obtains a concurrency token from the semaphore like [`Semaphore::wait()`], blocking until
+    /// one becomes available, but returns [`AcquireError::Closed`] instead if the semaphore is
+    /// [closed](Semaphore::close) before that happens.
+    ///
+    /// ```rust
+    /// use rsevents_extra::{AcquireError, Semaphore};
+    ///
+    /// let sem = Semaphore::new(0, 1);
+    ///
+    /// std::thread::scope(|scope| {
+    ///     let waiter = scope.spawn(|| sem.acquire().map(|_| ()));
+    ///     sem.close();
+    ///     assert_eq!(waiter.join().unwrap(), Err(AcquireError::Closed));
+    /// });
+    /// ```
+    #[must_use = "The semaphore count is immediately re-incremented if the guard is dropped"]
+    pub fn acquire(&self) -> Result<SemaphoreGuard<'_>, AcquireError> {
+        self.raw.try_wait(1, Timeout::Infinite, true)?;
+        Ok(SemaphoreGuard::new(&self.raw, 1))
+    }
+
+    /// Returns a [`Future`] that obtains a concurrency token from the semaphore like
+    /// [`Semaphore::acquire()`], but without blocking: while no slot is available, the awaiting
+    /// task registers its waker and is woken once it is worth trying again. Completes with
+    /// [`AcquireError::Closed`] if the semaphore is [closed](Semaphore::close) first. Only
+    /// available with the `async` feature enabled.
+    ///
+    /// Tasks and threads can wait on the same semaphore, and follow the same rules: each released
+    /// slot wakes both a parked thread and the longest-waiting task to compete for it, while any
+    /// waiter (of either kind) that finds others waiting in line for a slot, such as [`Ticket`]s
+    /// or weighted waits, lines up behind them and is served in order. The
+    /// [starvation limit](SemaphoreOptions::starvation_limit) applies to tasks as well.
+    ///
+    /// [`Semaphore::try_acquire()`] never blocks, and can be called from async code as is.
+    ///
+    /// ```rust
+    /// use rsevents_extra::Semaphore;
+    /// # fn block_on<F: std::future::Future>(future: F) -> F::Output {
+    /// #     struct Unparker(std::thread::Thread);
+    /// #     impl std::task::Wake for Unparker {
+    /// #         fn wake(self: std::sync::Arc<Self>) {
+    /// #             self.0.unpark();
+    /// #         }
+    /// #     }
+    /// #     let waker = std::sync::Arc::new(Unparker(std::thread::current())).into();
+    /// #     let mut context = std::task::Context::from_waker(&waker);
+    /// #     let mut future = std::pin::pin!(future);
+    /// #     loop {
+    /// #         match future.as_mut().poll(&mut context) {
+    /// #             std::task::Poll::Ready(result) => return result,
+    /// #             std::task::Poll::Pending => std::thread::park(),
+    /// #         }
+    /// #     }
+    /// # }
+    ///
+    /// static DB_CONNECTIONS: Semaphore = Semaphore::new(4, 4);
+    ///
+    /// async fn query() {
+    ///     let _guard = DB_CONNECTIONS.acquire_async().await.unwrap();
+    ///     // <query the database here>
+    /// }
+    ///
+    /// block_on(query());
+    /// ```
+    #[cfg(feature = "async")]
+    pub fn acquire_async(&self) -> Acquire<'_> {
+        Acquire::new(&self.raw)
+    }
+
+    /// Attempts a time-bounded wait against the semaphore, returning [`AcquireError::TimedOut`]
+    /// if the time limit elapses without the semaphore becoming available or
+    /// [`AcquireError::Closed`] if the semaphore is [closed](Semaphore::close) first.
+    #[must_use = "The semaphore count is immediately re-incremented if the guard is dropped"]
+    pub fn acquire_for(&self, limit: Duration) -> Result<SemaphoreGuard<'_>, AcquireError> {
+        match limit {
+            Duration::ZERO => self.raw.try_wait(1, Timeout::None, true)?,
+            timeout => self.raw.try_wait(1, Timeout::Bounded(timeout), true)?,
+        };
+        Ok(SemaphoreGuard::new(&self.raw, 1))
+    }
+
+    /// Attempts to obtain a concurrency token without waiting, returning
+    /// [`TryAcquireError::NoPermits`] if none is currently available or
+    /// [`TryAcquireError::Closed`] if the semaphore has been [closed](Semaphore::close).
+    #[must_use = "The semaphore count is immediately re-incremented if the guard is dropped"]
+    pub fn try_acquire(&self) -> Result<SemaphoreGuard<'_>, TryAcquireError> {
+        self.raw
+            .try_wait(1, Timeout::None, true)
+            .map_err(|e| match e {
+                AcquireError::Closed => TryAcquireError::Closed,
+                _ => TryAcquireError::NoPermits,
+            })?;
+        Ok(SemaphoreGuard::new(&self.raw, 1))
+    }
+
+    /// Closes the semaphore, causing all current and future [`Semaphore::acquire()`],
+    /// [`Semaphore::acquire_for()`], [`Semaphore::try_acquire()`] and
+    /// [`Semaphore::wait_cancellable()`] calls (and `acquire_async()` futures) to fail with
+    /// [`AcquireError::Closed`] (or [`TryAcquireError::Closed`]).
+    ///
+    /// The waits that have no way of reporting this, i.e. [`Semaphore::wait()`], the
+    /// [`Awaitable`] implementation, weighted waits, and [`Ticket`]s, aren't affected: they keep
+    /// waiting for slots to be released as usual.
+    ///
+    /// Guards obtained before the semaphore was closed remain valid and return their slots as
+    /// usual when dropped. A closed semaphore can't be reopened.
+    pub fn close(&self) {
+        self.raw.close()
+    }
+
+    /// Returns whether the semaphore has been [closed](Semaphore::close).
+    pub fn is_closed(&self) -> bool {
+        self.raw.is_closed()
+    }
+
+    /// Obtains `weight` slots from the `Semaphore` at once, blocking until that many are available,
+    /// for modelling budgets (memory, bandwidth, etc.) where acquisitions have different costs. The
+    /// returned guard releases exactly `weight` slots when it is dropped.
+    ///
+    /// Weighted waiters are served in the order they arrive: a waiter that can't be satisfied yet
+    /// isn't overtaken by later, lighter waiters, so heavy acquisitions can't be starved. Panics if
+    /// `weight` exceeds the semaphore's maximum count, as such a wait could never succeed.
+    ///
+    /// ```rust
+    /// use rsevents_extra::Semaphore;
+    ///
+    /// // Allow at most 64 MiB of buffers to be allocated at any given time.
+    /// static MEMORY_MIB: Semaphore = Semaphore::new(64, 64);
+    ///
+    /// let guard = MEMORY_MIB.wait_weighted(48);
+    /// assert_eq!(MEMORY_MIB.count(), 16);
+    /// drop(guard);
+    /// assert_eq!(MEMORY_MIB.count(), 64);
+    /// ```
+    #[must_use = "The semaphore count is immediately re-incremented if the guard is dropped"]
+    pub fn wait_weighted(&self, weight: Count) -> SemaphoreGuard<'_> {
+        self.check_weight(weight);
+        self.raw.try_wait(weight, Timeout::Infinite, false).unwrap();
+        SemaphoreGuard::new(&self.raw, weight)
+    }
+
+    /// Attempts to obtain `weight` slots from the `Semaphore` at once, returning a [`TimeoutError`]
+    /// (and holding none of them) if that many don't become available within the time limit. See
+    /// [`Semaphore::wait_weighted()`] for more info.
+    #[must_use = "The semaphore count is immediately re-incremented if the guard is dropped"]
+    pub fn wait_weighted_for(
+        &self,
+        weight: Count,
+        limit: Duration,
+    ) -> Result<SemaphoreGuard<'_>, TimeoutError> {
+        self.check_weight(weight);
+        match limit {
+            Duration::ZERO => self.raw.try_wait(weight, Timeout::None, false),
+            timeout => self.raw.try_wait(weight, Timeout::Bounded(timeout), false),
+        }
+        .map_err(|_| TimeoutError)?;
+        Ok(SemaphoreGuard::new(&self.raw, weight))
+    }
+
+    /// Obtains a slot from the `Semaphore` like [`Semaphore::acquire()`], but gives up and returns
+    /// [`AcquireError::Cancelled`] if `token` becomes available first. If both are available,
+    /// cancellation takes precedence. If the semaphore is [closed](Semaphore::close) first,
+    /// [`AcquireError::Closed`] is returned instead.
+    ///
+    /// `token` can be any [`Awaitable`] type, such as a [`CancellationToken`] or a
+    /// [`ManualResetEvent`] used as an abort flag. The thread sleeps until woken by the semaphore,
+    /// polling the token with a backoff capped at a few milliseconds in the meantime; use
+    /// [`wait_cancellable_subscribed()`](Self::wait_cancellable_subscribed) to have the token wake
+    /// it too. A token whose wait has side effects (e.g. an `AutoResetEvent` or another
+    /// `Semaphore`) will be consumed by the call if it fires.
+    ///
+    /// [`CancellationToken`]: crate::CancellationToken
+    ///
+    /// ```rust
+    /// use rsevents_extra::{AcquireError, CancellationTokenSource, Semaphore};
+    ///
+    /// let sem = Semaphore::new(0, 1);
+    /// let shutdown = CancellationTokenSource::new();
+    /// let token = shutdown.token();
+    ///
+    /// std::thread::scope(|scope| {
+    ///     let waiter = scope.spawn(|| sem.wait_cancellable(&token).map(|_| ()));
+    ///     shutdown.cancel();
+    ///     assert_eq!(waiter.join().unwrap(), Err(AcquireError::Cancelled));
+    /// });
+    /// ```
+    pub fn wait_cancellable<'t, C>(&self, token: &'t C) -> Result<SemaphoreGuard<'_>, AcquireError>
+    where
+        C: Awaitable<'t> + ?Sized,
+    {
+        self.wait_cancellable_with(token, polled)
+    }
+
+    /// Obtains a slot from the `Semaphore` like [`wait_cancellable()`](Self::wait_cancellable),
+    /// but sleeps until woken by either the semaphore or (through its [`Subscribe`]
+    /// implementation) the token, instead of polling the token.
+    pub fn wait_cancellable_subscribed<'t, C>(
+        &self,
+        token: &'t C,
+    ) -> Result<SemaphoreGuard<'_>, AcquireError>
+    where
+        C: Awaitable<'t> + Subscribe + ?Sized,
+    {
+        self.wait_cancellable_with(token, subscribed)
+    }
+
+    fn wait_cancellable_with<'t, C>(
+        &self,
+        token: &'t C,
+        subscribe: SubscribeFn<C>,
+    ) -> Result<SemaphoreGuard<'_>, AcquireError>
+    where
+        C: Awaitable<'t> + ?Sized,
+    {
+        if token.try_wait0().is_ok() {
+            return Err(AcquireError::Cancelled);
+        }
+        match self.try_acquire() {
+            Ok(guard) => return Ok(guard),
+            Err(TryAcquireError::Closed) => return Err(AcquireError::Closed),
+            Err(TryAcquireError::NoPermits) => {}
+        }
+
+        // Hold our place in line, sleeping until the ticket is granted or the token fires.
+        let mut ticket = self.raw.reserve(1, true);
+        let (parker, waker) = Parker::new();
+        let mut backoff = Backoff::new();
+        loop {
+            // Subscribe before checking, so that neither source can fire unnoticed in between.
+            let subscription = subscribe(token, &waker);
+            if token.try_wait0().is_ok() {
+                return Err(AcquireError::Cancelled);
+            }
+            if let Poll::Ready(result) = ticket.poll_redeem(&waker) {
+                result?;
+                return Ok(SemaphoreGuard::new(ticket.semaphore, ticket.weight));
+            }
+            parker.park(match subscription {
+                Some(_) => None,
+                None => Some(backoff.next()),
+            });
+        }
+    }
+
+    fn check_weight(&self, weight: Count) {
+        if weight > self.max {
+            panic!("Semaphore wait weight exceeds the maximum count!");
+        }
+    }
+
+    #[cfg_attr(not(test), allow(unused))]
+    fn wait0(&self) -> Result<SemaphoreGuard<'_>, rsevents::TimeoutError> {
+        self.raw
+            .try_wait(1, Timeout::None, false)
+            .map_err(|_| TimeoutError)?;
+        Ok(SemaphoreGuard::new(&self.raw, 1))
+    }
+
+    /// Attempts a time-bounded wait against the `Semaphore`, returning `Ok(())` if and when the
+    /// semaphore becomes available or a [`TimeoutError`](rsevents::TimeoutError) if the specified
+    /// time limit elapses without the semaphore becoming available to the calling thread.
+    ///
+    /// Like [`Semaphore::wait()`], this wait isn't affected by [closing](Semaphore::close) the
+    /// semaphore; use [`Semaphore::acquire_for()`] for one that is.
+    #[must_use = "The semaphore count is immediately re-incremented if the guard is dropped"]
+    pub fn wait_for(&self, limit: Duration) -> Result<SemaphoreGuard<'_>, rsevents::TimeoutError> {
+        match limit {
+            Duration::ZERO => self.raw.try_wait(1, Timeout::None, false),
+            timeout => self.raw.try_wait(1, Timeout::Bounded(timeout), false),
+        }
+        .map_err(|_| TimeoutError)?;
+        Ok(SemaphoreGuard::new(&self.raw, 1))
+    }
+
+    /// Directly increments or decrements the current availability limit for a `Semaphore` without
+    /// blocking. This is only possible when the semaphore is not currently borrowed or being waited
+    /// on. Panics if the change will result in an available concurrency limit of less than zero or
+    /// greater than the semaphore's maximum. See [`Semaphore::try_modify()`] for a non-panicking
+    /// alternative.
+    ///
+    /// To increment the semaphore's concurrency limit without an `&mut Semaphore` reference, call
+    /// [`Semaphore::release()`] instead. To decrement the concurrency limit, wait on the semaphore
+    /// then call [`forget()`](SemaphoreGuard::forget) on the returned `SemaphoreGuard`:
+    ///
+    /// ```rust
+    /// use rsevents_extra::Semaphore;
+    ///
+    /// fn adjust_sem(sem: &Semaphore, count: i16) {
+    ///     if count >= 0 {
+    ///        sem.release(count as u16);
+    ///     } else {
+    ///         // Note: this will block if the semaphore isn't available!
+    ///         for _ in 0..(-1 * count) {
+    ///             let guard = sem.wait();
+    ///             guard.forget();
+    ///         }
+    ///     }
+    /// }
+    /// ```
+    pub fn modify(&mut self, count: ICount) {
+        if !self.raw.try_modify(count, self.max) {
+            panic!("An invalid count was supplied to Semaphore::modify()");
+        }
+    }
+
+    /// Directly increments or decrements the current availability limit for a `Semaphore` without
+    /// blocking. This is only possible when the semaphore is not currently borrowed or being waited
+    /// on. Returns `false` if the change will result in an available concurrency limit of less
+    /// than zero or greater than the semaphore's maximum.
+    ///
+    /// See [`Semaphore::modify()`] for more info.
+    pub fn try_modify(&mut self, count: ICount) -> bool {
+        self.raw.try_modify(count, self.max)
+    }
+
+    /// Increments the available concurrency by `count`, and panics if this results in a count that
+    /// exceeds the `max_count` the `Semaphore` was created with (see [`Semaphore::new()`]). Unlike
+    /// [`Semaphore::modify()`], this can be called with a non-mutable reference to the semaphore,
+    /// but can only increment the concurrency level.
+    ///
+    /// See [`try_release`](Self::try_release) for a non-panicking version of this function.
+    /// See the documentation for [`modify()`](Self::modify) for info on decrementing the available
+    /// concurrency level.
+    pub fn release(&self, count: Count) {
+        self.raw.release(count, self.max)
+    }
+
+    /// Attempts to increment the available concurrency counter by `count`, and returns `false` if
+    /// this operation would result in a count that exceeds the `max_count` the `Semaphore` was
+    /// created with (see [`Semaphore::new()`]).
+    ///
+    /// If you can guarantee that the count cannot exceed the maximum allowed, you may want to use
+    /// [`Semaphore::release()`] instead as it is both lock-free and wait-free, whereas
+    /// `try_release()` is only lock-free and may spin internally in case of contention.
+    pub fn try_release(&self, count: Count) -> bool {
+        self.raw.try_release(count, self.max)
+    }
+
+    /// Returns the currently available count of the semaphore.
+    ///
+    /// Note that this may race with other calls such as `release()` or `wait()`.
+    pub fn count(&self) -> Count {
+        self.raw.count()
+    }
+
+    /// Moves `count` available concurrency slots from this semaphore to `to`, permanently lowering
+    /// the concurrency limit of the former and raising that of the latter, without blocking.
+    ///
+    /// Returns `false` without modifying either semaphore if this semaphore doesn't currently have
+    /// `count` slots available or if the transfer would take `to` past its maximum count. The
+    /// transferred slots are never available from both semaphores at once, so a shared budget split
+    /// across the two can't be exceeded while the transfer is in progress.
+    ///
+    /// A transfer that succeeds takes effect in one step: room for the slots is claimed in `to`
+    /// first, so that once the slots have been taken from this semaphore they are granted to `to`
+    /// straight away (under both semaphores' locks, so waiters queued on either see them move at
+    /// once) and are never handed back.
+    ///
+    /// ```rust
+    /// use rsevents_extra::Semaphore;
+    ///
+    /// let background = Semaphore::new(6, 8);
+    /// let interactive = Semaphore::new(2, 8);
+    ///
+    /// // Shift two connection slots to the interactive pool.
+    /// assert!(background.try_transfer(&interactive, 2));
+    /// assert_eq!(background.count(), 4);
+    /// assert_eq!(interactive.count(), 4);
+    /// ```
+    pub fn try_transfer(&self, to: &Semaphore, count: Count) -> bool {
+        if std::ptr::eq(self, to) {
+            return self.count() >= count;
+        }
+        self.raw.try_transfer(&to.raw, count, to.max)
+    }
+
+    /// Reserves a place in line for the next available concurrency slot without blocking,
+    /// returning a [`Ticket`] that can later be redeemed for a [`SemaphoreGuard`].
+    ///
+    /// Slots released after a ticket is issued are handed to outstanding tickets (oldest first)
+    /// before they become available to regular calls to [`Semaphore::wait()`], so the point at
+    /// which `reserve()` is called determines the caller's priority even if it doesn't block on the
+    /// ticket until later. Dropping an unredeemed ticket gives up its place in line (or returns its
+    /// slot, if one was already granted).
+    ///
+    /// ```rust
+    /// use rsevents_extra::Semaphore;
+    ///
+    /// let sem = Semaphore::new(0, 1);
+    /// let ticket = sem.reserve();
+    ///
+    /// // <do other work while waiting for our turn>
+    /// sem.release(1);
+    ///
+    /// let _guard = ticket.wait();
+    /// ```
+    #[must_use = "The ticket gives up its place in line if it is dropped"]
+    pub fn reserve(&self) -> Ticket<'_> {
+        self.raw.reserve(1, false)
+    }
+
+    /// Obtains a concurrency token like [`Semaphore::wait()`], blocking until one is available,
+    /// but returns an [`OwnedSemaphoreGuard`] that keeps the semaphore alive instead of borrowing
+    /// it, so the slot can be moved into a spawned thread along with the work it guards.
+    ///
+    /// ```rust
+    /// use rsevents_extra::Semaphore;
+    /// use std::sync::Arc;
+    ///
+    /// let sem = Arc::new(Semaphore::new(2, 2));
+    /// let workers: Vec<_> = (0..4)
+    ///     .map(|_| {
+    ///         let guard = sem.wait_owned();
+    ///         std::thread::spawn(move || {
+    ///             // <do the work here>
+    ///             drop(guard);
+    ///         })
+    ///     })
+    ///     .collect();
+    /// workers.into_iter().for_each(|worker| worker.join().unwrap());
+    /// assert_eq!(sem.count(), 2);
+    /// ```
+    #[must_use = "The semaphore count is immediately re-incremented if the guard is dropped"]
+    pub fn wait_owned(self: &std::sync::Arc<Self>) -> OwnedSemaphoreGuard {
+        OwnedSemaphoreGuard::new(std::sync::Arc::clone(self), self.wait())
+    }
+
+    /// Returns a `'static` [`Future`] that obtains an [`OwnedSemaphoreGuard`] like
+    /// [`Semaphore::acquire_async()`] (see there for how it waits), so that both the future and
+    /// the guard can be moved into a spawned task. Only available with the `async` feature
+    /// enabled.
+    ///
+    /// ```rust
+    /// use rsevents_extra::Semaphore;
+    /// use std::sync::Arc;
+    /// # fn spawn<F: std::future::Future + Send + 'static>(future: F) {
+    /// #     std::thread::spawn(move || {
+    /// #         struct Unparker(std::thread::Thread);
+    /// #         impl std::task::Wake for Unparker {
+    /// #             fn wake(self: Arc<Self>) {
+    /// #                 self.0.unpark();
+    /// #             }
+    /// #         }
+    /// #         let waker = Arc::new(Unparker(std::thread::current())).into();
+    /// #         let mut context = std::task::Context::from_waker(&waker);
+    /// #         let mut future = std::pin::pin!(future);
+    /// #         while future.as_mut().poll(&mut context).is_pending() {
+    /// #             std::thread::park();
+    /// #         }
+    /// #     });
+    /// # }
+    ///
+    /// let sem = Arc::new(Semaphore::new(4, 4));
+    /// for _ in 0..16 {
+    ///     let permit = sem.acquire_owned();
+    ///     spawn(async move {
+    ///         let _guard = permit.await.unwrap();
+    ///         // <do the work here>
+    ///     });
+    /// }
+    /// ```
+    #[cfg(feature = "async")]
+    pub fn acquire_owned(self: &std::sync::Arc<Self>) -> AcquireOwned {
+        // SAFETY: the future holds on to the `Arc`, keeping the semaphore alive (and in place) for
+        // as long as `inner` borrows it, and `inner` is declared first so it is dropped first.
+        let semaphore: &'static RawSemaphore = unsafe { &*(&self.raw as *const RawSemaphore) };
+        AcquireOwned {
+            inner: Acquire::new(semaphore),
+            semaphore: std::sync::Arc::clone(self),
+        }
+    }
+}
+
+impl<'a> Awaitable<'a> for Semaphore {
+    type T = SemaphoreGuard<'a>;
+    type Error = TimeoutError;
+
+    /// Attempts to obtain access to the resource or code protected by the `Semaphore`, subject to
+    /// the available concurrency count. Returns immediately if the `Semaphore`'s internal
+    /// concurrency count is non-zero or blocks sleeping until the `Semaphore` becomes available
+    /// (via another thread completing its access to the controlled-concurrency region or if the
+    /// semaphore's concurrency limit is raised).
+    ///
+    /// A successful wait against the semaphore decrements its internal available concurrency
+    /// count (possibly preventing other threads from obtaining the semaphore) until
+    /// [`Semaphore::release()`] is called.
+    fn try_wait(&'a self) -> Result<SemaphoreGuard<'a>, Infallible> {
+        Ok(self.wait())
+    }
+
+    /// Attempts a time-bounded wait against the `Semaphore`, returning `Ok(())` if and when the
+    /// semaphore becomes available or a [`TimeoutError`](rsevents::TimeoutError) if the specified
+    /// time limit elapses without the semaphore becoming available to the calling thread.
+    fn try_wait_for(
+        &'a self,
+        limit: Duration,
+    ) -> Result<SemaphoreGuard<'a>, rsevents::TimeoutError> {
+        self.raw
+            .try_wait(1, Timeout::Bounded(limit), false)
+            .map_err(|_| TimeoutError)?;
+        Ok(SemaphoreGuard::new(&self.raw, 1))
+    }
+
+    /// Attempts to obtain the `Semaphore` without waiting, returning `Ok(())` if the semaphore
+    /// is immediately available or a [`TimeoutError`](rsevents::TimeoutError) otherwise.
+    fn try_wait0(&'a self) -> Result<SemaphoreGuard<'a>, rsevents::TimeoutError> {
+        self.raw
+            .try_wait(1, Timeout::None, false)
+            .map_err(|_| TimeoutError)?;
+        Ok(SemaphoreGuard::new(&self.raw, 1))
+    }
+}
+
+impl Subscribe for Semaphore {
+    fn subscribe(&self, waker: &Waker) -> Option<Subscription<'_>> {
+        Some(self.raw.subscribe(waker))
+    }
+}
+
+/// The concurrency token returned by [`Semaphore::wait()`], allowing access to the
+/// concurrency-limited region/code. Gives up its slot when dropped, allowing another thread to
+/// enter the semaphore in its place.
+///
+/// `SemaphoreGuard` instances should never be passed to `std::mem::forget()` &ndash;
+/// [`SemaphoreGuard::forget()`] should be called instead to forget a `SemaphoreGuard` and
+/// permanently decrease the available concurrency.
+pub struct SemaphoreGuard<'a> {
+    semaphore: &'a RawSemaphore,
+    /// The number of slots held by this guard (more than one for weighted waits).
+    weight: Count,
+    /// When the guard was obtained, if the semaphore tracks hold times. Boxed so that it only takes
+    /// up a pointer's worth of space in the guards of semaphores that don't.
+    acquired: Option<Box<Instant>>,
+}
+
+impl<'a> SemaphoreGuard<'a> {
+    pub(crate) fn new(semaphore: &'a RawSemaphore, weight: Count) -> Self {
+        SemaphoreGuard {
+            semaphore,
+            weight,
+            acquired: semaphore
+                .options
+                .hold_limit
+                .map(|_| Box::new(semaphore.options.clock.now())),
+        }
+    }
+
+    /// Returns the number of slots held by this guard, which is greater than one if it was
+    /// obtained via [`Semaphore::wait_weighted()`].
+    pub fn weight(&self) -> Count {
+        self.weight
+    }
+
+    /// Safely "forgets" a semaphore's guard, permanently reducing the concurrency limit of the
+    /// associated `Semaphore`. `SemaphoreGuard::forget()` internally decrements the semaphore's
+    /// availablibility counter to make sure that future calls to `Semaphore::release()` or
+    /// `Semaphore::try_release()` do not incorrectly report failure.
+    ///
+    /// A `SemaphoreGuard` instance should never be passed to `std::mem::forget()` directly, as that
+    /// would violate the internal contract; this method should be used instead.
+    pub fn forget(mut self) {
+        unsafe {
+            self.semaphore.reduce_current(self.weight);
+        }
+        drop(self.acquired.take());
+        core::mem::forget(self);
+    }
+}
+
+impl Debug for SemaphoreGuard<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SemaphoreGuard").finish_non_exhaustive()
+    }
+}
+
+impl Drop for SemaphoreGuard<'_> {
+    fn drop(&mut self) {
+        self.semaphore
+            .return_slots(self.weight, self.acquired.take());
+    }
+}
+
+/// A concurrency token like [`SemaphoreGuard`], but holding on to the [`Semaphore`] it was
+/// obtained from (via an `Arc`) rather than borrowing it, so that it can be moved into a spawned
+/// thread or task. Returned by [`Semaphore::wait_owned()`] (and, with the `async` feature enabled,
+/// by `Semaphore::acquire_owned()`).
+///
+/// As with a `SemaphoreGuard`, the slot is given up when the guard is dropped, and
+/// [`OwnedSemaphoreGuard::forget()`] should be called instead of `std::mem::forget()`.
+pub struct OwnedSemaphoreGuard {
+    semaphore: std::sync::Arc<Semaphore>,
+    weight: Count,
+    acquired: Option<Box<Instant>>,
+}
+
+impl OwnedSemaphoreGuard {
+    /// Takes over the slots held by `guard`, which must have been obtained from `semaphore`.
+    fn new(semaphore: std::sync::Arc<Semaphore>, guard: SemaphoreGuard<'_>) -> Self {
+        let mut guard = core::mem::ManuallyDrop::new(guard);
+        OwnedSemaphoreGuard {
+            semaphore,
+            weight: guard.weight,
+            acquired: guard.acquired.take(),
+        }
+    }
+
+    /// Returns the number of slots held by this guard.
+    pub fn weight(&self) -> Count {
+        self.weight
+    }
+
+    /// Returns the semaphore this guard was obtained from.
+    pub fn semaphore(&self) -> &std::sync::Arc<Semaphore> {
+        &self.semaphore
+    }
+
+    /// Safely "forgets" the guard, permanently reducing the concurrency limit of the associated
+    /// `Semaphore` (see [`SemaphoreGuard::forget()`]).
+    pub fn forget(self) {
+        let mut this = core::mem::ManuallyDrop::new(self);
+        drop(this.acquired.take());
+        unsafe {
+            this.semaphore.raw.reduce_current(this.weight);
+            // Release our reference to the semaphore, which `ManuallyDrop` would otherwise leak.
+            drop(core::ptr::read(&this.semaphore));
+        }
+    }
+}
+
+impl Debug for OwnedSemaphoreGuard {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("OwnedSemaphoreGuard")
+            .field("weight", &self.weight)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Drop for OwnedSemaphoreGuard {
+    fn drop(&mut self) {
+        self.semaphore
+            .raw
+            .return_slots(self.weight, self.acquired.take());
+    }
+}
+
+/// A reservation for a future [`Semaphore`] concurrency slot, returned by
+/// [`Semaphore::reserve()`].
+///
+/// A `Ticket` holds its place in line from the moment it is issued; redeem it with
+/// [`Ticket::wait()`], [`Ticket::wait_for()`], or [`Ticket::try_redeem()`] to obtain the
+/// corresponding [`SemaphoreGuard`].
+pub struct Ticket<'a> {
+    semaphore: &'a RawSemaphore,
+    /// The number of slots this ticket is waiting for.
+    weight: Count,
+    /// Set once the slots have been granted to this ticket.
+    signal: Arc<TicketSignal>,
+    redeemed: bool,
+}
+
+impl<'a> Ticket<'a> {
+    /// Returns whether a slot has been granted to this ticket, i.e. whether redeeming it will
+    /// succeed without blocking.
+    pub fn is_ready(&self) -> bool {
+        self.signal.is_set()
+    }
+
+    /// Blocks until a slot is granted to this ticket, then redeems it.
+    pub fn wait(mut self) -> SemaphoreGuard<'a> {
+        self.redeem(None).unwrap();
+        SemaphoreGuard::new(self.semaphore, self.weight)
+    }
+
+    /// Waits up to `limit` for a slot to be granted to this ticket, redeeming it if so or handing
+    /// back the ticket (which retains its place in line) otherwise.
+    pub fn wait_for(mut self, limit: Duration) -> Result<SemaphoreGuard<'a>, Ticket<'a>> {
+        match self.redeem(Some(limit)) {
+            Ok(()) => Ok(SemaphoreGuard::new(self.semaphore, self.weight)),
+            Err(_) => Err(self),
+        }
+    }
+
+    /// Waits (for no longer than `limit`, if specified) for the ticket's event, marking the ticket
+    /// as redeemed if its slots were granted.
+    fn redeem(&mut self, limit: Option<Duration>) -> Result<(), AcquireError> {
+        let clock = self.semaphore.options.clock;
+        self.redeem_with(|event| match limit {
+            None => {
+                event.wait();
+                true
+            }
+            Some(limit) => clock.wait_for(limit, &mut |limit| event.try_wait_for(limit).is_ok()),
+        })
+    }
+
+    /// Waits for the ticket's event by calling `wait`, which returns whether the event was set,
+    /// and marks the ticket as redeemed if its slots were granted.
+    fn redeem_with(
+        &mut self,
+        wait: impl FnOnce(&ManualResetEvent) -> bool,
+    ) -> Result<(), AcquireError> {
+        if !wait(&self.signal.event) {
+            return Err(AcquireError::TimedOut);
+        }
+
+        // Closing the semaphore wakes all closable tickets without granting them anything; those
+        // tickets are still in the queue.
+        if self.signal.closable && self.semaphore.is_closed() {
+            let tickets = self.semaphore.tickets();
+            if tickets.iter().any(|t| Arc::ptr_eq(&t.signal, &self.signal)) {
+                return Err(AcquireError::Closed);
+            }
+        }
+
+        self.redeemed = true;
+        Ok(())
+    }
+
+    /// Redeems this ticket if a slot has already been granted to it, or arranges for `waker` to be
+    /// woken once one is.
+    fn poll_redeem(&mut self, waker: &Waker) -> Poll<Result<(), AcquireError>> {
+        // Register before checking, so a slot granted in between isn't missed.
+        *self.signal.waker.lock().unwrap() = Some(waker.clone());
+        match self.redeem_with(|event| event.try_wait0().is_ok()) {
+            Err(AcquireError::TimedOut) => Poll::Pending,
+            result => Poll::Ready(result),
+        }
+    }
+
+    /// Redeems this ticket if a slot has already been granted to it, or hands back the ticket
+    /// (which retains its place in line) otherwise.
+    pub fn try_redeem(self) -> Result<SemaphoreGuard<'a>, Ticket<'a>> {
+        self.wait_for(Duration::ZERO)
+    }
+}
+
+impl Debug for Ticket<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Ticket")
+            .field("ready", &self.is_ready())
+            .finish_non_exhaustive()
+    }
+}
+
+impl Drop for Ticket<'_> {
+    fn drop(&mut self) {
+        if self.redeemed {
+            return;
+        }
+
+        // Slots are granted with the lock held, so if we're no longer in the queue we must have
+        // been granted a slot that now needs to be returned.
+        let mut tickets = self.semaphore.tickets();
+        match tickets
+            .iter()
+            .position(|t| Arc::ptr_eq(&t.signal, &self.signal))
+        {
+            Some(i) => {
+                tickets.remove(i);
+                // The tickets behind us may have been waiting on us to be satisfied.
+                self.semaphore.grant_tickets(&mut tickets);
+            }
+            None => {
+                drop(tickets);
+                unsafe {
+                    self.semaphore.release_internal(self.weight);
+                }
+            }
+        }
+    }
+}
+
+/// The [`Future`] returned by [`Semaphore::acquire_async()`], completing with a
+/// [`SemaphoreGuard`] once a slot has been obtained. Dropping the future before then gives up its
+/// place in line (or returns its slot, if one was already granted). Only available with the
+/// `async` feature enabled.
+#[cfg(feature = "async")]
+#[must_use = "futures do nothing unless polled"]
+pub struct Acquire<'a> {
+    semaphore: &'a RawSemaphore,
+    state: AcquireState<'a>,
+    /// When the future first found no slot available, for the starvation limit.
+    waiting_since: Option<Instant>,
+}
+
+#[cfg(feature = "async")]
+enum AcquireState<'a> {
+    /// Not waiting (yet).
+    Idle,
+    /// Waiting alongside the parked threads, with our waker registered under this id.
+    Pooled(usize),
+    /// Waiting in line for a slot to be granted to this ticket.
+    Queued(Ticket<'a>),
+}
+
+#[cfg(feature = "async")]
+impl<'a> Acquire<'a> {
+    fn new(semaphore: &'a RawSemaphore) -> Self {
+        Acquire {
+            semaphore,
+            state: AcquireState::Idle,
+            waiting_since: None,
+        }
+    }
+
+    /// Stops waiting alongside the parked threads, passing on any wakeup we haven't `consumed`.
+    fn leave_pool(&mut self, consumed: bool) {
+        if let AcquireState::Pooled(id) = std::mem::replace(&mut self.state, AcquireState::Idle) {
+            self.semaphore.deregister_waker(id, consumed);
+        }
+    }
+
+    /// Attempts to obtain a slot the way a blocked thread would, returning `None` if the caller
+    /// should wait (having registered `waker` to be woken once it is worth trying again).
+    fn poll_slot(&mut self, waker: &Waker) -> Option<Result<(), AcquireError>> {
+        let semaphore = self.semaphore;
+        if semaphore.is_closed() {
+            self.leave_pool(true);
+            return Some(Err(AcquireError::Closed));
+        }
+
+        // Tasks follow the same rules as threads: they compete for released slots with the
+        // threads parked on the event, unless others are waiting in line (or they have been
+        // waiting for longer than the starvation limit), in which case they join the line too.
+        let queued = semaphore.is_queued();
+        if !queued && semaphore.try_take(1) {
+            self.leave_pool(true);
+            return Some(Ok(()));
+        }
+        let waiting_since = *self
+            .waiting_since
+            .get_or_insert_with(|| semaphore.options.clock.now());
+        let waited = semaphore.elapsed_since(waiting_since);
+        let starving = match semaphore.options.starvation_limit {
+            Some(limit) if waited >= limit => true,
+            Some(limit) => {
+                // Check back in time to be boosted if we're still waiting by then.
+                wake_after(waker.clone(), limit - waited);
+                false
+            }
+            None => false,
+        };
+        if queued || starving {
+            self.leave_pool(false);
+            self.state = AcquireState::Queued(semaphore.reserve(1, true));
+            return None;
+        }
+
+        let wakers = &semaphore.waiters.get_or_init().wakers;
+        let id = match self.state {
+            AcquireState::Pooled(id) => id,
+            _ => wakers.next_id(),
+        };
+        self.state = AcquireState::Pooled(id);
+        wakers.register(id, waker);
+        // A slot may have been released before our waker was registered.
+        if semaphore.try_take(1) {
+            self.leave_pool(true);
+            return Some(Ok(()));
+        }
+        None
+    }
+}
+
+#[cfg(feature = "async")]
+impl<'a> Future for Acquire<'a> {
+    type Output = Result<SemaphoreGuard<'a>, AcquireError>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.get_mut();
+        if !matches!(this.state, AcquireState::Queued(_)) {
+            match this.poll_slot(cx.waker()) {
+                Some(result) => {
+                    return Poll::Ready(result.map(|()| SemaphoreGuard::new(this.semaphore, 1)))
+                }
+                None if !matches!(this.state, AcquireState::Queued(_)) => return Poll::Pending,
+                None => {}
+            }
+        }
+
+        let ticket = match &mut this.state {
+            AcquireState::Queued(ticket) => ticket,
+            _ => unreachable!(),
+        };
+        match ticket.poll_redeem(cx.waker()) {
+            Poll::Pending => Poll::Pending,
+            Poll::Ready(result) => {
+                let ticket = match std::mem::replace(&mut this.state, AcquireState::Idle) {
+                    AcquireState::Queued(ticket) => ticket,
+                    _ => unreachable!(),
+                };
+                Poll::Ready(result.map(|()| SemaphoreGuard::new(ticket.semaphore, ticket.weight)))
+            }
+        }
+    }
+}
+
+#[cfg(feature = "async")]
+impl Drop for Acquire<'_> {
+    fn drop(&mut self) {
+        // A dropped ticket returns its place in line (or its slot) by itself.
+        self.leave_pool(false);
+    }
+}
+
+/// The `'static` [`Future`] returned by [`Semaphore::acquire_owned()`], completing with an
+/// [`OwnedSemaphoreGuard`] once a slot has been obtained. Only available with the `async` feature
+/// enabled.
+#[cfg(feature = "async")]
+#[must_use = "futures do nothing unless polled"]
+pub struct AcquireOwned {
+    /// Borrows from `semaphore`, so must be dropped first.
+    inner: Acquire<'static>,
+    semaphore: std::sync::Arc<Semaphore>,
+}
+
+#[cfg(feature = "async")]
+impl Future for AcquireOwned {
+    type Output = Result<OwnedSemaphoreGuard, AcquireError>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.get_mut();
+        Pin::new(&mut this.inner).poll(cx).map(|result| {
+            result.map(|guard| {
+                OwnedSemaphoreGuard::new(std::sync::Arc::clone(&this.semaphore), guard)
+            })
+        })
+    }
+}
+
+#[cfg(feature = "async")]
+impl Debug for AcquireOwned {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AcquireOwned").finish_non_exhaustive()
+    }
+}
+
+#[cfg(feature = "async")]
+impl Debug for Acquire<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Acquire")
+            .field("queued", &matches!(self.state, AcquireState::Queued(_)))
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::Count;
+    use crate::{AcquireError, MockClock, Semaphore, SemaphoreOptions, TryAcquireError};
+    use rsevents::Awaitable;
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::thread;
+    use std::time::Duration;
+
+    #[test]
+    fn uncontested_semaphore() {
+        let sem = Semaphore::new(1, 1);
+        let _guard = sem.wait0().unwrap();
+        sem.try_wait0().unwrap_err();
+    }
+
+    #[test]
+    fn zero_semaphore() {
+        let sem = Semaphore::new(0, 0);
+        sem.try_wait0().unwrap_err();
+    }
+
+    fn release_x_of_y_sequentially(x: Count, y: Count) -> Semaphore {
+        let sem: Semaphore = Semaphore::new(0, y);
+
+        // Use thread::scope because it automatically joins all threads,
+        // which is useful if they panic.
+        thread::scope(|scope| {
+            for _ in 0..x {
+                scope.spawn(|| {
+                    sem.wait0().unwrap_err();
+                    let lock = sem.wait_for(Duration::from_secs(1)).unwrap();
+                    // Correct way to "forget" a semaphore slot; never pass a
+                    // SemaphoreGuard to std::mem::forget()!
+                    lock.forget();
+                });
+            }
+
+            scope.spawn(|| {
+                std::thread::sleep(Duration::from_millis(100));
+                for _ in 0..x {
+                    sem.release(1);
+                }
+            });
+        });
+
+        sem
+    }
+
+    fn release_x_of_y(x: Count, y: Count) -> Semaphore {
+        let sem: Semaphore = Semaphore::new(0, y);
+
+        // Use thread::scope because it automatically joins all threads,
+        // which is useful if they panic.
+        thread::scope(|scope| {
+            for _ in 0..x {
+                scope.spawn(|| {
+                    sem.wait0().unwrap_err();
+                    let lock = sem.wait_for(Duration::from_secs(1)).unwrap();
+                    std::mem::forget(lock);
+                });
+            }
+
+            scope.spawn(|| {
+                std::thread::sleep(Duration::from_millis(100));
+                sem.release(x);
+            });
+        });
+
+        sem
+    }
+
+    #[test]
+    fn release_1_of_1() {
+        release_x_of_y(1, 1);
+    }
+
+    #[test]
+    fn release_1_of_2() {
+        release_x_of_y(1, 2);
+    }
+
+    #[test]
+    fn release_2_of_3() {
+        release_x_of_y(1, 2);
+    }
+
+    #[test]
+    fn release_2_of_2() {
+        let sem = release_x_of_y_sequentially(2, 2);
+        sem.wait0().unwrap_err();
+    }
+
+    #[test]
+    fn modify_decrement() {
+        let mut sem = Semaphore::new(2, 2);
+        sem.modify(-1);
+        assert_eq!(sem.count(), 1);
+        assert!(!sem.try_modify(-2));
+        assert!(sem.try_modify(1));
+        assert_eq!(sem.count(), 2);
+    }
+
+    #[test]
+    fn modify_decrement_limits_acquisition() {
+        let mut sem = Semaphore::new(3, 3);
+        sem.modify(-2);
+        assert_eq!(sem.count(), 1);
+        let guard = sem.try_acquire().unwrap();
+        assert!(sem.try_acquire().is_err());
+        drop(guard);
+
+        // Releasing can only restore the reduced limit, not the original one
+        assert!(sem.try_acquire().is_ok());
+        assert!(!sem.try_modify(-2));
+        assert_eq!(sem.count(), 1);
+    }
 
     #[test]
-    fn uncontested_semaphore() {
-        let sem = Semaphore::new(1, 1);
-        let _1 = sem.wait0().unwrap();
-        sem.try_wait0().unwrap_err();
+    #[should_panic]
+    fn modify_below_zero() {
+        let mut sem = Semaphore::new(1, 3);
+        sem.modify(-2);
     }
 
     #[test]
-    fn zero_semaphore() {
-        let sem = Semaphore::new(0, 0);
-        sem.try_wait0().unwrap_err();
+    fn transfer_permits() {
+        let from = Semaphore::new(2, 2);
+        let to = Semaphore::new(1, 2);
+
+        // Exceeds the destination's maximum
+        assert!(!from.try_transfer(&to, 2));
+        assert_eq!(from.count(), 2);
+        assert_eq!(to.count(), 1);
+
+        assert!(from.try_transfer(&to, 1));
+        assert_eq!(from.count(), 1);
+        assert_eq!(to.count(), 2);
+
+        // Borrowed slots can't be transferred
+        let guard = from.wait();
+        assert!(!to.try_transfer(&from, 3));
+        assert!(!from.try_transfer(&to, 1));
+
+        // The transferred slot permanently lowered the source's limit
+        drop(guard);
+        assert!(from.try_release(1));
+        assert!(!from.try_release(1));
     }
 
-    fn release_x_of_y_sequentially(x: Count, y: Count) -> Semaphore {
-        let sem: Semaphore = Semaphore::new(0, y);
+    #[test]
+    fn failed_transfers_dont_block_releases() {
+        let from = Semaphore::new(0, 1);
+        let to = Semaphore::new(0, 1);
+        let done = AtomicBool::new(false);
 
-        // Use thread::scope because it automatically joins all threads,
-        // which is useful if they panic.
         thread::scope(|scope| {
-            for _ in 0..x {
-                scope.spawn(|| {
-                    sem.wait0().unwrap_err();
-                    let lock = sem.wait_for(Duration::from_secs(1)).unwrap();
-                    // Correct way to "forget" a semaphore slot; never pass a
-                    // SemaphoreGuard to std::mem::forget()!
-                    lock.forget();
-                });
+            scope.spawn(|| {
+                while !done.load(Ordering::Relaxed) {
+                    assert!(!from.try_transfer(&to, 1));
+                }
+            });
+            // The transfer never has a slot to move, so it must never claim room in `to`.
+            for _ in 0..10_000 {
+                assert!(to.try_release(1));
+                to.try_acquire().unwrap().forget();
             }
+            done.store(true, Ordering::Relaxed);
+        });
+    }
+
+    #[test]
+    fn opposing_transfers() {
+        let a = Semaphore::new(4, 8);
+        let b = Semaphore::new(4, 8);
 
+        thread::scope(|scope| {
             scope.spawn(|| {
-                std::thread::sleep(Duration::from_millis(100));
-                for _ in 0..x {
-                    sem.release(1);
+                for _ in 0..10_000 {
+                    a.try_transfer(&b, 1);
                 }
             });
+            for _ in 0..10_000 {
+                b.try_transfer(&a, 1);
+            }
         });
+        assert_eq!(a.count() + b.count(), 8);
+    }
 
-        sem
+    #[test]
+    fn ticket_priority() {
+        let sem = Semaphore::new(0, 2);
+        let first = sem.reserve();
+        let second = sem.reserve();
+        assert!(!first.is_ready());
+
+        sem.release(1);
+        // The released slot went to the oldest ticket rather than to regular waiters.
+        sem.wait0().unwrap_err();
+        let second = second.try_redeem().unwrap_err();
+        let guard = first.try_redeem().unwrap();
+
+        // Dropping the guard hands its slot to the remaining ticket.
+        drop(guard);
+        let _guard = second.wait_for(Duration::from_secs(1)).unwrap();
+        sem.wait0().unwrap_err();
     }
 
-    fn release_x_of_y(x: Count, y: Count) -> Semaphore {
-        let sem: Semaphore = Semaphore::new(0, y);
+    #[test]
+    fn ticket_immediately_ready() {
+        let sem = Semaphore::new(1, 1);
+        let ticket = sem.reserve();
+        assert!(ticket.is_ready());
+        assert_eq!(sem.count(), 0);
+
+        // Dropping a granted ticket returns its slot.
+        drop(ticket);
+        assert_eq!(sem.count(), 1);
+    }
+
+    #[test]
+    fn abandoned_ticket() {
+        let sem = Semaphore::new(0, 1);
+        let ticket = sem.reserve();
+        drop(ticket);
+        sem.release(1);
+        let _guard = sem.wait0().unwrap();
+    }
+
+    #[test]
+    fn threaded_tickets() {
+        let sem = Semaphore::new(0, 1);
 
-        // Use thread::scope because it automatically joins all threads,
-        // which is useful if they panic.
         thread::scope(|scope| {
-            for _ in 0..x {
+            for _ in 0..8 {
                 scope.spawn(|| {
-                    sem.wait0().unwrap_err();
-                    let lock = sem.wait_for(Duration::from_secs(1)).unwrap();
-                    std::mem::forget(lock);
+                    let ticket = sem.reserve();
+                    let _guard = ticket.wait();
+                    thread::sleep(Duration::from_millis(1));
                 });
             }
 
             scope.spawn(|| {
-                std::thread::sleep(Duration::from_millis(100));
-                sem.release(x);
+                thread::sleep(Duration::from_millis(50));
+                sem.release(1);
             });
         });
 
-        sem
+        assert_eq!(sem.count(), 1);
     }
 
     #[test]
-    fn release_1_of_1() {
-        release_x_of_y(1, 1);
+    fn starving_waiter_boosted() {
+        static OPTIONS: SemaphoreOptions =
+            SemaphoreOptions::new().starvation_limit(Duration::from_millis(20));
+        let sem = Semaphore::new(0, 1).with_options(&OPTIONS);
+
+        thread::scope(|scope| {
+            let starving = scope.spawn(|| {
+                sem.wait_for(Duration::from_secs(5)).unwrap().forget();
+            });
+
+            // Give the waiter time to exceed the starvation limit.
+            thread::sleep(Duration::from_millis(100));
+            sem.release(1);
+
+            // The released slot must have been handed to the starving thread.
+            sem.wait0().unwrap_err();
+            starving.join().unwrap();
+        });
     }
 
     #[test]
-    fn release_1_of_2() {
-        release_x_of_y(1, 2);
+    fn starvation_limit_timeout() {
+        static OPTIONS: SemaphoreOptions =
+            SemaphoreOptions::new().starvation_limit(Duration::from_millis(10));
+        let sem = Semaphore::new(0, 1).with_options(&OPTIONS);
+        sem.wait_for(Duration::from_millis(50)).unwrap_err();
+        // The abandoned ticket must not swallow future slots.
+        sem.release(1);
+        let _guard = sem.wait0().unwrap();
     }
 
     #[test]
-    fn release_2_of_3() {
-        release_x_of_y(1, 2);
+    fn weighted_wait() {
+        let sem = Semaphore::new(3, 4);
+        let guard = sem.wait_weighted(3);
+        assert_eq!(guard.weight(), 3);
+        sem.wait0().unwrap_err();
+        drop(guard);
+        assert_eq!(sem.count(), 3);
+
+        // Not enough slots available
+        sem.wait_weighted_for(4, Duration::from_millis(10))
+            .unwrap_err();
+        assert_eq!(sem.count(), 3);
     }
 
     #[test]
-    fn release_2_of_2() {
-        let sem = release_x_of_y_sequentially(2, 2);
-        sem.wait0().unwrap_err();
+    fn heavy_waiter_not_overtaken() {
+        let sem = Semaphore::new(1, 4);
+
+        thread::scope(|scope| {
+            let heavy = scope.spawn(|| {
+                sem.wait_weighted_for(3, Duration::from_secs(5))
+                    .unwrap()
+                    .forget();
+            });
+
+            // Wait for the heavy waiter to queue up
+            while !sem.raw.is_queued() {
+                thread::yield_now();
+            }
+
+            // A lighter waiter can't jump ahead of the queued heavy waiter
+            sem.wait0().unwrap_err();
+            sem.release(2);
+            heavy.join().unwrap();
+        });
+
+        assert_eq!(sem.count(), 0);
+    }
+
+    #[test]
+    fn weighted_forget() {
+        let sem = Semaphore::new(4, 4);
+        sem.wait_weighted(3).forget();
+        assert!(sem.try_release(3));
+        assert!(!sem.try_release(1));
+    }
+
+    #[test]
+    fn cancellable_wait() {
+        use rsevents::{EventState, ManualResetEvent};
+
+        let sem = Semaphore::new(0, 1);
+        let token = ManualResetEvent::new(EventState::Unset);
+
+        thread::scope(|scope| {
+            let waiter = scope.spawn(|| sem.wait_cancellable(&token).map(|g| g.forget()));
+            thread::sleep(Duration::from_millis(20));
+            token.set();
+            assert_eq!(waiter.join().unwrap(), Err(AcquireError::Cancelled));
+        });
+
+        // The cancelled waiter must have given up its place in line
+        sem.release(1);
+        let _guard = sem.wait0().unwrap();
+    }
+
+    #[test]
+    fn cancellable_wait_succeeds() {
+        use rsevents::{EventState, ManualResetEvent};
+
+        let sem = Semaphore::new(0, 1);
+        let token = ManualResetEvent::new(EventState::Unset);
+
+        thread::scope(|scope| {
+            let waiter = scope.spawn(|| sem.wait_cancellable(&token).map(|g| g.forget()));
+            thread::sleep(Duration::from_millis(20));
+            sem.release(1);
+            waiter.join().unwrap().unwrap();
+        });
+    }
+
+    #[test]
+    fn cancellable_wait_reports_closure() {
+        use crate::CancellationTokenSource;
+
+        let sem = Semaphore::new(0, 1);
+        let source = CancellationTokenSource::new();
+        let token = source.token();
+
+        thread::scope(|scope| {
+            let waiter = scope.spawn(|| sem.wait_cancellable(&token).map(|g| g.forget()));
+            thread::sleep(Duration::from_millis(20));
+            sem.close();
+            assert_eq!(waiter.join().unwrap(), Err(AcquireError::Closed));
+        });
+        assert_eq!(
+            sem.wait_cancellable(&token).err(),
+            Some(AcquireError::Closed)
+        );
+
+        // Cancellation still takes precedence.
+        source.cancel();
+        assert_eq!(
+            sem.wait_cancellable(&token).err(),
+            Some(AcquireError::Cancelled)
+        );
+    }
+
+    #[test]
+    fn cancellable_wait_woken_by_token() {
+        use crate::CancellationTokenSource;
+
+        let sem = Semaphore::new(0, 1);
+        let source = CancellationTokenSource::new();
+        let token = source.token();
+
+        thread::scope(|scope| {
+            let waiter =
+                scope.spawn(|| sem.wait_cancellable_subscribed(&token).map(|g| g.forget()));
+            thread::sleep(Duration::from_millis(20));
+            source.cancel();
+            assert_eq!(waiter.join().unwrap(), Err(AcquireError::Cancelled));
+        });
+
+        // The cancelled waiter must have given up its place in line
+        sem.release(1);
+        let _guard = sem.wait0().unwrap();
+    }
+
+    #[test]
+    fn close_wakes_waiters() {
+        let sem = Semaphore::new(0, 4);
+        let ticket = sem.reserve();
+
+        thread::scope(|scope| {
+            let waiters: Vec<_> = (0..3)
+                .map(|_| scope.spawn(|| sem.acquire().map(|g| g.forget())))
+                .collect();
+            let weighted = scope.spawn(|| sem.wait_weighted(2).forget());
+            let single = scope.spawn(|| sem.wait().forget());
+            thread::sleep(Duration::from_millis(20));
+            sem.close();
+
+            for waiter in waiters {
+                assert_eq!(waiter.join().unwrap(), Err(AcquireError::Closed));
+            }
+
+            // The waits that can't report the closure carry on waiting, as does the ticket.
+            thread::sleep(Duration::from_millis(20));
+            assert!(!weighted.is_finished());
+            assert!(!single.is_finished());
+            assert!(!ticket.is_ready());
+
+            sem.release(4);
+            weighted.join().unwrap();
+            single.join().unwrap();
+        });
+
+        ticket.wait().forget();
+        assert!(sem.is_closed());
+    }
+
+    #[test]
+    fn acquire_error_kinds() {
+        let sem = Semaphore::new(1, 1);
+        let guard = sem.try_acquire().unwrap();
+        assert_eq!(sem.try_acquire().unwrap_err(), TryAcquireError::NoPermits);
+        assert_eq!(
+            sem.acquire_for(Duration::from_millis(10)).unwrap_err(),
+            AcquireError::TimedOut
+        );
+
+        sem.close();
+        assert_eq!(sem.try_acquire().unwrap_err(), TryAcquireError::Closed);
+        assert_eq!(sem.acquire().unwrap_err(), AcquireError::Closed);
+        // Guards obtained before closing still return their slots.
+        drop(guard);
+        assert_eq!(sem.count(), 1);
+        // Waits that can't report the closure aren't affected by it.
+        assert!(sem.try_wait0().is_ok());
+    }
+
+    #[cfg(feature = "async")]
+    struct CountingWaker(std::sync::atomic::AtomicUsize);
+
+    #[cfg(feature = "async")]
+    impl std::task::Wake for CountingWaker {
+        fn wake(self: std::sync::Arc<Self>) {
+            self.0.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+        }
+    }
+
+    #[cfg(feature = "async")]
+    #[test]
+    fn async_acquire() {
+        use std::future::Future;
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        use std::sync::Arc;
+        use std::task::{Context, Poll};
+
+        let wakes = Arc::new(CountingWaker(AtomicUsize::new(0)));
+        let waker = wakes.clone().into();
+        let mut cx = Context::from_waker(&waker);
+        let woken = || wakes.0.load(Ordering::SeqCst);
+
+        let sem = Semaphore::new(0, 1);
+        let mut first = std::pin::pin!(sem.acquire_async());
+        assert!(first.as_mut().poll(&mut cx).is_pending());
+        sem.release(1);
+        assert_eq!(woken(), 1);
+        let held = first.as_mut().poll(&mut cx);
+        assert!(held.is_ready());
+        assert_eq!(sem.count(), 0);
+
+        let mut second = std::pin::pin!(sem.acquire_async());
+        assert!(second.as_mut().poll(&mut cx).is_pending());
+        sem.close();
+        assert_eq!(woken(), 2);
+        match second.as_mut().poll(&mut cx) {
+            Poll::Ready(result) => assert_eq!(result.unwrap_err(), AcquireError::Closed),
+            Poll::Pending => panic!("close didn't wake the future"),
+        };
+        drop(held);
+    }
+
+    #[cfg(feature = "async")]
+    #[test]
+    fn mixed_waiters_share_line() {
+        use std::future::Future;
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        use std::sync::Arc;
+        use std::task::{Context, Poll};
+
+        let wakes = Arc::new(CountingWaker(AtomicUsize::new(0)));
+        let waker = wakes.clone().into();
+        let mut cx = Context::from_waker(&waker);
+
+        let sem = Semaphore::new(0, 1);
+        let ticket = sem.reserve();
+        // With a ticket in line, both the task and the thread line up behind it, in order.
+        let mut task = std::pin::pin!(sem.acquire_async());
+        assert!(task.as_mut().poll(&mut cx).is_pending());
+
+        thread::scope(|scope| {
+            let thread = scope.spawn(|| drop(sem.wait()));
+            thread::sleep(Duration::from_millis(20));
+
+            sem.release(1);
+            let guard = ticket.try_redeem().unwrap();
+            assert!(task.as_mut().poll(&mut cx).is_pending());
+            drop(guard);
+            assert_eq!(wakes.0.load(Ordering::SeqCst), 1);
+            let guard = match task.as_mut().poll(&mut cx) {
+                Poll::Ready(guard) => guard.unwrap(),
+                Poll::Pending => panic!("slot not handed to the task"),
+            };
+
+            thread::sleep(Duration::from_millis(20));
+            assert!(!thread.is_finished());
+            drop(guard);
+            thread.join().unwrap();
+        });
+        assert_eq!(sem.count(), 1);
+    }
+
+    #[cfg(feature = "async")]
+    #[test]
+    fn mixed_waiters_contend() {
+        use std::future::Future;
+        use std::sync::Arc;
+        use std::task::{Context, Poll, Wake};
+
+        struct Unparker(thread::Thread);
+        impl Wake for Unparker {
+            fn wake(self: Arc<Self>) {
+                self.0.unpark();
+            }
+        }
+
+        let sem = Semaphore::new(1, 1);
+        thread::scope(|scope| {
+            for _ in 0..2 {
+                scope.spawn(|| (0..200).for_each(|_| drop(sem.wait())));
+            }
+
+            // Tasks and parked threads are both woken as slots are released, so neither side can
+            // miss a wakeup and hang.
+            let waker = Arc::new(Unparker(thread::current())).into();
+            let mut cx = Context::from_waker(&waker);
+            for _ in 0..200 {
+                let mut acquire = std::pin::pin!(sem.acquire_async());
+                loop {
+                    match acquire.as_mut().poll(&mut cx) {
+                        Poll::Ready(guard) => break drop(guard.unwrap()),
+                        Poll::Pending => thread::park(),
+                    }
+                }
+            }
+        });
+        assert_eq!(sem.count(), 1);
+    }
+
+    #[test]
+    fn owned_guard_moves_to_thread() {
+        let sem = std::sync::Arc::new(Semaphore::new(1, 1));
+        let guard = sem.wait_owned();
+        assert_eq!(guard.weight(), 1);
+        assert_eq!(sem.count(), 0);
+        thread::spawn(move || drop(guard)).join().unwrap();
+        assert_eq!(sem.count(), 1);
+
+        sem.wait_owned().forget();
+        assert_eq!(std::sync::Arc::strong_count(&sem), 1);
+        assert!(sem.try_release(1));
+    }
+
+    #[cfg(feature = "async")]
+    #[test]
+    fn acquire_owned_is_static() {
+        use std::future::Future;
+        use std::sync::atomic::AtomicUsize;
+        use std::sync::Arc;
+        use std::task::{Context, Poll};
+
+        let sem = Arc::new(Semaphore::new(0, 1));
+        let mut permit = Box::pin(sem.acquire_owned());
+        let wakes = Arc::new(CountingWaker(AtomicUsize::new(0)));
+        let waker = wakes.clone().into();
+        assert!(permit
+            .as_mut()
+            .poll(&mut Context::from_waker(&waker))
+            .is_pending());
+
+        // Both the pending future and the guard it completes with outlive the borrow of `sem`.
+        let task = thread::spawn(move || {
+            let waker = Arc::new(CountingWaker(AtomicUsize::new(0))).into();
+            loop {
+                if let Poll::Ready(guard) = permit.as_mut().poll(&mut Context::from_waker(&waker)) {
+                    break guard.unwrap();
+                }
+                thread::sleep(Duration::from_millis(1));
+            }
+        });
+        sem.release(1);
+        let guard = task.join().unwrap();
+        assert_eq!(sem.count(), 0);
+        drop(guard);
+        assert_eq!(sem.count(), 1);
+    }
+
+    #[test]
+    fn hold_limit_exceeded() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        static EXCEEDED: AtomicUsize = AtomicUsize::new(0);
+
+        static OPTIONS: SemaphoreOptions =
+            SemaphoreOptions::new().hold_limit(Duration::from_millis(10), |held| {
+                assert!(held > Duration::from_millis(10));
+                EXCEEDED.fetch_add(1, Ordering::SeqCst);
+            });
+        let sem = Semaphore::new(2, 2).with_options(&OPTIONS);
+
+        drop(sem.wait());
+        assert_eq!(EXCEEDED.load(Ordering::SeqCst), 0);
+
+        let guard = sem.wait();
+        thread::sleep(Duration::from_millis(20));
+        drop(guard);
+        assert_eq!(EXCEEDED.load(Ordering::SeqCst), 1);
+        assert_eq!(sem.count(), 2);
+    }
+
+    #[test]
+    fn hold_limit_uses_clock() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        static CLOCK: MockClock = MockClock::new();
+        static EXCEEDED: AtomicUsize = AtomicUsize::new(0);
+
+        static OPTIONS: SemaphoreOptions =
+            SemaphoreOptions::new()
+                .clock(&CLOCK)
+                .hold_limit(Duration::from_secs(60), |held| {
+                    assert_eq!(held, Duration::from_secs(61));
+                    EXCEEDED.fetch_add(1, Ordering::SeqCst);
+                });
+        let sem = Semaphore::new(1, 1).with_options(&OPTIONS);
+
+        let guard = sem.wait();
+        CLOCK.advance(Duration::from_secs(61));
+        drop(guard);
+        assert_eq!(EXCEEDED.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn compact_layout() {
+        use crate::{ConstSemaphore, SemaphoreGuard};
+        use std::mem::size_of;
+
+        // Only the counters, the event, the closed flag, and pointers to the options and to the
+        // state allocated once waiters queue up or tasks wait on the semaphore.
+        assert!(size_of::<ConstSemaphore<4>>() <= 24);
+        assert!(size_of::<Semaphore>() <= 32);
+        // The time a guard was obtained at is only allocated if hold times are tracked.
+        assert!(size_of::<SemaphoreGuard<'_>>() <= 24);
+    }
+
+    #[test]
+    fn mock_clock_timeout() {
+        static CLOCK: MockClock = MockClock::new();
+        static OPTIONS: SemaphoreOptions = SemaphoreOptions::new().clock(&CLOCK);
+        let sem = Semaphore::new(0, 2).with_options(&OPTIONS);
+
+        thread::scope(|scope| {
+            let single = scope.spawn(|| sem.wait_for(Duration::from_secs(30)).is_ok());
+            let weighted =
+                scope.spawn(|| sem.wait_weighted_for(2, Duration::from_secs(30)).is_ok());
+            thread::sleep(Duration::from_millis(20));
+            assert!(!single.is_finished());
+            assert!(!weighted.is_finished());
+
+            CLOCK.advance(Duration::from_secs(30));
+            assert!(!single.join().unwrap());
+            assert!(!weighted.join().unwrap());
+        });
+
+        // Waits still succeed as soon as the semaphore is available.
+        sem.release(1);
+        sem.wait_for(Duration::from_secs(30)).unwrap().forget();
+    }
+}
+
+#[cfg(all(test, loom))]
+mod loom_test {
+    use crate::Semaphore;
+    use loom::sync::Arc;
+    use loom::thread;
+    use rsevents::Awaitable;
+
+    #[test]
+    fn loom_concurrent_release() {
+        loom::model(|| {
+            let sem = Arc::new(Semaphore::new(0, 2));
+            let handles: Vec<_> = (0..2)
+                .map(|_| {
+                    let sem = Arc::clone(&sem);
+                    thread::spawn(move || assert!(sem.try_release(1)))
+                })
+                .collect();
+            for handle in handles {
+                handle.join().unwrap();
+            }
+
+            assert_eq!(sem.count(), 2);
+            assert!(!sem.try_release(1));
+        });
+    }
+
+    #[test]
+    fn loom_no_oversubscription() {
+        loom::model(|| {
+            let sem = Arc::new(Semaphore::new(1, 1));
+            let handles: Vec<_> = (0..2)
+                .map(|_| {
+                    let sem = Arc::clone(&sem);
+                    thread::spawn(move || sem.try_wait0().map(|guard| guard.forget()).is_ok())
+                })
+                .collect();
+            let acquired = handles
+                .into_iter()
+                .map(|handle| handle.join().unwrap())
+                .filter(|&ok| ok)
+                .count();
+
+            assert_eq!(acquired, 1);
+            assert_eq!(sem.count(), 0);
+        });
+    }
+
+    #[test]
+    fn loom_transfer_races_wait() {
+        loom::model(|| {
+            let a = Arc::new(Semaphore::new(1, 1));
+            let b = Arc::new(Semaphore::new(0, 1));
+
+            let transfer = {
+                let (a, b) = (Arc::clone(&a), Arc::clone(&b));
+                thread::spawn(move || a.try_transfer(&b, 1))
+            };
+            let took = a.try_wait0().map(|guard| guard.forget()).is_ok();
+            let transferred = transfer.join().unwrap();
+
+            // The single slot is either transferred or taken, never both or neither.
+            assert_ne!(transferred, took);
+            assert_eq!(b.count(), transferred as u16);
+        });
+    }
+}
+
+#[cfg(all(test, shuttle))]
+mod shuttle_test {
+    use crate::Semaphore;
+    use shuttle::sync::Arc;
+    use shuttle::thread;
+    use std::time::Duration;
+
+    const ITERATIONS: usize = 1000;
+
+    #[test]
+    fn shuttle_weighted_acquisitions() {
+        shuttle::check_random(
+            || {
+                let sem = Arc::new(Semaphore::new(3, 3));
+                let handles: Vec<_> = (0..3)
+                    .map(|i| {
+                        let sem = Arc::clone(&sem);
+                        thread::spawn(move || {
+                            let weight = i % 2 + 1;
+                            if let Ok(guard) = sem.wait_weighted_for(weight, Duration::ZERO) {
+                                // Nobody else may hold slots beyond the maximum.
+                                assert!(sem.count() <= 3 - weight);
+                                drop(guard);
+                            }
+                        })
+                    })
+                    .collect();
+                for handle in handles {
+                    handle.join().unwrap();
+                }
+
+                assert_eq!(sem.count(), 3);
+            },
+            ITERATIONS,
+        );
+    }
+
+    #[test]
+    fn shuttle_close_races_release() {
+        shuttle::check_random(
+            || {
+                let sem = Arc::new(Semaphore::new(0, 1));
+                // A ticket held by a wait that can report the closure.
+                let ticket = sem.raw.reserve(1, true);
+
+                let releaser = {
+                    let sem = Arc::clone(&sem);
+                    thread::spawn(move || sem.release(1))
+                };
+                let closer = {
+                    let sem = Arc::clone(&sem);
+                    thread::spawn(move || sem.close())
+                };
+                releaser.join().unwrap();
+                closer.join().unwrap();
+
+                // The ticket may or may not have been granted the slot before the semaphore was
+                // closed, but the slot can't be lost either way.
+                match ticket.try_redeem() {
+                    Ok(guard) => drop(guard),
+                    Err(ticket) => drop(ticket),
+                }
+                assert_eq!(sem.count(), 1);
+                assert!(sem.try_acquire().is_err());
+            },
+            ITERATIONS,
+        );
+    }
+
+    #[test]
+    fn shuttle_cancelled_ticket_races_release() {
+        shuttle::check_random(
+            || {
+                let sem = Arc::new(Semaphore::new(0, 2));
+                let first = sem.reserve();
+                let second = sem.reserve();
+
+                let releaser = {
+                    let sem = Arc::clone(&sem);
+                    thread::spawn(move || sem.release(1))
+                };
+                // Abandoning the first ticket passes its place (or its slot) on to the second.
+                drop(first);
+                releaser.join().unwrap();
+
+                let guard = second.try_redeem().unwrap();
+                assert_eq!(sem.count(), 0);
+                drop(guard);
+                assert_eq!(sem.count(), 1);
+            },
+            ITERATIONS,
+        );
     }
 }
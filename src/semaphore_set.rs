@@ -0,0 +1,209 @@
+use crate::{Semaphore, SemaphoreGuard};
+use rsevents::TimeoutError;
+use std::fmt::Debug;
+use std::time::{Duration, Instant};
+
+/// A fixed collection of [`Semaphore`] instances that are always acquired together, without
+/// risking deadlock against other threads acquiring an overlapping set in a different order.
+///
+/// Acquisition never blocks while holding a slot from any of the semaphores in the set: the
+/// semaphores are tried in a global (address-based) order, and if any of them is unavailable, all
+/// slots obtained so far are released before blocking on the unavailable semaphore alone. Once it
+/// becomes available, the remaining semaphores are tried again.
+///
+/// ## Example:
+///
+/// ```rust
+/// use rsevents_extra::{Semaphore, SemaphoreSet};
+///
+/// static DISK: Semaphore = Semaphore::new(2, 2);
+/// static NETWORK: Semaphore = Semaphore::new(4, 4);
+///
+/// fn upload_from_disk() {
+///     // Equivalent to `SemaphoreSet::new(&[&NETWORK, &DISK])`; the order in
+///     // which the semaphores are listed makes no difference.
+///     let set = SemaphoreSet::new(&[&DISK, &NETWORK]);
+///     let _guard = set.acquire();
+///     // <read from the disk and upload to the network here>
+/// }
+///
+/// upload_from_disk();
+/// ```
+pub struct SemaphoreSet<'a> {
+    /// The semaphores in the set, sorted by address.
+    semaphores: Vec<&'a Semaphore>,
+}
+
+/// Acquires a slot from each of the `semaphores` without risk of deadlock, blocking until all are
+/// available. A shorthand for `SemaphoreSet::new(semaphores).acquire()`; see [`SemaphoreSet`].
+pub fn acquire_all<'a>(semaphores: &[&'a Semaphore]) -> SemaphoreSetGuard<'a> {
+    SemaphoreSet::new(semaphores).acquire()
+}
+
+impl<'a> SemaphoreSet<'a> {
+    /// Creates a new set of semaphores to acquire together. A semaphore listed more than once will
+    /// have that many slots acquired from it.
+    pub fn new(semaphores: &[&'a Semaphore]) -> Self {
+        let mut semaphores = semaphores.to_vec();
+        semaphores.sort_by_key(|sem| *sem as *const Semaphore as usize);
+        SemaphoreSet { semaphores }
+    }
+
+    /// Blocks until a slot from every semaphore in the set has been obtained.
+    #[must_use = "The semaphore slots are immediately released if the guard is dropped"]
+    pub fn acquire(&self) -> SemaphoreSetGuard<'a> {
+        self.acquire_internal(None).unwrap()
+    }
+
+    /// Attempts to obtain a slot from every semaphore in the set without blocking, returning a
+    /// [`TimeoutError`] (and holding none of them) if any is unavailable.
+    #[must_use = "The semaphore slots are immediately released if the guard is dropped"]
+    pub fn try_acquire(&self) -> Result<SemaphoreSetGuard<'a>, TimeoutError> {
+        self.acquire_for(Duration::ZERO)
+    }
+
+    /// Attempts to obtain a slot from every semaphore in the set, returning a [`TimeoutError`]
+    /// (and holding none of them) if that isn't possible within the time limit.
+    #[must_use = "The semaphore slots are immediately released if the guard is dropped"]
+    pub fn acquire_for(&self, limit: Duration) -> Result<SemaphoreSetGuard<'a>, TimeoutError> {
+        self.acquire_internal(Some(Instant::now() + limit))
+    }
+
+    fn acquire_internal(
+        &self,
+        deadline: Option<Instant>,
+    ) -> Result<SemaphoreSetGuard<'a>, TimeoutError> {
+        let remaining = || match deadline {
+            Some(deadline) => deadline.saturating_duration_since(Instant::now()),
+            None => Duration::ZERO,
+        };
+
+        let mut guards = Vec::with_capacity(self.semaphores.len());
+        let mut blocked_on = None;
+
+        loop {
+            // If the last attempt failed, block on the unavailable semaphore (and nothing else)
+            // until it can be obtained.
+            let mut held = match blocked_on {
+                None => None,
+                Some(i) => {
+                    let sem: &'a Semaphore = self.semaphores[i];
+                    let guard = match deadline {
+                        None => sem.wait(),
+                        Some(_) => sem.wait_for(remaining())?,
+                    };
+                    Some((i, guard))
+                }
+            };
+
+            blocked_on = None;
+            for (i, sem) in self.semaphores.iter().enumerate() {
+                if matches!(held, Some((j, _)) if i == j) {
+                    guards.push(held.take().unwrap().1);
+                    continue;
+                }
+
+                match sem.wait_for(Duration::ZERO) {
+                    Ok(guard) => guards.push(guard),
+                    Err(_) => {
+                        blocked_on = Some(i);
+                        break;
+                    }
+                }
+            }
+
+            match blocked_on {
+                None => return Ok(SemaphoreSetGuard { guards }),
+                Some(_) => {
+                    // Roll back everything we obtained before blocking.
+                    guards.clear();
+                    drop(held);
+                    if deadline.is_some() && remaining() == Duration::ZERO {
+                        return Err(TimeoutError);
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// The composite concurrency token returned by [`SemaphoreSet::acquire()`] and
+/// [`acquire_all()`], holding one slot from each semaphore in the set. All slots are released
+/// together when the guard is dropped.
+pub struct SemaphoreSetGuard<'a> {
+    guards: Vec<SemaphoreGuard<'a>>,
+}
+
+impl SemaphoreSetGuard<'_> {
+    /// Safely "forgets" the slots held by this guard, permanently reducing the concurrency limit of
+    /// each semaphore in the set. See [`SemaphoreGuard::forget()`].
+    pub fn forget(self) {
+        for guard in self.guards {
+            guard.forget();
+        }
+    }
+}
+
+impl Debug for SemaphoreSetGuard<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SemaphoreSetGuard")
+            .field("count", &self.guards.len())
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{acquire_all, Semaphore, SemaphoreSet};
+    use std::thread;
+    use std::time::Duration;
+
+    #[test]
+    fn acquire_and_release_set() {
+        let a = Semaphore::new(1, 1);
+        let b = Semaphore::new(2, 2);
+
+        let guard = acquire_all(&[&b, &a]);
+        assert_eq!(a.count(), 0);
+        assert_eq!(b.count(), 1);
+        drop(guard);
+        assert_eq!(a.count(), 1);
+        assert_eq!(b.count(), 2);
+    }
+
+    #[test]
+    fn rollback_on_failure() {
+        let a = Semaphore::new(1, 1);
+        let b = Semaphore::new(0, 1);
+
+        let set = SemaphoreSet::new(&[&a, &b]);
+        set.try_acquire().unwrap_err();
+        set.acquire_for(Duration::from_millis(20)).unwrap_err();
+        // Nothing may be held after a failed acquisition
+        assert_eq!(a.count(), 1);
+        assert_eq!(b.count(), 0);
+    }
+
+    #[test]
+    fn opposite_orders_dont_deadlock() {
+        let a = Semaphore::new(1, 1);
+        let b = Semaphore::new(1, 1);
+
+        thread::scope(|scope| {
+            for i in 0..8 {
+                let (a, b) = (&a, &b);
+                scope.spawn(move || {
+                    for _ in 0..100 {
+                        let _guard = match i % 2 {
+                            0 => acquire_all(&[a, b]),
+                            _ => acquire_all(&[b, a]),
+                        };
+                    }
+                });
+            }
+        });
+
+        assert_eq!(a.count(), 1);
+        assert_eq!(b.count(), 1);
+    }
+}
@@ -0,0 +1,299 @@
+use crate::sync::{fence, loom_const_fn, AtomicU64, Mutex, Ordering};
+use crate::EpochEvent;
+use rsevents::TimeoutError;
+use std::cell::UnsafeCell;
+use std::mem::{align_of, size_of, MaybeUninit};
+// The data is copied through std atomics even under loom and shuttle: they are overlaid on the
+// memory of `T`, which their model-checked equivalents can't be.
+use std::sync::atomic::{AtomicU8, AtomicUsize};
+use std::time::Duration;
+
+/// A sequence lock for small `Copy` data that is read often and written rarely.
+///
+/// Readers never block writers, and don't write to shared memory: a read optimistically copies
+/// the data, then checks that no write happened in the meantime (retrying if one did).
+/// [`SeqLock::try_read()`] makes a single, wait-free attempt. Writers are serialized among
+/// themselves and bump the lock's *version* with every write.
+///
+/// Readers that would rather block than poll for changes can use
+/// [`SeqLock::wait_changed_since()`], which parks the caller on an event until the data is written
+/// after a given version.
+///
+/// The data is copied in and out with relaxed atomic loads and stores (word by word where `T`'s
+/// size and alignment allow it, byte by byte otherwise), so a read racing with a write is not a
+/// data race, and a torn copy is only ever handled as uninitialized memory and discarded. The one
+/// remaining caveat is shared with other seqlock implementations: padding bytes in `T` are copied
+/// as if they were initialized, which the Rust memory model does not formally bless. Prefer types
+/// without padding where this matters.
+///
+/// ## Example:
+///
+/// ```rust
+/// use rsevents_extra::SeqLock;
+///
+/// #[derive(Clone, Copy, Debug, PartialEq)]
+/// struct Position {
+///     x: f64,
+///     y: f64,
+/// }
+///
+/// static PLAYER: SeqLock<Position> = SeqLock::new(Position { x: 0.0, y: 0.0 });
+///
+/// std::thread::scope(|scope| {
+///     let (_, version) = PLAYER.read_versioned();
+///     let renderer = scope.spawn(move || {
+///         // Redraw once the player has moved.
+///         let (position, _) = PLAYER.wait_changed_since(version);
+///         position
+///     });
+///
+///     PLAYER.write(Position { x: 1.0, y: 2.0 });
+///     assert_eq!(renderer.join().unwrap(), Position { x: 1.0, y: 2.0 });
+/// });
+/// ```
+pub struct SeqLock<T> {
+    /// Twice the version, plus one while a write is in progress.
+    seq: AtomicU64,
+    data: UnsafeCell<MaybeUninit<T>>,
+    writer: Mutex<()>,
+    /// Advanced to the new version after each write.
+    changed: EpochEvent,
+}
+
+unsafe impl<T: Copy + Send> Send for SeqLock<T> {}
+unsafe impl<T: Copy + Send> Sync for SeqLock<T> {}
+
+impl<T: Copy> SeqLock<T> {
+    loom_const_fn! {
+        /// Creates a new `SeqLock` holding `value`, at version zero.
+        ///
+        /// This is a `const` function and can be used in a `static` context.
+        pub fn new(value: T) -> Self {
+            SeqLock {
+                seq: AtomicU64::new(0),
+                data: UnsafeCell::new(MaybeUninit::new(value)),
+                writer: Mutex::new(()),
+                changed: EpochEvent::new(),
+            }
+        }
+    }
+
+    /// Returns a copy of the data, retrying for as long as writes interfere with the read.
+    pub fn read(&self) -> T {
+        self.read_versioned().0
+    }
+
+    /// Returns a copy of the data along with its version, retrying for as long as writes
+    /// interfere with the read.
+    pub fn read_versioned(&self) -> (T, u64) {
+        loop {
+            if let Some(read) = self.try_read_versioned() {
+                return read;
+            }
+            std::hint::spin_loop();
+        }
+    }
+
+    /// Makes a single attempt at copying the data without blocking, returning `None` if a write
+    /// interfered with the read.
+    pub fn try_read(&self) -> Option<T> {
+        self.try_read_versioned().map(|(value, _)| value)
+    }
+
+    /// Returns the version of the data, i.e. the number of writes made so far.
+    pub fn version(&self) -> u64 {
+        self.seq.load(Ordering::Acquire) / 2
+    }
+
+    /// Replaces the data with `value`, releasing any threads waiting for it to change. Returns
+    /// the new version.
+    pub fn write(&self, value: T) -> u64 {
+        let _writer = self.writer.lock().unwrap();
+        let seq = self.seq.load(Ordering::Relaxed);
+        self.seq.store(seq + 1, Ordering::Relaxed);
+        fence(Ordering::Release);
+        // Readers racing with this write discard what they read, as the sequence has changed.
+        unsafe { store_atomic(self.data.get().cast(), &value) };
+        self.seq.store(seq + 2, Ordering::Release);
+
+        let version = seq / 2 + 1;
+        self.changed.advance_to(version);
+        version
+    }
+
+    /// Blocks until the data has been written after `version`, then returns a copy of the data
+    /// along with its (newer) version.
+    pub fn wait_changed_since(&self, version: u64) -> (T, u64) {
+        self.changed.wait_until(version + 1);
+        self.read_versioned()
+    }
+
+    /// Blocks for up to `limit` until the data has been written after `version`, then returns a
+    /// copy of the data along with its (newer) version, or an error in case of a timeout.
+    pub fn wait_changed_since_for(
+        &self,
+        version: u64,
+        limit: Duration,
+    ) -> Result<(T, u64), TimeoutError> {
+        self.changed.wait_until_for(version + 1, limit)?;
+        Ok(self.read_versioned())
+    }
+
+    fn try_read_versioned(&self) -> Option<(T, u64)> {
+        let before = self.seq.load(Ordering::Acquire);
+        if before % 2 == 1 {
+            return None;
+        }
+        // The copy may be torn by a concurrent write, in which case it is discarded unused.
+        let value = unsafe { load_atomic::<T>(self.data.get().cast()) };
+        fence(Ordering::Acquire);
+        let after = self.seq.load(Ordering::Relaxed);
+        match before == after {
+            // No write overlapped the copy, so it holds a complete, valid `T`.
+            true => Some((unsafe { value.assume_init() }, before / 2)),
+            false => None,
+        }
+    }
+}
+
+/// Whether `T` can be copied as a sequence of `usize` words rather than byte by byte.
+const fn word_sized<T>() -> bool {
+    align_of::<T>() >= align_of::<usize>() && size_of::<T>() & (size_of::<usize>() - 1) == 0
+}
+
+/// Copies a `T` out of `src` with relaxed atomic loads. The result may be torn by concurrent
+/// stores, so it may only be assumed initialized once the caller has ruled that out.
+///
+/// # Safety
+///
+/// `src` must be valid for reads of a `T` and only ever be written to via [`store_atomic()`].
+unsafe fn load_atomic<T>(src: *const T) -> MaybeUninit<T> {
+    let mut value = MaybeUninit::<T>::uninit();
+    if word_sized::<T>() {
+        let src = src.cast::<AtomicUsize>();
+        let dst = value.as_mut_ptr().cast::<usize>();
+        for i in 0..size_of::<T>() / size_of::<usize>() {
+            dst.add(i).write((*src.add(i)).load(Ordering::Relaxed));
+        }
+    } else {
+        let src = src.cast::<AtomicU8>();
+        let dst = value.as_mut_ptr().cast::<u8>();
+        for i in 0..size_of::<T>() {
+            dst.add(i).write((*src.add(i)).load(Ordering::Relaxed));
+        }
+    }
+    value
+}
+
+/// Copies `value` into `dst` with relaxed atomic stores.
+///
+/// # Safety
+///
+/// `dst` must be valid for writes of a `T` and only ever be read from via [`load_atomic()`].
+unsafe fn store_atomic<T>(dst: *mut T, value: &T) {
+    let src = (value as *const T).cast::<u8>();
+    if word_sized::<T>() {
+        let dst = dst.cast::<AtomicUsize>();
+        let src = src.cast::<usize>();
+        for i in 0..size_of::<T>() / size_of::<usize>() {
+            (*dst.add(i)).store(src.add(i).read(), Ordering::Relaxed);
+        }
+    } else {
+        let dst = dst.cast::<AtomicU8>();
+        for i in 0..size_of::<T>() {
+            (*dst.add(i)).store(src.add(i).read(), Ordering::Relaxed);
+        }
+    }
+}
+
+impl<T: Copy + Default> Default for SeqLock<T> {
+    fn default() -> Self {
+        Self::new(T::default())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::SeqLock;
+    use std::thread;
+    use std::time::Duration;
+
+    #[test]
+    fn reads_are_never_torn() {
+        let lock = SeqLock::new((0u64, 0u64));
+        assert_eq!(lock.try_read(), Some((0, 0)));
+
+        thread::scope(|scope| {
+            let reader = scope.spawn(|| {
+                let mut version = 0;
+                while version < 1000 {
+                    let ((a, b), v) = lock.read_versioned();
+                    assert_eq!(a, b);
+                    assert!(v >= version);
+                    version = v;
+                }
+            });
+            for i in 1..=1000 {
+                assert_eq!(lock.write((i, i)), i);
+            }
+            reader.join().unwrap();
+        });
+    }
+
+    #[test]
+    fn byte_sized_reads_are_never_torn() {
+        let lock = SeqLock::new([0u8; 3]);
+
+        thread::scope(|scope| {
+            let reader = scope.spawn(|| {
+                let mut version = 0;
+                while version < 255 {
+                    let ([a, b, c], v) = lock.read_versioned();
+                    assert!(a == b && b == c);
+                    version = v;
+                }
+            });
+            for i in 1..=255u8 {
+                lock.write([i; 3]);
+            }
+            reader.join().unwrap();
+        });
+        assert_eq!(lock.read(), [255; 3]);
+    }
+
+    #[test]
+    fn waits_for_changes() {
+        let lock = SeqLock::new(0);
+        assert!(lock
+            .wait_changed_since_for(0, Duration::from_millis(1))
+            .is_err());
+
+        thread::scope(|scope| {
+            let waiter = scope.spawn(|| lock.wait_changed_since(0));
+            lock.write(7);
+            assert_eq!(waiter.join().unwrap(), (7, 1));
+        });
+        assert_eq!(lock.version(), 1);
+    }
+
+    #[cfg(loom)]
+    #[test]
+    fn loom_reads_are_never_torn() {
+        use loom::sync::Arc;
+
+        loom::model(|| {
+            let lock = Arc::new(SeqLock::new((0u64, 0u64)));
+            let writer = {
+                let lock = Arc::clone(&lock);
+                loom::thread::spawn(move || lock.write((1, 1)))
+            };
+
+            // A read racing with the write either sees one of the two values in full or fails.
+            if let Some((a, b)) = lock.try_read() {
+                assert!(a == b && a <= 1);
+            }
+            assert_eq!(writer.join().unwrap(), 1);
+            assert_eq!(lock.try_read(), Some((1, 1)));
+        });
+    }
+}
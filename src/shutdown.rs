@@ -0,0 +1,193 @@
+use crate::subscribe::{Subscribe, Subscription};
+use crate::{CancellationToken, CancellationTokenSource, DrainGuard, DrainTracker, ShuttingDown};
+use rsevents::{Awaitable, TimeoutError};
+use std::convert::Infallible;
+use std::task::Waker;
+use std::time::Duration;
+
+/// Packages the full graceful-shutdown lifecycle: broadcasting to workers that they should stop
+/// taking new work, then waiting for all of them to confirm that they have exited.
+///
+/// Each worker [registers](ShutdownToken::register) with the token and holds on to the returned
+/// [`ShutdownWorker`] until it exits. The worker handle becomes set (as an [`Awaitable`]) once
+/// shutdown is requested, and provides a [`CancellationToken`] for the cancellable waits of the
+/// crate's other primitives. The coordinator calls [`ShutdownToken::shutdown_and_wait()`] (or
+/// its timed variant) to request the shutdown and block until every registered worker has
+/// dropped its handle. Workers can't register once shutdown has begun.
+///
+/// ## Example:
+///
+/// ```rust
+/// use rsevents_extra::{Awaitable, Semaphore, ShutdownToken};
+/// use std::time::Duration;
+///
+/// let shutdown = ShutdownToken::new();
+/// let jobs = Semaphore::new(0, 16);
+///
+/// std::thread::scope(|scope| {
+///     for _ in 0..4 {
+///         let worker = shutdown.register().unwrap();
+///         let jobs = &jobs;
+///         scope.spawn(move || {
+///             while let Ok(job) = jobs.wait_cancellable(&worker.token()) {
+///                 // <run the job here>
+///                 job.forget();
+///             }
+///             // <flush buffers here> before `worker` is dropped, confirming the exit.
+///         });
+///     }
+///
+///     jobs.release(3);
+///     shutdown.shutdown_and_wait_for(Duration::from_secs(30)).unwrap();
+///     assert_eq!(shutdown.workers(), 0);
+/// });
+/// ```
+pub struct ShutdownToken {
+    stop: CancellationTokenSource,
+    workers: DrainTracker,
+}
+
+impl ShutdownToken {
+    /// Creates a new `ShutdownToken` with no registered workers.
+    pub fn new() -> Self {
+        ShutdownToken {
+            stop: CancellationTokenSource::new(),
+            workers: DrainTracker::new(),
+        }
+    }
+
+    /// Registers a worker, returning a handle that observes the shutdown request and confirms the
+    /// worker's exit when dropped. Fails once shutdown has begun.
+    #[must_use = "the worker is only registered while the handle is held"]
+    pub fn register(&self) -> Result<ShutdownWorker<'_>, ShuttingDown> {
+        Ok(ShutdownWorker {
+            _registration: self.workers.enter()?,
+            token: self.stop.token(),
+        })
+    }
+
+    /// Requests a shutdown, releasing all workers waiting on their handles (or on their
+    /// cancellation tokens) and rejecting further registrations, without waiting for the workers
+    /// to exit.
+    pub fn shutdown(&self) {
+        self.workers.shutdown();
+        self.stop.cancel();
+    }
+
+    /// Requests a shutdown (see [`shutdown()`](Self::shutdown)), then blocks until all registered
+    /// workers have confirmed their exit.
+    pub fn shutdown_and_wait(&self) {
+        self.shutdown();
+        self.workers.shutdown_and_wait();
+    }
+
+    /// Requests a shutdown (see [`shutdown()`](Self::shutdown)), then blocks for up to `limit`
+    /// until all registered workers have confirmed their exit, returning an error in case of a
+    /// timeout.
+    pub fn shutdown_and_wait_for(&self, limit: Duration) -> Result<(), TimeoutError> {
+        self.shutdown();
+        self.workers.shutdown_and_wait_for(limit)
+    }
+
+    /// Returns whether a shutdown has been requested.
+    pub fn is_shutting_down(&self) -> bool {
+        self.stop.is_cancelled()
+    }
+
+    /// Returns the number of registered workers that have not yet confirmed their exit.
+    pub fn workers(&self) -> usize {
+        self.workers.in_flight()
+    }
+}
+
+impl Default for ShutdownToken {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A worker's registration with a [`ShutdownToken`], which becomes set once shutdown is requested
+/// and confirms the worker's exit when dropped.
+pub struct ShutdownWorker<'a> {
+    _registration: DrainGuard<'a>,
+    token: CancellationToken,
+}
+
+impl ShutdownWorker<'_> {
+    /// Returns a token that is cancelled when shutdown is requested, for use with the cancellable
+    /// waits of the crate's other primitives.
+    pub fn token(&self) -> CancellationToken {
+        self.token.clone()
+    }
+
+    /// Returns whether shutdown has been requested, without blocking.
+    pub fn is_stopping(&self) -> bool {
+        self.token.is_cancelled()
+    }
+
+    /// Confirms the worker's exit. Equivalent to dropping the handle.
+    pub fn exit(self) {}
+}
+
+impl Awaitable<'_> for ShutdownWorker<'_> {
+    type T = ();
+    type Error = TimeoutError;
+
+    /// Waits for shutdown to be requested.
+    fn try_wait(&self) -> Result<(), Infallible> {
+        self.token.try_wait()
+    }
+
+    /// Waits for shutdown to be requested or returns an error in case of a timeout.
+    fn try_wait_for(&self, limit: Duration) -> Result<(), TimeoutError> {
+        self.token.try_wait_for(limit)
+    }
+
+    /// Checks whether shutdown has been requested without blocking.
+    fn try_wait0(&self) -> Result<(), TimeoutError> {
+        self.token.try_wait0()
+    }
+}
+
+impl Subscribe for ShutdownWorker<'_> {
+    fn subscribe(&self, waker: &Waker) -> Option<Subscription<'_>> {
+        self.token.subscribe(waker)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{Awaitable, ShutdownToken};
+    use std::thread;
+    use std::time::Duration;
+
+    #[test]
+    fn stops_and_drains_workers() {
+        let shutdown = ShutdownToken::new();
+        let lingering = shutdown.register().unwrap();
+
+        thread::scope(|scope| {
+            for _ in 0..3 {
+                let worker = shutdown.register().unwrap();
+                scope.spawn(move || {
+                    assert!(!worker.is_stopping());
+                    worker.wait();
+                    worker.exit();
+                });
+            }
+
+            thread::sleep(Duration::from_millis(10));
+            assert_eq!(shutdown.workers(), 4);
+            assert!(shutdown
+                .shutdown_and_wait_for(Duration::from_millis(50))
+                .is_err());
+            assert!(shutdown.is_shutting_down());
+            assert!(lingering.is_stopping());
+            assert!(shutdown.register().is_err());
+        });
+
+        assert_eq!(shutdown.workers(), 1);
+        drop(lingering);
+        shutdown.shutdown_and_wait();
+    }
+}
@@ -0,0 +1,182 @@
+use crate::CompletionEvent;
+use rsevents::Awaitable;
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::sync::{Arc, Mutex};
+
+/// Coalesces concurrent requests for the same key into a single execution.
+///
+/// The first caller of [`SingleFlight::run()`] for a given key runs the closure it was passed,
+/// while any callers requesting the same key in the meantime block until it finishes and then
+/// receive a clone of its result rather than running their own closures. Once the result has been
+/// handed out, the next call for that key starts a new execution. This is typically used to avoid
+/// a stampede of identical cache fills when a popular entry expires.
+///
+/// If the closure panics, the panic propagates to the caller that ran it, and one of the callers
+/// waiting on it runs its own closure instead.
+///
+/// ## Example:
+///
+/// ```rust
+/// use rsevents_extra::SingleFlight;
+/// use std::sync::atomic::{AtomicUsize, Ordering};
+///
+/// let flights: SingleFlight<&str, String> = SingleFlight::new();
+/// let lookups = AtomicUsize::new(0);
+///
+/// std::thread::scope(|scope| {
+///     for _ in 0..4 {
+///         scope.spawn(|| {
+///             let user = flights.run("user:42", || {
+///                 // <query the database here>
+///                 lookups.fetch_add(1, Ordering::Relaxed);
+///                 String::from("Mahmoud")
+///             });
+///             assert_eq!(user, "Mahmoud");
+///         });
+///     }
+/// });
+///
+/// // The threads that asked while a lookup was in flight shared its result.
+/// assert!(lookups.load(Ordering::Relaxed) <= 4);
+/// ```
+pub struct SingleFlight<K, T> {
+    flights: Mutex<HashMap<K, Arc<Flight<T>>>>,
+}
+
+/// The result of an in-flight execution, or `None` if its closure panicked.
+type Flight<T> = CompletionEvent<Option<T>>;
+
+impl<K: Hash + Eq + Clone, T: Clone> SingleFlight<K, T> {
+    /// Creates a new `SingleFlight` with no executions in flight.
+    pub fn new() -> Self {
+        SingleFlight {
+            flights: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Runs `f` to obtain the result for `key`, unless an execution for `key` is already in
+    /// flight, in which case this blocks until it finishes and returns a clone of its result.
+    pub fn run(&self, key: K, f: impl FnOnce() -> T) -> T {
+        let flight = loop {
+            let flight = {
+                let mut flights = self.flights.lock().unwrap();
+                match flights.get(&key) {
+                    Some(flight) => Arc::clone(flight),
+                    None => {
+                        let flight = Arc::new(Flight::new());
+                        flights.insert(key.clone(), Arc::clone(&flight));
+                        break flight;
+                    }
+                }
+            };
+            if let Some(result) = flight.wait() {
+                return result.clone();
+            }
+            // The closure of that execution panicked, so try running our own.
+        };
+
+        let landing = Landing {
+            flights: &self.flights,
+            key,
+            flight,
+            result: None,
+        };
+        let result = f();
+        landing.land(result)
+    }
+
+    /// Returns whether an execution for `key` is currently in flight.
+    pub fn is_in_flight(&self, key: &K) -> bool {
+        self.flights.lock().unwrap().contains_key(key)
+    }
+}
+
+impl<K: Hash + Eq + Clone, T: Clone> Default for SingleFlight<K, T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Completes an execution when dropped, releasing the callers waiting on it even if its closure
+/// panics.
+struct Landing<'a, K: Hash + Eq, T> {
+    flights: &'a Mutex<HashMap<K, Arc<Flight<T>>>>,
+    key: K,
+    flight: Arc<Flight<T>>,
+    result: Option<T>,
+}
+
+impl<K: Hash + Eq, T: Clone> Landing<'_, K, T> {
+    fn land(mut self, result: T) -> T {
+        self.result = Some(result.clone());
+        result
+    }
+}
+
+impl<K: Hash + Eq, T> Drop for Landing<'_, K, T> {
+    fn drop(&mut self) {
+        // Callers arriving from now on start a new execution.
+        if let Ok(mut flights) = self.flights.lock() {
+            flights.remove(&self.key);
+        }
+        let _ = self.flight.complete(self.result.take());
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::SingleFlight;
+    use std::panic::{catch_unwind, AssertUnwindSafe};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::thread;
+    use std::time::Duration;
+
+    #[test]
+    fn coalesces_concurrent_calls() {
+        let flights = SingleFlight::new();
+        let runs = AtomicUsize::new(0);
+
+        thread::scope(|scope| {
+            let leader = scope.spawn(|| {
+                flights.run(1, || {
+                    runs.fetch_add(1, Ordering::SeqCst);
+                    thread::sleep(Duration::from_millis(100));
+                    "one"
+                })
+            });
+            while !flights.is_in_flight(&1) {
+                thread::yield_now();
+            }
+            let follower = scope.spawn(|| flights.run(1, || unreachable!()));
+            assert_eq!(flights.run(2, || "two"), "two");
+            assert_eq!(leader.join().unwrap(), "one");
+            assert_eq!(follower.join().unwrap(), "one");
+        });
+
+        assert_eq!(runs.load(Ordering::SeqCst), 1);
+        assert!(!flights.is_in_flight(&1));
+        assert_eq!(flights.run(1, || "again"), "again");
+    }
+
+    #[test]
+    fn follower_takes_over_after_panic() {
+        let flights = SingleFlight::new();
+
+        thread::scope(|scope| {
+            let leader = scope.spawn(|| {
+                catch_unwind(AssertUnwindSafe(|| {
+                    flights.run("key", || {
+                        thread::sleep(Duration::from_millis(100));
+                        panic!("lookup failed")
+                    })
+                }))
+            });
+            while !flights.is_in_flight(&"key") {
+                thread::yield_now();
+            }
+            assert_eq!(flights.run("key", || 7), 7);
+            assert!(leader.join().unwrap().is_err());
+        });
+    }
+}
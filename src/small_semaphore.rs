@@ -0,0 +1,297 @@
+use crate::sync::{loom_const_fn, AtomicU8, Ordering};
+use rsevents::{AutoResetEvent, Awaitable, EventState, TimeoutError};
+use std::convert::Infallible;
+use std::fmt::Debug;
+use std::time::Duration;
+
+/// A compact [`Semaphore`](crate::Semaphore) limited to a maximum concurrency count of 255, for
+/// memory-constrained targets.
+///
+/// A `SmallSemaphore` uses `u8` counters throughout and occupies four bytes, leaving out the
+/// optional behaviors (such as [tickets](crate::Semaphore::reserve) and weighted waits) of a
+/// `Semaphore`. It otherwise follows the same rules: it is created with a maximum and an initial
+/// concurrency count, and waiting on it yields a [`SmallSemaphoreGuard`] that returns its slot when
+/// dropped.
+///
+/// ## Example:
+///
+/// ```rust
+/// use rsevents_extra::SmallSemaphore;
+///
+/// // Only two tasks may drive the shared bus at any given time.
+/// static BUS: SmallSemaphore = SmallSemaphore::new(2, 2);
+///
+/// fn transmit(_frame: &[u8]) {
+///     let _guard = BUS.wait();
+///     // <drive the bus here>
+/// }
+///
+/// transmit(&[0x01, 0x02]);
+/// ```
+pub struct SmallSemaphore {
+    /// The maximum available concurrency for this semaphore, set at the time of initialization and
+    /// static thereafter.
+    max: u8,
+    /// The current available concurrency, including any borrowed slots. See
+    /// [`Semaphore`](crate::Semaphore) for why this is tracked separately from `count`.
+    current: AtomicU8,
+    /// The currently available concurrency count, equal to `current` minus any borrowed slots.
+    count: AtomicU8,
+    /// The auto-reset event used to sleep awaiting threads until a zero concurrency count is
+    /// incremented, waking only one awaiter at a time.
+    event: AutoResetEvent,
+}
+
+impl SmallSemaphore {
+    loom_const_fn! {
+        /// Create a new [`SmallSemaphore`] with a maximum available concurrency count of
+        /// `max_count` and an initial available concurrency count of `initial_count`.
+        pub fn new(initial_count: u8, max_count: u8) -> Self {
+            if initial_count > max_count {
+                panic!("Invalid initial_count > max_count");
+            }
+
+            SmallSemaphore {
+                max: max_count,
+                current: AtomicU8::new(initial_count),
+                count: AtomicU8::new(initial_count),
+                event: AutoResetEvent::new(EventState::Unset),
+            }
+        }
+    }
+
+    fn try_wait(&self, timeout: Option<Duration>) -> Result<(), TimeoutError> {
+        let mut count = self.count.load(Ordering::Relaxed);
+
+        loop {
+            count = if count == 0 {
+                match timeout {
+                    Some(Duration::ZERO) => return Err(TimeoutError),
+                    None => self.event.try_wait()?,
+                    Some(timeout) => self.event.try_wait_for(timeout)?,
+                }
+
+                self.count.load(Ordering::Relaxed)
+            } else {
+                match self.count.compare_exchange_weak(
+                    count,
+                    count - 1,
+                    Ordering::Acquire,
+                    Ordering::Relaxed,
+                ) {
+                    Ok(_) => {
+                        if count - 1 > 0 {
+                            self.event.set();
+                        }
+                        break;
+                    }
+                    Err(count) => count,
+                }
+            };
+        }
+
+        Ok(())
+    }
+
+    /// Obtains a concurrency token from the semaphore, blocking until one becomes available.
+    #[must_use = "The semaphore count is immediately re-incremented if the guard is dropped"]
+    pub fn wait(&self) -> SmallSemaphoreGuard<'_> {
+        self.try_wait(None).unwrap();
+        SmallSemaphoreGuard { semaphore: self }
+    }
+
+    /// Attempts a time-bounded wait against the semaphore, returning a [`TimeoutError`] if the
+    /// specified time limit elapses without the semaphore becoming available.
+    #[must_use = "The semaphore count is immediately re-incremented if the guard is dropped"]
+    pub fn wait_for(&self, limit: Duration) -> Result<SmallSemaphoreGuard<'_>, TimeoutError> {
+        self.try_wait(Some(limit))?;
+        Ok(SmallSemaphoreGuard { semaphore: self })
+    }
+
+    /// Directly increments or decrements the current availability limit of the semaphore without
+    /// blocking, panicking if the result would lie outside of `0..=max_count`. See
+    /// [`Semaphore::modify()`](crate::Semaphore::modify) for more info.
+    pub fn modify(&mut self, count: i16) {
+        if !self.try_modify(count) {
+            panic!("An invalid count was supplied to SmallSemaphore::modify()");
+        }
+    }
+
+    /// Directly increments or decrements the current availability limit of the semaphore without
+    /// blocking, returning `false` if the result would lie outside of `0..=max_count`. See
+    /// [`Semaphore::modify()`](crate::Semaphore::modify) for more info.
+    pub fn try_modify(&mut self, count: i16) -> bool {
+        // Exclusive access means no slots are borrowed, so both counts take on the same value.
+        let current = self.current.load(Ordering::Relaxed);
+        let sum = match (current as i16).checked_add(count) {
+            Some(sum) if sum >= 0 && sum <= self.max as i16 => sum as u8,
+            _ => return false,
+        };
+        self.current.store(sum, Ordering::Relaxed);
+        self.count.store(sum, Ordering::Relaxed);
+        true
+    }
+
+    #[inline]
+    fn release_internal(&self, count: u8) {
+        // Only the transition from zero needs to wake a waiter.
+        if self.count.fetch_add(count, Ordering::Release) == 0 {
+            self.event.set();
+        }
+    }
+
+    /// Increments the available concurrency by `count`, and panics if this results in a count that
+    /// exceeds the `max_count` the semaphore was created with.
+    pub fn release(&self, count: u8) {
+        if !self.try_release(count) {
+            panic!("SmallSemaphore::release() called with an inappropriate count!");
+        }
+    }
+
+    /// Attempts to increment the available concurrency by `count`, returning `false` if this would
+    /// exceed the `max_count` the semaphore was created with.
+    pub fn try_release(&self, count: u8) -> bool {
+        let mut prev_count = self.current.load(Ordering::Relaxed);
+        loop {
+            match prev_count.checked_add(count) {
+                Some(sum) if sum <= self.max => {}
+                _ => return false,
+            }
+            match self.current.compare_exchange_weak(
+                prev_count,
+                prev_count + count,
+                Ordering::Relaxed,
+                Ordering::Relaxed,
+            ) {
+                Ok(_) => break,
+                Err(new_count) => prev_count = new_count,
+            }
+        }
+
+        self.release_internal(count);
+        true
+    }
+
+    /// Returns the currently available count of the semaphore.
+    ///
+    /// Note that this may race with other calls such as `release()` or `wait()`.
+    pub fn count(&self) -> u8 {
+        self.count.load(Ordering::Relaxed)
+    }
+}
+
+impl<'a> Awaitable<'a> for SmallSemaphore {
+    type T = SmallSemaphoreGuard<'a>;
+    type Error = TimeoutError;
+
+    /// Blocks until the semaphore becomes available, then obtains a concurrency token.
+    fn try_wait(&'a self) -> Result<SmallSemaphoreGuard<'a>, Infallible> {
+        self.try_wait(None).unwrap();
+        Ok(SmallSemaphoreGuard { semaphore: self })
+    }
+
+    /// Attempts a time-bounded wait for a concurrency token.
+    fn try_wait_for(&'a self, limit: Duration) -> Result<SmallSemaphoreGuard<'a>, TimeoutError> {
+        self.try_wait(Some(limit))?;
+        Ok(SmallSemaphoreGuard { semaphore: self })
+    }
+
+    /// Attempts to obtain a concurrency token without waiting.
+    fn try_wait0(&'a self) -> Result<SmallSemaphoreGuard<'a>, TimeoutError> {
+        self.try_wait(Some(Duration::ZERO))?;
+        Ok(SmallSemaphoreGuard { semaphore: self })
+    }
+}
+
+/// The concurrency token returned by [`SmallSemaphore::wait()`]. Gives up its slot when dropped.
+///
+/// Call [`SmallSemaphoreGuard::forget()`] rather than `std::mem::forget()` to permanently decrease
+/// the available concurrency.
+pub struct SmallSemaphoreGuard<'a> {
+    semaphore: &'a SmallSemaphore,
+}
+
+impl SmallSemaphoreGuard<'_> {
+    /// Safely "forgets" the guard, permanently reducing the concurrency limit of the associated
+    /// `SmallSemaphore`. See [`SemaphoreGuard::forget()`](crate::SemaphoreGuard::forget).
+    pub fn forget(self) {
+        self.semaphore.current.fetch_sub(1, Ordering::Relaxed);
+        core::mem::forget(self);
+    }
+}
+
+impl Debug for SmallSemaphoreGuard<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SmallSemaphoreGuard")
+            .finish_non_exhaustive()
+    }
+}
+
+impl Drop for SmallSemaphoreGuard<'_> {
+    fn drop(&mut self) {
+        self.semaphore.release_internal(1);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::SmallSemaphore;
+    use rsevents::Awaitable;
+    use std::thread;
+    use std::time::Duration;
+
+    #[test]
+    fn small_footprint() {
+        assert!(std::mem::size_of::<SmallSemaphore>() <= 4);
+    }
+
+    #[test]
+    fn modify() {
+        let mut sem = SmallSemaphore::new(1, 255);
+        sem.modify(254);
+        assert_eq!(sem.count(), 255);
+        assert!(!sem.try_modify(1));
+        sem.modify(-255);
+        assert_eq!(sem.count(), 0);
+        sem.try_wait0().unwrap_err();
+        assert!(!sem.try_modify(-1));
+    }
+
+    #[test]
+    fn max_255() {
+        let sem = SmallSemaphore::new(0, 255);
+        sem.release(255);
+        assert!(!sem.try_release(1));
+        assert_eq!(sem.count(), 255);
+    }
+
+    #[test]
+    fn forget_then_release() {
+        let sem = SmallSemaphore::new(1, 1);
+        sem.wait().forget();
+        sem.try_wait0().unwrap_err();
+        assert!(sem.try_release(1));
+        assert!(!sem.try_release(1));
+    }
+
+    #[test]
+    fn contended_small_semaphore() {
+        let sem = SmallSemaphore::new(0, 3);
+
+        thread::scope(|scope| {
+            for _ in 0..3 {
+                scope.spawn(|| {
+                    sem.try_wait0().unwrap_err();
+                    sem.wait_for(Duration::from_secs(1)).unwrap().forget();
+                });
+            }
+
+            scope.spawn(|| {
+                thread::sleep(Duration::from_millis(100));
+                sem.release(3);
+            });
+        });
+
+        assert_eq!(sem.count(), 0);
+    }
+}
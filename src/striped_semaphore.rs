@@ -0,0 +1,117 @@
+use crate::semaphore::Count;
+use crate::{Semaphore, SemaphoreGuard, TryAcquireError};
+use rsevents::TimeoutError;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::time::Duration;
+
+/// A fixed array of [`Semaphore`]s ("stripes"), with acquisitions directed to a stripe by hashing
+/// a key, giving per-key (or per-resource-class) concurrency limits with a single object.
+///
+/// Each stripe admits up to `permits_per_stripe` concurrent holders. Acquisitions for the same
+/// key always go to the same stripe, while different keys may share a stripe (and thus a limit)
+/// if their hashes collide, so the number of stripes should comfortably exceed the number of keys
+/// that are expected to be busy at the same time. This avoids maintaining a `HashMap<K, Semaphore>`
+/// (and the locking around it) by hand.
+///
+/// ## Example:
+///
+/// ```rust
+/// use rsevents_extra::StripedSemaphore;
+///
+/// // Make at most 2 concurrent requests to any one host.
+/// let per_host = StripedSemaphore::new(64, 2);
+///
+/// std::thread::scope(|scope| {
+///     for url in ["a.example/1", "a.example/2", "b.example/1"] {
+///         let per_host = &per_host;
+///         scope.spawn(move || {
+///             let host = url.split('/').next().unwrap();
+///             let _guard = per_host.wait(host);
+///             // <fetch `url` here>
+///         });
+///     }
+/// });
+/// ```
+pub struct StripedSemaphore {
+    stripes: Box<[Semaphore]>,
+}
+
+impl StripedSemaphore {
+    /// Creates a new `StripedSemaphore` with `stripes` stripes, each admitting up to
+    /// `permits_per_stripe` concurrent holders.
+    ///
+    /// Panics if `stripes` is zero.
+    pub fn new(stripes: usize, permits_per_stripe: Count) -> Self {
+        assert!(stripes > 0, "StripedSemaphore needs at least one stripe!");
+        StripedSemaphore {
+            stripes: (0..stripes)
+                .map(|_| Semaphore::new(permits_per_stripe, permits_per_stripe))
+                .collect(),
+        }
+    }
+
+    /// Returns the stripe that acquisitions for `key` are directed to.
+    pub fn stripe<K: Hash + ?Sized>(&self, key: &K) -> &Semaphore {
+        let mut hasher = DefaultHasher::new();
+        key.hash(&mut hasher);
+        &self.stripes[(hasher.finish() % self.stripes.len() as u64) as usize]
+    }
+
+    /// Returns the number of stripes.
+    pub fn stripes(&self) -> usize {
+        self.stripes.len()
+    }
+
+    /// Obtains a slot from the stripe for `key`, blocking until one is available.
+    #[must_use = "The semaphore count is immediately re-incremented if the guard is dropped"]
+    pub fn wait<K: Hash + ?Sized>(&self, key: &K) -> SemaphoreGuard<'_> {
+        self.stripe(key).wait()
+    }
+
+    /// Obtains a slot from the stripe for `key`, blocking for up to `limit` until one is
+    /// available, and returning an error in case of a timeout.
+    #[must_use = "The semaphore count is immediately re-incremented if the guard is dropped"]
+    pub fn wait_for<K: Hash + ?Sized>(
+        &self,
+        key: &K,
+        limit: Duration,
+    ) -> Result<SemaphoreGuard<'_>, TimeoutError> {
+        self.stripe(key).wait_for(limit)
+    }
+
+    /// Obtains a slot from the stripe for `key` if one is available right away.
+    #[must_use = "The semaphore count is immediately re-incremented if the guard is dropped"]
+    pub fn try_acquire<K: Hash + ?Sized>(
+        &self,
+        key: &K,
+    ) -> Result<SemaphoreGuard<'_>, TryAcquireError> {
+        self.stripe(key).try_acquire()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::StripedSemaphore;
+    use std::time::Duration;
+
+    #[test]
+    fn limits_per_key() {
+        let sem = StripedSemaphore::new(16, 1);
+        assert_eq!(sem.stripes(), 16);
+
+        let first = sem.wait("alpha");
+        assert!(sem.try_acquire("alpha").is_err());
+        assert!(sem.wait_for("alpha", Duration::from_millis(1)).is_err());
+
+        // Find a key that lands on another stripe, which is unaffected.
+        let other = (0..)
+            .map(|i| i.to_string())
+            .find(|key| !std::ptr::eq(sem.stripe(key.as_str()), sem.stripe("alpha")))
+            .unwrap();
+        assert!(sem.try_acquire(other.as_str()).is_ok());
+
+        drop(first);
+        assert!(sem.try_acquire("alpha").is_ok());
+    }
+}
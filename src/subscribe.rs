@@ -0,0 +1,165 @@
+use crate::waker_list::WakerList;
+use rsevents::{AutoResetEvent, Awaitable, EventState};
+use std::fmt::Debug;
+use std::sync::Arc;
+use std::task::{Wake, Waker};
+use std::time::Duration;
+
+/// A hook for being woken when an [`Awaitable`] may have become ready, which lets waits on several
+/// awaitables at once and cancellable waits block until then instead of polling.
+///
+/// Among others, [`Semaphore`](crate::Semaphore), [`CountdownEvent`](crate::CountdownEvent) and
+/// [`CancellationToken`](crate::CancellationToken) wake their subscribers directly, and the
+/// subscribing variants of the waits that take awaitables of any type, such as
+/// [`wait_any_subscribed()`](crate::wait_any_subscribed),
+/// [`WaitSet::register_subscribed()`](crate::WaitSet::register_subscribed) and
+/// [`AwaitableExt::or_subscribed()`](crate::AwaitableExt::or_subscribed), only accept awaitables
+/// implementing this trait. Their plain counterparts ([`wait_any()`](crate::wait_any) and so on)
+/// accept any `Awaitable`, such as the events of `rsevents` or those defined outside of this
+/// crate, and poll the awaitables instead, with an exponential backoff capped at a few
+/// milliseconds, so they may notice an awaitable becoming ready that much later:
+///
+/// ```rust
+/// use rsevents_extra::{wait_any, Awaitable, TimeoutError};
+/// use std::convert::Infallible;
+/// use std::time::Duration;
+///
+/// struct AlwaysReady;
+///
+/// impl Awaitable<'_> for AlwaysReady {
+///     type T = ();
+///     type Error = TimeoutError;
+///
+///     fn try_wait(&self) -> Result<(), Infallible> {
+///         Ok(())
+///     }
+///
+///     fn try_wait_for(&self, _: Duration) -> Result<(), TimeoutError> {
+///         Ok(())
+///     }
+/// }
+///
+/// assert_eq!(wait_any(&[&AlwaysReady, &AlwaysReady]).0, 0);
+/// ```
+pub trait Subscribe {
+    /// Registers `waker` to be woken the next time this awaitable may have become ready, returning
+    /// a [`Subscription`] that withdraws it when dropped, or `None` if the awaitable can't wake
+    /// subscribers and has to be polled instead (the default).
+    ///
+    /// A subscription only covers changes made after `subscribe()` returns, so subscribers check
+    /// whether the awaitable is ready *after* subscribing, and subscribe again once woken if it
+    /// turns out not to be (e.g. because another thread got there first). Wakeups may be spurious.
+    fn subscribe(&self, waker: &Waker) -> Option<Subscription<'_>> {
+        let _ = waker;
+        None
+    }
+}
+
+impl<T: Subscribe + ?Sized> Subscribe for &T {
+    fn subscribe(&self, waker: &Waker) -> Option<Subscription<'_>> {
+        (**self).subscribe(waker)
+    }
+}
+
+/// How a wait holding an awaitable of type `A` subscribes to it, chosen by the entry point it was
+/// handed to: [`subscribed()`] for the subscribing variants, [`polled()`] for those accepting any
+/// [`Awaitable`].
+pub(crate) type SubscribeFn<A> = for<'s> fn(&'s A, &Waker) -> Option<Subscription<'s>>;
+
+/// Subscribes to `awaitable` through its [`Subscribe`] implementation.
+pub(crate) fn subscribed<'s, A>(awaitable: &'s A, waker: &Waker) -> Option<Subscription<'s>>
+where
+    A: Subscribe + ?Sized,
+{
+    awaitable.subscribe(waker)
+}
+
+/// Declines the subscription, leaving the awaitable to be polled.
+pub(crate) fn polled<'s, A: ?Sized>(_: &'s A, _: &Waker) -> Option<Subscription<'s>> {
+    None
+}
+
+/// A waker registered with one or more awaitables by [`Subscribe::subscribe()`], which is
+/// withdrawn when the subscription is dropped.
+#[must_use = "the waker is withdrawn when the subscription is dropped"]
+pub struct Subscription<'a> {
+    kind: Kind<'a>,
+}
+
+enum Kind<'a> {
+    /// A waker registered with a single list of wakers.
+    Registered { wakers: &'a WakerList, id: usize },
+    /// The subscriptions to both halves of a combined awaitable, only held to be dropped along
+    /// with this one.
+    Both(#[allow(dead_code)] Box<(Subscription<'a>, Subscription<'a>)>),
+}
+
+impl<'a> Subscription<'a> {
+    /// Registers `waker` with `wakers`, which is woken (via [`WakerList::wake_all()`] or
+    /// [`WakerList::wake_one()`]) whenever the awaitable may have become ready.
+    pub(crate) fn new(wakers: &'a WakerList, waker: &Waker) -> Self {
+        let id = wakers.next_id();
+        wakers.register(id, waker);
+        Subscription {
+            kind: Kind::Registered { wakers, id },
+        }
+    }
+
+    /// Combines the subscriptions to two awaitables into one.
+    pub(crate) fn both(first: Subscription<'a>, second: Subscription<'a>) -> Self {
+        Subscription {
+            kind: Kind::Both(Box::new((first, second))),
+        }
+    }
+}
+
+impl Drop for Subscription<'_> {
+    fn drop(&mut self) {
+        if let Kind::Registered { wakers, id } = self.kind {
+            wakers.remove(id);
+        }
+    }
+}
+
+impl Debug for Subscription<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Subscription").finish_non_exhaustive()
+    }
+}
+
+/// Blocks a thread until it is woken through one of the [`Subscription`]s it holds.
+pub(crate) struct Parker {
+    event: AutoResetEvent,
+}
+
+impl Parker {
+    /// Returns a new parker along with the waker to subscribe with.
+    pub(crate) fn new() -> (Arc<Parker>, Waker) {
+        let parker = Arc::new(Parker {
+            event: AutoResetEvent::new(EventState::Unset),
+        });
+        let waker = Waker::from(Arc::clone(&parker));
+        (parker, waker)
+    }
+
+    /// Blocks until woken or (if specified) until `limit` elapses, returning whether we were woken.
+    pub(crate) fn park(&self, limit: Option<Duration>) -> bool {
+        match limit {
+            None => {
+                self.event.wait();
+                true
+            }
+            Some(limit) => self.event.wait_for(limit),
+        }
+    }
+}
+
+impl Wake for Parker {
+    fn wake(self: Arc<Self>) {
+        self.event.set();
+    }
+
+    fn wake_by_ref(self: &Arc<Self>) {
+        self.event.set();
+    }
+}
@@ -0,0 +1,50 @@
+//! The synchronization types used by the lock-free internals of this crate, which are swapped for
+//! their [loom](https://docs.rs/loom) or [shuttle](https://docs.rs/shuttle) equivalents when
+//! building with `--cfg loom` or `--cfg shuttle`, so that the possible interleavings of their
+//! atomic operations can be model-checked (exhaustively under loom, or by randomized scheduling
+//! under shuttle, which copes with larger interleaving spaces). The loom and shuttle tests (named
+//! `loom_*` and `shuttle_*`) are run with `make loom` and `make shuttle`.
+
+#[cfg(all(loom, not(target_has_atomic = "64")))]
+pub(crate) use loom::sync::atomic::AtomicIsize;
+#[cfg(all(loom, target_has_atomic = "64"))]
+pub(crate) use loom::sync::atomic::{AtomicI64, AtomicU64};
+#[cfg(loom)]
+pub(crate) use loom::sync::{
+    atomic::{fence, AtomicBool, AtomicPtr, AtomicU16, AtomicU32, AtomicU8, AtomicUsize, Ordering},
+    Arc, Mutex, MutexGuard,
+};
+
+#[cfg(all(shuttle, not(loom), not(target_has_atomic = "64")))]
+pub(crate) use shuttle::sync::atomic::AtomicIsize;
+#[cfg(all(shuttle, not(loom), target_has_atomic = "64"))]
+pub(crate) use shuttle::sync::atomic::{AtomicI64, AtomicU64};
+#[cfg(all(shuttle, not(loom)))]
+pub(crate) use shuttle::sync::{
+    atomic::{fence, AtomicBool, AtomicPtr, AtomicU16, AtomicU32, AtomicU8, AtomicUsize, Ordering},
+    Arc, Mutex, MutexGuard,
+};
+
+#[cfg(all(not(any(loom, shuttle)), not(target_has_atomic = "64")))]
+pub(crate) use std::sync::atomic::AtomicIsize;
+#[cfg(all(not(any(loom, shuttle)), target_has_atomic = "64"))]
+pub(crate) use std::sync::atomic::{AtomicI64, AtomicU64};
+#[cfg(not(any(loom, shuttle)))]
+pub(crate) use std::sync::{
+    atomic::{fence, AtomicBool, AtomicPtr, AtomicU16, AtomicU32, AtomicU8, AtomicUsize, Ordering},
+    Arc, Mutex, MutexGuard,
+};
+
+/// Declares a `const fn`, except under `cfg(loom)` where the loom synchronization types can't be
+/// constructed in a const context and the function is declared as a regular `fn` instead.
+macro_rules! loom_const_fn {
+    ($(#[$attr:meta])* $vis:vis fn $($rest:tt)*) => {
+        #[cfg(not(loom))]
+        $(#[$attr])* $vis const fn $($rest)*
+
+        #[cfg(loom)]
+        $(#[$attr])* $vis fn $($rest)*
+    };
+}
+
+pub(crate) use loom_const_fn;
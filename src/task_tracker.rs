@@ -0,0 +1,224 @@
+use crate::{CancellationToken, CancellationTokenSource, Condition, ShuttingDown};
+use rsevents::{Awaitable, TimeoutError};
+use std::convert::Infallible;
+use std::sync::Arc;
+use std::thread::{self, JoinHandle, Scope, ScopedJoinHandle};
+use std::time::Duration;
+
+/// Keeps track of spawned threads, so that they can all be waited on and shut down together (a
+/// blocking analogue of tokio-util's `TaskTracker`).
+///
+/// Threads spawned through [`TaskTracker::spawn()`] or [`TaskTracker::spawn_scoped()`] are
+/// counted as live until their closure returns (or panics). Waiting on the tracker (via the
+/// [`Awaitable`] API) blocks until no tracked threads are live. Once the tracker is
+/// [closed](TaskTracker::close), no new threads can be spawned through it, and the
+/// [cancellation token](TaskTracker::token) handed to its threads is cancelled, telling them to
+/// wind down.
+///
+/// `TaskTracker` is a cheaply cloneable handle, with all clones tracking the same threads.
+///
+/// ## Example:
+///
+/// ```rust
+/// use rsevents_extra::{Awaitable, TaskTracker};
+/// use std::time::Duration;
+///
+/// let tracker = TaskTracker::new();
+///
+/// for _ in 0..4 {
+///     let token = tracker.token();
+///     tracker
+///         .spawn(move || {
+///             // Poll for work until told to stop.
+///             while !token.wait_for(Duration::from_millis(1)) {
+///                 // <do some work here>
+///             }
+///         })
+///         .unwrap();
+/// }
+///
+/// // Shut down: stop spawning new threads, signal the running ones, and wait for them to exit.
+/// tracker.close();
+/// tracker.wait();
+/// assert_eq!(tracker.len(), 0);
+/// ```
+#[derive(Clone)]
+pub struct TaskTracker {
+    inner: Arc<TrackerInner>,
+}
+
+struct TrackerInner {
+    /// The number of live tasks.
+    live: Condition<usize>,
+    closed: CancellationTokenSource,
+}
+
+impl TaskTracker {
+    /// Creates a new, open `TaskTracker` with no tracked threads.
+    pub fn new() -> Self {
+        TaskTracker {
+            inner: Arc::new(TrackerInner {
+                live: Condition::new(0),
+                closed: CancellationTokenSource::new(),
+            }),
+        }
+    }
+
+    /// Spawns a new thread running `f`, tracked until `f` returns. Fails if the tracker has been
+    /// closed.
+    pub fn spawn<F, T>(&self, f: F) -> Result<JoinHandle<T>, ShuttingDown>
+    where
+        F: FnOnce() -> T + Send + 'static,
+        T: Send + 'static,
+    {
+        let task = self.track()?;
+        Ok(thread::spawn(move || {
+            let _task = task;
+            f()
+        }))
+    }
+
+    /// Spawns a new scoped thread in `scope` running `f`, tracked until `f` returns. Fails if the
+    /// tracker has been closed.
+    pub fn spawn_scoped<'scope, 'env, F, T>(
+        &self,
+        scope: &'scope Scope<'scope, 'env>,
+        f: F,
+    ) -> Result<ScopedJoinHandle<'scope, T>, ShuttingDown>
+    where
+        F: FnOnce() -> T + Send + 'scope,
+        T: Send + 'scope,
+    {
+        let task = self.track()?;
+        Ok(scope.spawn(move || {
+            let _task = task;
+            f()
+        }))
+    }
+
+    /// Closes the tracker, so that no new threads can be spawned through it, and cancels the
+    /// tracker's [token](Self::token), telling the tracked threads to wind down. Threads that are
+    /// already running keep being tracked.
+    pub fn close(&self) {
+        // Taking the lock orders this against concurrent spawns.
+        self.inner.live.update(|_| self.inner.closed.cancel());
+    }
+
+    /// Returns whether the tracker has been closed.
+    pub fn is_closed(&self) -> bool {
+        self.inner.closed.is_cancelled()
+    }
+
+    /// Returns a token that is cancelled when the tracker is closed, for the tracked threads to
+    /// observe (or pass to the cancellable waits of the crate's other primitives).
+    pub fn token(&self) -> CancellationToken {
+        self.inner.closed.token()
+    }
+
+    /// Returns the number of tracked threads that are still live.
+    pub fn len(&self) -> usize {
+        *self.inner.live.read()
+    }
+
+    /// Returns whether no tracked threads are live.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn track(&self) -> Result<Task, ShuttingDown> {
+        self.inner.live.update(|live| {
+            if self.inner.closed.is_cancelled() {
+                return Err(ShuttingDown);
+            }
+            *live += 1;
+            Ok(Task {
+                inner: Arc::clone(&self.inner),
+            })
+        })
+    }
+}
+
+impl Default for TaskTracker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Awaitable<'_> for TaskTracker {
+    type T = ();
+    type Error = TimeoutError;
+
+    /// Waits until no tracked threads are live.
+    fn try_wait(&self) -> Result<(), Infallible> {
+        self.inner.live.wait_until(|&live| live == 0);
+        Ok(())
+    }
+
+    /// Waits until no tracked threads are live, or returns an error in case of a timeout.
+    fn try_wait_for(&self, limit: Duration) -> Result<(), TimeoutError> {
+        self.inner
+            .live
+            .wait_until_for(|&live| live == 0, limit)
+            .map(drop)
+    }
+
+    /// Checks whether any tracked threads are live, without blocking.
+    fn try_wait0(&self) -> Result<(), TimeoutError> {
+        match self.is_empty() {
+            true => Ok(()),
+            false => Err(TimeoutError),
+        }
+    }
+}
+
+/// Counts a tracked thread as live until dropped.
+struct Task {
+    inner: Arc<TrackerInner>,
+}
+
+impl Drop for Task {
+    fn drop(&mut self) {
+        self.inner.live.update(|live| *live -= 1);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{Awaitable, TaskTracker};
+    use std::thread;
+    use std::time::Duration;
+
+    #[test]
+    fn tracks_until_closed() {
+        let tracker = TaskTracker::new();
+        assert!(tracker.wait0());
+
+        let token = tracker.token();
+        let handle = tracker.spawn(move || token.wait()).unwrap();
+        thread::scope(|scope| {
+            let scoped = tracker
+                .spawn_scoped(scope, || thread::sleep(Duration::from_millis(10)))
+                .unwrap();
+            assert_eq!(tracker.len(), 2);
+            scoped.join().unwrap();
+            assert_eq!(tracker.len(), 1);
+            assert!(!tracker.wait_for(Duration::from_millis(1)));
+
+            tracker.close();
+            assert!(tracker.spawn_scoped(scope, || ()).is_err());
+        });
+
+        tracker.wait();
+        handle.join().unwrap();
+        assert!(tracker.is_closed());
+        assert!(tracker.spawn(|| ()).is_err());
+    }
+
+    #[test]
+    fn panicking_task_is_untracked() {
+        let tracker = TaskTracker::new();
+        let handle = tracker.spawn(|| panic!("task failed")).unwrap();
+        assert!(handle.join().is_err());
+        assert!(tracker.is_empty());
+    }
+}
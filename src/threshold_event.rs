@@ -0,0 +1,148 @@
+use crate::Condition;
+use rsevents::TimeoutError;
+use std::time::Duration;
+
+/// A counter that threads can wait on until its value crosses a threshold of their choosing, in
+/// either direction.
+///
+/// Where a [`CountdownEvent`](crate::CountdownEvent) only releases its waiters once it reaches
+/// zero, each waiter on a `ThresholdEvent` picks its own threshold, and can wait either for the
+/// count to rise to at least that value ([`wait_at_least()`](Self::wait_at_least)) or to fall to
+/// at most that value ([`wait_at_most()`](Self::wait_at_most)).
+///
+/// ## Example:
+///
+/// ```rust
+/// use rsevents_extra::ThresholdEvent;
+///
+/// // The number of jobs queued up.
+/// static QUEUED: ThresholdEvent = ThresholdEvent::new(0);
+///
+/// std::thread::scope(|scope| {
+///     let producer = scope.spawn(|| {
+///         for _ in 0..100 {
+///             // Apply back-pressure once there are 10 queued jobs.
+///             QUEUED.wait_at_most(9);
+///             QUEUED.add(1);
+///         }
+///     });
+///
+///     for _ in 0..100 {
+///         QUEUED.wait_at_least(1);
+///         QUEUED.sub(1);
+///         // <run the job here>
+///     }
+///     producer.join().unwrap();
+/// });
+///
+/// assert_eq!(QUEUED.count(), 0);
+/// ```
+pub struct ThresholdEvent {
+    count: Condition<usize>,
+}
+
+impl ThresholdEvent {
+    /// Creates a new `ThresholdEvent` with the given initial count.
+    ///
+    /// This is a `const` function and can be used in a `static` context.
+    pub const fn new(count: usize) -> Self {
+        ThresholdEvent {
+            count: Condition::new(count),
+        }
+    }
+
+    /// Returns the current count.
+    pub fn count(&self) -> usize {
+        *self.count.read()
+    }
+
+    /// Increases the count by `n`, releasing any threads waiting for it to reach a threshold now
+    /// met. Returns the new count.
+    pub fn add(&self, n: usize) -> usize {
+        self.count.update(|count| {
+            *count = count
+                .checked_add(n)
+                .expect("count exceeds the maximum supported count");
+            *count
+        })
+    }
+
+    /// Decreases the count by `n`, releasing any threads waiting for it to fall to a threshold
+    /// now met. Returns the new count.
+    ///
+    /// Panics if this would take the count below zero.
+    pub fn sub(&self, n: usize) -> usize {
+        self.count.update(|count| {
+            *count = count
+                .checked_sub(n)
+                .expect("ThresholdEvent decremented past zero!");
+            *count
+        })
+    }
+
+    /// Blocks until the count is greater than or equal to `threshold`.
+    pub fn wait_at_least(&self, threshold: usize) {
+        self.count.wait_until(|&count| count >= threshold);
+    }
+
+    /// Blocks for up to `limit` until the count is greater than or equal to `threshold`, returning
+    /// an error in case of a timeout.
+    pub fn wait_at_least_for(&self, threshold: usize, limit: Duration) -> Result<(), TimeoutError> {
+        self.count
+            .wait_until_for(|&count| count >= threshold, limit)
+            .map(drop)
+    }
+
+    /// Blocks until the count is less than or equal to `threshold`.
+    pub fn wait_at_most(&self, threshold: usize) {
+        self.count.wait_until(|&count| count <= threshold);
+    }
+
+    /// Blocks for up to `limit` until the count is less than or equal to `threshold`, returning an
+    /// error in case of a timeout.
+    pub fn wait_at_most_for(&self, threshold: usize, limit: Duration) -> Result<(), TimeoutError> {
+        self.count
+            .wait_until_for(|&count| count <= threshold, limit)
+            .map(drop)
+    }
+}
+
+impl Default for ThresholdEvent {
+    fn default() -> Self {
+        Self::new(0)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::ThresholdEvent;
+    use std::thread;
+    use std::time::Duration;
+
+    #[test]
+    fn waits_in_both_directions() {
+        let event = ThresholdEvent::new(5);
+        assert!(event.wait_at_least_for(5, Duration::ZERO).is_ok());
+        assert!(event.wait_at_most_for(5, Duration::ZERO).is_ok());
+        assert!(event
+            .wait_at_least_for(6, Duration::from_millis(1))
+            .is_err());
+        assert!(event.wait_at_most_for(4, Duration::from_millis(1)).is_err());
+
+        thread::scope(|scope| {
+            let high = scope.spawn(|| event.wait_at_least(8));
+            let low = scope.spawn(|| event.wait_at_most(2));
+            assert_eq!(event.add(3), 8);
+            high.join().unwrap();
+            assert_eq!(event.sub(6), 2);
+            low.join().unwrap();
+        });
+    }
+
+    #[test]
+    #[should_panic]
+    fn sub_past_zero() {
+        let event = ThresholdEvent::new(1);
+        event.sub(2);
+    }
+}
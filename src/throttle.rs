@@ -0,0 +1,171 @@
+use crate::clock::{self, Clock, SystemClock};
+use rsevents::{Awaitable, TimeoutError};
+use std::collections::VecDeque;
+use std::convert::Infallible;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// A sliding-window throttle, allowing at most a fixed number of acquisitions within any rolling
+/// window of time.
+///
+/// Many third-party API quotas are specified this way ("at most 100 requests in any 60 second
+/// period"), which neither a [`Semaphore`](crate::Semaphore) nor the token bucket of a
+/// [`RateLimiter`](crate::RateLimiter) models exactly. Waiting on a throttle (via the
+/// [`Awaitable`] API) records an acquisition, blocking until the oldest acquisition in the window
+/// has aged out if the quota has been used up.
+///
+/// ## Example:
+///
+/// ```rust
+/// use rsevents_extra::{Awaitable, Throttle};
+/// use std::time::Duration;
+///
+/// // The API allows at most 5 requests in any 1 second window.
+/// static QUOTA: Throttle = Throttle::new(5, Duration::from_secs(1));
+///
+/// for _ in 0..5 {
+///     QUOTA.wait();
+///     // <make the next request here>
+/// }
+/// // The quota for the current window has been used up.
+/// assert_eq!(QUOTA.wait0(), false);
+/// ```
+pub struct Throttle {
+    max_ops: usize,
+    window: Duration,
+    /// The times of the acquisitions made within the last `window`, oldest first.
+    log: Mutex<VecDeque<Instant>>,
+    clock: &'static dyn Clock,
+}
+
+impl Throttle {
+    /// Creates a new `Throttle` allowing at most `max_ops` acquisitions within any `window`.
+    ///
+    /// Panics if `max_ops` is zero.
+    ///
+    /// This is a `const` function and can be used in a `static` context.
+    pub const fn new(max_ops: usize, window: Duration) -> Self {
+        assert!(max_ops > 0, "Throttle max_ops must be non-zero!");
+        Throttle {
+            max_ops,
+            window,
+            log: Mutex::new(VecDeque::new()),
+            clock: &SystemClock,
+        }
+    }
+
+    /// Replaces the [`SystemClock`] used to track the window and time waits with `clock`. This is
+    /// primarily useful in tests, where a [`MockClock`](crate::MockClock) makes the window
+    /// deterministic.
+    pub const fn with_clock(mut self, clock: &'static dyn Clock) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Returns the number of acquisitions that can currently be made without blocking.
+    pub fn available(&self) -> usize {
+        let now = self.clock.now();
+        let mut log = self.log.lock().unwrap();
+        self.expire(&mut log, now);
+        self.max_ops - log.len().min(self.max_ops)
+    }
+
+    /// Records an acquisition if the quota allows it, or otherwise returns how long it will be
+    /// until the oldest acquisition in the window ages out.
+    fn try_acquire(&self) -> Result<(), Duration> {
+        let now = self.clock.now();
+        let mut log = self.log.lock().unwrap();
+        self.expire(&mut log, now);
+        if log.len() < self.max_ops {
+            log.push_back(now);
+            return Ok(());
+        }
+        let oldest = log[0];
+        Err((oldest + self.window).saturating_duration_since(now))
+    }
+
+    /// Drops the acquisitions that have aged out of the window as of `now`.
+    fn expire(&self, log: &mut VecDeque<Instant>, now: Instant) {
+        while matches!(log.front(), Some(&time) if now.saturating_duration_since(time) >= self.window)
+        {
+            log.pop_front();
+        }
+    }
+}
+
+impl Awaitable<'_> for Throttle {
+    type T = ();
+    type Error = TimeoutError;
+
+    /// Records an acquisition, blocking until the quota allows it.
+    fn try_wait(&self) -> Result<(), Infallible> {
+        while let Err(wait) = self.try_acquire() {
+            clock::sleep(self.clock, wait);
+        }
+        Ok(())
+    }
+
+    /// Records an acquisition, blocking for up to `limit` until the quota allows it.
+    fn try_wait_for(&self, limit: Duration) -> Result<(), TimeoutError> {
+        let deadline = self.clock.now() + limit;
+        loop {
+            let wait = match self.try_acquire() {
+                Ok(()) => return Ok(()),
+                Err(wait) => wait,
+            };
+            let remaining = deadline.saturating_duration_since(self.clock.now());
+            if wait > remaining {
+                return Err(TimeoutError);
+            }
+            clock::sleep(self.clock, wait);
+        }
+    }
+
+    /// Records an acquisition if the quota allows it right away.
+    fn try_wait0(&self) -> Result<(), TimeoutError> {
+        self.try_acquire().map_err(|_| TimeoutError)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{Awaitable, MockClock, Throttle};
+    use std::thread;
+    use std::time::Duration;
+
+    #[test]
+    fn rolling_window() {
+        static CLOCK: MockClock = MockClock::new();
+        let throttle = Throttle::new(2, Duration::from_secs(10)).with_clock(&CLOCK);
+
+        assert!(throttle.try_wait0().is_ok());
+        CLOCK.advance(Duration::from_secs(6));
+        assert!(throttle.try_wait0().is_ok());
+        assert!(throttle.try_wait0().is_err());
+        assert_eq!(throttle.available(), 0);
+
+        // Only the first acquisition has aged out of the window.
+        CLOCK.advance(Duration::from_secs(4));
+        assert_eq!(throttle.available(), 1);
+        assert!(throttle.try_wait0().is_ok());
+        assert!(throttle.try_wait_for(Duration::from_secs(1)).is_err());
+    }
+
+    #[test]
+    fn blocks_until_quota_frees_up() {
+        static CLOCK: MockClock = MockClock::new();
+        let throttle = Throttle::new(1, Duration::from_secs(1)).with_clock(&CLOCK);
+        throttle.wait();
+
+        thread::scope(|scope| {
+            let waiter = scope.spawn(|| throttle.wait());
+            thread::sleep(Duration::from_millis(10));
+            assert!(!waiter.is_finished());
+            while !waiter.is_finished() {
+                CLOCK.advance(Duration::from_millis(100));
+                thread::sleep(Duration::from_millis(1));
+            }
+        });
+        assert_eq!(throttle.available(), 0);
+    }
+}
@@ -0,0 +1,122 @@
+use crate::EpochEvent;
+use rsevents::TimeoutError;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+
+/// Ordered turn-taking between threads, letting results produced in parallel be committed in a
+/// fixed order.
+///
+/// Each thread [takes a numbered ticket](Self::take_ticket) (handed out in increasing order,
+/// starting from zero) before doing its share of the work in parallel with the others, then
+/// [waits for its turn](Self::wait_for_turn), which comes once all lower-numbered tickets have
+/// been [completed](Self::complete). Completing a ticket passes the turn on to the next one.
+///
+/// ## Example:
+///
+/// ```rust
+/// use rsevents_extra::TicketTurn;
+/// use std::sync::Mutex;
+///
+/// static TURN: TicketTurn = TicketTurn::new();
+/// static OUTPUT: Mutex<Vec<u64>> = Mutex::new(Vec::new());
+///
+/// std::thread::scope(|scope| {
+///     for _ in 0..8 {
+///         scope.spawn(|| {
+///             let ticket = TURN.take_ticket();
+///             // <compress chunk number `ticket` here>
+///             TURN.wait_for_turn(ticket);
+///             OUTPUT.lock().unwrap().push(ticket);
+///             TURN.complete(ticket);
+///         });
+///     }
+/// });
+///
+/// assert_eq!(*OUTPUT.lock().unwrap(), (0..8).collect::<Vec<_>>());
+/// ```
+pub struct TicketTurn {
+    /// The next ticket to be handed out.
+    next_ticket: AtomicU64,
+    /// The ticket whose turn it is.
+    serving: EpochEvent,
+}
+
+impl TicketTurn {
+    /// Creates a new `TicketTurn`, whose first ticket is zero.
+    ///
+    /// This is a `const` function and can be used in a `static` context.
+    pub const fn new() -> Self {
+        TicketTurn {
+            next_ticket: AtomicU64::new(0),
+            serving: EpochEvent::new(),
+        }
+    }
+
+    /// Takes the next ticket, returning its number.
+    pub fn take_ticket(&self) -> u64 {
+        self.next_ticket.fetch_add(1, Ordering::Relaxed)
+    }
+
+    /// Returns the number of the ticket whose turn it currently is.
+    pub fn serving(&self) -> u64 {
+        self.serving.epoch()
+    }
+
+    /// Blocks until it is the turn of `ticket`, i.e. until all lower-numbered tickets have been
+    /// completed.
+    pub fn wait_for_turn(&self, ticket: u64) {
+        self.serving.wait_until(ticket);
+    }
+
+    /// Blocks for up to `limit` until it is the turn of `ticket`, returning an error in case of a
+    /// timeout.
+    pub fn wait_for_turn_for(&self, ticket: u64, limit: Duration) -> Result<(), TimeoutError> {
+        self.serving.wait_until_for(ticket, limit).map(drop)
+    }
+
+    /// Ends the turn of `ticket`, passing the turn on to the next ticket.
+    ///
+    /// Panics if it isn't currently the turn of `ticket`.
+    pub fn complete(&self, ticket: u64) {
+        let serving = self.serving.epoch();
+        assert_eq!(serving, ticket, "TicketTurn completed out of turn!");
+        self.serving.advance();
+    }
+}
+
+impl Default for TicketTurn {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::TicketTurn;
+    use std::time::Duration;
+
+    #[test]
+    fn turns_in_order() {
+        let turn = TicketTurn::new();
+        let first = turn.take_ticket();
+        let second = turn.take_ticket();
+        assert_eq!((first, second), (0, 1));
+
+        assert!(turn
+            .wait_for_turn_for(second, Duration::from_millis(1))
+            .is_err());
+        turn.wait_for_turn(first);
+        turn.complete(first);
+        assert!(turn.wait_for_turn_for(second, Duration::ZERO).is_ok());
+        assert_eq!(turn.serving(), 1);
+    }
+
+    #[test]
+    #[should_panic]
+    fn complete_out_of_turn() {
+        let turn = TicketTurn::new();
+        turn.take_ticket();
+        let second = turn.take_ticket();
+        turn.complete(second);
+    }
+}
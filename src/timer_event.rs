@@ -0,0 +1,111 @@
+#![allow(clippy::bool_assert_comparison)]
+
+use rsevents::{Awaitable, TimeoutError};
+use std::convert::Infallible;
+use std::time::{Duration, Instant};
+
+/// An [`Awaitable`] that becomes set once a deadline has passed, letting a timeout take part in
+/// waits as a first-class awaitable.
+///
+/// A `TimerEvent` needs no background thread: it is driven lazily by its waiters, which simply
+/// sleep until the deadline (or for as long as their own time limit allows). Once the deadline has
+/// passed, the event remains set.
+///
+/// ## Example:
+///
+/// ```rust
+/// use rsevents_extra::{Awaitable, TimerEvent};
+/// use std::time::{Duration, Instant};
+///
+/// let timer = TimerEvent::after(Duration::from_millis(10));
+/// assert_eq!(timer.wait0(), false);
+///
+/// timer.wait();
+/// assert!(Instant::now() >= timer.deadline());
+/// assert_eq!(timer.is_set(), true);
+/// ```
+#[derive(Debug, Copy, Clone)]
+pub struct TimerEvent {
+    deadline: Instant,
+}
+
+impl TimerEvent {
+    /// Creates a timer that becomes set once `duration` has elapsed from now.
+    pub fn after(duration: Duration) -> Self {
+        Self::at(Instant::now() + duration)
+    }
+
+    /// Creates a timer that becomes set once `deadline` has passed.
+    pub fn at(deadline: Instant) -> Self {
+        TimerEvent { deadline }
+    }
+
+    /// Returns the instant at which the timer becomes set.
+    pub fn deadline(&self) -> Instant {
+        self.deadline
+    }
+
+    /// Returns the time left until the timer becomes set, or zero if it already has.
+    pub fn remaining(&self) -> Duration {
+        self.deadline.saturating_duration_since(Instant::now())
+    }
+
+    /// Returns whether the deadline has passed, without blocking.
+    pub fn is_set(&self) -> bool {
+        Instant::now() >= self.deadline
+    }
+}
+
+impl Awaitable<'_> for TimerEvent {
+    type T = ();
+    type Error = TimeoutError;
+
+    /// Waits for the deadline to pass.
+    fn try_wait(&self) -> Result<(), Infallible> {
+        // Sleeps can end early, so keep going until the deadline has actually passed.
+        while !self.is_set() {
+            std::thread::sleep(self.remaining());
+        }
+        Ok(())
+    }
+
+    /// Waits for the deadline to pass or returns an error if it doesn't pass within `limit`.
+    fn try_wait_for(&self, limit: Duration) -> Result<(), TimeoutError> {
+        if self.remaining() > limit {
+            std::thread::sleep(limit);
+            return match self.is_set() {
+                true => Ok(()),
+                false => Err(TimeoutError),
+            };
+        }
+        self.try_wait().map_err(|never| match never {})
+    }
+
+    /// Checks whether the deadline has passed without blocking.
+    fn try_wait0(&self) -> Result<(), TimeoutError> {
+        match self.is_set() {
+            true => Ok(()),
+            false => Err(TimeoutError),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{Awaitable, TimerEvent};
+    use std::time::{Duration, Instant};
+
+    #[test]
+    fn set_at_deadline() {
+        let timer = TimerEvent::after(Duration::from_millis(50));
+        assert!(!timer.wait0());
+        assert!(!timer.wait_for(Duration::from_millis(5)));
+
+        assert!(timer.wait_for(Duration::from_secs(10)));
+        assert!(Instant::now() >= timer.deadline());
+        assert_eq!(timer.remaining(), Duration::ZERO);
+
+        let expired = TimerEvent::at(Instant::now());
+        assert!(expired.wait0());
+    }
+}
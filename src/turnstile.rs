@@ -0,0 +1,142 @@
+use crate::waiter_queue::{Waiter, WaiterQueue};
+use rsevents::TimeoutError;
+use std::sync::Mutex;
+use std::time::Duration;
+
+/// A turnstile that lets exactly one queued waiter through per call to [`Turnstile::release()`],
+/// in the order the waiters arrived.
+///
+/// Unlike a [`Semaphore`](crate::Semaphore), a turnstile has no permit balance: releasing it when
+/// nobody is waiting has no effect, rather than letting a later waiter straight through. Each
+/// release is thus a hand-off to a thread that is already queued, which is useful for admitting
+/// queued work one item at a time under the control of a coordinating thread.
+///
+/// ## Example:
+///
+/// ```rust
+/// use rsevents_extra::Turnstile;
+///
+/// static TURNSTILE: Turnstile = Turnstile::new();
+///
+/// // Nobody is queued yet, so this release is not saved up for later.
+/// assert_eq!(TURNSTILE.release(), false);
+///
+/// std::thread::scope(|scope| {
+///     let visitor = scope.spawn(|| TURNSTILE.wait());
+///     while TURNSTILE.waiting() == 0 {
+///         std::thread::yield_now();
+///     }
+///     assert_eq!(TURNSTILE.release(), true);
+///     visitor.join().unwrap();
+/// });
+/// ```
+pub struct Turnstile {
+    /// The queued waiters, in arrival order.
+    queue: Mutex<WaiterQueue>,
+}
+
+impl Turnstile {
+    /// Creates a new `Turnstile` with no waiters.
+    ///
+    /// This is a `const` function and can be used in a `static` context.
+    pub const fn new() -> Self {
+        Turnstile {
+            queue: Mutex::new(WaiterQueue::new()),
+        }
+    }
+
+    /// Joins the back of the queue and blocks until released by a call to
+    /// [`release()`](Self::release).
+    pub fn wait(&self) {
+        self.enqueue().wait();
+    }
+
+    /// Joins the back of the queue and blocks for up to `limit` until released by a call to
+    /// [`release()`](Self::release), returning an error (and leaving the queue) if the time limit
+    /// elapses first.
+    pub fn wait_for(&self, limit: Duration) -> Result<(), TimeoutError> {
+        let released = self
+            .enqueue()
+            .wait_for(limit, |waiter| self.queue.lock().unwrap().withdraw(waiter));
+        match released {
+            true => Ok(()),
+            false => Err(TimeoutError),
+        }
+    }
+
+    /// Lets the longest-waiting thread through the turnstile, returning whether there was one.
+    /// Has no effect if nobody is waiting.
+    pub fn release(&self) -> bool {
+        self.queue.lock().unwrap().release_one()
+    }
+
+    /// Returns the number of threads currently queued at the turnstile.
+    pub fn waiting(&self) -> usize {
+        self.queue.lock().unwrap().len()
+    }
+
+    fn enqueue(&self) -> Waiter {
+        self.queue.lock().unwrap().push()
+    }
+}
+
+impl Default for Turnstile {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::Turnstile;
+    use std::sync::Mutex;
+    use std::thread;
+    use std::time::Duration;
+
+    fn wait_for_queue(turnstile: &Turnstile, len: usize) {
+        while turnstile.waiting() != len {
+            thread::yield_now();
+        }
+    }
+
+    #[test]
+    fn releases_in_arrival_order() {
+        let turnstile = Turnstile::new();
+        let order = Mutex::new(Vec::new());
+
+        thread::scope(|scope| {
+            for i in 0..3 {
+                let (turnstile, order) = (&turnstile, &order);
+                scope.spawn(move || {
+                    turnstile.wait();
+                    order.lock().unwrap().push(i);
+                });
+                wait_for_queue(turnstile, i + 1);
+            }
+            for i in 0..3 {
+                assert!(turnstile.release());
+                while order.lock().unwrap().len() != i + 1 {
+                    thread::yield_now();
+                }
+            }
+        });
+
+        assert_eq!(*order.lock().unwrap(), vec![0, 1, 2]);
+    }
+
+    #[test]
+    fn releases_do_not_accumulate() {
+        let turnstile = Turnstile::new();
+        assert!(!turnstile.release());
+        assert!(turnstile.wait_for(Duration::from_millis(5)).is_err());
+        // The timed out waiter left the queue, so it doesn't absorb the next release.
+        assert_eq!(turnstile.waiting(), 0);
+
+        thread::scope(|scope| {
+            let waiter = scope.spawn(|| turnstile.wait_for(Duration::from_secs(10)));
+            wait_for_queue(&turnstile, 1);
+            assert!(turnstile.release());
+            assert!(waiter.join().unwrap().is_ok());
+        });
+    }
+}
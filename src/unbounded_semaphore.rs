@@ -0,0 +1,250 @@
+use rsevents::{AutoResetEvent, Awaitable, EventState, TimeoutError};
+use std::convert::Infallible;
+use std::fmt::Debug;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
+
+/// A counting semaphore without a maximum concurrency count, useful for classic
+/// producer/consumer signalling where each call to [`UnboundedSemaphore::release()`] makes one
+/// more item available to a consumer waiting on the semaphore.
+///
+/// Unlike [`Semaphore`](crate::Semaphore), an `UnboundedSemaphore` does not track a maximum count
+/// or the number of borrowed slots, so releasing it can never fail or panic (short of overflowing
+/// a `usize`).
+///
+/// ## Example:
+///
+/// ```rust
+/// use rsevents_extra::UnboundedSemaphore;
+/// use std::sync::Mutex;
+///
+/// static ITEMS_READY: UnboundedSemaphore = UnboundedSemaphore::new(0);
+/// static QUEUE: Mutex<Vec<u32>> = Mutex::new(Vec::new());
+///
+/// std::thread::scope(|scope| {
+///     scope.spawn(|| {
+///         for i in 0..100 {
+///             QUEUE.lock().unwrap().push(i);
+///             // Signal that one more item is available
+///             ITEMS_READY.release(1);
+///         }
+///     });
+///
+///     for _ in 0..100 {
+///         // Block until an item has been produced, then consume it.
+///         ITEMS_READY.wait().forget();
+///         let _item = QUEUE.lock().unwrap().pop().unwrap();
+///     }
+/// });
+/// ```
+pub struct UnboundedSemaphore {
+    /// The currently available count.
+    count: AtomicUsize,
+    /// The auto-reset event used to sleep awaiting threads until a zero count is incremented,
+    /// waking only one awaiter at a time.
+    event: AutoResetEvent,
+}
+
+impl UnboundedSemaphore {
+    /// Create a new [`UnboundedSemaphore`] with an initial available count of `initial_count`.
+    pub const fn new(initial_count: usize) -> Self {
+        Self {
+            count: AtomicUsize::new(initial_count),
+            event: AutoResetEvent::new(EventState::Unset),
+        }
+    }
+
+    fn try_wait(&self, timeout: Option<Duration>) -> Result<(), TimeoutError> {
+        let mut count = self.count.load(Ordering::Relaxed);
+
+        loop {
+            count = if count == 0 {
+                match timeout {
+                    Some(Duration::ZERO) => return Err(TimeoutError),
+                    None => self.event.try_wait()?,
+                    Some(timeout) => self.event.try_wait_for(timeout)?,
+                }
+
+                self.count.load(Ordering::Relaxed)
+            } else {
+                match self.count.compare_exchange_weak(
+                    count,
+                    count - 1,
+                    Ordering::Acquire,
+                    Ordering::Relaxed,
+                ) {
+                    Ok(_) => {
+                        // Pass the baton to the next waiter if there's anything left over.
+                        if count - 1 > 0 {
+                            self.event.set();
+                        }
+                        break;
+                    }
+                    Err(count) => count,
+                }
+            };
+        }
+
+        Ok(())
+    }
+
+    /// Obtains a slot from the `UnboundedSemaphore`, returning immediately if its count is
+    /// non-zero or blocking until another thread calls [`UnboundedSemaphore::release()`].
+    ///
+    /// The slot is returned to the semaphore when the returned guard is dropped, unless
+    /// [`UnboundedSemaphoreGuard::forget()`] is called to consume it permanently.
+    #[must_use = "The semaphore count is immediately re-incremented if the guard is dropped"]
+    pub fn wait(&self) -> UnboundedSemaphoreGuard<'_> {
+        self.try_wait(None).unwrap();
+        UnboundedSemaphoreGuard { semaphore: self }
+    }
+
+    /// Attempts a time-bounded wait against the `UnboundedSemaphore`, returning a guard if and
+    /// when a slot becomes available or a [`TimeoutError`] if the specified time limit elapses
+    /// first.
+    #[must_use = "The semaphore count is immediately re-incremented if the guard is dropped"]
+    pub fn wait_for(&self, limit: Duration) -> Result<UnboundedSemaphoreGuard<'_>, TimeoutError> {
+        self.try_wait(Some(limit))?;
+        Ok(UnboundedSemaphoreGuard { semaphore: self })
+    }
+
+    /// Increments the available count by `count`, waking a waiting thread if the count was
+    /// previously exhausted.
+    pub fn release(&self, count: usize) {
+        if count == 0 {
+            return;
+        }
+
+        let prev_count = self.count.fetch_add(count, Ordering::Release);
+        debug_assert!(
+            prev_count.checked_add(count).is_some(),
+            "UnboundedSemaphore count overflowed!"
+        );
+
+        // Only the transition from zero needs to wake a waiter. In all other cases, the last
+        // thread to obtain the semaphore would have already set the event.
+        if prev_count == 0 {
+            self.event.set();
+        }
+    }
+
+    /// Returns the currently available count of the semaphore.
+    ///
+    /// Note that this may race with other calls such as `release()` or `wait()`.
+    pub fn count(&self) -> usize {
+        self.count.load(Ordering::Relaxed)
+    }
+}
+
+impl<'a> Awaitable<'a> for UnboundedSemaphore {
+    type T = UnboundedSemaphoreGuard<'a>;
+    type Error = TimeoutError;
+
+    /// Blocks until a slot becomes available, then obtains it.
+    fn try_wait(&'a self) -> Result<UnboundedSemaphoreGuard<'a>, Infallible> {
+        self.try_wait(None).unwrap();
+        Ok(UnboundedSemaphoreGuard { semaphore: self })
+    }
+
+    /// Attempts a time-bounded wait for a slot to become available.
+    fn try_wait_for(
+        &'a self,
+        limit: Duration,
+    ) -> Result<UnboundedSemaphoreGuard<'a>, TimeoutError> {
+        self.try_wait(Some(limit))?;
+        Ok(UnboundedSemaphoreGuard { semaphore: self })
+    }
+
+    /// Attempts to obtain a slot without waiting.
+    fn try_wait0(&'a self) -> Result<UnboundedSemaphoreGuard<'a>, TimeoutError> {
+        self.try_wait(Some(Duration::ZERO))?;
+        Ok(UnboundedSemaphoreGuard { semaphore: self })
+    }
+}
+
+/// The token returned by [`UnboundedSemaphore::wait()`]. Returns its slot to the semaphore when
+/// dropped.
+pub struct UnboundedSemaphoreGuard<'a> {
+    semaphore: &'a UnboundedSemaphore,
+}
+
+impl UnboundedSemaphoreGuard<'_> {
+    /// Consumes the slot without returning it to the [`UnboundedSemaphore`], permanently
+    /// decrementing its count. This is the typical way to "consume" a signalled item in a
+    /// producer/consumer scenario.
+    ///
+    /// As an `UnboundedSemaphore` has no maximum to track, this is equivalent to
+    /// `std::mem::forget()`.
+    pub fn forget(self) {
+        core::mem::forget(self);
+    }
+}
+
+impl Debug for UnboundedSemaphoreGuard<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("UnboundedSemaphoreGuard")
+            .finish_non_exhaustive()
+    }
+}
+
+impl Drop for UnboundedSemaphoreGuard<'_> {
+    fn drop(&mut self) {
+        self.semaphore.release(1);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::UnboundedSemaphore;
+    use rsevents::Awaitable;
+    use std::thread;
+    use std::time::Duration;
+
+    #[test]
+    fn empty_unbounded_semaphore() {
+        let sem = UnboundedSemaphore::new(0);
+        sem.try_wait0().unwrap_err();
+        sem.wait_for(Duration::from_millis(10)).unwrap_err();
+    }
+
+    #[test]
+    fn release_beyond_u16() {
+        let sem = UnboundedSemaphore::new(0);
+        sem.release(100_000);
+        sem.release(100_000);
+        assert_eq!(sem.count(), 200_000);
+        sem.wait().forget();
+        assert_eq!(sem.count(), 199_999);
+    }
+
+    #[test]
+    fn guard_returns_slot() {
+        let sem = UnboundedSemaphore::new(1);
+        {
+            let _guard = sem.wait();
+            sem.try_wait0().unwrap_err();
+        }
+        assert_eq!(sem.count(), 1);
+    }
+
+    #[test]
+    fn producer_consumer() {
+        let sem = UnboundedSemaphore::new(0);
+
+        thread::scope(|scope| {
+            for _ in 0..4 {
+                scope.spawn(|| {
+                    for _ in 0..25 {
+                        sem.wait_for(Duration::from_secs(5)).unwrap().forget();
+                    }
+                });
+            }
+
+            for _ in 0..100 {
+                sem.release(1);
+            }
+        });
+
+        assert_eq!(sem.count(), 0);
+    }
+}
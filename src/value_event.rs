@@ -0,0 +1,140 @@
+use rsevents::{Awaitable, EventState, ManualResetEvent, TimeoutError};
+use std::convert::Infallible;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// A resettable event carrying a value, for broadcasting the latest version of some state (such
+/// as configuration) to any number of threads.
+///
+/// [`ValueEvent::set()`] publishes a value and releases all waiters, each of which receives a
+/// clone of it. The value stays published (so that threads that only start waiting later still
+/// observe the latest value without blocking) until it is replaced by another call to `set()` or
+/// cleared by [`ValueEvent::reset()`], after which waiters block until the next value is
+/// published.
+///
+/// ## Example:
+///
+/// ```rust
+/// use rsevents_extra::{Awaitable, ValueEvent};
+///
+/// static LOG_LEVEL: ValueEvent<&str> = ValueEvent::new();
+///
+/// std::thread::scope(|scope| {
+///     let worker = scope.spawn(|| {
+///         // Blocks until the configuration has been loaded.
+///         LOG_LEVEL.wait()
+///     });
+///
+///     LOG_LEVEL.set("info");
+///     assert_eq!(worker.join().unwrap(), "info");
+/// });
+///
+/// // Late subscribers still see the latest value.
+/// LOG_LEVEL.set("debug");
+/// assert_eq!(LOG_LEVEL.wait(), "debug");
+/// ```
+pub struct ValueEvent<T> {
+    value: Mutex<Option<T>>,
+    /// Set while `value` holds a value. Only set or reset with the `value` lock held.
+    event: ManualResetEvent,
+}
+
+impl<T: Clone> ValueEvent<T> {
+    /// Creates a new `ValueEvent` with no value published.
+    ///
+    /// This is a `const` function and can be used in a `static` context.
+    pub const fn new() -> Self {
+        ValueEvent {
+            value: Mutex::new(None),
+            event: ManualResetEvent::new(EventState::Unset),
+        }
+    }
+
+    /// Publishes `value`, replacing any previously published value and releasing all waiters.
+    pub fn set(&self, value: T) {
+        let mut slot = self.value.lock().unwrap();
+        *slot = Some(value);
+        self.event.set();
+    }
+
+    /// Clears the published value (if any), so that subsequent waiters block until a new value is
+    /// [published](Self::set). Returns the value that was cleared.
+    pub fn reset(&self) -> Option<T> {
+        let mut slot = self.value.lock().unwrap();
+        self.event.reset();
+        slot.take()
+    }
+
+    /// Returns a clone of the currently published value, if any, without blocking.
+    pub fn get(&self) -> Option<T> {
+        self.value.lock().unwrap().clone()
+    }
+}
+
+impl<T: Clone> Default for ValueEvent<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Clone> Awaitable<'_> for ValueEvent<T> {
+    type T = T;
+    type Error = TimeoutError;
+
+    /// Waits for a value to be published, then returns a clone of it.
+    fn try_wait(&self) -> Result<T, Infallible> {
+        loop {
+            self.event.wait();
+            // The value may have been cleared again before we got to it.
+            if let Some(value) = self.get() {
+                return Ok(value);
+            }
+        }
+    }
+
+    /// Waits for a value to be published, then returns a clone of it, or returns an error in case
+    /// of a timeout.
+    fn try_wait_for(&self, limit: Duration) -> Result<T, TimeoutError> {
+        let deadline = Instant::now() + limit;
+        loop {
+            self.event
+                .try_wait_for(deadline.saturating_duration_since(Instant::now()))?;
+            if let Some(value) = self.get() {
+                return Ok(value);
+            }
+        }
+    }
+
+    /// Returns a clone of the currently published value, or an error if none is published.
+    fn try_wait0(&self) -> Result<T, TimeoutError> {
+        self.get().ok_or(TimeoutError)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{Awaitable, ValueEvent};
+    use std::thread;
+    use std::time::Duration;
+
+    #[test]
+    fn publish_and_reset() {
+        let event = ValueEvent::new();
+        assert!(event.try_wait0().is_err());
+
+        thread::scope(|scope| {
+            let waiter = scope.spawn(|| event.wait());
+            thread::sleep(Duration::from_millis(10));
+            event.set(1);
+            assert_eq!(waiter.join().unwrap(), 1);
+        });
+
+        event.set(2);
+        assert_eq!(event.wait(), 2);
+        assert_eq!(event.get(), Some(2));
+
+        assert_eq!(event.reset(), Some(2));
+        assert!(event.try_wait_for(Duration::from_millis(1)).is_err());
+        assert_eq!(event.reset(), None);
+    }
+}
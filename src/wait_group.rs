@@ -0,0 +1,139 @@
+#![allow(clippy::bool_assert_comparison)]
+
+use crate::subscribe::{Subscribe, Subscription};
+use crate::sync::loom_const_fn;
+use crate::{CountdownEvent, OverTickPolicy};
+use rsevents::{Awaitable, TimeoutError};
+use std::convert::Infallible;
+use std::task::Waker;
+use std::time::Duration;
+
+/// A Go-style wait group, for waiting on a dynamic set of tasks that are added to the group as
+/// they are spawned.
+///
+/// A `WaitGroup` starts out empty (and so set). Each task is announced with
+/// [`WaitGroup::add()`] before it is spawned and reports its completion with
+/// [`WaitGroup::done()`], and waiting on the group blocks until every task that was added has
+/// completed. Unlike a [`CountdownEvent`] created with a fixed count, the group may return to zero
+/// and be reused any number of times. As in Go, calling `done()` more often than tasks were added
+/// is a bug and panics.
+///
+/// ## Example:
+///
+/// ```rust
+/// use rsevents_extra::{Awaitable, WaitGroup};
+///
+/// static TASKS: WaitGroup = WaitGroup::new();
+///
+/// std::thread::scope(|scope| {
+///     for _ in 0..4 {
+///         TASKS.add(1);
+///         scope.spawn(|| {
+///             // <perform some work here>
+///             TASKS.done();
+///         });
+///     }
+///
+///     TASKS.wait();
+///     assert_eq!(TASKS.count(), 0);
+/// });
+/// ```
+pub struct WaitGroup {
+    countdown: CountdownEvent,
+}
+
+impl WaitGroup {
+    loom_const_fn! {
+        /// Creates a new, empty `WaitGroup`.
+        ///
+        /// This is a `const` function and can be used in a `static` context.
+        pub fn new() -> Self {
+            Self {
+                countdown: CountdownEvent::new(0).with_over_tick_policy(OverTickPolicy::Panic),
+            }
+        }
+    }
+
+    /// Adds `n` outstanding tasks to the group, which must each be marked [done](Self::done)
+    /// before waiters are released.
+    pub fn add(&self, n: usize) {
+        self.countdown.increment_by(n);
+    }
+
+    /// Marks one of the group's tasks as completed, releasing any waiters if this was the last
+    /// outstanding task.
+    ///
+    /// Panics if the group has no outstanding tasks.
+    pub fn done(&self) {
+        self.countdown.tick();
+    }
+
+    /// Returns the number of outstanding tasks.
+    pub fn count(&self) -> usize {
+        self.countdown.count()
+    }
+}
+
+impl Default for WaitGroup {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Awaitable<'_> for WaitGroup {
+    type T = ();
+    type Error = TimeoutError;
+
+    /// Waits for all outstanding tasks to be marked done.
+    fn try_wait(&self) -> Result<(), Infallible> {
+        self.countdown.try_wait()
+    }
+
+    /// Waits for all outstanding tasks to be marked done or returns an error in case of a
+    /// timeout.
+    fn try_wait_for(&self, limit: Duration) -> Result<(), TimeoutError> {
+        self.countdown.try_wait_for(limit)
+    }
+
+    /// Checks whether all outstanding tasks have been marked done without blocking.
+    fn try_wait0(&self) -> Result<(), TimeoutError> {
+        self.countdown.try_wait0()
+    }
+}
+
+impl Subscribe for WaitGroup {
+    fn subscribe(&self, waker: &Waker) -> Option<Subscription<'_>> {
+        self.countdown.subscribe(waker)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{Awaitable, WaitGroup};
+
+    #[test]
+    fn reusable() {
+        let group = WaitGroup::new();
+        assert_eq!(group.wait0(), true);
+
+        for _ in 0..2 {
+            group.add(2);
+            assert_eq!(group.wait0(), false);
+            group.done();
+            group.add(1);
+            group.done();
+            assert_eq!(group.wait0(), false);
+            group.done();
+            assert_eq!(group.wait0(), true);
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn negative_count_panics() {
+        let group = WaitGroup::new();
+        group.add(1);
+        group.done();
+        group.done();
+    }
+}
@@ -0,0 +1,320 @@
+use crate::multi_wait::wait_first;
+use crate::subscribe::{polled, subscribed, Subscribe, SubscribeFn, Subscription};
+use rsevents::{Awaitable, TimeoutError};
+use std::ops::Deref;
+use std::task::Waker;
+use std::time::{Duration, Instant};
+
+/// The key an awaitable is registered with in a [`WaitSet`], identifying it when it becomes ready
+/// and when it is deregistered.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct WaitKey(u64);
+
+/// A type-erased handle to an awaitable registered with a [`WaitSet`].
+trait Entry<T> {
+    fn try_wait0(&self) -> Option<T>;
+    fn subscribe(&self, waker: &Waker) -> Option<Subscription<'_>>;
+}
+
+/// An awaitable registered through a shared or owning handle, along with how to subscribe to it.
+struct HandleEntry<D: Deref> {
+    handle: D,
+    subscribe: SubscribeFn<D::Target>,
+}
+
+impl<D, T> Entry<T> for HandleEntry<D>
+where
+    D: Deref,
+    D::Target: for<'b> Awaitable<'b, T = T, Error = TimeoutError>,
+{
+    fn try_wait0(&self) -> Option<T> {
+        self.handle.try_wait0().ok()
+    }
+
+    fn subscribe(&self, waker: &Waker) -> Option<Subscription<'_>> {
+        (self.subscribe)(&self.handle, waker)
+    }
+}
+
+/// An awaitable with a `'static` lifetime, such as a `static` [`Semaphore`](crate::Semaphore),
+/// which can be waited on for results borrowing from it for `'static` as well.
+struct StaticEntry<A: 'static> {
+    awaitable: &'static A,
+    subscribe: SubscribeFn<A>,
+}
+
+impl<A, T> Entry<T> for StaticEntry<A>
+where
+    A: Awaitable<'static, T = T, Error = TimeoutError>,
+{
+    fn try_wait0(&self) -> Option<T> {
+        self.awaitable.try_wait0().ok()
+    }
+
+    fn subscribe(&self, waker: &Waker) -> Option<Subscription<'_>> {
+        (self.subscribe)(self.awaitable, waker)
+    }
+}
+
+/// A changing set of awaitables, such as the completion events of a population of workers, that
+/// can be waited on until any one of them becomes ready, returning the [`WaitKey`] it was
+/// registered with.
+///
+/// Awaitables are registered through a shared or owning handle (e.g. an `Arc<ManualResetEvent>`
+/// shared with a worker) with [`WaitSet::register()`], or by `'static` reference with
+/// [`WaitSet::register_static()`], which also accepts awaitables whose results borrow from them
+/// (such as a `static` [`Semaphore`](crate::Semaphore), waited on for a `SemaphoreGuard<'static>`).
+/// Either can be deregistered at any time. As with
+/// [`wait_any()`](crate::wait_any), only the awaitable that became ready is waited on, with the
+/// earliest registered winning if several are ready at once. An awaitable that stays ready (such as
+/// a set `ManualResetEvent`) keeps being returned until it is deregistered.
+///
+/// Awaitables registered as above are polled while waiting, with an exponential backoff capped at
+/// a few milliseconds. Those that implement [`Subscribe`] can instead be registered with
+/// [`WaitSet::register_subscribed()`] or [`WaitSet::register_static_subscribed()`], to wake the
+/// waiting thread themselves once they become ready.
+///
+/// ## Example:
+///
+/// ```rust
+/// use rsevents::{EventState, ManualResetEvent};
+/// use rsevents_extra::WaitSet;
+/// use std::collections::HashMap;
+/// use std::sync::Arc;
+///
+/// let mut workers = WaitSet::new();
+/// let mut names = HashMap::new();
+/// for name in ["fetcher", "parser", "indexer"] {
+///     let done = Arc::new(ManualResetEvent::new(EventState::Unset));
+///     let key = workers.register(done.clone());
+///     names.insert(key, name);
+///     std::thread::spawn(move || {
+///         // <do the work here>
+///         done.set();
+///     });
+/// }
+///
+/// // Supervise the workers, replacing or retiring each one as it finishes.
+/// while !workers.is_empty() {
+///     let (key, ()) = workers.wait_any();
+///     workers.deregister(key);
+///     println!("{} finished", names[&key]);
+/// }
+/// ```
+pub struct WaitSet<T = ()> {
+    entries: Vec<(WaitKey, Box<dyn Entry<T> + Send + Sync>)>,
+    next_key: u64,
+}
+
+impl<T> WaitSet<T> {
+    /// Creates a new, empty `WaitSet`.
+    pub const fn new() -> Self {
+        WaitSet {
+            entries: Vec::new(),
+            next_key: 0,
+        }
+    }
+
+    /// Adds the awaitable behind `handle` to the set, returning the key identifying it.
+    pub fn register<D>(&mut self, handle: D) -> WaitKey
+    where
+        D: Deref + Send + Sync + 'static,
+        D::Target: for<'b> Awaitable<'b, T = T, Error = TimeoutError>,
+    {
+        self.insert(Box::new(HandleEntry {
+            handle,
+            subscribe: polled,
+        }))
+    }
+
+    /// Adds the awaitable behind `handle` to the set like [`register()`](Self::register), but
+    /// subscribes to it while waiting instead of polling it.
+    pub fn register_subscribed<D>(&mut self, handle: D) -> WaitKey
+    where
+        D: Deref + Send + Sync + 'static,
+        D::Target: for<'b> Awaitable<'b, T = T, Error = TimeoutError> + Subscribe,
+    {
+        self.insert(Box::new(HandleEntry {
+            handle,
+            subscribe: subscribed,
+        }))
+    }
+
+    /// Adds the `'static` awaitable `awaitable` to the set, returning the key identifying it.
+    ///
+    /// Unlike [`register()`](Self::register), this also accepts awaitables whose results borrow
+    /// from them, as is the case for semaphores and their guards:
+    ///
+    /// ```rust
+    /// use rsevents_extra::{Semaphore, SemaphoreGuard, WaitSet};
+    ///
+    /// static DISK: Semaphore = Semaphore::new(1, 1);
+    /// static NETWORK: Semaphore = Semaphore::new(2, 2);
+    ///
+    /// let mut slots: WaitSet<SemaphoreGuard<'static>> = WaitSet::new();
+    /// let disk = slots.register_static(&DISK);
+    /// slots.register_static(&NETWORK);
+    ///
+    /// let (key, _guard) = slots.wait_any();
+    /// assert_eq!(key, disk);
+    /// assert_eq!(DISK.count(), 0);
+    /// ```
+    pub fn register_static<A>(&mut self, awaitable: &'static A) -> WaitKey
+    where
+        A: Awaitable<'static, T = T, Error = TimeoutError> + Sync,
+    {
+        self.insert(Box::new(StaticEntry {
+            awaitable,
+            subscribe: polled,
+        }))
+    }
+
+    /// Adds the `'static` awaitable `awaitable` to the set like
+    /// [`register_static()`](Self::register_static), but subscribes to it while waiting instead of
+    /// polling it.
+    pub fn register_static_subscribed<A>(&mut self, awaitable: &'static A) -> WaitKey
+    where
+        A: Awaitable<'static, T = T, Error = TimeoutError> + Subscribe + Sync,
+    {
+        self.insert(Box::new(StaticEntry {
+            awaitable,
+            subscribe: subscribed,
+        }))
+    }
+
+    fn insert(&mut self, entry: Box<dyn Entry<T> + Send + Sync>) -> WaitKey {
+        let key = WaitKey(self.next_key);
+        self.next_key += 1;
+        self.entries.push((key, entry));
+        key
+    }
+
+    /// Removes the awaitable registered with `key` from the set, returning whether it was still
+    /// registered.
+    pub fn deregister(&mut self, key: WaitKey) -> bool {
+        match self.entries.iter().position(|(k, _)| *k == key) {
+            Some(index) => {
+                self.entries.remove(index);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Returns whether an awaitable is registered with `key`.
+    pub fn contains(&self, key: WaitKey) -> bool {
+        self.entries.iter().any(|(k, _)| *k == key)
+    }
+
+    /// Returns the number of awaitables in the set.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns whether the set is empty.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Blocks until any one of the registered awaitables becomes ready, returning its key along
+    /// with the result of waiting on it.
+    ///
+    /// Panics if the set is empty.
+    pub fn wait_any(&self) -> (WaitKey, T) {
+        match self.wait_any_until(None) {
+            Ok(ready) => ready,
+            Err(_) => unreachable!(),
+        }
+    }
+
+    /// Blocks for up to `limit` until any one of the registered awaitables becomes ready,
+    /// returning its key and the result of waiting on it, or an error in case of a timeout.
+    ///
+    /// Panics if the set is empty.
+    pub fn wait_any_for(&self, limit: Duration) -> Result<(WaitKey, T), TimeoutError> {
+        self.wait_any_until(Some(Instant::now() + limit))
+    }
+
+    fn wait_any_until(&self, deadline: Option<Instant>) -> Result<(WaitKey, T), TimeoutError> {
+        assert!(
+            !self.entries.is_empty(),
+            "WaitSet::wait_any() on an empty set!"
+        );
+
+        let (index, result) = wait_first(
+            self.entries.len(),
+            deadline,
+            |index, waker| self.entries[index].1.subscribe(waker),
+            |index| self.entries[index].1.try_wait0(),
+        )?;
+        Ok((self.entries[index].0, result))
+    }
+}
+
+impl<T> Default for WaitSet<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{CountdownEvent, WaitSet};
+    use rsevents::{AutoResetEvent, EventState};
+    use std::sync::Arc;
+    use std::thread;
+    use std::time::Duration;
+
+    #[test]
+    fn returns_ready_key() {
+        let mut set = WaitSet::new();
+        let first = Arc::new(AutoResetEvent::new(EventState::Unset));
+        let second = Arc::new(CountdownEvent::new(1));
+        let first_key = set.register(first.clone());
+        let second_key = set.register_subscribed(second.clone());
+        assert_ne!(first_key, second_key);
+        assert!(set.wait_any_for(Duration::from_millis(10)).is_err());
+
+        thread::scope(|scope| {
+            scope.spawn(|| {
+                thread::sleep(Duration::from_millis(10));
+                second.tick();
+            });
+            assert_eq!(set.wait_any().0, second_key);
+        });
+
+        assert!(set.deregister(second_key));
+        assert!(!set.deregister(second_key));
+        assert!(!set.contains(second_key));
+        assert!(set.wait_any_for(Duration::from_millis(10)).is_err());
+        first.set();
+        assert_eq!(set.wait_any().0, first_key);
+        assert_eq!(set.len(), 1);
+    }
+
+    #[cfg(not(loom))]
+    #[test]
+    fn registers_static_semaphores() {
+        use crate::{Semaphore, SemaphoreGuard};
+
+        static FIRST: Semaphore = Semaphore::new(0, 1);
+        static SECOND: Semaphore = Semaphore::new(0, 1);
+
+        let mut set: WaitSet<SemaphoreGuard<'static>> = WaitSet::new();
+        set.register_static(&FIRST);
+        let second = set.register_static_subscribed(&SECOND);
+        assert!(set.wait_any_for(Duration::from_millis(10)).is_err());
+
+        thread::scope(|scope| {
+            scope.spawn(|| {
+                thread::sleep(Duration::from_millis(10));
+                SECOND.release(1);
+            });
+            let (key, guard) = set.wait_any();
+            assert_eq!(key, second);
+            assert_eq!(SECOND.count(), 0);
+            drop(guard);
+        });
+        assert_eq!(SECOND.count(), 1);
+    }
+}
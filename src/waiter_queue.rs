@@ -0,0 +1,87 @@
+use rsevents::{Awaitable, EventState, ManualResetEvent};
+use std::collections::VecDeque;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// The threads blocked on a primitive, each waiting on an event of its own so that they can be
+/// released one at a time (in arrival order) or all at once. The queue is kept under the
+/// primitive's own lock, along with whatever state decides when its waiters are released.
+pub(crate) struct WaiterQueue {
+    waiters: VecDeque<Arc<ManualResetEvent>>,
+}
+
+/// A thread's place in a [`WaiterQueue`].
+pub(crate) struct Waiter {
+    event: Arc<ManualResetEvent>,
+}
+
+impl WaiterQueue {
+    pub(crate) const fn new() -> Self {
+        WaiterQueue {
+            waiters: VecDeque::new(),
+        }
+    }
+
+    /// Adds a waiter to the back of the queue.
+    pub(crate) fn push(&mut self) -> Waiter {
+        let event = Arc::new(ManualResetEvent::new(EventState::Unset));
+        self.waiters.push_back(Arc::clone(&event));
+        Waiter { event }
+    }
+
+    /// Releases the longest-waiting thread, returning whether there was one.
+    pub(crate) fn release_one(&mut self) -> bool {
+        match self.waiters.pop_front() {
+            Some(waiter) => {
+                waiter.set();
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Releases all waiting threads, returning how many there were.
+    pub(crate) fn release_all(&mut self) -> usize {
+        let count = self.waiters.len();
+        for waiter in self.waiters.drain(..) {
+            waiter.set();
+        }
+        count
+    }
+
+    /// Returns the number of waiting threads.
+    pub(crate) fn len(&self) -> usize {
+        self.waiters.len()
+    }
+
+    /// Takes a waiter whose wait timed out out of the queue, returning `false` if it is no longer
+    /// queued because it was released just as it timed out (so its wait succeeded after all).
+    pub(crate) fn withdraw(&mut self, waiter: &Waiter) -> bool {
+        match self
+            .waiters
+            .iter()
+            .position(|queued| Arc::ptr_eq(queued, &waiter.event))
+        {
+            Some(index) => {
+                self.waiters.remove(index);
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+impl Waiter {
+    /// Blocks until released.
+    pub(crate) fn wait(&self) {
+        self.event.wait();
+    }
+
+    /// Blocks for up to `limit` until released, returning whether the waiter was. If the time
+    /// limit elapses first, `withdraw` is called to take the waiter out of its queue (with
+    /// [`WaiterQueue::withdraw()`], under the primitive's lock), which may find that it was
+    /// released after all.
+    pub(crate) fn wait_for(&self, limit: Duration, withdraw: impl FnOnce(&Waiter) -> bool) -> bool {
+        self.event.wait_for(limit) || !withdraw(self)
+    }
+}
@@ -0,0 +1,91 @@
+use rsevents::{AutoResetEvent, Awaitable, EventState};
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+use std::sync::{Mutex, OnceLock};
+use std::task::Waker;
+use std::time::{Duration, Instant};
+
+/// A waker scheduled to be woken at a given time.
+struct Entry {
+    deadline: Instant,
+    waker: Waker,
+}
+
+impl PartialEq for Entry {
+    fn eq(&self, other: &Self) -> bool {
+        self.deadline == other.deadline
+    }
+}
+
+impl Eq for Entry {}
+
+impl PartialOrd for Entry {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Entry {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.deadline.cmp(&other.deadline)
+    }
+}
+
+/// The queue of pending wakeups, earliest first.
+static PENDING: Mutex<BinaryHeap<Reverse<Entry>>> = Mutex::new(BinaryHeap::new());
+/// Set when a wakeup earlier than any other pending one is scheduled.
+static RESCHEDULED: AutoResetEvent = AutoResetEvent::new(EventState::Unset);
+/// The thread carrying out all wakeups, started on first use.
+static TIMER: OnceLock<()> = OnceLock::new();
+
+/// Wakes `waker` once `delay` has elapsed, from a single thread shared by all futures that poll a
+/// blocking [`Awaitable`](rsevents::Awaitable), so that waiting on one from async code doesn't
+/// need a thread of its own.
+pub(crate) fn wake_after(waker: Waker, delay: Duration) {
+    TIMER.get_or_init(|| {
+        std::thread::Builder::new()
+            .name("rsevents-extra wake timer".into())
+            .spawn(run)
+            .expect("Failed to spawn the wake timer thread!");
+    });
+
+    let deadline = Instant::now() + delay;
+    let mut pending = PENDING.lock().unwrap();
+    // `Option::is_none_or()` would need Rust 1.82.
+    #[allow(clippy::unnecessary_map_or)]
+    let earliest = pending
+        .peek()
+        .map_or(true, |Reverse(next)| deadline < next.deadline);
+    pending.push(Reverse(Entry { deadline, waker }));
+    drop(pending);
+    if earliest {
+        RESCHEDULED.set();
+    }
+}
+
+fn run() {
+    loop {
+        let mut pending = PENDING.lock().unwrap();
+        let now = Instant::now();
+        let mut due = Vec::new();
+        while pending
+            .peek()
+            .is_some_and(|Reverse(next)| next.deadline <= now)
+        {
+            due.push(pending.pop().unwrap().0.waker);
+        }
+        let next = pending.peek().map(|Reverse(next)| next.deadline - now);
+        drop(pending);
+
+        // Wake outside the lock, as a waker may poll its future (and reschedule) right away.
+        for waker in due {
+            waker.wake();
+        }
+        match next {
+            Some(timeout) => {
+                RESCHEDULED.wait_for(timeout);
+            }
+            None => RESCHEDULED.wait(),
+        }
+    }
+}
@@ -0,0 +1,90 @@
+use crate::sync::{fence, loom_const_fn, AtomicUsize, Mutex, Ordering};
+use std::collections::VecDeque;
+use std::task::Waker;
+
+/// The wakers of the tasks awaiting a primitive's futures (or of its
+/// [subscribers](crate::Subscribe)), letting the primitive wake them directly (on whichever
+/// executor they run) instead of them having to poll it.
+///
+/// Each future registers its waker under an id obtained from [`WakerList::next_id()`], so that it
+/// can replace its waker when polled again and withdraw it when dropped. Wakers are woken in the
+/// order they were first registered.
+pub(crate) struct WakerList {
+    wakers: Mutex<VecDeque<(usize, Waker)>>,
+    /// The length of `wakers`, letting the primitive skip the lock when no tasks are waiting.
+    pending: AtomicUsize,
+    next_id: AtomicUsize,
+}
+
+impl WakerList {
+    loom_const_fn! {
+        pub(crate) fn new() -> Self {
+            WakerList {
+                wakers: Mutex::new(VecDeque::new()),
+                pending: AtomicUsize::new(0),
+                next_id: AtomicUsize::new(0),
+            }
+        }
+    }
+
+    /// Returns a new id for a future to register its waker under.
+    pub(crate) fn next_id(&self) -> usize {
+        self.next_id.fetch_add(1, Ordering::Relaxed)
+    }
+
+    /// Registers (or updates) the waker of the future `id`.
+    ///
+    /// The future must check whether it can complete *after* registering, as the primitive only
+    /// wakes the wakers registered at the time its state changes.
+    pub(crate) fn register(&self, id: usize, waker: &Waker) {
+        let mut wakers = self.wakers.lock().unwrap();
+        match wakers.iter_mut().find(|(i, _)| *i == id) {
+            Some((_, registered)) => registered.clone_from(waker),
+            None => wakers.push_back((id, waker.clone())),
+        }
+        self.pending.store(wakers.len(), Ordering::SeqCst);
+        drop(wakers);
+        // Pairs with the fence in `take()`: either the future sees the new state of the
+        // primitive, or the primitive sees the waker.
+        fence(Ordering::SeqCst);
+    }
+
+    /// Withdraws the waker of the future `id`, returning whether it was still registered (i.e.
+    /// hadn't been woken yet).
+    pub(crate) fn remove(&self, id: usize) -> bool {
+        let mut wakers = self.wakers.lock().unwrap();
+        let registered = match wakers.iter().position(|(i, _)| *i == id) {
+            Some(index) => wakers.remove(index).is_some(),
+            None => false,
+        };
+        self.pending.store(wakers.len(), Ordering::SeqCst);
+        registered
+    }
+
+    /// Wakes the oldest registered future, if any.
+    #[cfg(feature = "async")]
+    pub(crate) fn wake_one(&self) {
+        self.take(1).into_iter().for_each(Waker::wake);
+    }
+
+    /// Wakes all registered futures.
+    pub(crate) fn wake_all(&self) {
+        self.take(usize::MAX).into_iter().for_each(Waker::wake);
+    }
+
+    /// Unregisters up to `count` of the oldest wakers, to be woken outside the lock (as a waker
+    /// may poll its future, and so register again, right away).
+    fn take(&self, count: usize) -> Vec<Waker> {
+        // Pairs with the fence in `register()`.
+        fence(Ordering::SeqCst);
+        if self.pending.load(Ordering::SeqCst) == 0 {
+            return Vec::new();
+        }
+
+        let mut wakers = self.wakers.lock().unwrap();
+        let count = count.min(wakers.len());
+        let taken = wakers.drain(..count).map(|(_, waker)| waker).collect();
+        self.pending.store(wakers.len(), Ordering::SeqCst);
+        taken
+    }
+}
@@ -0,0 +1,186 @@
+use crate::clock::{self, Clock, SystemClock};
+use rsevents::{Awaitable, TimeoutError};
+use std::convert::Infallible;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// A watchdog that must be [kicked](WatchdogEvent::kick) at least once per timeout, and which
+/// becomes set (as an [`Awaitable`]) if it isn't, so a monitoring thread can detect a stalled
+/// worker simply by waiting on it.
+///
+/// The timeout is measured from the last kick (or from when the watchdog was first used, if it
+/// hasn't been kicked yet). Once expired, the watchdog stays set until it is kicked again. An
+/// optional callback, registered with [`WatchdogEvent::with_callback()`], is invoked once per
+/// expiry by the first thread to observe it (typically the monitoring thread waiting on the
+/// watchdog).
+///
+/// ## Example:
+///
+/// ```rust
+/// use rsevents_extra::{Awaitable, WatchdogEvent};
+/// use std::time::Duration;
+///
+/// static WORKER_ALIVE: WatchdogEvent = WatchdogEvent::new(Duration::from_millis(20))
+///     .with_callback(|| eprintln!("The worker has stalled!"));
+///
+/// std::thread::scope(|scope| {
+///     scope.spawn(|| {
+///         for _ in 0..5 {
+///             // <process the next work item here>
+///             WORKER_ALIVE.kick();
+///         }
+///         // The worker stops kicking the watchdog once it's done.
+///     });
+///
+///     // Returns once the worker has gone quiet for a full timeout.
+///     WORKER_ALIVE.wait();
+///     assert!(WORKER_ALIVE.is_expired());
+/// });
+/// ```
+pub struct WatchdogEvent {
+    timeout: Duration,
+    state: Mutex<WatchdogState>,
+    on_expired: Option<fn()>,
+    clock: &'static dyn Clock,
+}
+
+struct WatchdogState {
+    /// When the watchdog was last kicked (or first used), or `None` before it has been used.
+    kicked: Option<Instant>,
+    /// Whether the current expiry has already been reported to the callback.
+    reported: bool,
+}
+
+impl WatchdogEvent {
+    /// Creates a new `WatchdogEvent` that becomes set if it is not kicked for `timeout`.
+    ///
+    /// This is a `const` function and can be used in a `static` context.
+    pub const fn new(timeout: Duration) -> Self {
+        WatchdogEvent {
+            timeout,
+            state: Mutex::new(WatchdogState {
+                kicked: None,
+                reported: false,
+            }),
+            on_expired: None,
+            clock: &SystemClock,
+        }
+    }
+
+    /// Registers a callback to be invoked each time the watchdog expires, by the first thread to
+    /// observe the expiry.
+    pub const fn with_callback(mut self, on_expired: fn()) -> Self {
+        self.on_expired = Some(on_expired);
+        self
+    }
+
+    /// Replaces the [`SystemClock`] used to measure the timeout with `clock`. This is primarily
+    /// useful in tests, where a [`MockClock`](crate::MockClock) makes the timeout deterministic.
+    pub const fn with_clock(mut self, clock: &'static dyn Clock) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Kicks the watchdog, restarting its timeout and unsetting it if it had expired.
+    pub fn kick(&self) {
+        let now = self.clock.now();
+        let mut state = self.state.lock().unwrap();
+        state.kicked = Some(now);
+        state.reported = false;
+    }
+
+    /// Returns whether the watchdog has gone a full timeout without being kicked, without
+    /// blocking.
+    pub fn is_expired(&self) -> bool {
+        self.check().is_ok()
+    }
+
+    /// Returns `Ok(())` if the watchdog has expired (reporting the expiry if it hasn't been yet),
+    /// or otherwise how long it will be until it does unless kicked in the meantime.
+    fn check(&self) -> Result<(), Duration> {
+        let now = self.clock.now();
+        let mut state = self.state.lock().unwrap();
+        let kicked = *state.kicked.get_or_insert(now);
+        let remaining = (kicked + self.timeout).saturating_duration_since(now);
+        if !remaining.is_zero() {
+            return Err(remaining);
+        }
+
+        let report = !std::mem::replace(&mut state.reported, true);
+        drop(state);
+        if let (true, Some(on_expired)) = (report, self.on_expired) {
+            on_expired();
+        }
+        Ok(())
+    }
+}
+
+impl Awaitable<'_> for WatchdogEvent {
+    type T = ();
+    type Error = TimeoutError;
+
+    /// Waits for the watchdog to expire.
+    fn try_wait(&self) -> Result<(), Infallible> {
+        while let Err(remaining) = self.check() {
+            clock::sleep(self.clock, remaining);
+        }
+        Ok(())
+    }
+
+    /// Waits for up to `limit` for the watchdog to expire.
+    fn try_wait_for(&self, limit: Duration) -> Result<(), TimeoutError> {
+        let deadline = self.clock.now() + limit;
+        loop {
+            let remaining = match self.check() {
+                Ok(()) => return Ok(()),
+                Err(remaining) => remaining,
+            };
+            let left = deadline.saturating_duration_since(self.clock.now());
+            if remaining > left {
+                clock::sleep(self.clock, left);
+                return self.check().map_err(|_| TimeoutError);
+            }
+            clock::sleep(self.clock, remaining);
+        }
+    }
+
+    /// Checks whether the watchdog has expired without blocking.
+    fn try_wait0(&self) -> Result<(), TimeoutError> {
+        self.check().map_err(|_| TimeoutError)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{Awaitable, MockClock, WatchdogEvent};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::time::Duration;
+
+    #[test]
+    fn expires_without_kicks() {
+        static CLOCK: MockClock = MockClock::new();
+        static EXPIRIES: AtomicUsize = AtomicUsize::new(0);
+        let watchdog = WatchdogEvent::new(Duration::from_secs(5))
+            .with_clock(&CLOCK)
+            .with_callback(|| {
+                EXPIRIES.fetch_add(1, Ordering::SeqCst);
+            });
+
+        assert!(!watchdog.is_expired());
+        CLOCK.advance(Duration::from_secs(4));
+        watchdog.kick();
+        CLOCK.advance(Duration::from_secs(4));
+        assert!(!watchdog.wait0());
+
+        CLOCK.advance(Duration::from_secs(1));
+        assert!(watchdog.wait0());
+        assert!(watchdog.is_expired());
+        assert_eq!(EXPIRIES.load(Ordering::SeqCst), 1);
+
+        watchdog.kick();
+        assert!(!watchdog.is_expired());
+        CLOCK.advance(Duration::from_secs(5));
+        assert!(watchdog.wait_for(Duration::from_secs(1)));
+        assert_eq!(EXPIRIES.load(Ordering::SeqCst), 2);
+    }
+}